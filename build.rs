@@ -9,9 +9,11 @@
 //! new memory settings.
 
 use std::env;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Write;
 use std::path::PathBuf;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 fn main() {
     // Put `memory.x` in our output directory and ensure it's
@@ -28,4 +30,67 @@ fn main() {
     // here, we ensure the build script is only re-run when
     // `memory.x` is changed.
     println!("cargo:rerun-if-changed=memory.x");
+
+    // Embed the short git commit hash, so the running firmware can be
+    // matched to the source. Builds outside a git checkout show "unknown".
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=GIT_HASH={}", git_hash);
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/index");
+
+    // Embed the UTC build date, as YYYY-MM-DD.
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days((secs / 86400) as i64);
+    println!(
+        "cargo:rustc-env=BUILD_DATE={:04}-{:02}-{:02}",
+        year, month, day
+    );
+
+    // The wifi firmware is not in the repository. Copy it from CYW43_FIRMWARE_DIR, or the
+    // cyw43-firmware folder by default. Without it the build carries on with empty firmware,
+    // so the wifi code can still be checked, but the wifi will not start on the clock.
+    if env::var_os("CARGO_FEATURE_WIFI").is_some() {
+        let dir = env::var("CYW43_FIRMWARE_DIR").unwrap_or_else(|_| "cyw43-firmware".to_string());
+        println!("cargo:rerun-if-env-changed=CYW43_FIRMWARE_DIR");
+
+        for name in ["43439A0.bin", "43439A0_clm.bin"] {
+            let path = PathBuf::from(&dir).join(name);
+            println!("cargo:rerun-if-changed={}", path.display());
+
+            let firmware = fs::read(&path).unwrap_or_else(|_| {
+                println!(
+                    "cargo:warning={} not found, building with empty wifi firmware",
+                    path.display()
+                );
+                Vec::new()
+            });
+            fs::write(out.join(name), firmware).unwrap();
+        }
+    }
+}
+
+/// Convert days since 1970-01-01 to a (year, month, day) date.
+///
+/// Uses Howard Hinnant's days to civil algorithm, to avoid a build dependency for one date.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
 }
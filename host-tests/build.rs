@@ -0,0 +1,7 @@
+//! Sets the environment variables the firmware's build script sets, so the included modules compile.
+//!
+//! The build date is fixed rather than the day of the build, so tests that read it always see the same date.
+
+fn main() {
+    println!("cargo:rustc-env=BUILD_DATE=2023-08-01");
+}
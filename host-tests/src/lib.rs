@@ -0,0 +1,109 @@
+//! Host tests for the parts of the clock firmware that do not need the hardware.
+//!
+//! The firmware only builds for the RP2040, so each module is included from `src` by path and tested here.
+//! `.cargo/config.toml` builds for the RP2040 by default, so pass the host target when running the tests, e.g.
+//! `cargo test --manifest-path host-tests/Cargo.toml --target x86_64-unknown-linux-gnu`.
+
+// the firmware uses the rest of each module, not this crate
+#![allow(dead_code)]
+
+/// Use date module.
+#[path = "../../src/date.rs"]
+mod date;
+
+/// Use formatting module.
+#[path = "../../src/formatting.rs"]
+mod formatting;
+
+/// Use time display module.
+#[path = "../../src/time_display.rs"]
+mod time_display;
+
+/// Use display text module.
+#[path = "../../src/display/text.rs"]
+mod text;
+
+/// Stand-ins for the config types the included modules use, copied from `src/config.rs`.
+///
+/// The config module reads and writes flash, so it can not be included. Keep these in step with it.
+mod config {
+    /// Temperature preference representation.
+    #[derive(Copy, Clone, PartialEq)]
+    pub enum TemperaturePreference {
+        /// Celcius.
+        Celcius,
+
+        /// Fahrenheit.
+        Fahrenheit,
+    }
+
+    /// Temperature precision representation.
+    #[derive(Copy, Clone, PartialEq)]
+    pub enum TemperaturePrecision {
+        /// Whole degrees.
+        Whole,
+
+        /// One decimal place.
+        Tenths,
+    }
+
+    impl TemperaturePrecision {
+        /// The number of decimal places to show.
+        pub const fn decimal_places(&self) -> usize {
+            match self {
+                TemperaturePrecision::Whole => 0,
+                TemperaturePrecision::Tenths => 1,
+            }
+        }
+    }
+
+    /// Time colon preference representation.
+    #[derive(Copy, Clone, PartialEq)]
+    pub enum TimeColonPreference {
+        /// Do not blink the colon.
+        Solid,
+
+        /// Blink the colon.
+        Blink,
+
+        /// Show the alternate blinking colon.
+        Alt,
+
+        /// Keep the colon solid and march a pixel along the bottom row as the minute goes by.
+        Seconds,
+    }
+}
+
+/// Stand-ins for the display types the included modules use, copied from `src/display.rs`.
+///
+/// The display module drives the pins, so it can not be included. Keep these in step with it.
+mod display {
+    /// Display matrix module.
+    pub mod display_matrix {
+        /// The type of colon to use when showing the time.
+        pub enum TimeColon {
+            /// Display a full colon.
+            Full,
+
+            /// Display nothing.
+            Empty,
+
+            /// Display top half of a colon.
+            Top,
+
+            /// Display bottom half of a colon.
+            Bottom,
+        }
+
+        /// The display, for the size constants only.
+        pub struct DisplayMatrix;
+
+        impl DisplayMatrix {
+            /// The first column after the icons.
+            pub const DISPLAY_OFFSET: usize = 2;
+
+            /// The last column that can be rendered.
+            pub const LAST_INDEX: usize = 24;
+        }
+    }
+}
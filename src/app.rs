@@ -1,17 +1,42 @@
-use embassy_executor::Spawner;
+use core::{
+    cell::Cell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use defmt::info;
+use embassy_executor::{SpawnToken, Spawner};
+use embassy_futures::select::{select, select3, Either, Either3};
 use embassy_futures::select::{
-    select4, Either4::First, Either4::Fourth, Either4::Second, Either4::Third,
+    select4, Either4, Either4::First, Either4::Fourth, Either4::Second, Either4::Third,
 };
-use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, signal::Signal};
+use embassy_sync::{
+    blocking_mutex::{
+        raw::{CriticalSectionRawMutex, ThreadModeRawMutex},
+        Mutex,
+    },
+    signal::Signal,
+};
+use embassy_time::{Duration, Instant, Timer};
 
 use crate::{
-    buttons::{ButtonPress, BUTTON_ONE_PRESS, BUTTON_THREE_PRESS, BUTTON_TWO_PRESS},
+    buttons::{ButtonCombo, ButtonPress, BUTTON_ONE_PRESS, BUTTON_THREE_PRESS, BUTTON_TWO_PRESS},
     clock::ClockApp,
-    config::{self},
-    display::display_matrix::DISPLAY_MATRIX,
-    pomodoro::PomodoroApp,
+    config,
+    dice::DiceApp,
+    display::{backlight, display_matrix::DISPLAY_MATRIX},
+    event_log::{self, Event},
+    graph::GraphApp,
+    instrumentation,
+    interval::IntervalApp,
+    life::LifeApp,
+    notification::{self, Notification},
+    pomodoro::{self, PomodoroApp},
+    prompt,
+    reaction::ReactionApp,
+    rtc,
+    sequence::SequenceApp,
     settings::SettingsApp,
-    stopwatch::StopwatchApp,
+    stopwatch::{self, StopwatchApp},
 };
 
 /// Named struct for stopping app spawned tasks.
@@ -24,6 +49,153 @@ pub struct ShowAppSwitcher;
 /// Static signal channel for when a task decides to show the app switcher.
 pub static SHOW_APP_SWITCHER: Signal<ThreadModeRawMutex, ShowAppSwitcher> = Signal::new();
 
+/// Named struct for the last app task exiting.
+struct AppTasksStopped;
+
+/// Static signal channel for when the last running app task exits.
+static APP_TASKS_STOPPED: Signal<ThreadModeRawMutex, AppTasksStopped> = Signal::new();
+
+/// The number of app tasks that have been spawned and not exited yet.
+static RUNNING_APP_TASKS: Mutex<CriticalSectionRawMutex, Cell<u8>> = Mutex::new(Cell::new(0));
+
+/// How long to wait for the app tasks to exit after stopping an app, in milliseconds.
+const STOP_WAIT_MS: u64 = 1000;
+
+/// How long to show the lost time warning for at boot, long enough for it to scroll across the display.
+const TIME_LOST_WARNING_SECS: u64 = 18;
+
+/// How long to keep trying to spawn an app task while the last run of it is still stopping, in milliseconds.
+const SPAWN_WAIT_MS: u64 = 2000;
+
+/// How often to try spawning an app task again while waiting for the last run of it to stop, in milliseconds.
+const SPAWN_RETRY_MS: u64 = 50;
+
+/// Whether the keypad is locked. All presses are ignored except the unlock combo.
+static KEYPAD_LOCKED: AtomicBool = AtomicBool::new(false);
+
+/// Get whether the keypad is locked.
+pub fn is_keypad_locked() -> bool {
+    KEYPAD_LOCKED.load(Ordering::Relaxed)
+}
+
+/// Lock or unlock the keypad.
+///
+/// Whilst locked, the app controller will ignore every button press except the unlock combo.
+pub fn set_keypad_locked(state: bool) {
+    KEYPAD_LOCKED.store(state, Ordering::Relaxed);
+}
+
+/// Spawn an app task, waiting for the last run of it to exit first.
+///
+/// Each app task has a pool of one, so spawning fails while the last run is still stopping, such as after a quick app switch.
+/// If the task can still not be spawned after [SPAWN_WAIT_MS], "TASK ERR" is shown rather than panicking.
+pub async fn spawn_app_task<S>(spawner: Spawner, name: &str, task: impl Fn() -> SpawnToken<S>) {
+    let give_up_at = Instant::now() + Duration::from_millis(SPAWN_WAIT_MS);
+
+    loop {
+        match spawner.spawn(task()) {
+            Ok(_) => {
+                RUNNING_APP_TASKS.lock(|count| count.set(count.get() + 1));
+                return;
+            }
+            Err(_) if Instant::now() < give_up_at => {
+                Timer::after(Duration::from_millis(SPAWN_RETRY_MS)).await
+            }
+            Err(_) => {
+                info!("Failed to spawn app task {}", name);
+                notification::notify(Notification::new("TASK ERR", None).urgent());
+                return;
+            }
+        }
+    }
+}
+
+/// Counts an app task as running until it is dropped.
+///
+/// Every task spawned with [spawn_app_task] must create one as it starts and hold it until it returns, so the app controller knows when it has exited.
+pub struct RunningAppTask {
+    /// Stops the guard being created without [new](RunningAppTask::new).
+    _private: (),
+}
+
+impl RunningAppTask {
+    /// Create the guard for the app task that has just started.
+    pub fn new() -> Self {
+        Self { _private: () }
+    }
+}
+
+impl Drop for RunningAppTask {
+    fn drop(&mut self) {
+        let count = RUNNING_APP_TASKS.lock(|count| {
+            count.set(count.get().saturating_sub(1));
+            count.get()
+        });
+
+        if count == 0 {
+            APP_TASKS_STOPPED.signal(AppTasksStopped);
+        }
+    }
+}
+
+/// Wait for every app task to exit, such as after an app has been told to stop.
+///
+/// Gives up after [STOP_WAIT_MS], so a task that never stops can not hold up the app controller.
+async fn wait_for_app_tasks() {
+    let give_up_at = Instant::now() + Duration::from_millis(STOP_WAIT_MS);
+
+    while RUNNING_APP_TASKS.lock(|count| count.get()) > 0 {
+        if let Either::Second(_) = select(APP_TASKS_STOPPED.wait(), Timer::at(give_up_at)).await {
+            info!("App tasks did not stop in time");
+            return;
+        }
+    }
+}
+
+/// Answer the question being asked. This is the one place presses are taken as an answer.
+///
+/// The top button answers yes and the bottom button no. Other presses are dropped until it is answered or cancelled.
+/// The first press while the display is asleep only wakes it, as the question can not be seen.
+async fn answer_question() {
+    let answer = async {
+        loop {
+            let press = select3(
+                BUTTON_ONE_PRESS.recv(),
+                BUTTON_TWO_PRESS.recv(),
+                BUTTON_THREE_PRESS.recv(),
+            )
+            .await;
+
+            if backlight::is_display_asleep() {
+                backlight::wake_display();
+                continue;
+            }
+
+            match press {
+                Either3::First(ButtonPress::Short) => {
+                    prompt::answer(true);
+                    return;
+                }
+                Either3::Third(ButtonPress::Short) => {
+                    prompt::answer(false);
+                    return;
+                }
+                _ => {}
+            }
+        }
+    };
+
+    select(answer, prompt::wait_for_close()).await;
+}
+
+/// Answer each question asked while the main loop is held up, such as by an app confirming a change as it saves.
+async fn answer_questions() -> ! {
+    loop {
+        prompt::wait_for_question().await;
+        answer_question().await;
+    }
+}
+
 /// Common trait that all "Apps" should implement.
 pub trait App {
     /// The name of the app for use in the app picker.
@@ -57,6 +229,24 @@ enum Apps {
     /// The stopwatch app.
     Stopwatch,
 
+    /// The reaction app.
+    Reaction,
+
+    /// The dice app.
+    Dice,
+
+    /// The interval app.
+    Interval,
+
+    /// The sequence app.
+    Sequence,
+
+    /// The life app.
+    Life,
+
+    /// The graph app.
+    Graph,
+
     /// The settings app.
     Settings,
 }
@@ -74,6 +264,9 @@ pub struct AppController {
     /// Showing the app picker or not.
     showing_app_picker: bool,
 
+    /// Whether the life app was started as the screensaver, so any press goes back to the clock.
+    showing_screensaver: bool,
+
     /// Clock app.
     clock_app: ClockApp,
 
@@ -83,6 +276,24 @@ pub struct AppController {
     /// Stopwatch app.
     stopwatch_app: StopwatchApp,
 
+    /// Reaction app.
+    reaction_app: ReactionApp,
+
+    /// Dice app.
+    dice_app: DiceApp,
+
+    /// Interval app.
+    interval_app: IntervalApp,
+
+    /// Sequence app.
+    sequence_app: SequenceApp,
+
+    /// Life app.
+    life_app: LifeApp,
+
+    /// Graph app.
+    graph_app: GraphApp,
+
     /// Settings app.
     settings_app: SettingsApp,
 
@@ -97,14 +308,27 @@ impl AppController {
         clock_app: ClockApp,
         pomodoro_app: PomodoroApp,
         stopwatch_app: StopwatchApp,
+        reaction_app: ReactionApp,
+        dice_app: DiceApp,
+        interval_app: IntervalApp,
+        life_app: LifeApp,
+        graph_app: GraphApp,
+        sequence_app: SequenceApp,
         settings_app: SettingsApp,
     ) -> Self {
         Self {
             active_app: Apps::Clock,
             showing_app_picker: false,
+            showing_screensaver: false,
             clock_app,
             pomodoro_app,
             stopwatch_app,
+            reaction_app,
+            dice_app,
+            interval_app,
+            life_app,
+            graph_app,
+            sequence_app,
             settings_app,
             spawner,
         }
@@ -112,22 +336,102 @@ impl AppController {
 
     /// The main program loop.
     pub async fn run_forever(&mut self) -> ! {
-        self.app_selected().await;
+        // carry on with a timer that was running before a power cut
+        let pomodoro_running = pomodoro::restore().await;
+        let stopwatch_running = stopwatch::restore().await;
+
+        if rtc::check_date().await {
+            self.set_lost_time().await;
+        } else {
+            if pomodoro_running {
+                self.active_app = Apps::Pomodoro;
+            } else if stopwatch_running {
+                self.active_app = Apps::Stopwatch;
+            }
+
+            self.app_selected().await;
+        }
 
         loop {
-            let t = select4(
+            // presses made while the last one was handled wait in their queues, so each is taken in turn
+            let presses = select4(
                 SHOW_APP_SWITCHER.wait(),
-                BUTTON_ONE_PRESS.wait(),
-                BUTTON_TWO_PRESS.wait(),
-                BUTTON_THREE_PRESS.wait(),
-            )
-            .await;
+                BUTTON_ONE_PRESS.recv(),
+                BUTTON_TWO_PRESS.recv(),
+                BUTTON_THREE_PRESS.recv(),
+            );
+
+            // a question asked by an app task takes the presses until it is answered
+            let presses = async {
+                match select(prompt::wait_for_question(), presses).await {
+                    Either::First(_) => None,
+                    Either::Second(t) => Some(t),
+                }
+            };
+
+            let t = match self.screensaver_wait().await {
+                Some(wait) => match select(presses, Timer::after(wait)).await {
+                    Either::First(t) => t,
+                    Either::Second(_) => {
+                        self.start_screensaver().await;
+                        continue;
+                    }
+                },
+                None => presses.await,
+            };
+
+            let t = match t {
+                Some(t) => t,
+                None => {
+                    answer_question().await;
+                    continue;
+                }
+            };
 
-            match t {
-                First(_) => self.show_app_picker().await,
-                Second(press) => self.button_one_press(press).await,
-                Third(press) => self.button_two_press(press).await,
-                Fourth(press) => self.button_three_press(press).await,
+            // any press leaves the screensaver, without being passed on to the clock
+            if self.showing_screensaver && !matches!(t, First(_)) {
+                self.go_to_clock().await;
+                continue;
+            }
+
+            // the loop is held up until the press is handled, so answer any question asked while handling it
+            select(self.handle_press(t), answer_questions()).await;
+        }
+    }
+
+    /// Route a press, or the app switcher being shown, from the main loop.
+    async fn handle_press(
+        &mut self,
+        t: Either4<ShowAppSwitcher, ButtonPress, ButtonPress, ButtonPress>,
+    ) {
+        match t {
+            First(_) if is_keypad_locked() => self.go_to_clock().await,
+            First(_) => self.show_app_picker().await,
+            // the first press in night mode only wakes the display
+            _ if backlight::is_display_asleep() => backlight::wake_display(),
+            // the top button pauses long text while it scrolls, so it can be read, unless the keypad is locked
+            Second(ButtonPress::Short) if DISPLAY_MATRIX.is_scrolling() && !is_keypad_locked() => {
+                DISPLAY_MATRIX.toggle_scroll_pause()
+            }
+            Second(ButtonPress::Combo(combo))
+            | Third(ButtonPress::Combo(combo))
+            | Fourth(ButtonPress::Combo(combo)) => self.combo_press(combo).await,
+            Second(ButtonPress::Triple) => self.toggle_lock().await,
+            _ if is_keypad_locked() => {
+                DISPLAY_MATRIX.queue_text("Locked", 0, true, true).await;
+            }
+            Third(ButtonPress::Triple) => self.save_config().await,
+            Second(press) => {
+                let name = self.active_app_name();
+                instrumentation::measure(name, self.button_one_press(press)).await
+            }
+            Third(press) => {
+                let name = self.active_app_name();
+                instrumentation::measure(name, self.button_two_press(press)).await
+            }
+            Fourth(press) => {
+                let name = self.active_app_name();
+                instrumentation::measure(name, self.button_three_press(press)).await
             }
         }
     }
@@ -149,17 +453,40 @@ impl AppController {
                                 .button_one_short_press(self.spawner)
                                 .await
                         }
+                        Apps::Reaction => {
+                            self.reaction_app.button_one_short_press(self.spawner).await
+                        }
+                        Apps::Dice => self.dice_app.button_one_short_press(self.spawner).await,
+                        Apps::Interval => {
+                            self.interval_app.button_one_short_press(self.spawner).await
+                        }
+                        Apps::Life => self.life_app.button_one_short_press(self.spawner).await,
+                        Apps::Graph => self.graph_app.button_one_short_press(self.spawner).await,
+                        Apps::Sequence => {
+                            self.sequence_app.button_one_short_press(self.spawner).await
+                        }
                         Apps::Settings => {
                             self.settings_app.button_one_short_press(self.spawner).await
                         }
                     }
                 }
             }
-            ButtonPress::Long => self.show_app_picker().await,
+            ButtonPress::Long => {
+                // settings uses a long press to go back, until it is back at its menu
+                let went_back = !self.showing_app_picker
+                    && self.active_app == Apps::Settings
+                    && self.settings_app.go_back().await;
+
+                if !went_back {
+                    self.show_app_picker().await;
+                }
+            }
             ButtonPress::Double => {
                 let state = config::toggle_autolight().await;
                 DISPLAY_MATRIX.show_autolight_icon(state);
             }
+            ButtonPress::Triple => self.toggle_lock().await,
+            ButtonPress::Combo(combo) => self.combo_press(combo).await,
         };
     }
 
@@ -182,6 +509,24 @@ impl AppController {
                     .button_two_press(press, self.spawner)
                     .await
             }
+            Apps::Reaction => {
+                self.reaction_app
+                    .button_two_press(press, self.spawner)
+                    .await
+            }
+            Apps::Dice => self.dice_app.button_two_press(press, self.spawner).await,
+            Apps::Interval => {
+                self.interval_app
+                    .button_two_press(press, self.spawner)
+                    .await
+            }
+            Apps::Life => self.life_app.button_two_press(press, self.spawner).await,
+            Apps::Graph => self.graph_app.button_two_press(press, self.spawner).await,
+            Apps::Sequence => {
+                self.sequence_app
+                    .button_two_press(press, self.spawner)
+                    .await
+            }
             Apps::Settings => {
                 self.settings_app
                     .button_two_press(press, self.spawner)
@@ -209,6 +554,24 @@ impl AppController {
                     .button_three_press(press, self.spawner)
                     .await
             }
+            Apps::Reaction => {
+                self.reaction_app
+                    .button_three_press(press, self.spawner)
+                    .await
+            }
+            Apps::Dice => self.dice_app.button_three_press(press, self.spawner).await,
+            Apps::Interval => {
+                self.interval_app
+                    .button_three_press(press, self.spawner)
+                    .await
+            }
+            Apps::Life => self.life_app.button_three_press(press, self.spawner).await,
+            Apps::Graph => self.graph_app.button_three_press(press, self.spawner).await,
+            Apps::Sequence => {
+                self.sequence_app
+                    .button_three_press(press, self.spawner)
+                    .await
+            }
             Apps::Settings => {
                 self.settings_app
                     .button_three_press(press, self.spawner)
@@ -217,17 +580,130 @@ impl AppController {
         };
     }
 
-    /// Show the app picker. Must stop the active app first to allow it to clean up.
-    async fn show_app_picker(&mut self) {
-        self.showing_app_picker = true;
+    /// Handle a combination of buttons being held down together.
+    ///
+    /// - Top and bottom will go straight to the clock app.
+    /// - Top and middle will exit the settings app without saving.
+    /// - Middle and bottom will lock or unlock the keypad.
+    async fn combo_press(&mut self, combo: ButtonCombo) {
+        match combo {
+            ButtonCombo::TopBottom if !is_keypad_locked() => self.go_to_clock().await,
+            ButtonCombo::TopMiddle
+                if !is_keypad_locked()
+                    && !self.showing_app_picker
+                    && self.active_app == Apps::Settings =>
+            {
+                self.settings_app.exit_without_saving().await
+            }
+            ButtonCombo::MiddleBottom => self.toggle_lock().await,
+            _ => {}
+        }
+    }
+
+    /// Warn that the time has been lost, likely from a flat RTC battery, and go straight to setting the time.
+    ///
+    /// The warning can be skipped with the top button.
+    async fn set_lost_time(&mut self) {
+        DISPLAY_MATRIX
+            .queue_text("SET TIME - BATTERY?", 0, true, true)
+            .await;
+
+        select(
+            Timer::after(Duration::from_secs(TIME_LOST_WARNING_SECS)),
+            BUTTON_ONE_PRESS.recv(),
+        )
+        .await;
+
+        self.active_app = Apps::Settings;
+        self.settings_app.start_time_setting(self.spawner).await;
+    }
+
+    /// Lock or unlock the keypad.
+    async fn toggle_lock(&mut self) {
+        let locked = !is_keypad_locked();
+        set_keypad_locked(locked);
+
+        let text = if locked { "Locked" } else { "Unlocked" };
+        notification::notify(Notification::new(text, None).urgent());
+    }
+
+    /// Force the config to be written to flash now.
+    async fn save_config(&mut self) {
+        config::save().await;
+        notification::notify(Notification::new("Saved", None).urgent());
+    }
+
+    /// Get how long the clock has to be left alone for before the screensaver starts.
+    ///
+    /// Returns [None](Option::None) if the screensaver is off, or the clock app is not showing.
+    async fn screensaver_wait(&self) -> Option<Duration> {
+        if self.showing_app_picker || self.active_app != Apps::Clock {
+            return None;
+        }
 
+        match config::get_screensaver().await {
+            0 => None,
+            mins => Some(Duration::from_secs(mins as u64 * 60)),
+        }
+    }
+
+    /// Stop the clock app and start the life app as the screensaver.
+    async fn start_screensaver(&mut self) {
+        self.stop_active_app().await;
+
+        self.active_app = Apps::Life;
+        self.app_selected().await;
+
+        self.showing_screensaver = true;
+    }
+
+    /// Go straight to the clock app, from any app or the app picker.
+    async fn go_to_clock(&mut self) {
+        if !self.showing_app_picker {
+            if self.active_app == Apps::Clock {
+                return;
+            }
+
+            self.stop_active_app().await;
+        }
+
+        critical_section::with(|cs| {
+            DISPLAY_MATRIX.clear_all(cs, true);
+        });
+
+        self.active_app = Apps::Clock;
+        self.app_selected().await;
+    }
+
+    /// Stop the active app to allow it to clean up.
+    async fn stop_active_app(&mut self) {
         match self.active_app {
             Apps::Clock => self.clock_app.stop().await,
             Apps::Pomodoro => self.pomodoro_app.stop().await,
             Apps::Stopwatch => self.stopwatch_app.stop().await,
+            Apps::Reaction => self.reaction_app.stop().await,
+            Apps::Dice => self.dice_app.stop().await,
+            Apps::Interval => self.interval_app.stop().await,
+            Apps::Life => self.life_app.stop().await,
+            Apps::Graph => self.graph_app.stop().await,
+            Apps::Sequence => self.sequence_app.stop().await,
             Apps::Settings => self.settings_app.stop().await,
         }
 
+        // the tasks only see the stop message once they are next polled, so wait for them before another app spawns its own
+        wait_for_app_tasks().await;
+
+        // don't leave changes made by the app waiting to be written
+        config::flush().await;
+    }
+
+    /// Show the app picker. Must stop the active app first to allow it to clean up.
+    async fn show_app_picker(&mut self) {
+        self.showing_app_picker = true;
+        self.showing_screensaver = false;
+
+        self.stop_active_app().await;
+
         critical_section::with(|cs| {
             DISPLAY_MATRIX.clear_all(cs, true);
         });
@@ -253,6 +729,48 @@ impl AppController {
                 self.active_app = Apps::Stopwatch;
             }
             Apps::Stopwatch => {
+                DISPLAY_MATRIX
+                    .queue_text(self.interval_app.get_name(), 1000, true, false)
+                    .await;
+
+                self.active_app = Apps::Interval;
+            }
+            Apps::Interval => {
+                DISPLAY_MATRIX
+                    .queue_text(self.sequence_app.get_name(), 1000, true, false)
+                    .await;
+
+                self.active_app = Apps::Sequence;
+            }
+            Apps::Sequence => {
+                DISPLAY_MATRIX
+                    .queue_text(self.reaction_app.get_name(), 1000, true, false)
+                    .await;
+
+                self.active_app = Apps::Reaction;
+            }
+            Apps::Reaction => {
+                DISPLAY_MATRIX
+                    .queue_text(self.dice_app.get_name(), 1000, true, false)
+                    .await;
+
+                self.active_app = Apps::Dice;
+            }
+            Apps::Dice => {
+                DISPLAY_MATRIX
+                    .queue_text(self.life_app.get_name(), 1000, true, false)
+                    .await;
+
+                self.active_app = Apps::Life;
+            }
+            Apps::Life => {
+                DISPLAY_MATRIX
+                    .queue_text(self.graph_app.get_name(), 1000, true, false)
+                    .await;
+
+                self.active_app = Apps::Graph;
+            }
+            Apps::Graph => {
                 DISPLAY_MATRIX
                     .queue_text(self.settings_app.get_name(), 1000, true, false)
                     .await;
@@ -294,6 +812,48 @@ impl AppController {
                 self.active_app = Apps::Pomodoro;
             }
             Apps::Settings => {
+                DISPLAY_MATRIX
+                    .queue_text(self.graph_app.get_name(), 1000, true, false)
+                    .await;
+
+                self.active_app = Apps::Graph;
+            }
+            Apps::Graph => {
+                DISPLAY_MATRIX
+                    .queue_text(self.life_app.get_name(), 1000, true, false)
+                    .await;
+
+                self.active_app = Apps::Life;
+            }
+            Apps::Life => {
+                DISPLAY_MATRIX
+                    .queue_text(self.dice_app.get_name(), 1000, true, false)
+                    .await;
+
+                self.active_app = Apps::Dice;
+            }
+            Apps::Dice => {
+                DISPLAY_MATRIX
+                    .queue_text(self.reaction_app.get_name(), 1000, true, false)
+                    .await;
+
+                self.active_app = Apps::Reaction;
+            }
+            Apps::Reaction => {
+                DISPLAY_MATRIX
+                    .queue_text(self.sequence_app.get_name(), 1000, true, false)
+                    .await;
+
+                self.active_app = Apps::Sequence;
+            }
+            Apps::Sequence => {
+                DISPLAY_MATRIX
+                    .queue_text(self.interval_app.get_name(), 1000, true, false)
+                    .await;
+
+                self.active_app = Apps::Interval;
+            }
+            Apps::Interval => {
                 DISPLAY_MATRIX
                     .queue_text(self.stopwatch_app.get_name(), 1000, true, false)
                     .await;
@@ -303,14 +863,39 @@ impl AppController {
         }
     }
 
+    /// Get the name of the active app, for logging.
+    fn active_app_name(&self) -> &'static str {
+        match self.active_app {
+            Apps::Clock => "Clock",
+            Apps::Pomodoro => "Pomodoro",
+            Apps::Stopwatch => "Stopwatch",
+            Apps::Reaction => "React",
+            Apps::Dice => "Dice",
+            Apps::Interval => "Interval",
+            Apps::Life => "Life",
+            Apps::Graph => "Graph",
+            Apps::Sequence => "Recipe",
+            Apps::Settings => "Settings",
+        }
+    }
+
     /// Dismiss the app picker and start the active app.
     async fn app_selected(&mut self) {
         self.showing_app_picker = false;
+        self.showing_screensaver = false;
+
+        event_log::record(Event::AppStarted(self.active_app_name()));
 
         match self.active_app {
             Apps::Clock => self.clock_app.start(self.spawner).await,
             Apps::Pomodoro => self.pomodoro_app.start(self.spawner).await,
             Apps::Stopwatch => self.stopwatch_app.start(self.spawner).await,
+            Apps::Reaction => self.reaction_app.start(self.spawner).await,
+            Apps::Dice => self.dice_app.start(self.spawner).await,
+            Apps::Interval => self.interval_app.start(self.spawner).await,
+            Apps::Life => self.life_app.start(self.spawner).await,
+            Apps::Graph => self.graph_app.start(self.spawner).await,
+            Apps::Sequence => self.sequence_app.start(self.spawner).await,
             Apps::Settings => self.settings_app.start(self.spawner).await,
         }
     }
@@ -0,0 +1,254 @@
+use core::cell::RefCell;
+
+use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, mutex::Mutex};
+
+/// The last pressure in hectopascals read from the BME280.
+///
+/// Will always be None if the sensor is not fitted or the firmware is built without the bme280 feature.
+static LAST_PRESSURE: Mutex<ThreadModeRawMutex, RefCell<Option<f32>>> =
+    Mutex::new(RefCell::new(None));
+
+/// Get the last pressure in hectopascals from the BME280, if there is one.
+pub async fn get_pressure() -> Option<f32> {
+    *LAST_PRESSURE.lock().await.borrow()
+}
+
+/// Set the last pressure read from the BME280.
+#[cfg(feature = "bme280")]
+async fn set_pressure(pressure: Option<f32>) {
+    LAST_PRESSURE.lock().await.replace(pressure);
+}
+
+/// Driver for the BME280 temperature, humidity and pressure sensor on the shared I2C bus.
+#[cfg(feature = "bme280")]
+pub mod driver {
+    use defmt::info;
+    use embassy_rp::i2c;
+    use embassy_time::{Duration, Timer};
+    use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+    use crate::{
+        humidity::{self, Reading},
+        i2c_bus::SharedI2c,
+    };
+
+    /// The I2C address of the sensor, with the SDO pin pulled low.
+    const ADDRESS: u8 = 0x76;
+
+    /// The value of the chip id register for a BME280.
+    const CHIP_ID: u8 = 0x60;
+
+    /// The chip id register.
+    const REG_CHIP_ID: u8 = 0xD0;
+
+    /// The first block of calibration registers.
+    const REG_CALIB_00: u8 = 0x88;
+
+    /// The second block of calibration registers.
+    const REG_CALIB_26: u8 = 0xE1;
+
+    /// The humidity oversampling control register.
+    const REG_CTRL_HUM: u8 = 0xF2;
+
+    /// The temperature and pressure oversampling and mode control register.
+    const REG_CTRL_MEAS: u8 = 0xF4;
+
+    /// The standby time and filter config register.
+    const REG_CONFIG: u8 = 0xF5;
+
+    /// The first of the pressure, temperature and humidity data registers.
+    const REG_DATA: u8 = 0xF7;
+
+    /// How often to read the sensor.
+    const READ_INTERVAL_SECS: u64 = 30;
+
+    /// How often to look for the sensor again when it is not found.
+    const RETRY_INTERVAL_SECS: u64 = 60;
+
+    /// Errors that can happen when talking to the sensor.
+    #[derive(defmt::Format)]
+    enum Error {
+        /// Nothing answered on the bus, or the transfer failed.
+        Bus,
+
+        /// Something answered on the bus that is not a BME280.
+        WrongChip,
+    }
+
+    impl From<i2c::Error> for Error {
+        fn from(_: i2c::Error) -> Self {
+            Error::Bus
+        }
+    }
+
+    /// Factory calibration values read from the sensor, used to compensate the raw readings.
+    struct Calibration {
+        /// Temperature calibration 1.
+        t1: f32,
+        /// Temperature calibration 2.
+        t2: f32,
+        /// Temperature calibration 3.
+        t3: f32,
+        /// Pressure calibration 1.
+        p1: f32,
+        /// Pressure calibration 2.
+        p2: f32,
+        /// Pressure calibration 3.
+        p3: f32,
+        /// Pressure calibration 4.
+        p4: f32,
+        /// Pressure calibration 5.
+        p5: f32,
+        /// Pressure calibration 6.
+        p6: f32,
+        /// Pressure calibration 7.
+        p7: f32,
+        /// Pressure calibration 8.
+        p8: f32,
+        /// Pressure calibration 9.
+        p9: f32,
+        /// Humidity calibration 1.
+        h1: f32,
+        /// Humidity calibration 2.
+        h2: f32,
+        /// Humidity calibration 3.
+        h3: f32,
+        /// Humidity calibration 4.
+        h4: f32,
+        /// Humidity calibration 5.
+        h5: f32,
+        /// Humidity calibration 6.
+        h6: f32,
+    }
+
+    /// Look for the sensor and read it on a loop, updating the last reading.
+    ///
+    /// If the sensor is not fitted or stops answering, readings are cleared so the clock falls back to the RTC temperature.
+    #[embassy_executor::task]
+    pub async fn bme280_task() {
+        let mut bus = SharedI2c::new();
+
+        loop {
+            match setup(&mut bus) {
+                Ok(calibration) => loop {
+                    match read(&mut bus, &calibration) {
+                        Ok((reading, pressure)) => {
+                            humidity::set_reading(Some(reading)).await;
+                            super::set_pressure(Some(pressure)).await;
+                        }
+                        Err(e) => {
+                            info!("BME280 read failed: {}", e);
+                            break;
+                        }
+                    }
+
+                    Timer::after(Duration::from_secs(READ_INTERVAL_SECS)).await;
+                },
+                Err(e) => info!("BME280 not found: {}", e),
+            }
+
+            humidity::set_reading(None).await;
+            super::set_pressure(None).await;
+
+            Timer::after(Duration::from_secs(RETRY_INTERVAL_SECS)).await;
+        }
+    }
+
+    /// Check the sensor is there, read the calibration and start it measuring.
+    fn setup(bus: &mut SharedI2c) -> Result<Calibration, Error> {
+        let mut id = [0u8; 1];
+        bus.write_read(ADDRESS, &[REG_CHIP_ID], &mut id)?;
+        if id[0] != CHIP_ID {
+            return Err(Error::WrongChip);
+        }
+
+        let mut a = [0u8; 26];
+        bus.write_read(ADDRESS, &[REG_CALIB_00], &mut a)?;
+        let mut b = [0u8; 7];
+        bus.write_read(ADDRESS, &[REG_CALIB_26], &mut b)?;
+
+        let u16_at = |i: usize| u16::from_le_bytes([a[i], a[i + 1]]) as f32;
+        let i16_at = |i: usize| i16::from_le_bytes([a[i], a[i + 1]]) as f32;
+
+        let calibration = Calibration {
+            t1: u16_at(0),
+            t2: i16_at(2),
+            t3: i16_at(4),
+            p1: u16_at(6),
+            p2: i16_at(8),
+            p3: i16_at(10),
+            p4: i16_at(12),
+            p5: i16_at(14),
+            p6: i16_at(16),
+            p7: i16_at(18),
+            p8: i16_at(20),
+            p9: i16_at(22),
+            h1: a[25] as f32,
+            h2: i16::from_le_bytes([b[0], b[1]]) as f32,
+            h3: b[2] as f32,
+            // h4 and h5 are 12 bit values that share a byte
+            h4: (((b[3] as i8 as i16) << 4) | (b[4] & 0x0F) as i16) as f32,
+            h5: (((b[5] as i8 as i16) << 4) | (b[4] >> 4) as i16) as f32,
+            h6: b[6] as i8 as f32,
+        };
+
+        // x1 oversampling for everything, normal mode with 1 second standby
+        bus.write(ADDRESS, &[REG_CTRL_HUM, 0x01])?;
+        bus.write(ADDRESS, &[REG_CONFIG, 0xA0])?;
+        bus.write(ADDRESS, &[REG_CTRL_MEAS, 0x27])?;
+
+        Ok(calibration)
+    }
+
+    /// Read and compensate the temperature, humidity and pressure in hectopascals.
+    ///
+    /// Uses the floating point compensation formulas from the BME280 datasheet.
+    fn read(bus: &mut SharedI2c, cal: &Calibration) -> Result<(Reading, f32), Error> {
+        let mut data = [0u8; 8];
+        bus.write_read(ADDRESS, &[REG_DATA], &mut data)?;
+
+        let adc_p = ((data[0] as u32) << 12 | (data[1] as u32) << 4 | (data[2] as u32) >> 4) as f32;
+        let adc_t = ((data[3] as u32) << 12 | (data[4] as u32) << 4 | (data[5] as u32) >> 4) as f32;
+        let adc_h = ((data[6] as u32) << 8 | data[7] as u32) as f32;
+
+        // temperature
+        let var1 = (adc_t / 16384.0 - cal.t1 / 1024.0) * cal.t2;
+        let var2 =
+            (adc_t / 131072.0 - cal.t1 / 8192.0) * (adc_t / 131072.0 - cal.t1 / 8192.0) * cal.t3;
+        let t_fine = var1 + var2;
+        let temperature = t_fine / 5120.0;
+
+        // pressure
+        let mut var1 = t_fine / 2.0 - 64000.0;
+        let mut var2 = var1 * var1 * cal.p6 / 32768.0;
+        var2 += var1 * cal.p5 * 2.0;
+        var2 = var2 / 4.0 + cal.p4 * 65536.0;
+        var1 = (cal.p3 * var1 * var1 / 524288.0 + cal.p2 * var1) / 524288.0;
+        var1 = (1.0 + var1 / 32768.0) * cal.p1;
+        let pressure = if var1 == 0.0 {
+            0.0
+        } else {
+            let mut p = 1048576.0 - adc_p;
+            p = (p - var2 / 4096.0) * 6250.0 / var1;
+            let var1 = cal.p9 * p * p / 2147483648.0;
+            let var2 = p * cal.p8 / 32768.0;
+            p + (var1 + var2 + cal.p7) / 16.0
+        };
+
+        // humidity
+        let mut h = t_fine - 76800.0;
+        h = (adc_h - (cal.h4 * 64.0 + cal.h5 / 16384.0 * h))
+            * (cal.h2 / 65536.0
+                * (1.0 + cal.h6 / 67108864.0 * h * (1.0 + cal.h3 / 67108864.0 * h)));
+        h *= 1.0 - cal.h1 * h / 524288.0;
+        let humidity = h.clamp(0.0, 100.0);
+
+        Ok((
+            Reading {
+                temperature,
+                humidity,
+            },
+            pressure / 100.0,
+        ))
+    }
+}
@@ -0,0 +1,96 @@
+use embassy_rp::{
+    adc::Channel,
+    gpio::{AnyPin, Input, Output},
+};
+
+#[cfg(feature = "dht22")]
+use embassy_rp::gpio::Flex;
+
+use crate::{buttons::ButtonDriver, display::DisplayPins};
+
+/// The pins the clock uses, with each pin type erased so the drivers do not depend on which GPIO they are wired to.
+///
+/// Build with a board macro such as [pico_clock_green], so the wiring is only set in one place.
+pub struct BoardPins {
+    /// The display row select and shift register pins.
+    pub display: DisplayPins<'static>,
+
+    /// The display output enable pin, used to set the backlight.
+    pub oe: Output<'static, AnyPin>,
+
+    /// The light sensor ADC channel.
+    pub light_sensor: Channel<'static>,
+
+    /// The top button.
+    pub button_one: ButtonDriver<'static, AnyPin>,
+
+    /// The middle button.
+    pub button_two: ButtonDriver<'static, AnyPin>,
+
+    /// The bottom button.
+    pub button_three: ButtonDriver<'static, AnyPin>,
+
+    /// The speaker pin.
+    pub speaker: Output<'static, AnyPin>,
+
+    /// The RTC square wave pin.
+    pub sqw: Input<'static, AnyPin>,
+
+    /// The DHT22 humidity sensor pin.
+    #[cfg(feature = "dht22")]
+    pub dht22: Flex<'static, AnyPin>,
+
+    /// The radio time signal receiver pin.
+    #[cfg(any(feature = "dcf77", feature = "wwvb"))]
+    pub radio_clock: Input<'static, AnyPin>,
+}
+
+/// Take the pins for the Waveshare Pico Clock Green from the peripherals.
+///
+/// A macro rather than a function, so only the pins are moved out of the peripherals and the rest can still be used.
+/// Another board or wiring only needs another macro like this one.
+macro_rules! pico_clock_green {
+    ($p:ident) => {{
+        use embassy_rp::{
+            adc::Channel,
+            gpio::{Input, Level, Output, Pin, Pull},
+        };
+
+        use crate::{
+            board::BoardPins,
+            buttons::{Button, ButtonDriver},
+            display::DisplayPins,
+        };
+
+        BoardPins {
+            display: DisplayPins::new(
+                Output::new($p.PIN_16.degrade(), Level::Low),
+                Output::new($p.PIN_18.degrade(), Level::Low),
+                Output::new($p.PIN_22.degrade(), Level::Low),
+                Output::new($p.PIN_11.degrade(), Level::Low),
+                Output::new($p.PIN_10.degrade(), Level::Low),
+                Output::new($p.PIN_12.degrade(), Level::Low),
+            ),
+            oe: Output::new($p.PIN_13.degrade(), Level::Low),
+            light_sensor: Channel::new_pin($p.PIN_26, Pull::None),
+            button_one: ButtonDriver::new(Input::new($p.PIN_2.degrade(), Pull::Up), Button::Top),
+            button_two: ButtonDriver::new(
+                Input::new($p.PIN_17.degrade(), Pull::Up),
+                Button::Middle,
+            ),
+            button_three: ButtonDriver::new(
+                Input::new($p.PIN_15.degrade(), Pull::Up),
+                Button::Bottom,
+            ),
+            speaker: Output::new($p.PIN_14.degrade(), Level::Low),
+            // wired from the SQW header pin
+            sqw: Input::new($p.PIN_4.degrade(), Pull::Up),
+            #[cfg(feature = "dht22")]
+            dht22: embassy_rp::gpio::Flex::new($p.PIN_3.degrade()),
+            #[cfg(any(feature = "dcf77", feature = "wwvb"))]
+            radio_clock: Input::new($p.PIN_5.degrade(), Pull::None),
+        }
+    }};
+}
+
+pub(crate) use pico_clock_green;
@@ -1,10 +1,22 @@
-use defmt::info;
+use core::{
+    cell::Cell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use defmt::{debug, info};
 use embassy_futures::select::{select, Either};
-use embassy_rp::{gpio::Input, peripherals::*};
-use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, signal::Signal};
-use embassy_time::{Duration, Timer};
+use embassy_rp::gpio::{AnyPin, Input, Pin};
+use embassy_sync::{
+    blocking_mutex::{raw::ThreadModeRawMutex, Mutex},
+    channel::Channel,
+    signal::Signal,
+};
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::event_log::{self, Event};
 
 /// Type of button press made.
+#[derive(Clone, Copy, defmt::Format)]
 pub enum ButtonPress {
     /// When the button click duration is <=500ms.
     Short,
@@ -14,135 +26,408 @@ pub enum ButtonPress {
 
     /// When the button click duration is <=500ms and a second click happens in the next 300ms.
     Double,
+
+    /// When a double press is followed by a third click in the next 300ms.
+    Triple,
+
+    /// When two buttons are held down together for >500ms.
+    Combo(ButtonCombo),
 }
 
-/// Signal for when the top button has been pressed.
-pub static BUTTON_ONE_PRESS: Signal<ThreadModeRawMutex, ButtonPress> = Signal::new();
+/// Combination of buttons held down together.
+#[derive(Clone, Copy, PartialEq, defmt::Format)]
+pub enum ButtonCombo {
+    /// The top and middle buttons.
+    TopMiddle,
 
-/// Signal for when the middle button has been pressed.
-pub static BUTTON_TWO_PRESS: Signal<ThreadModeRawMutex, ButtonPress> = Signal::new();
+    /// The top and bottom buttons.
+    TopBottom,
 
-/// Signal for when the bottom button has been pressed.
-pub static BUTTON_THREE_PRESS: Signal<ThreadModeRawMutex, ButtonPress> = Signal::new();
+    /// The middle and bottom buttons.
+    MiddleBottom,
+}
 
-/// Wait for changes async on the top button being pressed.
-///
-/// Will inform signal of button press after the full press has been completed.
-/// The type of press is recorded in the ButtonPress enum.
+/// Index of the top button in the button state arrays.
+const TOP: usize = 0;
+
+/// Index of the middle button in the button state arrays.
+const MIDDLE: usize = 1;
+
+/// Index of the bottom button in the button state arrays.
+const BOTTOM: usize = 2;
+
+/// Whether each button is held down and the type of press has not been determined yet.
+static BUTTON_HELD: [AtomicBool; 3] = [
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+];
+
+/// Whether each button is part of a combo that has already been signalled by another button.
+static BUTTON_IN_COMBO: [AtomicBool; 3] = [
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+];
+
+/// How long a button has to be held down to be a long press, in milliseconds.
+const LONG_PRESS_MS: u64 = 500;
+
+/// How long after release another press counts as a double or triple press, in milliseconds.
+const DOUBLE_PRESS_MS: u64 = 300;
+
+/// How long the button level must be stable after an edge to not be a bounce, in milliseconds.
+const DEBOUNCE_MS: u64 = 20;
+
+/// How long a button has to be held down before short presses start repeating, in milliseconds.
+const REPEAT_START_MS: u64 = 700;
+
+/// How long a button has to be held down before the repeating speeds up, in milliseconds.
+const REPEAT_FAST_MS: u64 = 2000;
+
+/// The interval between repeated short presses, in milliseconds.
+const REPEAT_INTERVAL_MS: u64 = 150;
+
+/// The interval between repeated short presses once sped up, in milliseconds.
+const REPEAT_FAST_INTERVAL_MS: u64 = 50;
+
+/// Whether holding the middle or bottom button should repeat short presses instead of a long press.
+static AUTO_REPEAT: AtomicBool = AtomicBool::new(false);
+
+/// How many presses of each button can wait to be handled, such as while the app controller waits on the display.
 ///
-/// This task has no way of cancellation.
-#[embassy_executor::task]
-pub async fn button_one_task(mut button: Input<'static, PIN_2>) -> ! {
-    loop {
-        // sit here until button is pressed down
-        button.wait_for_low().await;
+/// Presses from holding a button down are coalesced, so this only needs to cover presses made by hand.
+const PRESS_QUEUE_LEN: usize = 4;
 
-        let press = button_pressed(&mut button).await;
-        BUTTON_ONE_PRESS.signal(press);
+/// A queue of the presses of one button waiting to be handled.
+pub struct PressQueue {
+    /// The presses waiting to be handled.
+    presses: Channel<ThreadModeRawMutex, ButtonPress, PRESS_QUEUE_LEN>,
 
-        // wait for button to be released
-        if button.is_low() {
-            button.wait_for_high().await;
+    /// How many presses are waiting, as the channel can not be asked.
+    waiting: Mutex<ThreadModeRawMutex, Cell<usize>>,
+}
+
+impl PressQueue {
+    /// Create an empty queue.
+    const fn new() -> Self {
+        Self {
+            presses: Channel::new(),
+            waiting: Mutex::new(Cell::new(0)),
         }
+    }
+
+    /// Wait for the next press to handle.
+    pub async fn recv(&self) -> ButtonPress {
+        let press = self.presses.recv().await;
+        self.waiting
+            .lock(|waiting| waiting.set(waiting.get().saturating_sub(1)));
+        press
+    }
 
-        // add debounce
-        Timer::after(Duration::from_millis(200)).await;
+    /// Add a press to the queue, dropping it if the queue is full.
+    fn send(&self, press: ButtonPress) {
+        match self.presses.try_send(press) {
+            Ok(_) => self.waiting.lock(|waiting| waiting.set(waiting.get() + 1)),
+            Err(_) => info!("Button queue full, dropping press"),
+        }
+    }
+
+    /// Add a repeated short press from the button being held down, unless a press is still waiting to be handled.
+    ///
+    /// Repeats can come every 50ms, faster than most presses are handled. Coalescing them stops the queue filling up,
+    /// and stops the app carrying on with old repeats after the button is let go.
+    fn send_repeat(&self) {
+        if self.waiting.lock(|waiting| waiting.get()) > 0 {
+            debug!("Press still waiting, coalescing repeat");
+            return;
+        }
+
+        self.send(ButtonPress::Short);
     }
 }
 
-/// Wait for changes async on the middle button being pressed.
-///
-/// Will inform signal of button press after the full press has been completed.
-/// The type of press is recorded in the ButtonPress enum.
+/// Queue of the top button presses waiting to be handled.
+pub static BUTTON_ONE_PRESS: PressQueue = PressQueue::new();
+
+/// Queue of the middle button presses waiting to be handled.
+pub static BUTTON_TWO_PRESS: PressQueue = PressQueue::new();
+
+/// Queue of the bottom button presses waiting to be handled.
+pub static BUTTON_THREE_PRESS: PressQueue = PressQueue::new();
+
+/// Signal for when any button is first pressed down, with when it was pressed from the first edge.
 ///
-/// This task has no way of cancellation.
-#[embassy_executor::task]
-pub async fn button_two_task(mut button: Input<'static, PIN_17>) -> ! {
-    loop {
-        // sit here until button is pressed down
-        button.wait_for_low().await;
+/// Signalled before the type of press is known, for when the time of the press matters more than its type.
+pub static BUTTON_DOWN: Signal<ThreadModeRawMutex, Instant> = Signal::new();
 
-        let press = button_pressed(&mut button).await;
-        BUTTON_TWO_PRESS.signal(press);
+/// The physical buttons on the clock.
+#[derive(Clone, Copy, PartialEq, defmt::Format)]
+pub enum Button {
+    /// The top button.
+    Top,
 
-        // wait for button to be released
-        if button.is_low() {
-            button.wait_for_high().await;
+    /// The middle button.
+    Middle,
+
+    /// The bottom button.
+    Bottom,
+}
+
+impl Button {
+    /// Index of the button in the button state arrays.
+    const fn index(&self) -> usize {
+        match self {
+            Button::Top => TOP,
+            Button::Middle => MIDDLE,
+            Button::Bottom => BOTTOM,
+        }
+    }
+
+    /// The queue to add presses of the button to.
+    fn queue(&self) -> &'static PressQueue {
+        match self {
+            Button::Top => &BUTTON_ONE_PRESS,
+            Button::Middle => &BUTTON_TWO_PRESS,
+            Button::Bottom => &BUTTON_THREE_PRESS,
+        }
+    }
+
+    /// Add a press to the queue for the button, dropping it if the queue is full.
+    fn send(&self, press: ButtonPress) {
+        self.queue().send(press);
+    }
+}
+
+/// Driver for a single button. Handles debouncing and determining the type of press.
+pub struct ButtonDriver<'a, T: Pin> {
+    /// The button input pin.
+    input: Input<'a, T>,
+
+    /// Which button this driver is for.
+    button: Button,
+
+    /// When the button was last pressed down, taken from the first edge before debouncing.
+    pressed_at: Instant,
+
+    /// When the button was last released, taken from the first edge before debouncing.
+    released_at: Instant,
+}
+
+impl<'a, T: Pin> ButtonDriver<'a, T> {
+    /// Create a new button driver. The input must be pulled up, so the button is low when pressed.
+    pub fn new(input: Input<'a, T>, button: Button) -> Self {
+        Self {
+            input,
+            button,
+            pressed_at: Instant::now(),
+            released_at: Instant::now(),
+        }
+    }
+
+    /// Wait until the button is pressed down. Any edge that does not stay low for `DEBOUNCE_MS` is ignored as a bounce.
+    async fn wait_for_press(&mut self) {
+        loop {
+            self.input.wait_for_low().await;
+            let edge = Instant::now();
+
+            Timer::after(Duration::from_millis(DEBOUNCE_MS)).await;
+
+            if self.input.is_low() {
+                self.pressed_at = edge;
+                break;
+            }
+        }
+    }
+
+    /// Wait until the button is released. Any edge that does not stay high for `DEBOUNCE_MS` is ignored as a bounce.
+    async fn wait_for_release(&mut self) {
+        loop {
+            self.input.wait_for_high().await;
+            let edge = Instant::now();
+
+            Timer::after(Duration::from_millis(DEBOUNCE_MS)).await;
+
+            if self.input.is_high() {
+                self.released_at = edge;
+                break;
+            }
+        }
+    }
+
+    /// Determine the type of press performed on the button.
+    ///
+    /// Returns [None](Option::None) if the button was part of a combo that another button has already signalled.
+    async fn button_pressed(&mut self) -> Option<ButtonPress> {
+        let index = self.button.index();
+
+        BUTTON_IN_COMBO[index].store(false, Ordering::Relaxed);
+        BUTTON_HELD[index].store(true, Ordering::Relaxed);
+
+        let press = self.press_type().await;
+
+        BUTTON_HELD[index].store(false, Ordering::Relaxed);
+
+        // another button has already signalled the combo this button is part of
+        if BUTTON_IN_COMBO[index].load(Ordering::Relaxed) {
+            BUTTON_IN_COMBO[index].store(false, Ordering::Relaxed);
+            return None;
         }
 
-        // add debounce
-        Timer::after(Duration::from_millis(200)).await;
+        Some(press)
+    }
+
+    /// Determine the type of press performed on the button.
+    ///
+    /// All timings are measured from the edge timestamps, so debouncing does not add to the press duration.
+    async fn press_type(&mut self) -> ButtonPress {
+        let long_press_at = self.pressed_at + Duration::from_millis(LONG_PRESS_MS);
+
+        // wait until button is released or long press
+        let res = select(self.wait_for_release(), Timer::at(long_press_at)).await;
+
+        match res {
+            // button is released before long press
+            Either::First(_) => {
+                let double_press_until = self.released_at + Duration::from_millis(DOUBLE_PRESS_MS);
+
+                // see if button is pressed down again before the double press window ends
+                let res = select(self.wait_for_press(), Timer::at(double_press_until)).await;
+
+                match res {
+                    // button is pressed again
+                    Either::First(_) => self.multi_press_type().await,
+                    // double press window passed by
+                    Either::Second(_) => {
+                        info!("Short press");
+                        ButtonPress::Short
+                    }
+                }
+            }
+            // long press time passed by
+            Either::Second(_) => match detect_combo(self.button.index()) {
+                Some(combo) => {
+                    info!("Combo press");
+                    ButtonPress::Combo(combo)
+                }
+                None => {
+                    info!("Long press");
+                    ButtonPress::Long
+                }
+            },
+        }
+    }
+
+    /// Determine if a double press is followed by a third press.
+    ///
+    /// Must only be called once the second press has been pressed down.
+    async fn multi_press_type(&mut self) -> ButtonPress {
+        let long_press_at = self.pressed_at + Duration::from_millis(LONG_PRESS_MS);
+
+        // wait until second press is released, holding it down is still just a double press
+        let res = select(self.wait_for_release(), Timer::at(long_press_at)).await;
+
+        if let Either::First(_) = res {
+            let triple_press_until = self.released_at + Duration::from_millis(DOUBLE_PRESS_MS);
+
+            // see if button is pressed down a third time before the window ends
+            let res = select(self.wait_for_press(), Timer::at(triple_press_until)).await;
+
+            if let Either::First(_) = res {
+                info!("Triple press");
+                return ButtonPress::Triple;
+            }
+        }
+
+        info!("Double press");
+        ButtonPress::Double
+    }
+
+    /// Send short presses for as long as the button is held down.
+    ///
+    /// Must only be called once a long press has been detected. The first short press is sent straight away, then will repeat
+    /// once the button has been held for `REPEAT_START_MS`, speeding up after `REPEAT_FAST_MS`.
+    async fn auto_repeat(&mut self) {
+        let pressed_at = self.pressed_at;
+        let mut next_repeat = pressed_at + Duration::from_millis(REPEAT_START_MS);
+
+        self.button.send(ButtonPress::Short);
+
+        loop {
+            let res = select(self.wait_for_release(), Timer::at(next_repeat)).await;
+
+            match res {
+                // button is released
+                Either::First(_) => break,
+                // button is still held down
+                Either::Second(_) => {
+                    info!("Repeat press");
+                    self.button.queue().send_repeat();
+
+                    let held_for = Instant::now().duration_since(pressed_at);
+                    if held_for >= Duration::from_millis(REPEAT_FAST_MS) {
+                        next_repeat += Duration::from_millis(REPEAT_FAST_INTERVAL_MS);
+                    } else {
+                        next_repeat += Duration::from_millis(REPEAT_INTERVAL_MS);
+                    }
+                }
+            }
+        }
     }
 }
 
-/// Wait for changes async on the bottom button being pressed.
+/// Wait for changes async on a button being pressed. One task is spawned per button.
 ///
-/// Will inform signal of button press after the full press has been completed.
+/// Will add the press to the button's queue after the full press has been completed.
 /// The type of press is recorded in the ButtonPress enum.
 ///
 /// This task has no way of cancellation.
-#[embassy_executor::task]
-pub async fn button_three_task(mut button: Input<'static, PIN_15>) -> ! {
+#[embassy_executor::task(pool_size = 3)]
+pub async fn button_task(mut driver: ButtonDriver<'static, AnyPin>) -> ! {
     loop {
         // sit here until button is pressed down
-        button.wait_for_low().await;
+        driver.wait_for_press().await;
+        BUTTON_DOWN.signal(driver.pressed_at);
 
-        let press = button_pressed(&mut button).await;
-        BUTTON_THREE_PRESS.signal(press);
+        let press = driver.button_pressed().await;
+        match press {
+            Some(ButtonPress::Long)
+                if driver.button != Button::Top && AUTO_REPEAT.load(Ordering::Relaxed) =>
+            {
+                driver.auto_repeat().await
+            }
+            Some(press) => {
+                event_log::record(Event::ButtonPress(driver.button, press));
+                driver.button.send(press)
+            }
+            None => {}
+        }
 
         // wait for button to be released
-        if button.is_low() {
-            button.wait_for_high().await;
+        if driver.input.is_low() {
+            driver.wait_for_release().await;
         }
-
-        // add debounce
-        Timer::after(Duration::from_millis(200)).await;
     }
 }
 
-/// Determine the type of press performed on the button.
-#[allow(clippy::needless_pass_by_ref_mut)] // needs to be mutable to use wait_for_*()
-async fn button_pressed<T>(button: &mut Input<'_, T>) -> ButtonPress
-where
-    T: embassy_rp::gpio::Pin,
-{
-    // wait until button is released or 500ms (long press)
-    let res = select(
-        button.wait_for_high(),
-        Timer::after(Duration::from_millis(500)),
-    )
-    .await;
-
-    match res {
-        // button is released before 500ms
-        Either::First(_) => {
-            // add debounce
-            Timer::after(Duration::from_millis(50)).await;
-
-            // see if button is pressed down again or 250ms
-            let res = select(
-                button.wait_for_low(),
-                Timer::after(Duration::from_millis(250)),
-            )
-            .await;
+/// Enable or disable auto repeat on the middle and bottom buttons.
+///
+/// When enabled, holding the button will signal repeated short presses instead of a single long press.
+pub fn set_auto_repeat(state: bool) {
+    AUTO_REPEAT.store(state, Ordering::Relaxed);
+}
 
-            match res {
-                // button is released before 250ms
-                Either::First(_) => {
-                    info!("Double press");
-                    ButtonPress::Double
-                }
-                // 250ms passed by
-                Either::Second(_) => {
-                    info!("Short press");
-                    ButtonPress::Short
-                }
-            }
-        }
-        // 500ms passed by
-        Either::Second(_) => {
-            info!("Long press");
-            ButtonPress::Long
-        }
+/// Find the combo for the button at `index` if another button is also being held down.
+///
+/// The other button is marked as part of the combo, so it does not signal a press of its own.
+fn detect_combo(index: usize) -> Option<ButtonCombo> {
+    let other = (0..3).find(|&i| i != index && BUTTON_HELD[i].load(Ordering::Relaxed))?;
+
+    BUTTON_IN_COMBO[other].store(true, Ordering::Relaxed);
+
+    match (index.min(other), index.max(other)) {
+        (TOP, MIDDLE) => Some(ButtonCombo::TopMiddle),
+        (TOP, BOTTOM) => Some(ButtonCombo::TopBottom),
+        _ => Some(ButtonCombo::MiddleBottom),
     }
 }
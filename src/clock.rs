@@ -1,22 +1,128 @@
+use chrono::Weekday;
+use core::fmt::Write;
 use ds323x::{Datelike, Timelike};
 use embassy_executor::Spawner;
-use embassy_futures::select::{select, Either::First, Either::Second};
+use embassy_futures::select::{select3, Either3::First, Either3::Second, Either3::Third};
 use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, pubsub::PubSubChannel};
-use embassy_time::{Duration, Timer};
+use heapless::String;
 
 use crate::{
-    app::{App, StopAppTasks},
+    app::{self, App, StopAppTasks},
+    bme280,
     buttons::ButtonPress,
-    config::{self, TimePreference},
-    display::display_matrix::{TimeColon, DISPLAY_MATRIX},
+    config::{
+        self, ClockAction, ClockButton, ClockFace, Days, DisplayRotation, RotationItem,
+        TimeColonPreference, TimePreference,
+    },
+    display::{
+        backlight,
+        display_matrix::{DisplayMatrix, IconAnimation, TimeColon, DISPLAY_MATRIX},
+    },
+    formatting, humidity,
+    notification::{self, Notification},
     rtc::{self},
-    speaker, temperature,
+    scheduler::Scheduler,
+    speaker::{self, Chime, SoundType},
+    temperature, time_display,
 };
 
 /// Channel for firing events of when tasks should be stopped.
 static PUB_SUB_CHANNEL: PubSubChannel<ThreadModeRawMutex, StopAppTasks, 1, 1, 1> =
     PubSubChannel::new();
 
+/// The jobs the clock runs on a schedule.
+#[derive(Clone, Copy)]
+enum ClockJob {
+    /// Show the next auto display rotation item.
+    Rotation,
+
+    /// Record the temperature for today's range.
+    RecordTemperature,
+}
+
+/// How many seconds past each auto display rotation interval the item is shown, so it is not shown as the minute changes.
+const ROTATION_OFFSET_SECS: u32 = 25;
+
+/// How often the time is scrolled again when shown as words, so it can be read at any time.
+const WORDS_REPEAT_SECS: u32 = 15;
+
+/// The preferences the clock task uses on each tick, read again whenever a config option changes.
+struct ClockSettings {
+    /// Whether do not disturb is on, which silences the hourly ring and chime and stops the auto display rotation.
+    do_not_disturb: bool,
+
+    /// How the time is shown.
+    clock_face: ClockFace,
+
+    /// How the colon between the hours and minutes is shown.
+    colon_pref: TimeColonPreference,
+
+    /// The days the hourly ring is on. None while do not disturb is on.
+    hourly_ring_days: Days,
+
+    /// Whether to play the quarter hour chime.
+    quarter_chime: bool,
+
+    /// The days night mode is on.
+    night_mode_days: Days,
+
+    /// The items to show in the auto display rotation.
+    rotation: DisplayRotation,
+}
+
+impl ClockSettings {
+    /// Read the clock settings from the config.
+    async fn load() -> Self {
+        let do_not_disturb = config::get_do_not_disturb().await;
+
+        let hourly_ring_days = if do_not_disturb {
+            Days::NONE
+        } else {
+            config::get_hourly_ring_days().await
+        };
+
+        Self {
+            do_not_disturb,
+            clock_face: config::get_clock_face().await,
+            colon_pref: config::get_time_colon_preference().await,
+            hourly_ring_days,
+            quarter_chime: config::get_quarter_chime().await && !do_not_disturb,
+            night_mode_days: config::get_night_mode_days().await,
+            rotation: config::get_display_rotation().await,
+        }
+    }
+
+    /// Update the icons, backlight and auto display rotation to match the settings.
+    async fn apply(&self, scheduler: &mut Scheduler<ClockJob, 2>, day: Weekday, hour: u32) {
+        let time_pref = config::get_time_preference().await;
+        DISPLAY_MATRIX.show_time_icon(time_pref, hour);
+
+        let temp_pref = temperature::get_temperature_preference().await;
+        DISPLAY_MATRIX.show_temperature_icon(temp_pref);
+
+        show_hourly_ring_icon(self.hourly_ring_days, day);
+
+        backlight::set_standby(config::get_standby().await);
+        backlight::set_night_mode(config::is_night_on(self.night_mode_days, day, hour));
+
+        scheduler.remove("rotation");
+        // the MoveOn icon pulses while the rotation is waiting to show the next item
+        if self.rotation.any_enabled() && !self.do_not_disturb {
+            DISPLAY_MATRIX.show_icon("MoveOn");
+            DISPLAY_MATRIX.animate_icon("MoveOn", IconAnimation::Pulse);
+            scheduler.add(
+                "rotation",
+                self.rotation.interval.minutes() * 60,
+                ROTATION_OFFSET_SECS,
+                ClockJob::Rotation,
+            );
+        } else {
+            DISPLAY_MATRIX.hide_icon("MoveOn");
+            DISPLAY_MATRIX.animate_icon("MoveOn", IconAnimation::Steady);
+        }
+    }
+}
+
 /// Clock app.
 /// Will show the current time on the display.
 pub struct ClockApp {}
@@ -44,48 +150,85 @@ impl App for ClockApp {
     async fn button_one_short_press(&mut self, _: Spawner) {}
 
     async fn button_two_press(&mut self, press: ButtonPress, _: Spawner) {
-        match press {
-            ButtonPress::Short => {
+        let action = config::get_clock_action(ClockButton::Middle, &press).await;
+        self.perform_action(action).await;
+    }
+
+    async fn button_three_press(&mut self, press: ButtonPress, _: Spawner) {
+        let action = config::get_clock_action(ClockButton::Bottom, &press).await;
+        self.perform_action(action).await;
+    }
+}
+
+impl ClockApp {
+    /// Perform the action a button press has been mapped to.
+    async fn perform_action(&mut self, action: ClockAction) {
+        match action {
+            ClockAction::Nothing => {}
+            ClockAction::ShowTemperature => {
                 show_temperature().await;
             }
-            ButtonPress::Long => {
+            ClockAction::ToggleTemperaturePreference => {
                 config::toggle_temperature_preference().await;
 
                 let temp_pref = config::get_temperature_preference().await;
                 DISPLAY_MATRIX.show_temperature_icon(temp_pref);
             }
-            ButtonPress::Double => {
+            ClockAction::ToggleTimePreference => {
                 config::toggle_time_preference().await;
 
                 let time_pref = config::get_time_preference().await;
-                let datetime = rtc::get_datetime().await;
+                let datetime = rtc::get_datetime_or_estimate().await;
                 DISPLAY_MATRIX.show_time_icon(time_pref, datetime.hour());
             }
+            ClockAction::ShowDate => {
+                show_date().await;
+            }
+            ClockAction::ShowMessage => {
+                DISPLAY_MATRIX
+                    .queue_text("Built with rust + embassy", 0, true, true)
+                    .await;
+            }
+            ClockAction::ShowTemperatureRange => {
+                show_temperature_range().await;
+            }
+            ClockAction::ShowEnvironment => {
+                show_environment().await;
+            }
+            ClockAction::CycleTimeColon => {
+                let colon_pref = match config::get_time_colon_preference().await {
+                    TimeColonPreference::Solid => TimeColonPreference::Blink,
+                    TimeColonPreference::Blink => TimeColonPreference::Alt,
+                    TimeColonPreference::Alt => TimeColonPreference::Seconds,
+                    TimeColonPreference::Seconds => TimeColonPreference::Solid,
+                };
+                // the clock task picks up the new style from the config update
+                config::set_time_colon_preference(colon_pref).await;
+
+                let text = match colon_pref {
+                    TimeColonPreference::Solid => "Colon solid",
+                    TimeColonPreference::Blink => "Colon blink",
+                    TimeColonPreference::Alt => "Colon alt",
+                    TimeColonPreference::Seconds => "Colon seconds",
+                };
+                DISPLAY_MATRIX.queue_text(text, 0, true, true).await;
+            }
         }
     }
-
-    async fn button_three_press(&mut self, _: ButtonPress, _: Spawner) {
-        DISPLAY_MATRIX
-            .queue_text("Built with rust + embassy", 0, true, true)
-            .await;
-    }
 }
 
 impl ClockApp {
     /// Start the clock background task.
     async fn start_clock(&self, spawner: Spawner) {
-        // try to start the clock, but wait if the spawner is busy and retry
-        loop {
-            let res = spawner.spawn(clock());
-            match res {
-                Ok(_) => break,
-                Err(_) => Timer::after(Duration::from_millis(100)).await,
-            }
-        }
+        app::spawn_app_task(spawner, "clock", clock).await;
     }
 
     /// Cancel the clock background task.
+    ///
+    /// Night mode is only applied in the clock app, so the display is turned back on for other apps.
     fn cancel_clock(&self) {
+        backlight::set_night_mode(false);
+
         PUB_SUB_CHANNEL
             .immediate_publisher()
             .publish_immediate(StopAppTasks);
@@ -97,94 +240,144 @@ impl ClockApp {
 /// Will continue to run until signalled not too.
 #[embassy_executor::task]
 async fn clock() {
+    let _running = app::RunningAppTask::new();
     let mut sub = PUB_SUB_CHANNEL.subscriber().unwrap();
+    let mut config_sub = config::subscribe();
 
-    let datetime = rtc::get_datetime().await;
+    let datetime = rtc::get_datetime_or_estimate().await;
     let mut last_hour = datetime.hour();
     let mut last_min = datetime.minute();
     let mut last_day = datetime.weekday();
 
-    show_time(last_hour, last_min, TimeColon::Full, true).await;
+    let mut settings = ClockSettings::load().await;
+
+    let mut last_words = formatting::format_words(last_hour, last_min);
+    match settings.clock_face {
+        ClockFace::Digits => {
+            show_time(last_hour, last_min, TimeColon::Full, true).await;
+            show_do_not_disturb(settings.do_not_disturb);
+        }
+        ClockFace::Words => show_words(&last_words, true).await,
+        ClockFace::Stacked => show_stacked(last_hour, last_min, TimeColon::Full, true).await,
+    }
 
     DISPLAY_MATRIX.show_day_icon(last_day);
 
-    let time_pref = config::get_time_preference().await;
-    DISPLAY_MATRIX.show_time_icon(time_pref, last_hour);
+    let mut scheduler: Scheduler<ClockJob, 2> = Scheduler::new();
+    scheduler.add("temperature", 60, 0, ClockJob::RecordTemperature);
 
-    let should_hourly_ring = config::get_hourly_ring().await;
-    if should_hourly_ring {
-        DISPLAY_MATRIX.show_icon("Hourly");
-    }
+    let mut rotation_index = 0;
+    settings.apply(&mut scheduler, last_day, last_hour).await;
 
-    let should_scroll_temp = config::get_auto_scroll_temp().await;
-    if should_scroll_temp {
-        DISPLAY_MATRIX.show_icon("MoveOn");
-    }
+    temperature::record_temperature().await;
 
-    let temp_pref = temperature::get_temperature_preference().await;
-    DISPLAY_MATRIX.show_temperature_icon(temp_pref);
-
-    let colon_pref = config::get_time_colon_preference().await;
+    // whether the last read of the RTC worked, so the error is shown as soon as it stops responding
+    let mut rtc_ok = true;
 
     loop {
-        let res = select(sub.next_message(), Timer::after(Duration::from_secs(1))).await;
+        let res = select3(
+            sub.next_message(),
+            config::wait_for_update(&mut config_sub),
+            rtc::wait_for_tick(),
+        )
+        .await;
 
         match res {
             First(_) => break,
             Second(_) => {
-                let datetime = rtc::get_datetime().await;
+                let old_settings = settings;
+                settings = ClockSettings::load().await;
+                settings.apply(&mut scheduler, last_day, last_hour).await;
+
+                if old_settings.rotation != settings.rotation {
+                    rotation_index = 0;
+                }
+
+                // redraw straight away when the face has changed, rather than waiting for the next tick
+                if old_settings.clock_face != settings.clock_face {
+                    match settings.clock_face {
+                        ClockFace::Digits => {
+                            show_time(last_hour, last_min, TimeColon::Full, true).await
+                        }
+                        ClockFace::Words => {
+                            last_words = formatting::format_words(last_hour, last_min);
+                            show_words(&last_words, true).await
+                        }
+                        ClockFace::Stacked => {
+                            show_stacked(last_hour, last_min, TimeColon::Full, true).await
+                        }
+                    }
+                }
+            }
+            Third(_) => {
+                let datetime = match rtc::get_datetime().await {
+                    Ok(datetime) => {
+                        rtc_ok = true;
+                        datetime
+                    }
+                    Err(_) => {
+                        let datetime = rtc::get_datetime_or_estimate().await;
+
+                        // warn when the RTC first stops responding, then once a minute after
+                        if rtc_ok || datetime.second() == 0 {
+                            rtc_ok = false;
+                            notification::notify(
+                                Notification::new("RTC ERR", None)
+                                    .with_sound(SoundType::ShortBeep)
+                                    .urgent(),
+                            );
+                        }
+
+                        datetime
+                    }
+                };
 
                 let hour = datetime.hour();
                 let min = datetime.minute();
                 let second = datetime.second();
 
-                match colon_pref {
-                    config::TimeColonPreference::Solid => {
-                        show_time(hour, min, TimeColon::Full, false).await
-                    }
-                    config::TimeColonPreference::Blink => {
-                        if second % 2 == 0 {
-                            show_time(hour, min, TimeColon::Empty, false).await;
-                        } else {
-                            show_time(hour, min, TimeColon::Full, false).await;
+                match settings.clock_face {
+                    ClockFace::Words => {
+                        let words = formatting::format_words(hour, min);
+                        if words != last_words || second % WORDS_REPEAT_SECS == 0 {
+                            show_words(&words, false).await;
+                            last_words = words;
                         }
                     }
-                    config::TimeColonPreference::Alt => {
-                        if second < 15 {
-                            if second % 2 == 0 {
-                                show_time(hour, min, TimeColon::Empty, false).await;
-                            } else {
-                                show_time(hour, min, TimeColon::Top, false).await;
-                            }
-                        } else if second < 30 {
-                            if second % 2 == 0 {
-                                show_time(hour, min, TimeColon::Empty, false).await;
-                            } else {
-                                show_time(hour, min, TimeColon::Bottom, false).await;
-                            }
-                        } else if second < 45 {
-                            if second % 2 == 0 {
-                                show_time(hour, min, TimeColon::Top, false).await;
-                            } else {
-                                show_time(hour, min, TimeColon::Bottom, false).await;
-                            }
-                        } else if second % 2 == 0 {
-                            show_time(hour, min, TimeColon::Empty, false).await;
-                        } else {
-                            show_time(hour, min, TimeColon::Full, false).await;
-                        }
+                    ClockFace::Digits => {
+                        let colon = time_display::colon_phase(second, settings.colon_pref);
+                        show_time(hour, min, colon, false).await
+                    }
+                    ClockFace::Stacked => {
+                        let colon = time_display::colon_phase(second, settings.colon_pref);
+                        show_stacked(hour, min, colon, false).await
                     }
                 };
 
+                if let ClockFace::Digits = settings.clock_face {
+                    show_do_not_disturb(settings.do_not_disturb);
+                    show_seconds_pixel(time_display::seconds_pixel(second, settings.colon_pref));
+                } else {
+                    show_seconds_pixel(0);
+                }
+
                 if hour != last_hour || min != last_min {
+                    let chimed = settings.quarter_chime && play_quarter_chime(hour, min);
+
                     if hour != last_hour {
+                        backlight::set_night_mode(config::is_night_on(
+                            settings.night_mode_days,
+                            datetime.weekday(),
+                            hour,
+                        ));
+
                         if hour == 0 || hour == 12 {
                             let time_pref = config::get_time_preference().await;
                             DISPLAY_MATRIX.show_time_icon(time_pref, hour);
                         }
 
-                        if should_hourly_ring {
-                            speaker::sound(speaker::SoundType::ShortBeep);
+                        if settings.hourly_ring_days.contains(datetime.weekday()) && !chimed {
+                            speaker::sound(SoundType::ShortBeep);
                         }
                     }
 
@@ -195,35 +388,158 @@ async fn clock() {
                 let day = datetime.weekday();
                 if day != last_day {
                     DISPLAY_MATRIX.show_day_icon(day);
+                    show_hourly_ring_icon(settings.hourly_ring_days, day);
                     last_day = day;
                 }
 
-                if min % 5 == 0 && second == 25 && should_scroll_temp {
-                    let temp_pref = temperature::get_temperature_preference().await;
-                    let temp = temperature::get_temperature_off_preference().await;
-
-                    let mut hour = hour;
-                    let pref = config::get_time_preference().await;
-                    if let TimePreference::Twelve = pref {
-                        hour = convert_24_to_12(hour);
+                for job in scheduler.due(datetime) {
+                    match job {
+                        ClockJob::Rotation => {
+                            if let Some(item) = settings.rotation.next_item(&mut rotation_index) {
+                                show_rotation_item(item, hour, min).await;
+                            }
+                        }
+                        ClockJob::RecordTemperature => temperature::record_temperature().await,
                     }
-
-                    DISPLAY_MATRIX
-                        .queue_time_temperature(hour, min, temp, temp_pref, false)
-                        .await;
                 }
             }
         }
     }
+
+    // the seconds pixel is drawn over the display and the MoveOn icon is used by other apps, so do not leave either behind
+    show_seconds_pixel(0);
+    DISPLAY_MATRIX.animate_icon("MoveOn", IconAnimation::Steady);
+}
+
+/// Play the Westminster chime if it is a quarter hour and not night time.
+///
+/// On the hour, the chime is followed by a long beep for each hour (12hr).
+///
+/// Returns true if the chime was played.
+fn play_quarter_chime(hour: u32, min: u32) -> bool {
+    if config::is_night_hour(hour) {
+        return false;
+    }
+
+    let chime = match min {
+        0 => Chime::Hour,
+        15 => Chime::Quarter,
+        30 => Chime::Half,
+        45 => Chime::ThreeQuarter,
+        _ => return false,
+    };
+
+    speaker::sound(SoundType::Chime(chime));
+
+    if min == 0 {
+        let strikes = match convert_24_to_12(hour) {
+            0 => 12,
+            hour => hour,
+        };
+        speaker::sound(SoundType::RepeatLongBeep(strikes as u8));
+    }
+
+    true
+}
+
+/// Show an item from the auto display rotation.
+async fn show_rotation_item(item: RotationItem, mut hour: u32, min: u32) {
+    match item {
+        RotationItem::Temperature => {
+            let temp_pref = temperature::get_temperature_preference().await;
+            let temp_precision = temperature::get_temperature_precision().await;
+            let temp = temperature::get_temperature_off_preference().await;
+
+            let pref = config::get_time_preference().await;
+            if let TimePreference::Twelve = pref {
+                hour = convert_24_to_12(hour);
+            }
+
+            DISPLAY_MATRIX
+                .queue_time_temperature(hour, min, temp, temp_pref, temp_precision, false)
+                .await;
+        }
+        RotationItem::Humidity => show_humidity().await,
+        RotationItem::Pressure => show_pressure().await,
+        RotationItem::Date => show_date().await,
+    }
 }
 
 /// Show the temperature.
 async fn show_temperature() {
     let temp_pref = temperature::get_temperature_preference().await;
+    let temp_precision = temperature::get_temperature_precision().await;
     let temp = temperature::get_temperature_off_preference().await;
     // show temperature (holds for 5 seconds) and then show time again
     DISPLAY_MATRIX
-        .queue_temperature(temp, temp_pref, false, false)
+        .queue_temperature(temp, temp_pref, temp_precision, false, false)
+        .await;
+}
+
+/// Show the highest and lowest temperature recorded today.
+async fn show_temperature_range() {
+    let (min, max) = match temperature::get_temperature_range_off_preference().await {
+        Some(range) => range,
+        None => return,
+    };
+    let temp_pref = temperature::get_temperature_preference().await;
+    let temp_precision = temperature::get_temperature_precision().await;
+
+    DISPLAY_MATRIX
+        .queue_temperature_range(min, max, temp_pref, temp_precision, true)
+        .await;
+}
+
+/// Scroll the temperature, today's highest and lowest temperature and the humidity, if there is a reading, as one notification.
+async fn show_environment() {
+    let text = formatting::format_environment(
+        temperature::get_temperature_off_preference().await,
+        temperature::get_temperature_range_off_preference().await,
+        humidity::get_humidity().await,
+        temperature::get_temperature_preference().await,
+        temperature::get_temperature_precision().await,
+    );
+
+    notification::notify(Notification::new(text.as_str(), None));
+}
+
+/// Show the humidity, if there is a reading from the external humidity sensor.
+async fn show_humidity() {
+    let humidity = match humidity::get_humidity().await {
+        Some(humidity) => humidity,
+        None => return,
+    };
+
+    let mut text = String::<8>::new();
+    _ = write!(text, "{humidity:.0} RH");
+
+    // show humidity (holds for 2.5 seconds) and then show time again
+    DISPLAY_MATRIX
+        .queue_text(text.as_str(), 2500, true, false)
+        .await;
+}
+
+/// Show the pressure, if there is a reading from the BME280.
+async fn show_pressure() {
+    let pressure = match bme280::get_pressure().await {
+        Some(pressure) => pressure,
+        None => return,
+    };
+
+    let mut text = String::<16>::new();
+    _ = write!(text, "{pressure:.0} hPa");
+
+    DISPLAY_MATRIX
+        .queue_text(text.as_str(), 0, true, true)
+        .await;
+}
+
+/// Show the date.
+async fn show_date() {
+    let datetime = rtc::get_datetime_or_estimate().await;
+    // show date (holds for 2.5 seconds) and then show time again
+    DISPLAY_MATRIX
+        .queue_date(datetime.month(), datetime.day(), 2500, true)
         .await;
 }
 
@@ -240,6 +556,55 @@ async fn show_time(mut hour: u32, minute: u32, colon: TimeColon, show_now: bool)
         .await;
 }
 
+/// Show the time in mini digits with the temperature below it.
+async fn show_stacked(mut hour: u32, minute: u32, colon: TimeColon, show_now: bool) {
+    if let TimePreference::Twelve = config::get_time_preference().await {
+        hour = convert_24_to_12(hour);
+    }
+
+    let temp_pref = temperature::get_temperature_preference().await;
+    let temp_precision = temperature::get_temperature_precision().await;
+    let temp = temperature::get_temperature_off_preference().await;
+
+    let time = formatting::format_time(hour, minute, colon);
+    let temp = formatting::format_temp(temp, temp_pref, temp_precision);
+    DISPLAY_MATRIX
+        .queue_mini_lines(&time, &temp, 0, show_now)
+        .await;
+}
+
+/// Show the hourly ring icon if the hourly ring is on for the day, otherwise hide it.
+fn show_hourly_ring_icon(hourly_ring_days: Days, day: Weekday) {
+    if hourly_ring_days.contains(day) {
+        DISPLAY_MATRIX.show_icon("Hourly");
+    } else {
+        DISPLAY_MATRIX.hide_icon("Hourly");
+    }
+}
+
+/// Light the top pixel in the column after the time while do not disturb is on, as there is no icon for it.
+fn show_do_not_disturb(do_not_disturb: bool) {
+    if !do_not_disturb {
+        return;
+    }
+
+    critical_section::with(|cs| {
+        DISPLAY_MATRIX.show_columns(cs, DisplayMatrix::LAST_INDEX, 1, &[1, 0, 0, 0, 0, 0, 0]);
+    });
+}
+
+/// Light the marching seconds pixel along the bottom of the time, or remove it when 0 is passed.
+fn show_seconds_pixel(bits: u32) {
+    critical_section::with(|cs| {
+        DISPLAY_MATRIX.show_overlay(cs, time_display::SECONDS_ROW, bits);
+    });
+}
+
+/// Scroll the time as words. The end of the text is left on the display until the next scroll.
+async fn show_words(words: &str, show_now: bool) {
+    DISPLAY_MATRIX.queue_text(words, 0, show_now, false).await;
+}
+
 /// Convert 24hr time into 12hr time.
 fn convert_24_to_12(hour: u32) -> u32 {
     if hour <= 12 {
@@ -1,12 +1,31 @@
 use core::cell::RefCell;
 
-use embassy_rp::flash::{Async, Flash, ERASE_SIZE};
-use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, mutex::Mutex};
+use chrono::Weekday;
+use defmt::info;
+use embassy_futures::select::{select, Either};
+use embassy_rp::{
+    flash::{Async, Flash, ERASE_SIZE},
+    watchdog::Watchdog,
+};
+use embassy_sync::{
+    blocking_mutex::raw::ThreadModeRawMutex,
+    mutex::Mutex,
+    pubsub::{PubSubChannel, Subscriber},
+    signal::Signal,
+};
+use embassy_time::{Duration, Timer};
+use heapless::String;
+
+use crate::{
+    buttons::ButtonPress,
+    eeprom,
+    event_log::{self, Event},
+};
 
 use self::flash_config::FlashOveride;
 
 /// Temperature preference representation.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq)]
 pub enum TemperaturePreference {
     /// Celcius.
     Celcius,
@@ -15,8 +34,159 @@ pub enum TemperaturePreference {
     Fahrenheit,
 }
 
+/// Temperature precision representation.
+#[derive(Copy, Clone, PartialEq)]
+pub enum TemperaturePrecision {
+    /// Whole degrees.
+    Whole,
+
+    /// One decimal place.
+    Tenths,
+}
+
+impl TemperaturePrecision {
+    /// The number of decimal places to show.
+    pub const fn decimal_places(&self) -> usize {
+        match self {
+            TemperaturePrecision::Whole => 0,
+            TemperaturePrecision::Tenths => 1,
+        }
+    }
+}
+
+/// How readily autolight dims the display as the room gets darker.
+#[derive(Copy, Clone, PartialEq)]
+pub enum LightSensitivity {
+    /// Only dim in a darker room.
+    Low,
+
+    /// The default thresholds.
+    Normal,
+
+    /// Dim as soon as the room starts getting darker.
+    High,
+}
+
+impl LightSensitivity {
+    /// The amount to move each light sensor threshold by. Higher readings are darker.
+    pub const fn threshold_offset(&self) -> i32 {
+        match self {
+            LightSensitivity::Low => 50,
+            LightSensitivity::Normal => 0,
+            LightSensitivity::High => -50,
+        }
+    }
+}
+
+/// Light sensor readings taken in a dark and a bright room, to set the autolight thresholds for the board.
+///
+/// Higher readings are darker.
+#[derive(Copy, Clone, PartialEq)]
+pub struct LightCalibration {
+    /// The reading in a dark room.
+    pub dark: u16,
+
+    /// The reading in a bright room.
+    pub bright: u16,
+}
+
+impl LightCalibration {
+    /// The smallest gap between the dark and bright readings for a calibration to be used.
+    const MIN_SPREAD: u16 = 25;
+
+    /// Determine if the dark reading is far enough above the bright reading to set levels from.
+    pub fn is_valid(&self) -> bool {
+        self.dark != u16::MAX && self.dark >= self.bright.saturating_add(Self::MIN_SPREAD)
+    }
+
+    /// The readings where the light level changes, from brightest to darkest, spread evenly between the bright and dark readings.
+    pub fn thresholds(&self) -> [i32; 4] {
+        let bright = self.bright as i32;
+        let spread = self.dark as i32 - bright;
+        core::array::from_fn(|i| bright + spread * (i as i32 + 1) / 5)
+    }
+}
+
+/// An interval training protocol, of rounds of work each followed by rest.
+#[derive(Copy, Clone, PartialEq)]
+pub struct IntervalProtocol {
+    /// The seconds of work in each round.
+    pub work_secs: u16,
+
+    /// The seconds of rest after each round, or 0 for no rest.
+    pub rest_secs: u16,
+
+    /// The number of rounds.
+    pub rounds: u8,
+}
+
+impl IntervalProtocol {
+    /// The protocol used until another is set, 8 rounds of 20 seconds work and 10 seconds rest.
+    pub const DEFAULT: Self = Self {
+        work_secs: 20,
+        rest_secs: 10,
+        rounds: 8,
+    };
+
+    /// The longest work or rest interval, in seconds.
+    pub const MAX_SECS: u16 = 10 * 60;
+
+    /// The most rounds in a protocol.
+    pub const MAX_ROUNDS: u8 = 99;
+
+    /// Determine if every part of the protocol is in range, with at least some work in each round.
+    pub fn is_valid(&self) -> bool {
+        (1..=Self::MAX_SECS).contains(&self.work_secs)
+            && self.rest_secs <= Self::MAX_SECS
+            && (1..=Self::MAX_ROUNDS).contains(&self.rounds)
+    }
+}
+
+/// A sequence of countdown steps run one after another, such as for a recipe.
+#[derive(Copy, Clone, PartialEq)]
+pub struct TimerSequence {
+    /// The seconds of each step, in order. The sequence ends at the first step of 0 seconds.
+    pub steps_secs: [u16; Self::MAX_STEPS],
+}
+
+impl TimerSequence {
+    /// The sequence used until another is set, 3 minutes, then 30 seconds, then 2 minutes.
+    pub const DEFAULT: Self = Self {
+        steps_secs: [180, 30, 120, 0, 0],
+    };
+
+    /// The most steps in a sequence.
+    pub const MAX_STEPS: usize = 5;
+
+    /// The longest step, in seconds.
+    pub const MAX_SECS: u16 = 60 * 60;
+
+    /// Determine if every step is in range, with at least the first step set.
+    pub fn is_valid(&self) -> bool {
+        self.steps_secs[0] > 0 && self.steps_secs.iter().all(|&secs| secs <= Self::MAX_SECS)
+    }
+
+    /// The number of steps that will run, up to the first step of 0 seconds.
+    pub fn step_count(&self) -> usize {
+        self.steps_secs
+            .iter()
+            .position(|&secs| secs == 0)
+            .unwrap_or(Self::MAX_STEPS)
+    }
+}
+
+/// Which sensor the temperature is shown from.
+#[derive(Copy, Clone, PartialEq)]
+pub enum TemperatureSource {
+    /// The sensor built into the RTC.
+    Rtc,
+
+    /// The external humidity sensor, falling back to the RTC when there is no reading.
+    External,
+}
+
 /// Time preference representation.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq)]
 pub enum TimePreference {
     /// 12hr.
     Twelve,
@@ -36,12 +206,381 @@ pub enum TimeColonPreference {
 
     /// Show the alternate blinking colon.
     Alt,
+
+    /// Keep the colon solid and march a pixel along the bottom row as the minute goes by.
+    Seconds,
+}
+
+/// How the clock app shows the time.
+#[derive(Copy, Clone, PartialEq)]
+pub enum ClockFace {
+    /// The hours and minutes as digits, e.g. "07:45".
+    Digits,
+
+    /// The time as words to the nearest five minutes, e.g. "QUARTER TO 8".
+    Words,
+
+    /// The time in mini digits with the temperature below it.
+    Stacked,
+}
+
+/// The hour night time starts from.
+///
+/// Night time is fixed rather than a setting, as the quarter chime, night mode, standby, night mute and still running reminder all share it.
+/// The hours are listed in the usage guide, so keep it up to date if they change.
+pub const NIGHT_START_HOUR: u32 = 22;
+
+/// The hour night time ends at.
+pub const NIGHT_END_HOUR: u32 = 7;
+
+/// Determine if the passed hour (24hr) is within night time.
+pub fn is_night_hour(hour: u32) -> bool {
+    hour >= NIGHT_START_HOUR || hour < NIGHT_END_HOUR
+}
+
+/// Determine if the passed hour (24hr) is within the night time that starts on one of the days.
+///
+/// The early hours are part of the night before, so a night that starts on Friday ends on Saturday morning.
+pub fn is_night_on(days: Days, day: Weekday, hour: u32) -> bool {
+    if hour >= NIGHT_START_HOUR {
+        days.contains(day)
+    } else if hour < NIGHT_END_HOUR {
+        days.contains(day.pred())
+    } else {
+        false
+    }
+}
+
+/// Action to perform in the clock app when a button is pressed.
+#[derive(Copy, Clone, PartialEq)]
+pub enum ClockAction {
+    /// Do nothing.
+    Nothing,
+
+    /// Show the current temperature.
+    ShowTemperature,
+
+    /// Switch between celcius and fahrenheit.
+    ToggleTemperaturePreference,
+
+    /// Switch between 12hr and 24hr time.
+    ToggleTimePreference,
+
+    /// Show the current date.
+    ShowDate,
+
+    /// Scroll the about message.
+    ShowMessage,
+
+    /// Scroll the highest and lowest temperature of the day.
+    ShowTemperatureRange,
+
+    /// Cycle through the time colon styles.
+    CycleTimeColon,
+
+    /// Scroll the temperature, the day's range and the humidity together.
+    ShowEnvironment,
+}
+
+impl ClockAction {
+    /// Get the action after this one, wrapping back to the first.
+    pub fn next(&self) -> Self {
+        match self {
+            ClockAction::Nothing => ClockAction::ShowTemperature,
+            ClockAction::ShowTemperature => ClockAction::ToggleTemperaturePreference,
+            ClockAction::ToggleTemperaturePreference => ClockAction::ToggleTimePreference,
+            ClockAction::ToggleTimePreference => ClockAction::ShowDate,
+            ClockAction::ShowDate => ClockAction::ShowMessage,
+            ClockAction::ShowMessage => ClockAction::ShowTemperatureRange,
+            ClockAction::ShowTemperatureRange => ClockAction::CycleTimeColon,
+            ClockAction::CycleTimeColon => ClockAction::ShowEnvironment,
+            ClockAction::ShowEnvironment => ClockAction::Nothing,
+        }
+    }
+}
+
+/// The buttons that can have their actions remapped in the clock app.
+#[derive(Copy, Clone)]
+pub enum ClockButton {
+    /// The middle button.
+    Middle,
+
+    /// The bottom button.
+    Bottom,
+}
+
+/// The number of remappable button presses in the clock app. (short, long, double) for each [button](ClockButton).
+pub const CLOCK_ACTIONS_SIZE: usize = 6;
+
+/// The default clock actions, in the order (middle short, middle long, middle double, bottom short, bottom long, bottom double).
+const CLOCK_ACTIONS_DEFAULT: [ClockAction; CLOCK_ACTIONS_SIZE] = [
+    ClockAction::ShowTemperature,
+    ClockAction::ToggleTemperaturePreference,
+    ClockAction::ToggleTimePreference,
+    ClockAction::ShowMessage,
+    ClockAction::ShowEnvironment,
+    ClockAction::CycleTimeColon,
+];
+
+/// The number of pomodoro quick start presets.
+pub const TIMER_PRESETS_SIZE: usize = 3;
+
+/// The longest pomodoro quick start preset, in minutes.
+pub const TIMER_PRESET_MAX_MINS: u8 = 60;
+
+/// The default pomodoro quick start presets, in minutes.
+const TIMER_PRESETS_DEFAULT: [u8; TIMER_PRESETS_SIZE] = [3, 5, 10];
+
+/// The most minutes before the pomodoro finishes that the pre-finish warning can be set to.
+pub const PRE_FINISH_MAX_MINUTES: u8 = 15;
+
+/// Items that can be shown in the auto display rotation of the clock app.
+#[derive(Copy, Clone, PartialEq)]
+pub enum RotationItem {
+    /// The temperature alongside the time.
+    Temperature,
+
+    /// The humidity from the external humidity sensor.
+    Humidity,
+
+    /// The pressure from the BME280.
+    Pressure,
+
+    /// The current date.
+    Date,
+}
+
+impl RotationItem {
+    /// The bit used for the item in the enabled items of [DisplayRotation].
+    const fn mask(&self) -> u8 {
+        match self {
+            RotationItem::Temperature => 0b01,
+            RotationItem::Date => 0b10,
+            RotationItem::Humidity => 0b100,
+            RotationItem::Pressure => 0b1000,
+        }
+    }
+}
+
+/// All the items that can be shown in the auto display rotation, in the order they are shown.
+pub const ROTATION_ITEMS: [RotationItem; 4] = [
+    RotationItem::Temperature,
+    RotationItem::Humidity,
+    RotationItem::Pressure,
+    RotationItem::Date,
+];
+
+/// How often the auto display rotation shows the next item.
+#[derive(Copy, Clone, PartialEq)]
+pub enum RotationInterval {
+    /// Every minute.
+    One,
+
+    /// Every 5 minutes.
+    Five,
+
+    /// Every 10 minutes.
+    Ten,
+
+    /// Every 30 minutes.
+    Thirty,
+}
+
+impl RotationInterval {
+    /// The interval in minutes.
+    pub const fn minutes(&self) -> u32 {
+        match self {
+            RotationInterval::One => 1,
+            RotationInterval::Five => 5,
+            RotationInterval::Ten => 10,
+            RotationInterval::Thirty => 30,
+        }
+    }
+
+    /// Get the interval after this one, wrapping back to the first.
+    pub fn next(&self) -> Self {
+        match self {
+            RotationInterval::One => RotationInterval::Five,
+            RotationInterval::Five => RotationInterval::Ten,
+            RotationInterval::Ten => RotationInterval::Thirty,
+            RotationInterval::Thirty => RotationInterval::One,
+        }
+    }
+}
+
+/// Which items the clock app should automatically show, and how often.
+#[derive(Copy, Clone, PartialEq)]
+pub struct DisplayRotation {
+    /// The enabled items, with a bit set for each from [RotationItem::mask].
+    items: u8,
+
+    /// How often to show the next item.
+    pub interval: RotationInterval,
+}
+
+impl DisplayRotation {
+    /// Create a display rotation with no items enabled.
+    pub const fn new() -> Self {
+        Self {
+            items: 0,
+            interval: RotationInterval::Five,
+        }
+    }
+
+    /// Determine if the item is part of the rotation.
+    pub fn is_enabled(&self, item: RotationItem) -> bool {
+        self.items & item.mask() != 0
+    }
+
+    /// Add or remove the item from the rotation.
+    pub fn set_enabled(&mut self, item: RotationItem, state: bool) {
+        if state {
+            self.items |= item.mask();
+        } else {
+            self.items &= !item.mask();
+        }
+    }
+
+    /// Determine if any item is part of the rotation.
+    pub fn any_enabled(&self) -> bool {
+        self.items != 0
+    }
+
+    /// Get the next enabled item, starting the search from `index` into [ROTATION_ITEMS].
+    ///
+    /// `index` is moved past the returned item, so the following call will return the next item along.
+    pub fn next_item(&self, index: &mut usize) -> Option<RotationItem> {
+        for offset in 0..ROTATION_ITEMS.len() {
+            let i = (*index + offset) % ROTATION_ITEMS.len();
+            if self.is_enabled(ROTATION_ITEMS[i]) {
+                *index = i + 1;
+                return Some(ROTATION_ITEMS[i]);
+            }
+        }
+
+        None
+    }
+}
+
+/// A set of days of the week, such as the days the hourly ring is on.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct Days(u8);
+
+impl Days {
+    /// No days.
+    pub const NONE: Self = Self(0);
+
+    /// Every day of the week.
+    pub const EVERY_DAY: Self = Self(0x7F);
+
+    /// Monday to Friday.
+    pub const WEEKDAYS: Self = Self(0x1F);
+
+    /// Saturday and Sunday.
+    pub const WEEKENDS: Self = Self(0x60);
+
+    /// The common sets of days, in the order they are cycled through.
+    const PRESETS: [Self; 4] = [Self::EVERY_DAY, Self::WEEKDAYS, Self::WEEKENDS, Self::NONE];
+
+    /// Get the bit for the day, with Monday in the lowest bit.
+    fn mask(day: Weekday) -> u8 {
+        1 << day.num_days_from_monday()
+    }
+
+    /// Determine if the day is in the set.
+    pub fn contains(&self, day: Weekday) -> bool {
+        self.0 & Self::mask(day) != 0
+    }
+
+    /// Add the day to the set if it is not in it, otherwise remove it.
+    pub fn toggle(&mut self, day: Weekday) {
+        self.0 ^= Self::mask(day);
+    }
+
+    /// Get the common set of days after this one, going back to every day after the last.
+    ///
+    /// A set that is not one of the common sets goes to every day.
+    pub fn next_preset(&self) -> Self {
+        match Self::PRESETS.iter().position(|preset| preset == self) {
+            Some(i) => Self::PRESETS[(i + 1) % Self::PRESETS.len()],
+            None => Self::EVERY_DAY,
+        }
+    }
+}
+
+/// The longest wifi network name that can be stored.
+pub const WIFI_SSID_SIZE: usize = 32;
+
+/// The longest wifi password that can be stored.
+pub const WIFI_PASSWORD_SIZE: usize = 64;
+
+/// The wifi network for the clock to connect to.
+#[derive(Clone)]
+pub struct WifiCredentials {
+    /// The network name. Empty when no network is set.
+    pub ssid: String<WIFI_SSID_SIZE>,
+
+    /// The network password. Empty for an open network.
+    pub password: String<WIFI_PASSWORD_SIZE>,
+}
+
+impl WifiCredentials {
+    /// Create wifi credentials, cutting off any values that are too long.
+    pub fn new(ssid: &str, password: &str) -> Self {
+        let mut credentials = Self {
+            ssid: String::new(),
+            password: String::new(),
+        };
+
+        for c in ssid.chars() {
+            if credentials.ssid.push(c).is_err() {
+                break;
+            }
+        }
+
+        for c in password.chars() {
+            if credentials.password.push(c).is_err() {
+                break;
+            }
+        }
+
+        credentials
+    }
+
+    /// Create wifi credentials from the `WIFI_SSID` and `WIFI_PASSWORD` environment variables at build time.
+    ///
+    /// Used while no network is saved in the config, and empty if the variables were not set.
+    pub fn from_build_env() -> Self {
+        Self::new(
+            option_env!("WIFI_SSID").unwrap_or(""),
+            option_env!("WIFI_PASSWORD").unwrap_or(""),
+        )
+    }
+}
+
+/// Get the index into the clock actions table for the button and press type.
+///
+/// Returns [None](Option::None) if the press type can not be remapped.
+pub fn clock_action_index(button: ClockButton, press: &ButtonPress) -> Option<usize> {
+    let button_offset = match button {
+        ClockButton::Middle => 0,
+        ClockButton::Bottom => 3,
+    };
+
+    let press_offset = match press {
+        ButtonPress::Short => 0,
+        ButtonPress::Long => 1,
+        ButtonPress::Double => 2,
+        ButtonPress::Triple | ButtonPress::Combo(_) => return None,
+    };
+
+    Some(button_offset + press_offset)
 }
 
 /// All the configuration options that can be edited at runtime.
 pub struct ConfigOptions {
-    /// Whether the clock should beep on the hour.
-    hourly_ring: bool,
+    /// The days the clock should beep on the hour.
+    hourly_ring_days: Days,
 
     /// The users colon blinking preference.
     time_colon_pref: TimeColonPreference,
@@ -49,14 +588,80 @@ pub struct ConfigOptions {
     /// The users temperature reporting preference.
     temp_pref: TemperaturePreference,
 
-    /// Whether the clock should auto scroll and show the temperature.
-    auto_scroll_temp: bool,
+    /// Which items the clock should automatically show, and how often.
+    display_rotation: DisplayRotation,
 
     /// The users time representation preference.
     time_pref: TimePreference,
 
     /// Whether the display should use auto brightness or not.
     autolight: bool,
+
+    /// Whether the clock should play the Westminster chime every quarter hour.
+    quarter_chime: bool,
+
+    /// The action for each remappable button press in the clock app.
+    clock_actions: [ClockAction; CLOCK_ACTIONS_SIZE],
+
+    /// The days the display should blank from night time.
+    night_mode_days: Days,
+
+    /// The number of decimal places to show temperatures with.
+    temp_precision: TemperaturePrecision,
+
+    /// Which sensor to show the temperature from.
+    temp_source: TemperatureSource,
+
+    /// The RTC aging offset, to trim the crystal frequency.
+    aging_offset: i8,
+
+    /// The wifi network to connect to.
+    wifi_credentials: WifiCredentials,
+
+    /// Whether night mode should put the display into the low power standby.
+    standby: bool,
+
+    /// How readily autolight dims the display as the room gets darker.
+    light_sensitivity: LightSensitivity,
+
+    /// The light sensor readings to set the autolight thresholds from, if the sensor has been calibrated.
+    light_calibration: Option<LightCalibration>,
+
+    /// The minutes the room has to be dark for before the display turns off, or 0 to never turn it off.
+    lights_out: u8,
+
+    /// The last interval training protocol used.
+    interval_protocol: IntervalProtocol,
+
+    /// The minutes the clock has to be left alone for before the screensaver starts, or 0 to never start it.
+    screensaver: u8,
+
+    /// How the clock app shows the time.
+    clock_face: ClockFace,
+
+    /// Whether a paused pomodoro should nudge to be resumed.
+    pause_nudge: bool,
+
+    /// The steps of the timer sequence.
+    timer_sequence: TimerSequence,
+
+    /// The minutes of each pomodoro quick start preset.
+    timer_presets: [u8; TIMER_PRESETS_SIZE],
+
+    /// Whether hourly chimes, the auto display rotation and notification sounds are held back.
+    do_not_disturb: bool,
+
+    /// Whether the speaker is muted on the nights night mode is on, except for timers finishing.
+    night_mute: bool,
+
+    /// The hour (24hr) from which a timer left running in the dark is reminded about, where 0 is off.
+    still_running_hour: u8,
+
+    /// Whether timers blink and tick through their final seconds.
+    final_countdown: bool,
+
+    /// How many minutes before the pomodoro finishes to beep, where 0 is off.
+    pre_finish_minutes: u8,
 }
 
 /// Manage active configuration.
@@ -64,8 +669,14 @@ pub struct Config {
     /// The flash memory peripheral.
     flash: Flash<'static, embassy_rp::peripherals::FLASH, Async, { flash_config::FLASH_SIZE }>,
 
+    /// The watchdog peripheral, used to reboot after a factory reset.
+    watchdog: Watchdog,
+
     /// The config options.
     config_options: ConfigOptions,
+
+    /// Whether the config options have changed since they were last written to flash.
+    dirty: bool,
 }
 
 impl Config {
@@ -77,128 +688,406 @@ impl Config {
             Async,
             { flash_config::FLASH_SIZE },
         >,
+        watchdog: Watchdog,
     ) -> Self {
         let bytes = flash.read_all();
 
-        let hourly_ring = flash_config::hourly_ring_from_bytes(&bytes);
+        let hourly_ring_days = flash_config::hourly_ring_days_from_bytes(&bytes);
         let time_colon_pref = flash_config::time_colon_from_bytes(&bytes);
         let temp_pref = flash_config::temp_pref_from_bytes(&bytes);
-        let auto_scroll_temp = flash_config::auto_scroll_temp_from_bytes(&bytes);
+        let display_rotation = flash_config::display_rotation_from_bytes(&bytes);
         let time_pref = flash_config::time_pref_from_bytes(&bytes);
         let autolight = flash_config::autolight_from_bytes(&bytes);
+        let quarter_chime = flash_config::quarter_chime_from_bytes(&bytes);
+        let clock_actions = flash_config::clock_actions_from_bytes(&bytes);
+        let night_mode_days = flash_config::night_mode_days_from_bytes(&bytes);
+        let temp_precision = flash_config::temp_precision_from_bytes(&bytes);
+        let temp_source = flash_config::temp_source_from_bytes(&bytes);
+        let aging_offset = flash_config::aging_offset_from_bytes(&bytes);
+        let wifi_credentials = flash_config::wifi_credentials_from_bytes(&bytes);
+        let standby = flash_config::standby_from_bytes(&bytes);
+        let light_sensitivity = flash_config::light_sensitivity_from_bytes(&bytes);
+        let light_calibration = flash_config::light_calibration_from_bytes(&bytes);
+        let lights_out = flash_config::lights_out_from_bytes(&bytes);
+        let interval_protocol = flash_config::interval_protocol_from_bytes(&bytes);
+        let screensaver = flash_config::screensaver_from_bytes(&bytes);
+        let clock_face = flash_config::clock_face_from_bytes(&bytes);
+        let pause_nudge = flash_config::pause_nudge_from_bytes(&bytes);
+        let timer_sequence = flash_config::timer_sequence_from_bytes(&bytes);
+        let timer_presets = flash_config::timer_presets_from_bytes(&bytes);
+        let do_not_disturb = flash_config::do_not_disturb_from_bytes(&bytes);
+        let night_mute = flash_config::night_mute_from_bytes(&bytes);
+        let still_running_hour = flash_config::still_running_hour_from_bytes(&bytes);
+        let final_countdown = flash_config::final_countdown_from_bytes(&bytes);
+        let pre_finish_minutes = flash_config::pre_finish_minutes_from_bytes(&bytes);
 
         Self {
             flash,
+            watchdog,
             config_options: ConfigOptions {
-                hourly_ring,
+                hourly_ring_days,
                 time_colon_pref,
                 temp_pref,
-                auto_scroll_temp,
+                display_rotation,
                 time_pref,
                 autolight,
+                quarter_chime,
+                clock_actions,
+                night_mode_days,
+                temp_precision,
+                temp_source,
+                aging_offset,
+                wifi_credentials,
+                standby,
+                light_sensitivity,
+                light_calibration,
+                lights_out,
+                interval_protocol,
+                screensaver,
+                clock_face,
+                pause_nudge,
+                timer_sequence,
+                timer_presets,
+                do_not_disturb,
+                night_mute,
+                still_running_hour,
+                final_countdown,
+                pre_finish_minutes,
             },
+            dirty: false,
         }
     }
 }
 
 impl Config {
-    /// Set the hourly ring state.
-    fn set_hourly_ring(&mut self, new_state: bool) {
-        self.config_options.hourly_ring = new_state;
-        self.flash.write_all(&self.config_options);
+    /// Set the hourly ring days.
+    fn set_hourly_ring_days(&mut self, new_state: Days) {
+        self.config_options.hourly_ring_days = new_state;
+        self.mark_dirty();
     }
 
     /// Set the users time colon preference.
     fn set_time_colon_preference(&mut self, new_state: TimeColonPreference) {
         self.config_options.time_colon_pref = new_state;
-        self.flash.write_all(&self.config_options);
+        self.mark_dirty();
     }
 
     /// Set the users temperature preference.
     fn set_temperature_preference(&mut self, new_state: TemperaturePreference) {
         self.config_options.temp_pref = new_state;
-        self.flash.write_all(&self.config_options);
+        self.mark_dirty();
     }
 
-    /// Set the auto scroll temperature state.
-    fn set_auto_scroll_temp(&mut self, new_state: bool) {
-        self.config_options.auto_scroll_temp = new_state;
-        self.flash.write_all(&self.config_options);
+    /// Set the auto display rotation.
+    fn set_display_rotation(&mut self, new_state: DisplayRotation) {
+        self.config_options.display_rotation = new_state;
+        self.mark_dirty();
     }
 
     /// Set the users time preference.
     fn set_time_preference(&mut self, new_state: TimePreference) {
         self.config_options.time_pref = new_state;
-        self.flash.write_all(&self.config_options);
+        self.mark_dirty();
     }
 
     /// Set the autolight state.
     fn set_autolight(&mut self, new_state: bool) {
         self.config_options.autolight = new_state;
-        self.flash.write_all(&self.config_options);
+        self.mark_dirty();
     }
-}
 
-/// Static reference to the config so it can be accessed by all otehr apps.
-static CONFIG: Mutex<ThreadModeRawMutex, RefCell<Option<Config>>> = Mutex::new(RefCell::new(None));
+    /// Set the quarter chime state.
+    fn set_quarter_chime(&mut self, new_state: bool) {
+        self.config_options.quarter_chime = new_state;
+        self.mark_dirty();
+    }
 
-/// Get hourly ring state.
-pub async fn get_hourly_ring() -> bool {
-    let guard = CONFIG.lock().await;
-    let state = guard.borrow().as_ref().unwrap().config_options.hourly_ring;
-    drop(guard);
-    state
-}
+    /// Set the clock actions table.
+    fn set_clock_actions(&mut self, new_state: [ClockAction; CLOCK_ACTIONS_SIZE]) {
+        self.config_options.clock_actions = new_state;
+        self.mark_dirty();
+    }
 
-/// Set the hourly ring state.
-pub async fn set_hourly_ring(new_state: bool) {
-    let guard = CONFIG.lock().await;
+    /// Set the night mode days.
+    fn set_night_mode_days(&mut self, new_state: Days) {
+        self.config_options.night_mode_days = new_state;
+        self.mark_dirty();
+    }
 
-    guard
-        .borrow_mut()
-        .as_mut()
-        .unwrap()
-        .set_hourly_ring(new_state);
+    /// Set the temperature precision.
+    fn set_temp_precision(&mut self, new_state: TemperaturePrecision) {
+        self.config_options.temp_precision = new_state;
+        self.mark_dirty();
+    }
 
-    drop(guard);
-}
+    /// Set the temperature source.
+    fn set_temp_source(&mut self, new_state: TemperatureSource) {
+        self.config_options.temp_source = new_state;
+        self.mark_dirty();
+    }
 
-/// Get the time colon preference.
-pub async fn get_time_colon_preference() -> TimeColonPreference {
-    let guard = CONFIG.lock().await;
-    let state = guard
-        .borrow()
-        .as_ref()
-        .unwrap()
-        .config_options
-        .time_colon_pref;
-    drop(guard);
-    state
-}
+    /// Set the RTC aging offset.
+    fn set_aging_offset(&mut self, new_state: i8) {
+        self.config_options.aging_offset = new_state;
+        self.mark_dirty();
+    }
 
-/// Set the time colon preference.
-pub async fn set_time_colon_preference(new_state: TimeColonPreference) {
-    let guard = CONFIG.lock().await;
+    /// Set the wifi network credentials.
+    fn set_wifi_credentials(&mut self, new_state: WifiCredentials) {
+        self.config_options.wifi_credentials = new_state;
+        self.mark_dirty();
+    }
 
-    guard
-        .borrow_mut()
-        .as_mut()
-        .unwrap()
-        .set_time_colon_preference(new_state);
+    /// Set the standby state.
+    fn set_standby(&mut self, new_state: bool) {
+        self.config_options.standby = new_state;
+        self.mark_dirty();
+    }
 
-    drop(guard);
-}
+    /// Set the light sensitivity.
+    fn set_light_sensitivity(&mut self, new_state: LightSensitivity) {
+        self.config_options.light_sensitivity = new_state;
+        self.mark_dirty();
+    }
 
-/// Get the temperature preference.
-pub async fn get_temperature_preference() -> TemperaturePreference {
-    let guard = CONFIG.lock().await;
-    let state = guard.borrow().as_ref().unwrap().config_options.temp_pref;
-    drop(guard);
-    state
-}
+    /// Set the light sensor calibration.
+    fn set_light_calibration(&mut self, new_state: Option<LightCalibration>) {
+        self.config_options.light_calibration = new_state;
+        self.mark_dirty();
+    }
 
-/// Toggle the temperature preference.
-pub async fn toggle_temperature_preference() {
+    /// Set the lights out wait.
+    fn set_lights_out(&mut self, new_state: u8) {
+        self.config_options.lights_out = new_state;
+        self.mark_dirty();
+    }
+
+    /// Set the interval training protocol.
+    fn set_interval_protocol(&mut self, new_state: IntervalProtocol) {
+        self.config_options.interval_protocol = new_state;
+        self.mark_dirty();
+    }
+
+    /// Set the screensaver wait.
+    fn set_screensaver(&mut self, new_state: u8) {
+        self.config_options.screensaver = new_state;
+        self.mark_dirty();
+    }
+
+    /// Set the clock face.
+    fn set_clock_face(&mut self, new_state: ClockFace) {
+        self.config_options.clock_face = new_state;
+        self.mark_dirty();
+    }
+
+    /// Set the pause nudge state.
+    fn set_pause_nudge(&mut self, new_state: bool) {
+        self.config_options.pause_nudge = new_state;
+        self.mark_dirty();
+    }
+
+    /// Set the timer sequence.
+    fn set_timer_sequence(&mut self, new_state: TimerSequence) {
+        self.config_options.timer_sequence = new_state;
+        self.mark_dirty();
+    }
+
+    /// Set the timer presets.
+    fn set_timer_presets(&mut self, new_state: [u8; TIMER_PRESETS_SIZE]) {
+        self.config_options.timer_presets = new_state;
+        self.mark_dirty();
+    }
+
+    /// Set the do not disturb state.
+    fn set_do_not_disturb(&mut self, new_state: bool) {
+        self.config_options.do_not_disturb = new_state;
+        self.mark_dirty();
+    }
+
+    /// Set the night mute state.
+    fn set_night_mute(&mut self, new_state: bool) {
+        self.config_options.night_mute = new_state;
+        self.mark_dirty();
+    }
+
+    /// Set the still running reminder hour.
+    fn set_still_running_hour(&mut self, new_state: u8) {
+        self.config_options.still_running_hour = new_state;
+        self.mark_dirty();
+    }
+
+    /// Set the final countdown warning state.
+    fn set_final_countdown(&mut self, new_state: bool) {
+        self.config_options.final_countdown = new_state;
+        self.mark_dirty();
+    }
+
+    /// Set the pomodoro pre-finish warning minutes.
+    fn set_pre_finish_minutes(&mut self, new_state: u8) {
+        self.config_options.pre_finish_minutes = new_state;
+        self.mark_dirty();
+    }
+
+    /// Mark the config options as changed so the flush task will write them to flash.
+    fn mark_dirty(&mut self) {
+        self.dirty = true;
+        CONFIG_CHANGED.signal(ConfigChanged);
+        CONFIG_UPDATES
+            .immediate_publisher()
+            .publish_immediate(ConfigUpdated);
+    }
+
+    /// Write all the current config options to flash.
+    fn save(&mut self) {
+        event_log::record(Event::FlashWrite);
+        self.flash.write_all(&self.config_options);
+        self.dirty = false;
+    }
+
+    /// Write the current config options to flash if they have changed since the last write.
+    fn flush(&mut self) {
+        if self.dirty {
+            self.save();
+        }
+    }
+
+    /// Erase the config from flash and reboot.
+    ///
+    /// Erased flash does not match any stored value, so every option will load with its default after the reboot.
+    fn factory_reset(&mut self) {
+        self.flash.erase_all();
+        self.dirty = false;
+        self.watchdog.trigger_reset();
+    }
+}
+
+/// How long to wait after the last config change before writing to flash, in seconds.
+const FLUSH_DELAY_SECS: u64 = 5;
+
+/// Signal type for when a config option has changed.
+struct ConfigChanged;
+
+/// Signal for when a config option has changed and needs writing to flash.
+static CONFIG_CHANGED: Signal<ThreadModeRawMutex, ConfigChanged> = Signal::new();
+
+/// Message type for when a config option has changed.
+#[derive(Clone)]
+pub struct ConfigUpdated;
+
+/// The most tasks that can listen for config changes at once, the clock app and the backlight with one spare.
+///
+/// Raise this when adding another listener, as [subscribe] returns None once they are all taken.
+const CONFIG_SUBSCRIBERS: usize = 3;
+
+/// Channel for telling running tasks a config option has changed, so they can read the new value straight away.
+static CONFIG_UPDATES: PubSubChannel<ThreadModeRawMutex, ConfigUpdated, 1, CONFIG_SUBSCRIBERS, 1> =
+    PubSubChannel::new();
+
+/// A listener for config changes.
+pub type ConfigSubscriber =
+    Subscriber<'static, ThreadModeRawMutex, ConfigUpdated, 1, CONFIG_SUBSCRIBERS, 1>;
+
+/// Listen for config changes. Changes made before subscribing are not seen.
+///
+/// Only the latest change is kept, so a slow task sees several changes as one.
+/// Returns None if [CONFIG_SUBSCRIBERS] tasks are already listening.
+pub fn subscribe() -> Option<ConfigSubscriber> {
+    let sub = CONFIG_UPDATES.subscriber().ok();
+    if sub.is_none() {
+        info!("Too many config listeners, not listening for changes");
+    }
+
+    sub
+}
+
+/// Wait for the next config change. Waits forever when there is no listener, as the change would never be seen.
+pub async fn wait_for_update(sub: &mut Option<ConfigSubscriber>) {
+    match sub {
+        Some(sub) => {
+            sub.next_message().await;
+        }
+        None => core::future::pending().await,
+    }
+}
+
+/// Static reference to the config so it can be accessed by all otehr apps.
+static CONFIG: Mutex<ThreadModeRawMutex, RefCell<Option<Config>>> = Mutex::new(RefCell::new(None));
+
+/// Get the days the hourly ring is on.
+pub async fn get_hourly_ring_days() -> Days {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .hourly_ring_days;
+    drop(guard);
+    state
+}
+
+/// Set the days the hourly ring is on.
+pub async fn set_hourly_ring_days(new_state: Days) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_hourly_ring_days(new_state);
+
+    drop(guard);
+}
+
+/// Get the time colon preference.
+pub async fn get_time_colon_preference() -> TimeColonPreference {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .time_colon_pref;
+    drop(guard);
+    state
+}
+
+/// Set the time colon preference.
+pub async fn set_time_colon_preference(new_state: TimeColonPreference) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_time_colon_preference(new_state);
+
+    drop(guard);
+}
+
+/// Get the temperature preference.
+pub async fn get_temperature_preference() -> TemperaturePreference {
+    let guard = CONFIG.lock().await;
+    let state = guard.borrow().as_ref().unwrap().config_options.temp_pref;
+    drop(guard);
+    state
+}
+
+/// Set the temperature preference.
+pub async fn set_temperature_preference(new_state: TemperaturePreference) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_temperature_preference(new_state);
+
+    drop(guard);
+}
+
+/// Toggle the temperature preference.
+pub async fn toggle_temperature_preference() {
     let guard = CONFIG.lock().await;
     let state = guard.borrow().as_ref().unwrap().config_options.temp_pref;
     match state {
@@ -216,85 +1105,731 @@ pub async fn toggle_temperature_preference() {
     drop(guard);
 }
 
-/// Get the auto scroll preference.
-pub async fn get_auto_scroll_temp() -> bool {
+/// Get the auto display rotation.
+pub async fn get_display_rotation() -> DisplayRotation {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .display_rotation;
+    drop(guard);
+    state
+}
+
+/// Set the auto display rotation.
+pub async fn set_display_rotation(new_state: DisplayRotation) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_display_rotation(new_state);
+
+    drop(guard);
+}
+
+/// Get the time preference.
+pub async fn get_time_preference() -> TimePreference {
+    let guard = CONFIG.lock().await;
+    let state = guard.borrow().as_ref().unwrap().config_options.time_pref;
+    drop(guard);
+    state
+}
+
+/// Set the time preference.
+pub async fn set_time_preference(new_state: TimePreference) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_time_preference(new_state);
+
+    drop(guard);
+}
+
+/// Toggle the time preference.
+pub async fn toggle_time_preference() {
+    let guard = CONFIG.lock().await;
+    let state = guard.borrow().as_ref().unwrap().config_options.time_pref;
+
+    match state {
+        TimePreference::Twelve => guard
+            .borrow_mut()
+            .as_mut()
+            .unwrap()
+            .set_time_preference(TimePreference::TwentyFour),
+        TimePreference::TwentyFour => guard
+            .borrow_mut()
+            .as_mut()
+            .unwrap()
+            .set_time_preference(TimePreference::Twelve),
+    }
+
+    drop(guard);
+}
+
+/// Get the autolight preference.
+pub async fn get_autolight() -> bool {
+    let guard = CONFIG.lock().await;
+    let state = guard.borrow().as_ref().unwrap().config_options.autolight;
+    drop(guard);
+    state
+}
+
+/// Toggle the autolight preference.
+pub async fn toggle_autolight() -> bool {
+    let guard = CONFIG.lock().await;
+
+    let state = guard.borrow().as_ref().unwrap().config_options.autolight;
+    guard.borrow_mut().as_mut().unwrap().set_autolight(!state);
+
+    drop(guard);
+    !state
+}
+
+/// Set the autolight preference.
+#[cfg(feature = "mqtt")]
+pub async fn set_autolight(new_state: bool) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_autolight(new_state);
+
+    drop(guard);
+}
+
+/// Get the quarter chime state.
+pub async fn get_quarter_chime() -> bool {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .quarter_chime;
+    drop(guard);
+    state
+}
+
+/// Set the quarter chime state.
+pub async fn set_quarter_chime(new_state: bool) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_quarter_chime(new_state);
+
+    drop(guard);
+}
+
+/// Get the action for a button press in the clock app.
+pub async fn get_clock_action(button: ClockButton, press: &ButtonPress) -> ClockAction {
+    let index = match clock_action_index(button, press) {
+        Some(index) => index,
+        None => return ClockAction::Nothing,
+    };
+
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .clock_actions[index];
+    drop(guard);
+    state
+}
+
+/// Get the full clock actions table.
+pub async fn get_clock_actions() -> [ClockAction; CLOCK_ACTIONS_SIZE] {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .clock_actions;
+    drop(guard);
+    state
+}
+
+/// Set the full clock actions table.
+pub async fn set_clock_actions(new_state: [ClockAction; CLOCK_ACTIONS_SIZE]) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_clock_actions(new_state);
+
+    drop(guard);
+}
+
+/// Get the days night mode starts on.
+pub async fn get_night_mode_days() -> Days {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .night_mode_days;
+    drop(guard);
+    state
+}
+
+/// Set the days night mode starts on.
+pub async fn set_night_mode_days(new_state: Days) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_night_mode_days(new_state);
+
+    drop(guard);
+}
+
+/// Get the temperature precision.
+pub async fn get_temperature_precision() -> TemperaturePrecision {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .temp_precision;
+    drop(guard);
+    state
+}
+
+/// Set the temperature precision.
+pub async fn set_temperature_precision(new_state: TemperaturePrecision) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_temp_precision(new_state);
+
+    drop(guard);
+}
+
+/// Get the temperature source.
+pub async fn get_temperature_source() -> TemperatureSource {
+    let guard = CONFIG.lock().await;
+    let state = guard.borrow().as_ref().unwrap().config_options.temp_source;
+    drop(guard);
+    state
+}
+
+/// Set the temperature source.
+pub async fn set_temperature_source(new_state: TemperatureSource) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_temp_source(new_state);
+
+    drop(guard);
+}
+
+/// Get the RTC aging offset.
+pub async fn get_aging_offset() -> i8 {
+    let guard = CONFIG.lock().await;
+    let state = guard.borrow().as_ref().unwrap().config_options.aging_offset;
+    drop(guard);
+    state
+}
+
+/// Set the RTC aging offset.
+pub async fn set_aging_offset(new_state: i8) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_aging_offset(new_state);
+
+    drop(guard);
+}
+
+/// Get the wifi network credentials.
+#[cfg(any(feature = "wifi", feature = "esp-at"))]
+pub async fn get_wifi_credentials() -> WifiCredentials {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .wifi_credentials
+        .clone();
+    drop(guard);
+    state
+}
+
+/// Set the wifi network credentials.
+///
+/// Nothing sets these on the clock yet, so the build time credentials are used until then.
+#[allow(dead_code)]
+pub async fn set_wifi_credentials(new_state: WifiCredentials) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_wifi_credentials(new_state);
+
+    drop(guard);
+}
+
+/// Get the standby state.
+pub async fn get_standby() -> bool {
+    let guard = CONFIG.lock().await;
+    let state = guard.borrow().as_ref().unwrap().config_options.standby;
+    drop(guard);
+    state
+}
+
+/// Set the standby state.
+pub async fn set_standby(new_state: bool) {
+    let guard = CONFIG.lock().await;
+
+    guard.borrow_mut().as_mut().unwrap().set_standby(new_state);
+
+    drop(guard);
+}
+
+/// Get the light sensitivity.
+pub async fn get_light_sensitivity() -> LightSensitivity {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .light_sensitivity;
+    drop(guard);
+    state
+}
+
+/// Set the light sensitivity.
+pub async fn set_light_sensitivity(new_state: LightSensitivity) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_light_sensitivity(new_state);
+
+    drop(guard);
+}
+
+/// Get the light sensor calibration.
+pub async fn get_light_calibration() -> Option<LightCalibration> {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .light_calibration;
+    drop(guard);
+    state
+}
+
+/// Set the light sensor calibration.
+pub async fn set_light_calibration(new_state: Option<LightCalibration>) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_light_calibration(new_state);
+
+    drop(guard);
+}
+
+/// Get the lights out wait.
+pub async fn get_lights_out() -> u8 {
+    let guard = CONFIG.lock().await;
+    let state = guard.borrow().as_ref().unwrap().config_options.lights_out;
+    drop(guard);
+    state
+}
+
+/// Set the lights out wait.
+pub async fn set_lights_out(new_state: u8) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_lights_out(new_state);
+
+    drop(guard);
+}
+
+/// Get the interval training protocol.
+pub async fn get_interval_protocol() -> IntervalProtocol {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .interval_protocol;
+    drop(guard);
+    state
+}
+
+/// Set the interval training protocol.
+pub async fn set_interval_protocol(new_state: IntervalProtocol) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_interval_protocol(new_state);
+
+    drop(guard);
+}
+
+/// Get the screensaver wait.
+pub async fn get_screensaver() -> u8 {
+    let guard = CONFIG.lock().await;
+    let state = guard.borrow().as_ref().unwrap().config_options.screensaver;
+    drop(guard);
+    state
+}
+
+/// Set the screensaver wait.
+pub async fn set_screensaver(new_state: u8) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_screensaver(new_state);
+
+    drop(guard);
+}
+
+/// Get the clock face.
+pub async fn get_clock_face() -> ClockFace {
+    let guard = CONFIG.lock().await;
+    let state = guard.borrow().as_ref().unwrap().config_options.clock_face;
+    drop(guard);
+    state
+}
+
+/// Set the clock face.
+pub async fn set_clock_face(new_state: ClockFace) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_clock_face(new_state);
+
+    drop(guard);
+}
+
+/// Get the pause nudge state.
+pub async fn get_pause_nudge() -> bool {
+    let guard = CONFIG.lock().await;
+    let state = guard.borrow().as_ref().unwrap().config_options.pause_nudge;
+    drop(guard);
+    state
+}
+
+/// Set the pause nudge state.
+pub async fn set_pause_nudge(new_state: bool) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_pause_nudge(new_state);
+
+    drop(guard);
+}
+
+/// Get the timer sequence.
+pub async fn get_timer_sequence() -> TimerSequence {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .timer_sequence;
+    drop(guard);
+    state
+}
+
+/// Set the timer sequence.
+pub async fn set_timer_sequence(new_state: TimerSequence) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_timer_sequence(new_state);
+
+    drop(guard);
+}
+
+/// Get the timer presets.
+pub async fn get_timer_presets() -> [u8; TIMER_PRESETS_SIZE] {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .timer_presets;
+    drop(guard);
+    state
+}
+
+/// Set the timer presets.
+pub async fn set_timer_presets(new_state: [u8; TIMER_PRESETS_SIZE]) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_timer_presets(new_state);
+
+    drop(guard);
+}
+
+/// Get the do not disturb state.
+pub async fn get_do_not_disturb() -> bool {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .do_not_disturb;
+    drop(guard);
+    state
+}
+
+/// Set the do not disturb state.
+pub async fn set_do_not_disturb(new_state: bool) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_do_not_disturb(new_state);
+
+    drop(guard);
+}
+
+/// Get the night mute state.
+pub async fn get_night_mute() -> bool {
+    let guard = CONFIG.lock().await;
+    let state = guard.borrow().as_ref().unwrap().config_options.night_mute;
+    drop(guard);
+    state
+}
+
+/// Set the night mute state.
+pub async fn set_night_mute(new_state: bool) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_night_mute(new_state);
+
+    drop(guard);
+}
+
+/// Determine if the speaker should be quiet at the passed time (24hr), as night mute is on and it is a night that night mode is on for.
+pub async fn is_quiet_time(day: Weekday, hour: u32) -> bool {
+    get_night_mute().await && is_night_on(get_night_mode_days().await, day, hour)
+}
+
+/// Get the still running reminder hour.
+pub async fn get_still_running_hour() -> u8 {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .still_running_hour;
+    drop(guard);
+    state
+}
+
+/// Set the still running reminder hour.
+pub async fn set_still_running_hour(new_state: u8) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_still_running_hour(new_state);
+
+    drop(guard);
+}
+
+/// Get the final countdown warning state.
+pub async fn get_final_countdown() -> bool {
+    let guard = CONFIG.lock().await;
+    let state = guard
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .config_options
+        .final_countdown;
+    drop(guard);
+    state
+}
+
+/// Set the final countdown warning state.
+pub async fn set_final_countdown(new_state: bool) {
+    let guard = CONFIG.lock().await;
+
+    guard
+        .borrow_mut()
+        .as_mut()
+        .unwrap()
+        .set_final_countdown(new_state);
+
+    drop(guard);
+}
+
+/// Get the pomodoro pre-finish warning minutes.
+pub async fn get_pre_finish_minutes() -> u8 {
     let guard = CONFIG.lock().await;
     let state = guard
         .borrow()
         .as_ref()
         .unwrap()
         .config_options
-        .auto_scroll_temp;
+        .pre_finish_minutes;
     drop(guard);
     state
 }
 
-/// Set the auto scroll preference.
-pub async fn set_auto_scroll_temp(new_state: bool) {
+/// Set the pomodoro pre-finish warning minutes.
+pub async fn set_pre_finish_minutes(new_state: u8) {
     let guard = CONFIG.lock().await;
 
     guard
         .borrow_mut()
         .as_mut()
         .unwrap()
-        .set_auto_scroll_temp(new_state);
+        .set_pre_finish_minutes(new_state);
 
     drop(guard);
 }
 
-/// Get the time preference.
-pub async fn get_time_preference() -> TimePreference {
-    let guard = CONFIG.lock().await;
-    let state = guard.borrow().as_ref().unwrap().config_options.time_pref;
-    drop(guard);
-    state
+/// Wait for the config to be initialised, for tasks that are started before [init] is called.
+#[cfg(any(feature = "wifi", feature = "esp-at"))]
+pub async fn wait_for_init() {
+    while CONFIG.lock().await.borrow().is_none() {
+        Timer::after(Duration::from_millis(10)).await;
+    }
 }
 
-/// Toggle the time preference.
-pub async fn toggle_time_preference() {
+/// Force all the current config options to be written to flash.
+pub async fn save() {
     let guard = CONFIG.lock().await;
-    let state = guard.borrow().as_ref().unwrap().config_options.time_pref;
 
-    match state {
-        TimePreference::Twelve => guard
-            .borrow_mut()
-            .as_mut()
-            .unwrap()
-            .set_time_preference(TimePreference::TwentyFour),
-        TimePreference::TwentyFour => guard
-            .borrow_mut()
-            .as_mut()
-            .unwrap()
-            .set_time_preference(TimePreference::Twelve),
-    }
+    guard.borrow_mut().as_mut().unwrap().save();
 
     drop(guard);
 }
 
-/// Get the autolight preference.
-pub async fn get_autolight() -> bool {
+/// Write the config to flash now if it has changed, instead of waiting for the flush task.
+pub async fn flush() {
     let guard = CONFIG.lock().await;
-    let state = guard.borrow().as_ref().unwrap().config_options.autolight;
+
+    guard.borrow_mut().as_mut().unwrap().flush();
+
     drop(guard);
-    state
 }
 
-/// Toggle the autolight preference.
-pub async fn toggle_autolight() -> bool {
+/// Write config changes to flash once no changes have been made for `FLUSH_DELAY_SECS`.
+///
+/// Erasing flash blocks the executor, so this stops rapid changes from writing to flash each time.
+#[embassy_executor::task]
+pub async fn flush_task() {
+    loop {
+        CONFIG_CHANGED.wait().await;
+
+        // keep waiting while changes are still being made
+        while let Either::First(_) = select(
+            CONFIG_CHANGED.wait(),
+            Timer::after(Duration::from_secs(FLUSH_DELAY_SECS)),
+        )
+        .await
+        {}
+
+        flush().await;
+    }
+}
+
+/// Erase all config from flash and the data kept in the EEPROM, restoring the defaults, and reboot the clock.
+pub async fn factory_reset() {
+    // otherwise a paused timer, the drift and the temperature range would come back after the reset
+    for (start, end) in eeprom::REGIONS {
+        if let Err(e) = eeprom::erase(start, end).await {
+            info!("Failed to erase EEPROM region {}: {}", start, e);
+        }
+    }
+
     let guard = CONFIG.lock().await;
 
-    let state = guard.borrow().as_ref().unwrap().config_options.autolight;
-    guard.borrow_mut().as_mut().unwrap().set_autolight(!state);
+    guard.borrow_mut().as_mut().unwrap().factory_reset();
 
     drop(guard);
-    !state
 }
 
 /// Init the config. Must have an initialised flash memory.
 pub async fn init(
     flash: Flash<'static, embassy_rp::peripherals::FLASH, Async, { flash_config::FLASH_SIZE }>,
+    watchdog: Watchdog,
 ) {
-    let config = Config::new(flash).await;
+    let config = Config::new(flash, watchdog).await;
     CONFIG.lock().await.replace(Some(config));
 }
 
@@ -308,18 +1843,78 @@ pub mod flash_config {
     /// The initial offset of where to save the config in flash.
     pub const ADDR_OFFSET: u32 = 0x100000;
 
-    /// The offset and end offset for hourly ring.
+    /// The offset and end offset for hourly ring days.
     const HOURLY_RING: (usize, usize) = (10, 11);
     /// The offset and end offset for time colon preference.
     const TIME_COLON_PREF: (usize, usize) = (HOURLY_RING.0 + 10, HOURLY_RING.0 + 11);
     /// The offset and end offset for temperature preference.
     const TEMP_PREF: (usize, usize) = (TIME_COLON_PREF.0 + 10, TIME_COLON_PREF.0 + 11);
-    /// The offset and end offset for auto scrolling features.
-    const AUTO_SCROLL_TEMP: (usize, usize) = (TEMP_PREF.0 + 10, TEMP_PREF.0 + 11);
+    /// The offset and end offset for the auto display rotation items.
+    ///
+    /// This was the auto scroll temperature flag, so a saved true value is read as only the temperature enabled.
+    const DISPLAY_ROTATION: (usize, usize) = (TEMP_PREF.0 + 10, TEMP_PREF.0 + 11);
     /// The offset and end offset for time hour preference.
-    const TIME_PREF: (usize, usize) = (AUTO_SCROLL_TEMP.0 + 10, AUTO_SCROLL_TEMP.0 + 11);
+    const TIME_PREF: (usize, usize) = (DISPLAY_ROTATION.0 + 10, DISPLAY_ROTATION.0 + 11);
     /// The offset and end offset for autolight.
     const AUTOLIGHT: (usize, usize) = (TIME_PREF.0 + 10, TIME_PREF.0 + 11);
+    /// The offset and end offset for the quarter chime.
+    const QUARTER_CHIME: (usize, usize) = (AUTOLIGHT.0 + 10, AUTOLIGHT.0 + 11);
+    /// The offset and end offset for the clock actions table.
+    const CLOCK_ACTIONS: (usize, usize) = (
+        QUARTER_CHIME.0 + 10,
+        QUARTER_CHIME.0 + 10 + CLOCK_ACTIONS_SIZE,
+    );
+    /// The offset and end offset for night mode days.
+    const NIGHT_MODE: (usize, usize) = (CLOCK_ACTIONS.1 + 10, CLOCK_ACTIONS.1 + 11);
+    /// The offset and end offset for the auto display rotation interval.
+    const ROTATION_INTERVAL: (usize, usize) = (NIGHT_MODE.0 + 10, NIGHT_MODE.0 + 11);
+    /// The offset and end offset for the temperature precision.
+    const TEMP_PRECISION: (usize, usize) = (ROTATION_INTERVAL.1 + 10, ROTATION_INTERVAL.1 + 11);
+    /// The offset and end offset for the temperature source.
+    const TEMP_SOURCE: (usize, usize) = (TEMP_PRECISION.1 + 10, TEMP_PRECISION.1 + 11);
+    /// The offset and end offset for the RTC aging offset.
+    const AGING_OFFSET: (usize, usize) = (TEMP_SOURCE.1 + 10, TEMP_SOURCE.1 + 12);
+    /// The offset and end offset for the wifi network credentials.
+    const WIFI_CREDENTIALS: (usize, usize) = (
+        AGING_OFFSET.1 + 10,
+        AGING_OFFSET.1 + 10 + WIFI_CREDENTIALS_SIZE,
+    );
+    /// The offset and end offset for the standby state.
+    const STANDBY: (usize, usize) = (WIFI_CREDENTIALS.1 + 10, WIFI_CREDENTIALS.1 + 11);
+    /// The offset and end offset for the light sensitivity.
+    const LIGHT_SENSITIVITY: (usize, usize) = (STANDBY.1 + 10, STANDBY.1 + 11);
+    /// The offset and end offset for the light sensor calibration.
+    const LIGHT_CALIBRATION: (usize, usize) = (LIGHT_SENSITIVITY.1 + 10, LIGHT_SENSITIVITY.1 + 14);
+    /// The offset and end offset for the lights out wait.
+    const LIGHTS_OUT: (usize, usize) = (LIGHT_CALIBRATION.1 + 10, LIGHT_CALIBRATION.1 + 11);
+    /// The offset and end offset for the interval training protocol.
+    const INTERVAL_PROTOCOL: (usize, usize) = (LIGHTS_OUT.1 + 10, LIGHTS_OUT.1 + 15);
+    /// The offset and end offset for the screensaver wait.
+    const SCREENSAVER: (usize, usize) = (INTERVAL_PROTOCOL.1 + 10, INTERVAL_PROTOCOL.1 + 11);
+    /// The offset and end offset for the clock face.
+    const CLOCK_FACE: (usize, usize) = (SCREENSAVER.1 + 10, SCREENSAVER.1 + 11);
+    /// The offset and end offset for the pause nudge state.
+    const PAUSE_NUDGE: (usize, usize) = (CLOCK_FACE.1 + 10, CLOCK_FACE.1 + 11);
+    /// The offset and end offset for the timer sequence.
+    const TIMER_SEQUENCE: (usize, usize) = (PAUSE_NUDGE.1 + 10, PAUSE_NUDGE.1 + 20);
+    /// The offset and end offset for the timer presets.
+    const TIMER_PRESETS: (usize, usize) = (
+        TIMER_SEQUENCE.1 + 10,
+        TIMER_SEQUENCE.1 + 10 + TIMER_PRESETS_SIZE,
+    );
+    /// The offset and end offset for the do not disturb state.
+    const DO_NOT_DISTURB: (usize, usize) = (TIMER_PRESETS.1 + 10, TIMER_PRESETS.1 + 11);
+    /// The offset and end offset for the night mute state.
+    const NIGHT_MUTE: (usize, usize) = (DO_NOT_DISTURB.1 + 10, DO_NOT_DISTURB.1 + 11);
+    /// The offset and end offset for the still running reminder hour.
+    const STILL_RUNNING_HOUR: (usize, usize) = (NIGHT_MUTE.1 + 10, NIGHT_MUTE.1 + 11);
+    /// The offset and end offset for the final countdown warning state.
+    const FINAL_COUNTDOWN: (usize, usize) = (STILL_RUNNING_HOUR.1 + 10, STILL_RUNNING_HOUR.1 + 11);
+    /// The offset and end offset for the pomodoro pre-finish warning minutes.
+    const PRE_FINISH_MINUTES: (usize, usize) = (FINAL_COUNTDOWN.1 + 10, FINAL_COUNTDOWN.1 + 11);
+
+    /// The size of the stored wifi credentials, with a length byte before each value.
+    const WIFI_CREDENTIALS_SIZE: usize = 1 + WIFI_SSID_SIZE + 1 + WIFI_PASSWORD_SIZE;
 
     /// Bytes to use to reperesent a false value.
     const FALSE_BYTES: u8 = 0x00;
@@ -327,6 +1922,12 @@ pub mod flash_config {
     /// Bytes to use to represent a true value.
     const TRUE_BYTES: u8 = 0x01;
 
+    /// Bytes read from flash that has been erased and not written to.
+    const ERASED_BYTES: u8 = 0xFF;
+
+    /// The flag set on a byte of [Days], as on and off were stored in the same byte before.
+    const DAYS_FLAG: u8 = 0x80;
+
     /// Trait to overload embassy flash.
     pub trait FlashOveride {
         /// Read all flash bytes from *ADDR_OFFSET*.
@@ -334,6 +1935,9 @@ pub mod flash_config {
 
         /// Write all config into flash.
         fn write_all(&mut self, state: &ConfigOptions);
+
+        /// Erase all config from flash.
+        fn erase_all(&mut self);
     }
 
     impl FlashOveride for Flash<'static, embassy_rp::peripherals::FLASH, Async, FLASH_SIZE> {
@@ -345,37 +1949,87 @@ pub mod flash_config {
 
         fn write_all(&mut self, state: &ConfigOptions) {
             // erase everything first
-            self.blocking_erase(ADDR_OFFSET, ADDR_OFFSET + ERASE_SIZE as u32)
-                .unwrap();
+            self.erase_all();
 
             let mut read_buf = [0u8; ERASE_SIZE];
-            read_buf[HOURLY_RING.0] = hourly_ring_to_bytes(state.hourly_ring);
+            read_buf[HOURLY_RING.0] = hourly_ring_days_to_bytes(state.hourly_ring_days);
             read_buf[TIME_COLON_PREF.0] = time_colon_to_bytes(state.time_colon_pref);
             read_buf[TEMP_PREF.0] = temp_pref_to_bytes(state.temp_pref);
-            read_buf[AUTO_SCROLL_TEMP.0] = auto_scroll_temp_to_bytes(state.auto_scroll_temp);
+            let (items, interval) = display_rotation_to_bytes(state.display_rotation);
+            read_buf[DISPLAY_ROTATION.0] = items;
+            read_buf[ROTATION_INTERVAL.0] = interval;
             read_buf[TIME_PREF.0] = time_pref_to_bytes(state.time_pref);
             read_buf[AUTOLIGHT.0] = autolight_to_bytes(state.autolight);
+            read_buf[QUARTER_CHIME.0] = quarter_chime_to_bytes(state.quarter_chime);
+            read_buf[CLOCK_ACTIONS.0..CLOCK_ACTIONS.1]
+                .copy_from_slice(&clock_actions_to_bytes(state.clock_actions));
+            read_buf[NIGHT_MODE.0] = night_mode_days_to_bytes(state.night_mode_days);
+            read_buf[TEMP_PRECISION.0] = temp_precision_to_bytes(state.temp_precision);
+            read_buf[TEMP_SOURCE.0] = temp_source_to_bytes(state.temp_source);
+            read_buf[AGING_OFFSET.0..AGING_OFFSET.1]
+                .copy_from_slice(&aging_offset_to_bytes(state.aging_offset));
+            read_buf[WIFI_CREDENTIALS.0..WIFI_CREDENTIALS.1]
+                .copy_from_slice(&wifi_credentials_to_bytes(&state.wifi_credentials));
+            read_buf[STANDBY.0] = standby_to_bytes(state.standby);
+            read_buf[LIGHT_SENSITIVITY.0] = light_sensitivity_to_bytes(state.light_sensitivity);
+            read_buf[LIGHT_CALIBRATION.0..LIGHT_CALIBRATION.1]
+                .copy_from_slice(&light_calibration_to_bytes(state.light_calibration));
+            read_buf[LIGHTS_OUT.0] = lights_out_to_bytes(state.lights_out);
+            read_buf[INTERVAL_PROTOCOL.0..INTERVAL_PROTOCOL.1]
+                .copy_from_slice(&interval_protocol_to_bytes(state.interval_protocol));
+            read_buf[SCREENSAVER.0] = screensaver_to_bytes(state.screensaver);
+            read_buf[CLOCK_FACE.0] = clock_face_to_bytes(state.clock_face);
+            read_buf[PAUSE_NUDGE.0] = pause_nudge_to_bytes(state.pause_nudge);
+            read_buf[TIMER_SEQUENCE.0..TIMER_SEQUENCE.1]
+                .copy_from_slice(&timer_sequence_to_bytes(state.timer_sequence));
+            read_buf[TIMER_PRESETS.0..TIMER_PRESETS.1]
+                .copy_from_slice(&timer_presets_to_bytes(state.timer_presets));
+            read_buf[DO_NOT_DISTURB.0] = do_not_disturb_to_bytes(state.do_not_disturb);
+            read_buf[NIGHT_MUTE.0] = night_mute_to_bytes(state.night_mute);
+            read_buf[STILL_RUNNING_HOUR.0] = still_running_hour_to_bytes(state.still_running_hour);
+            read_buf[FINAL_COUNTDOWN.0] = final_countdown_to_bytes(state.final_countdown);
+            read_buf[PRE_FINISH_MINUTES.0] = pre_finish_minutes_to_bytes(state.pre_finish_minutes);
 
             self.blocking_write(ADDR_OFFSET, &read_buf).unwrap();
         }
-    }
 
-    /// Get the hourly ring config from the full flash byte array.
-    pub fn hourly_ring_from_bytes(bytes: &[u8; ERASE_SIZE]) -> bool {
-        let state_bytes = &bytes[HOURLY_RING.0..HOURLY_RING.1];
-        if state_bytes == [TRUE_BYTES] {
-            return true;
+        fn erase_all(&mut self) {
+            self.blocking_erase(ADDR_OFFSET, ADDR_OFFSET + ERASE_SIZE as u32)
+                .unwrap();
         }
+    }
 
-        false
+    /// Get the hourly ring days config from the full flash byte array.
+    pub fn hourly_ring_days_from_bytes(bytes: &[u8; ERASE_SIZE]) -> Days {
+        days_from_byte(bytes[HOURLY_RING.0])
     }
 
-    /// Convert the hourly ring state to bytes.
-    pub fn hourly_ring_to_bytes(state: bool) -> u8 {
-        if state {
-            TRUE_BYTES
-        } else {
-            FALSE_BYTES
+    /// Convert the hourly ring days to bytes.
+    pub fn hourly_ring_days_to_bytes(state: Days) -> u8 {
+        days_to_byte(state)
+    }
+
+    /// Read a set of days from a byte that held an on or off state before days were added.
+    ///
+    /// The days are stored with the top bit set, so they can not be mistaken for the on and off bytes.
+    /// On is every day, and off or erased flash is no days.
+    fn days_from_byte(byte: u8) -> Days {
+        match byte {
+            TRUE_BYTES => Days::EVERY_DAY,
+            ERASED_BYTES => Days::NONE,
+            b if b & DAYS_FLAG != 0 => Days(b & !DAYS_FLAG),
+            _ => Days::NONE,
+        }
+    }
+
+    /// Convert a set of days to a byte that can be read by [days_from_byte].
+    ///
+    /// Every day and no days are still stored as on and off.
+    fn days_to_byte(days: Days) -> u8 {
+        match days {
+            Days::EVERY_DAY => TRUE_BYTES,
+            Days::NONE => FALSE_BYTES,
+            Days(days) => days | DAYS_FLAG,
         }
     }
 
@@ -386,6 +2040,7 @@ pub mod flash_config {
             [0x00] => TimeColonPreference::Alt,
             [0x01] => TimeColonPreference::Blink,
             [0x02] => TimeColonPreference::Solid,
+            [0x03] => TimeColonPreference::Seconds,
             _ => TimeColonPreference::Blink,
         }
     }
@@ -396,6 +2051,7 @@ pub mod flash_config {
             TimeColonPreference::Alt => 0x00,
             TimeColonPreference::Blink => 0x01,
             TimeColonPreference::Solid => 0x02,
+            TimeColonPreference::Seconds => 0x03,
         }
     }
 
@@ -417,23 +2073,32 @@ pub mod flash_config {
         }
     }
 
-    /// Get the auto scroll feature config from the full flash byte array.
-    pub fn auto_scroll_temp_from_bytes(bytes: &[u8; ERASE_SIZE]) -> bool {
-        let state_bytes = &bytes[AUTO_SCROLL_TEMP.0..AUTO_SCROLL_TEMP.1];
-        if state_bytes == [TRUE_BYTES] {
-            return true;
+    /// Get the auto display rotation config from the full flash byte array.
+    ///
+    /// Erased flash will have no items enabled, and an unknown interval will use the default.
+    pub fn display_rotation_from_bytes(bytes: &[u8; ERASE_SIZE]) -> DisplayRotation {
+        let mut state = DisplayRotation::new();
+
+        let items = bytes[DISPLAY_ROTATION.0];
+        if items != ERASED_BYTES {
+            for item in ROTATION_ITEMS {
+                state.set_enabled(item, items & item.mask() != 0);
+            }
         }
 
-        false
+        state.interval = match bytes[ROTATION_INTERVAL.0] {
+            1 => RotationInterval::One,
+            10 => RotationInterval::Ten,
+            30 => RotationInterval::Thirty,
+            _ => RotationInterval::Five,
+        };
+
+        state
     }
 
-    /// Convert the auto scroll feature state to bytes.
-    pub fn auto_scroll_temp_to_bytes(state: bool) -> u8 {
-        if state {
-            TRUE_BYTES
-        } else {
-            FALSE_BYTES
-        }
+    /// Convert the auto display rotation config to bytes. (items, interval)
+    pub fn display_rotation_to_bytes(state: DisplayRotation) -> (u8, u8) {
+        (state.items, state.interval.minutes() as u8)
     }
 
     /// Get the time preference config from the full flash byte array.
@@ -472,4 +2137,456 @@ pub mod flash_config {
             FALSE_BYTES
         }
     }
+
+    /// Get the quarter chime config from the full flash byte array.
+    pub fn quarter_chime_from_bytes(bytes: &[u8; ERASE_SIZE]) -> bool {
+        let state_bytes = &bytes[QUARTER_CHIME.0..QUARTER_CHIME.1];
+        if state_bytes == [TRUE_BYTES] {
+            return true;
+        }
+
+        false
+    }
+
+    /// Convert the quarter chime state to bytes.
+    pub fn quarter_chime_to_bytes(state: bool) -> u8 {
+        if state {
+            TRUE_BYTES
+        } else {
+            FALSE_BYTES
+        }
+    }
+
+    /// Get the clock actions table from the full flash byte array.
+    ///
+    /// Any unknown bytes will use the default action for that button press.
+    pub fn clock_actions_from_bytes(bytes: &[u8; ERASE_SIZE]) -> [ClockAction; CLOCK_ACTIONS_SIZE] {
+        let state_bytes = &bytes[CLOCK_ACTIONS.0..CLOCK_ACTIONS.1];
+
+        let mut actions = CLOCK_ACTIONS_DEFAULT;
+        for (action, byte) in actions.iter_mut().zip(state_bytes) {
+            *action = match byte {
+                0x01 => ClockAction::Nothing,
+                0x02 => ClockAction::ShowTemperature,
+                0x03 => ClockAction::ToggleTemperaturePreference,
+                0x04 => ClockAction::ToggleTimePreference,
+                0x05 => ClockAction::ShowDate,
+                0x06 => ClockAction::ShowMessage,
+                0x07 => ClockAction::ShowTemperatureRange,
+                0x08 => ClockAction::CycleTimeColon,
+                0x09 => ClockAction::ShowEnvironment,
+                _ => *action,
+            };
+        }
+
+        actions
+    }
+
+    /// Convert the clock actions table to bytes.
+    pub fn clock_actions_to_bytes(
+        state: [ClockAction; CLOCK_ACTIONS_SIZE],
+    ) -> [u8; CLOCK_ACTIONS_SIZE] {
+        state.map(|action| match action {
+            ClockAction::Nothing => 0x01,
+            ClockAction::ShowTemperature => 0x02,
+            ClockAction::ToggleTemperaturePreference => 0x03,
+            ClockAction::ToggleTimePreference => 0x04,
+            ClockAction::ShowDate => 0x05,
+            ClockAction::ShowMessage => 0x06,
+            ClockAction::ShowTemperatureRange => 0x07,
+            ClockAction::CycleTimeColon => 0x08,
+            ClockAction::ShowEnvironment => 0x09,
+        })
+    }
+
+    /// Get the night mode days config from the full flash byte array.
+    pub fn night_mode_days_from_bytes(bytes: &[u8; ERASE_SIZE]) -> Days {
+        days_from_byte(bytes[NIGHT_MODE.0])
+    }
+
+    /// Convert the night mode days to bytes.
+    pub fn night_mode_days_to_bytes(state: Days) -> u8 {
+        days_to_byte(state)
+    }
+
+    /// Get the temperature precision config from the full flash byte array.
+    pub fn temp_precision_from_bytes(bytes: &[u8; ERASE_SIZE]) -> TemperaturePrecision {
+        let state_bytes = &bytes[TEMP_PRECISION.0..TEMP_PRECISION.1];
+        if state_bytes == [0x01] {
+            return TemperaturePrecision::Tenths;
+        }
+
+        TemperaturePrecision::Whole
+    }
+
+    /// Convert the temperature precision to bytes.
+    pub fn temp_precision_to_bytes(state: TemperaturePrecision) -> u8 {
+        match state {
+            TemperaturePrecision::Whole => 0x00,
+            TemperaturePrecision::Tenths => 0x01,
+        }
+    }
+
+    /// Get the temperature source config from the full flash byte array.
+    pub fn temp_source_from_bytes(bytes: &[u8; ERASE_SIZE]) -> TemperatureSource {
+        let state_bytes = &bytes[TEMP_SOURCE.0..TEMP_SOURCE.1];
+        if state_bytes == [0x01] {
+            return TemperatureSource::External;
+        }
+
+        TemperatureSource::Rtc
+    }
+
+    /// Convert the temperature source to bytes.
+    pub fn temp_source_to_bytes(state: TemperatureSource) -> u8 {
+        match state {
+            TemperatureSource::Rtc => 0x00,
+            TemperatureSource::External => 0x01,
+        }
+    }
+
+    /// Get the RTC aging offset config from the full flash byte array.
+    pub fn aging_offset_from_bytes(bytes: &[u8; ERASE_SIZE]) -> i8 {
+        // the first byte marks the offset as set, as an erased 0xFF byte would otherwise be -1
+        let state_bytes = &bytes[AGING_OFFSET.0..AGING_OFFSET.1];
+        if state_bytes[0] == TRUE_BYTES {
+            return state_bytes[1] as i8;
+        }
+
+        0
+    }
+
+    /// Convert the RTC aging offset to bytes.
+    pub fn aging_offset_to_bytes(state: i8) -> [u8; 2] {
+        [TRUE_BYTES, state as u8]
+    }
+
+    /// Get a string stored as a length byte followed by the value.
+    ///
+    /// Returns [None](Option::None) if the length is not valid, such as erased flash, or the value is not valid UTF-8.
+    fn str_from_bytes(bytes: &[u8]) -> Option<&str> {
+        let len = bytes[0] as usize;
+        if len >= bytes.len() {
+            return None;
+        }
+
+        core::str::from_utf8(&bytes[1..1 + len]).ok()
+    }
+
+    /// Get the wifi network credentials config from the full flash byte array.
+    pub fn wifi_credentials_from_bytes(bytes: &[u8; ERASE_SIZE]) -> WifiCredentials {
+        // each value is stored as a length byte followed by the value, padded to the maximum size
+        let state_bytes = &bytes[WIFI_CREDENTIALS.0..WIFI_CREDENTIALS.1];
+        let (ssid_bytes, password_bytes) = state_bytes.split_at(1 + WIFI_SSID_SIZE);
+
+        // configs saved before the credentials were added have zero bytes here, which read as an empty ssid,
+        // and a network can not have an empty ssid, so treat it as unset
+        match (str_from_bytes(ssid_bytes), str_from_bytes(password_bytes)) {
+            (Some(ssid), Some(password)) if !ssid.is_empty() => {
+                WifiCredentials::new(ssid, password)
+            }
+            _ => WifiCredentials::from_build_env(),
+        }
+    }
+
+    /// Convert the wifi network credentials to bytes.
+    pub fn wifi_credentials_to_bytes(state: &WifiCredentials) -> [u8; WIFI_CREDENTIALS_SIZE] {
+        let mut bytes = [0u8; WIFI_CREDENTIALS_SIZE];
+
+        bytes[0] = state.ssid.len() as u8;
+        bytes[1..1 + state.ssid.len()].copy_from_slice(state.ssid.as_bytes());
+
+        let password_offset = 1 + WIFI_SSID_SIZE;
+        bytes[password_offset] = state.password.len() as u8;
+        bytes[password_offset + 1..password_offset + 1 + state.password.len()]
+            .copy_from_slice(state.password.as_bytes());
+
+        bytes
+    }
+
+    /// Get the standby state config from the full flash byte array.
+    pub fn standby_from_bytes(bytes: &[u8; ERASE_SIZE]) -> bool {
+        let state_bytes = &bytes[STANDBY.0..STANDBY.1];
+        if state_bytes == [TRUE_BYTES] {
+            return true;
+        }
+
+        false
+    }
+
+    /// Convert the standby state to bytes.
+    pub fn standby_to_bytes(state: bool) -> u8 {
+        if state {
+            TRUE_BYTES
+        } else {
+            FALSE_BYTES
+        }
+    }
+
+    /// Get the light sensitivity config from the full flash byte array.
+    pub fn light_sensitivity_from_bytes(bytes: &[u8; ERASE_SIZE]) -> LightSensitivity {
+        let state_bytes = &bytes[LIGHT_SENSITIVITY.0..LIGHT_SENSITIVITY.1];
+        // normal is 0, so configs saved before this setting existed read as normal
+        match state_bytes {
+            [0x01] => LightSensitivity::Low,
+            [0x02] => LightSensitivity::High,
+            _ => LightSensitivity::Normal,
+        }
+    }
+
+    /// Convert the light sensitivity to bytes.
+    pub fn light_sensitivity_to_bytes(state: LightSensitivity) -> u8 {
+        match state {
+            LightSensitivity::Normal => 0x00,
+            LightSensitivity::Low => 0x01,
+            LightSensitivity::High => 0x02,
+        }
+    }
+
+    /// Get the light sensor calibration config from the full flash byte array.
+    pub fn light_calibration_from_bytes(bytes: &[u8; ERASE_SIZE]) -> Option<LightCalibration> {
+        let state_bytes = &bytes[LIGHT_CALIBRATION.0..LIGHT_CALIBRATION.1];
+        let calibration = LightCalibration {
+            dark: u16::from_le_bytes([state_bytes[0], state_bytes[1]]),
+            bright: u16::from_le_bytes([state_bytes[2], state_bytes[3]]),
+        };
+
+        // erased bytes, or the zeros from configs saved before calibration existed, are not valid
+        if calibration.is_valid() {
+            Some(calibration)
+        } else {
+            None
+        }
+    }
+
+    /// Convert the light sensor calibration to bytes.
+    pub fn light_calibration_to_bytes(state: Option<LightCalibration>) -> [u8; 4] {
+        match state {
+            Some(calibration) => {
+                let mut bytes = [0u8; 4];
+                bytes[0..2].copy_from_slice(&calibration.dark.to_le_bytes());
+                bytes[2..4].copy_from_slice(&calibration.bright.to_le_bytes());
+                bytes
+            }
+            None => [ERASED_BYTES; 4],
+        }
+    }
+
+    /// Get the lights out wait config from the full flash byte array.
+    pub fn lights_out_from_bytes(bytes: &[u8; ERASE_SIZE]) -> u8 {
+        let state_bytes = &bytes[LIGHTS_OUT.0..LIGHTS_OUT.1];
+        match state_bytes {
+            [ERASED_BYTES] => 0,
+            [mins] => *mins,
+            _ => 0,
+        }
+    }
+
+    /// Convert the lights out wait to bytes.
+    pub fn lights_out_to_bytes(state: u8) -> u8 {
+        state
+    }
+
+    /// Get the interval training protocol config from the full flash byte array.
+    pub fn interval_protocol_from_bytes(bytes: &[u8; ERASE_SIZE]) -> IntervalProtocol {
+        let state_bytes = &bytes[INTERVAL_PROTOCOL.0..INTERVAL_PROTOCOL.1];
+        let protocol = IntervalProtocol {
+            work_secs: u16::from_le_bytes([state_bytes[0], state_bytes[1]]),
+            rest_secs: u16::from_le_bytes([state_bytes[2], state_bytes[3]]),
+            rounds: state_bytes[4],
+        };
+
+        // erased bytes, or the zeros from configs saved before interval training existed, are not valid
+        if protocol.is_valid() {
+            protocol
+        } else {
+            IntervalProtocol::DEFAULT
+        }
+    }
+
+    /// Convert the interval training protocol to bytes.
+    pub fn interval_protocol_to_bytes(state: IntervalProtocol) -> [u8; 5] {
+        let mut bytes = [0u8; 5];
+        bytes[0..2].copy_from_slice(&state.work_secs.to_le_bytes());
+        bytes[2..4].copy_from_slice(&state.rest_secs.to_le_bytes());
+        bytes[4] = state.rounds;
+        bytes
+    }
+
+    /// Get the screensaver wait config from the full flash byte array.
+    pub fn screensaver_from_bytes(bytes: &[u8; ERASE_SIZE]) -> u8 {
+        let state_bytes = &bytes[SCREENSAVER.0..SCREENSAVER.1];
+        match state_bytes {
+            [ERASED_BYTES] => 0,
+            [mins] => *mins,
+            _ => 0,
+        }
+    }
+
+    /// Convert the screensaver wait to bytes.
+    pub fn screensaver_to_bytes(state: u8) -> u8 {
+        state
+    }
+
+    /// Get the clock face config from the full flash byte array.
+    pub fn clock_face_from_bytes(bytes: &[u8; ERASE_SIZE]) -> ClockFace {
+        let state_bytes = &bytes[CLOCK_FACE.0..CLOCK_FACE.1];
+        match state_bytes {
+            [0x00] => ClockFace::Digits,
+            [0x01] => ClockFace::Words,
+            [0x02] => ClockFace::Stacked,
+            _ => ClockFace::Digits,
+        }
+    }
+
+    /// Convert the clock face to bytes.
+    pub fn clock_face_to_bytes(state: ClockFace) -> u8 {
+        match state {
+            ClockFace::Digits => 0x00,
+            ClockFace::Words => 0x01,
+            ClockFace::Stacked => 0x02,
+        }
+    }
+
+    /// Get the pause nudge state config from the full flash byte array.
+    ///
+    /// Stored as whether the nudge is turned off, so it is on for configs saved before it was added.
+    pub fn pause_nudge_from_bytes(bytes: &[u8; ERASE_SIZE]) -> bool {
+        let state_bytes = &bytes[PAUSE_NUDGE.0..PAUSE_NUDGE.1];
+        state_bytes != [TRUE_BYTES]
+    }
+
+    /// Convert the pause nudge state to bytes.
+    pub fn pause_nudge_to_bytes(state: bool) -> u8 {
+        if state {
+            FALSE_BYTES
+        } else {
+            TRUE_BYTES
+        }
+    }
+
+    /// Get the timer sequence config from the full flash byte array.
+    pub fn timer_sequence_from_bytes(bytes: &[u8; ERASE_SIZE]) -> TimerSequence {
+        let state_bytes = &bytes[TIMER_SEQUENCE.0..TIMER_SEQUENCE.1];
+        let mut sequence = TimerSequence {
+            steps_secs: [0; TimerSequence::MAX_STEPS],
+        };
+        for (step, chunk) in sequence.steps_secs.iter_mut().zip(state_bytes.chunks(2)) {
+            *step = u16::from_le_bytes([chunk[0], chunk[1]]);
+        }
+
+        // erased bytes, or the zeros from configs saved before timer sequences existed, are not valid
+        if sequence.is_valid() {
+            sequence
+        } else {
+            TimerSequence::DEFAULT
+        }
+    }
+
+    /// Convert the timer sequence to bytes.
+    pub fn timer_sequence_to_bytes(state: TimerSequence) -> [u8; 10] {
+        let mut bytes = [0u8; 10];
+        for (chunk, step) in bytes.chunks_mut(2).zip(state.steps_secs) {
+            chunk.copy_from_slice(&step.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Get the timer presets config from the full flash byte array.
+    pub fn timer_presets_from_bytes(bytes: &[u8; ERASE_SIZE]) -> [u8; TIMER_PRESETS_SIZE] {
+        let state_bytes = &bytes[TIMER_PRESETS.0..TIMER_PRESETS.1];
+        let mut presets = [0; TIMER_PRESETS_SIZE];
+        presets.copy_from_slice(state_bytes);
+
+        // erased bytes, or the zeros from configs saved before presets existed, are not valid
+        if presets
+            .iter()
+            .all(|&mins| (1..=TIMER_PRESET_MAX_MINS).contains(&mins))
+        {
+            presets
+        } else {
+            TIMER_PRESETS_DEFAULT
+        }
+    }
+
+    /// Convert the timer presets to bytes.
+    pub fn timer_presets_to_bytes(state: [u8; TIMER_PRESETS_SIZE]) -> [u8; TIMER_PRESETS_SIZE] {
+        state
+    }
+
+    /// Get the do not disturb state config from the full flash byte array.
+    pub fn do_not_disturb_from_bytes(bytes: &[u8; ERASE_SIZE]) -> bool {
+        let state_bytes = &bytes[DO_NOT_DISTURB.0..DO_NOT_DISTURB.1];
+        state_bytes == [TRUE_BYTES]
+    }
+
+    /// Convert the do not disturb state to bytes.
+    pub fn do_not_disturb_to_bytes(state: bool) -> u8 {
+        if state {
+            TRUE_BYTES
+        } else {
+            FALSE_BYTES
+        }
+    }
+
+    /// Get the night mute state config from the full flash byte array.
+    pub fn night_mute_from_bytes(bytes: &[u8; ERASE_SIZE]) -> bool {
+        let state_bytes = &bytes[NIGHT_MUTE.0..NIGHT_MUTE.1];
+        state_bytes == [TRUE_BYTES]
+    }
+
+    /// Convert the night mute state to bytes.
+    pub fn night_mute_to_bytes(state: bool) -> u8 {
+        if state {
+            TRUE_BYTES
+        } else {
+            FALSE_BYTES
+        }
+    }
+
+    /// Get the still running reminder hour config from the full flash byte array.
+    pub fn still_running_hour_from_bytes(bytes: &[u8; ERASE_SIZE]) -> u8 {
+        let state_bytes = &bytes[STILL_RUNNING_HOUR.0..STILL_RUNNING_HOUR.1];
+        match state_bytes {
+            [ERASED_BYTES] => 0,
+            [hour] if *hour < 24 => *hour,
+            _ => 0,
+        }
+    }
+
+    /// Convert the still running reminder hour to bytes.
+    pub fn still_running_hour_to_bytes(state: u8) -> u8 {
+        state
+    }
+
+    /// Get the final countdown warning state config from the full flash byte array.
+    ///
+    /// Stored as whether the warning is turned off, so it is on for configs saved before it was added.
+    pub fn final_countdown_from_bytes(bytes: &[u8; ERASE_SIZE]) -> bool {
+        let state_bytes = &bytes[FINAL_COUNTDOWN.0..FINAL_COUNTDOWN.1];
+        state_bytes != [TRUE_BYTES]
+    }
+
+    /// Convert the final countdown warning state to bytes.
+    pub fn final_countdown_to_bytes(state: bool) -> u8 {
+        if state {
+            FALSE_BYTES
+        } else {
+            TRUE_BYTES
+        }
+    }
+
+    /// Get the pomodoro pre-finish warning minutes config from the full flash byte array.
+    pub fn pre_finish_minutes_from_bytes(bytes: &[u8; ERASE_SIZE]) -> u8 {
+        let state_bytes = &bytes[PRE_FINISH_MINUTES.0..PRE_FINISH_MINUTES.1];
+        match state_bytes {
+            [minutes] if *minutes <= PRE_FINISH_MAX_MINUTES => *minutes,
+            _ => 0,
+        }
+    }
+
+    /// Convert the pomodoro pre-finish warning minutes to bytes.
+    pub fn pre_finish_minutes_to_bytes(state: u8) -> u8 {
+        state
+    }
 }
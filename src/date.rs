@@ -0,0 +1,161 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+
+/// Determine if the passed year is a leap year.
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 400 == 0 || year % 100 != 0) && year % 4 == 0
+}
+
+/// Get the maximum possible day in the passed month of the passed year.
+///
+/// Returns [None](Option::None) if the month does not exist.
+pub fn max_day_in_month(year: i32, month: u32) -> Option<u32> {
+    match month {
+        2 if is_leap_year(year) => Some(29),
+        2 => Some(28),
+        4 | 6 | 9 | 11 => Some(30),
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => Some(31),
+        _ => None,
+    }
+}
+
+/// Clamp the day to the days that exist in the passed month of the passed year.
+///
+/// For example, the 31st of April will become the 30th of April.
+pub fn clamp_day(year: i32, month: u32, day: u32) -> Option<u32> {
+    max_day_in_month(year, month).map(|max_day| day.clamp(1, max_day))
+}
+
+/// Change the day of the datetime, clamping it to the days in the month.
+///
+/// For example, setting the 29th on February of a non leap year will become the 28th.
+pub fn with_day(datetime: NaiveDateTime, day: u32) -> Option<NaiveDateTime> {
+    let day = clamp_day(datetime.year(), datetime.month(), day)?;
+    datetime.with_day(day)
+}
+
+/// Change the month of the datetime, clamping the day to the days in the new month.
+///
+/// For example, changing from the 31st of December into February will become the 28th February, or the 29th in a leap year.
+pub fn with_month(datetime: NaiveDateTime, month: u32) -> Option<NaiveDateTime> {
+    let day = clamp_day(datetime.year(), month, datetime.day())?;
+    datetime.with_day(day)?.with_month(month)
+}
+
+/// Change the year of the datetime, clamping the day to the days in the month of the new year.
+///
+/// For example, going from 29th February 2024 to 2025 will become 28th February 2025.
+pub fn with_year(datetime: NaiveDateTime, year: i32) -> Option<NaiveDateTime> {
+    let day = clamp_day(year, datetime.month(), datetime.day())?;
+    datetime.with_day(day)?.with_year(year)
+}
+
+/// Get the UTC date the firmware was built on.
+///
+/// The clock can not be earlier than this, so it is a better guess than 2000 when the time has been lost.
+/// Returns [None](Option::None) if the build date could not be read.
+pub fn build_date() -> Option<NaiveDate> {
+    let mut parts = env!("BUILD_DATE").split('-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+/// Tests run on the host by the `host-tests` crate.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a datetime at 12:30 on the date.
+    fn datetime(year: i32, month: u32, day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(12, 30, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn leap_years() {
+        assert!(!is_leap_year(1900));
+        assert!(is_leap_year(2000));
+        assert!(!is_leap_year(2023));
+        assert!(is_leap_year(2024));
+        assert!(!is_leap_year(2100));
+    }
+
+    #[test]
+    fn max_days() {
+        assert_eq!(max_day_in_month(2023, 1), Some(31));
+        assert_eq!(max_day_in_month(2023, 2), Some(28));
+        assert_eq!(max_day_in_month(2024, 2), Some(29));
+        assert_eq!(max_day_in_month(1900, 2), Some(28));
+        assert_eq!(max_day_in_month(2000, 2), Some(29));
+        assert_eq!(max_day_in_month(2023, 4), Some(30));
+        assert_eq!(max_day_in_month(2023, 12), Some(31));
+        assert_eq!(max_day_in_month(2023, 0), None);
+        assert_eq!(max_day_in_month(2023, 13), None);
+    }
+
+    #[test]
+    fn clamps_day() {
+        assert_eq!(clamp_day(2023, 4, 31), Some(30));
+        assert_eq!(clamp_day(2023, 4, 0), Some(1));
+        assert_eq!(clamp_day(2023, 4, 15), Some(15));
+        assert_eq!(clamp_day(2023, 13, 15), None);
+    }
+
+    #[test]
+    fn with_day_clamps_to_month() {
+        assert_eq!(
+            with_day(datetime(2023, 2, 1), 29),
+            Some(datetime(2023, 2, 28))
+        );
+        assert_eq!(
+            with_day(datetime(2024, 2, 1), 29),
+            Some(datetime(2024, 2, 29))
+        );
+        assert_eq!(
+            with_day(datetime(2023, 6, 1), 31),
+            Some(datetime(2023, 6, 30))
+        );
+    }
+
+    #[test]
+    fn with_month_from_end_of_january() {
+        let end_of_january = datetime(2023, 1, 31);
+        assert_eq!(with_month(end_of_january, 2), Some(datetime(2023, 2, 28)));
+        assert_eq!(with_month(end_of_january, 3), Some(datetime(2023, 3, 31)));
+        assert_eq!(with_month(end_of_january, 4), Some(datetime(2023, 4, 30)));
+        assert_eq!(with_month(end_of_january, 13), None);
+
+        let leap_january = datetime(2024, 1, 31);
+        assert_eq!(with_month(leap_january, 2), Some(datetime(2024, 2, 29)));
+    }
+
+    #[test]
+    fn with_month_from_leap_day() {
+        let leap_day = datetime(2024, 2, 29);
+        assert_eq!(with_month(leap_day, 1), Some(datetime(2024, 1, 29)));
+        assert_eq!(with_month(leap_day, 4), Some(datetime(2024, 4, 29)));
+    }
+
+    #[test]
+    fn with_year_from_leap_day() {
+        let leap_day = datetime(2024, 2, 29);
+        assert_eq!(with_year(leap_day, 2025), Some(datetime(2025, 2, 28)));
+        assert_eq!(with_year(leap_day, 2028), Some(datetime(2028, 2, 29)));
+        assert_eq!(with_year(leap_day, 2100), Some(datetime(2100, 2, 28)));
+    }
+
+    #[test]
+    fn with_year_from_end_of_january() {
+        let end_of_january = datetime(2023, 1, 31);
+        assert_eq!(with_year(end_of_january, 2024), Some(datetime(2024, 1, 31)));
+    }
+
+    #[test]
+    fn reads_build_date() {
+        assert_eq!(build_date(), NaiveDate::from_ymd_opt(2023, 8, 1));
+    }
+}
@@ -0,0 +1,233 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use chrono::NaiveDateTime;
+use defmt::info;
+use embassy_rp::pac;
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::eeprom;
+
+/// The shortest time between time syncs to measure drift over.
+///
+/// Shorter gaps are usually from setting the hour and then the minute, so do not say anything about drift.
+const MIN_SYNC_GAP_SECS: i64 = 24 * 60 * 60;
+
+/// The largest correction to the time that is counted as drift.
+///
+/// Larger corrections are taken as the time being changed on purpose, such as for daylight saving.
+const MAX_DRIFT_CORRECTION_SECS: i64 = 30 * 60;
+
+/// The number of seconds in a day.
+const SECS_PER_DAY: f32 = 86400.0;
+
+/// How often the total uptime is saved to the EEPROM. Uptime since the last save is lost when the power is cut.
+const UPTIME_SAVE_SECS: u64 = 10 * 60;
+
+/// The total uptime saved before this boot, in seconds.
+static PREVIOUS_UPTIME_SECS: AtomicU32 = AtomicU32::new(0);
+
+/// The number of unexpected resets, including this boot if it was one.
+static UNEXPECTED_RESETS: AtomicU32 = AtomicU32::new(0);
+
+/// Why the clock last reset.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ResetReason {
+    /// Power was turned on, or dropped too low.
+    Power,
+
+    /// The RUN pin was pulled low.
+    RunPin,
+
+    /// A debugger reset the chip.
+    Debugger,
+
+    /// The watchdog timer ran out.
+    Watchdog,
+
+    /// The firmware asked for a reset, such as after a factory reset.
+    Software,
+}
+
+impl ResetReason {
+    /// The name to show on the display.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            ResetReason::Power => "Power",
+            ResetReason::RunPin => "Run pin",
+            ResetReason::Debugger => "Debug",
+            ResetReason::Watchdog => "Watchdog",
+            ResetReason::Software => "Software",
+        }
+    }
+
+    /// Determine if the reset was not asked for, such as the power dropping out.
+    pub const fn is_unexpected(&self) -> bool {
+        matches!(self, ResetReason::Power | ResetReason::Watchdog)
+    }
+}
+
+/// Get why the clock last reset.
+///
+/// The reset registers keep their value until the next reset, so this can be called at any time.
+pub fn get_reset_reason() -> ResetReason {
+    let watchdog = pac::WATCHDOG.reason().read();
+    if watchdog.force() {
+        return ResetReason::Software;
+    }
+    if watchdog.timer() {
+        return ResetReason::Watchdog;
+    }
+
+    let chip = pac::VREG_AND_CHIP_RESET.chip_reset().read();
+    if chip.had_psm_restart() {
+        ResetReason::Debugger
+    } else if chip.had_run() {
+        ResetReason::RunPin
+    } else {
+        ResetReason::Power
+    }
+}
+
+/// Get how many bytes of the core 1 stack have never been used.
+///
+/// The stack starts zeroed and grows down, so this counts the zero bytes from the bottom.
+/// Zeros written by the display task are counted as unused, so this is a close estimate.
+pub fn get_core1_stack_free() -> usize {
+    // SAFETY: only reads bytes from the bottom of the stack, which core 1 does not write until it is nearly full
+    let stack = unsafe { &*core::ptr::addr_of!(crate::CORE1_STACK.mem) };
+    stack.iter().take_while(|b| **b == 0).count()
+}
+
+/// Count this boot if it was an unexpected reset, then keep adding the uptime to the total saved in the EEPROM.
+#[embassy_executor::task]
+pub async fn uptime_task() -> ! {
+    let (previous_uptime, mut resets) = load_uptime_stats().await;
+    if get_reset_reason().is_unexpected() {
+        resets += 1;
+    }
+
+    PREVIOUS_UPTIME_SECS.store(previous_uptime, Ordering::Relaxed);
+    UNEXPECTED_RESETS.store(resets, Ordering::Relaxed);
+    save_uptime_stats().await;
+
+    loop {
+        Timer::after(Duration::from_secs(UPTIME_SAVE_SECS)).await;
+        save_uptime_stats().await;
+    }
+}
+
+/// Get the total uptime in seconds across all boots.
+pub fn get_total_uptime() -> u32 {
+    PREVIOUS_UPTIME_SECS
+        .load(Ordering::Relaxed)
+        .saturating_add(Instant::now().as_secs() as u32)
+}
+
+/// Get the number of unexpected resets, such as from power cuts or the watchdog.
+pub fn get_unexpected_resets() -> u32 {
+    UNEXPECTED_RESETS.load(Ordering::Relaxed)
+}
+
+/// Save the (total uptime, unexpected resets) to the EEPROM.
+async fn save_uptime_stats() {
+    let mut bytes = [0u8; eeprom::UPTIME_STATS.1 - eeprom::UPTIME_STATS.0];
+    bytes[0..4].copy_from_slice(&get_total_uptime().to_le_bytes());
+    bytes[4..8].copy_from_slice(&get_unexpected_resets().to_le_bytes());
+
+    if let Err(e) = eeprom::write(eeprom::UPTIME_STATS.0, &bytes).await {
+        info!("Failed to save uptime stats: {}", e);
+    }
+}
+
+/// Load the (total uptime, unexpected resets) from the EEPROM. Erased values are 0.
+async fn load_uptime_stats() -> (u32, u32) {
+    let mut bytes = [0u8; eeprom::UPTIME_STATS.1 - eeprom::UPTIME_STATS.0];
+    if let Err(e) = eeprom::read(eeprom::UPTIME_STATS.0, &mut bytes).await {
+        info!("Failed to load uptime stats: {}", e);
+        return (0, 0);
+    }
+
+    let value = |bytes: &[u8]| {
+        if bytes.iter().all(|b| *b == eeprom::ERASED_BYTES) {
+            0
+        } else {
+            u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+        }
+    };
+
+    (value(&bytes[0..4]), value(&bytes[4..8]))
+}
+
+/// Record that the time has been set, measuring the drift since the previous time set if possible.
+///
+/// # Arguments
+///
+/// * `old` - The time in the RTC before it was set.
+/// * `new` - The time set into the RTC.
+pub async fn record_time_set(old: NaiveDateTime, new: NaiveDateTime) {
+    let (last_sync, mut drift) = load_time_sync().await;
+
+    if let Some(last_sync) = last_sync {
+        let elapsed = (old - last_sync).num_seconds();
+        let correction = (new - old).num_seconds();
+
+        if elapsed >= MIN_SYNC_GAP_SECS && correction.abs() <= MAX_DRIFT_CORRECTION_SECS {
+            // the RTC running fast needs the time taking back, so is positive drift
+            drift = Some(-correction as f32 * SECS_PER_DAY / elapsed as f32);
+        }
+    }
+
+    let bytes = time_sync_to_bytes(new, drift);
+    if let Err(e) = eeprom::write(eeprom::TIME_SYNC.0, &bytes).await {
+        info!("Failed to save time sync: {}", e);
+    }
+}
+
+/// Get the RTC drift in seconds per day measured between the last two time syncs. Positive is running fast.
+///
+/// Returns None if the drift has not been measured yet.
+pub async fn get_drift() -> Option<f32> {
+    let (_, drift) = load_time_sync().await;
+    drift
+}
+
+/// Load the (last time sync, drift) from the EEPROM.
+async fn load_time_sync() -> (Option<NaiveDateTime>, Option<f32>) {
+    let mut bytes = [0u8; eeprom::TIME_SYNC.1 - eeprom::TIME_SYNC.0];
+    if let Err(e) = eeprom::read(eeprom::TIME_SYNC.0, &mut bytes).await {
+        info!("Failed to load time sync: {}", e);
+        return (None, None);
+    }
+
+    time_sync_from_bytes(&bytes)
+}
+
+/// Convert the time sync to bytes, in the order (timestamp, drift).
+fn time_sync_to_bytes(
+    last_sync: NaiveDateTime,
+    drift: Option<f32>,
+) -> [u8; eeprom::TIME_SYNC.1 - eeprom::TIME_SYNC.0] {
+    let mut bytes = [eeprom::ERASED_BYTES; eeprom::TIME_SYNC.1 - eeprom::TIME_SYNC.0];
+    bytes[0..8].copy_from_slice(&last_sync.timestamp().to_le_bytes());
+    if let Some(drift) = drift {
+        bytes[8..12].copy_from_slice(&drift.to_le_bytes());
+    }
+    bytes
+}
+
+/// Get the (last time sync, drift) from the saved bytes. Erased bytes are None.
+fn time_sync_from_bytes(bytes: &[u8]) -> (Option<NaiveDateTime>, Option<f32>) {
+    let last_sync = if bytes[0..8].iter().all(|b| *b == eeprom::ERASED_BYTES) {
+        None
+    } else {
+        let mut timestamp = [0u8; 8];
+        timestamp.copy_from_slice(&bytes[0..8]);
+        NaiveDateTime::from_timestamp_opt(i64::from_le_bytes(timestamp), 0)
+    };
+
+    // erased bytes are a NaN
+    let drift = f32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
+    let drift = if drift.is_nan() { None } else { Some(drift) };
+
+    (last_sync, drift)
+}
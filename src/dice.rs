@@ -0,0 +1,142 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, pubsub::PubSubChannel};
+use embassy_time::{Duration, Timer};
+
+use crate::{
+    app::{self, App, StopAppTasks},
+    buttons::ButtonPress,
+    display::display_matrix::DISPLAY_MATRIX,
+    rng,
+};
+
+/// Channel for firing events of when tasks should be stopped.
+static STOP_APP_CHANNEL: PubSubChannel<ThreadModeRawMutex, StopAppTasks, 1, 1, 1> =
+    PubSubChannel::new();
+
+/// Whether the die is tumbling. Presses are ignored until it lands.
+static ROLLING: AtomicBool = AtomicBool::new(false);
+
+/// How many faces to show while the die tumbles, before it lands.
+const TUMBLE_FRAMES: u64 = 10;
+
+/// How long to show the first face while tumbling, in milliseconds.
+const TUMBLE_START_MS: u64 = 50;
+
+/// How much longer to show each face than the last while tumbling, so the die slows down, in milliseconds.
+const TUMBLE_STEP_MS: u64 = 20;
+
+/// How many columns in from the display offset the die face is drawn, to center it on the display.
+const FACE_OFFSET: usize = 8;
+
+/// The pips on each face of the die, from 1 to 6, as (row, column) in a 7 by 7 face.
+const FACES: [&[(usize, usize)]; 6] = [
+    &[(3, 3)],
+    &[(1, 1), (5, 5)],
+    &[(1, 1), (3, 3), (5, 5)],
+    &[(1, 1), (1, 5), (5, 1), (5, 5)],
+    &[(1, 1), (1, 5), (3, 3), (5, 1), (5, 5)],
+    &[(1, 1), (1, 5), (3, 1), (3, 5), (5, 1), (5, 5)],
+];
+
+/// Dice app.
+/// Rolls a six sided die or flips a coin.
+pub struct DiceApp {}
+
+impl DiceApp {
+    /// Create a new dice app.
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl App for DiceApp {
+    fn get_name(&self) -> &str {
+        "Dice"
+    }
+
+    async fn start(&mut self, _: Spawner) {
+        critical_section::with(|cs| {
+            DISPLAY_MATRIX.clear_all(cs, true);
+        });
+
+        show_face(6);
+    }
+
+    async fn stop(&mut self) {
+        STOP_APP_CHANNEL
+            .immediate_publisher()
+            .publish_immediate(StopAppTasks);
+    }
+
+    async fn button_one_short_press(&mut self, _: Spawner) {}
+
+    async fn button_two_press(&mut self, press: ButtonPress, spawner: Spawner) {
+        if ROLLING.load(Ordering::Relaxed) {
+            return;
+        }
+
+        if let ButtonPress::Short = press {
+            ROLLING.store(true, Ordering::Relaxed);
+            app::spawn_app_task(spawner, "roll", roll).await;
+        }
+    }
+
+    async fn button_three_press(&mut self, press: ButtonPress, _: Spawner) {
+        if ROLLING.load(Ordering::Relaxed) {
+            return;
+        }
+
+        if let ButtonPress::Short = press {
+            let side = if rng::below(2) == 0 { "HEADS" } else { "TAILS" };
+            DISPLAY_MATRIX.queue_text(side, 0, true, false).await;
+        }
+    }
+}
+
+/// Show a face of the die, from 1 to 6, in place of anything else on the display.
+fn show_face(value: usize) {
+    let mut pattern = [0; 7];
+
+    for &(row, col) in FACES[value - 1] {
+        pattern[row] |= 1 << (FACE_OFFSET + col);
+    }
+
+    critical_section::with(|cs| {
+        DISPLAY_MATRIX.clear(cs, true);
+        DISPLAY_MATRIX.show_pattern(cs, &pattern);
+    });
+}
+
+/// Tumble the die through random faces, slowing down until it lands on the rolled face.
+#[embassy_executor::task]
+async fn roll() {
+    let _running = app::RunningAppTask::new();
+    let mut stop_task_sub = STOP_APP_CHANNEL.subscriber().unwrap();
+
+    let mut face = rng::below(6) as usize + 1;
+
+    for frame in 0..TUMBLE_FRAMES {
+        show_face(face);
+
+        let delay = TUMBLE_START_MS + frame * TUMBLE_STEP_MS;
+        let res = select(
+            stop_task_sub.next_message(),
+            Timer::after(Duration::from_millis(delay)),
+        )
+        .await;
+
+        if let Either::First(_) = res {
+            ROLLING.store(false, Ordering::Relaxed);
+            return;
+        }
+
+        // never show the same face twice in a row, so the die always looks like it is moving
+        face = (face + rng::below(5) as usize) % 6 + 1;
+    }
+
+    show_face(rng::below(6) as usize + 1);
+    ROLLING.store(false, Ordering::Relaxed);
+}
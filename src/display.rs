@@ -1,47 +1,47 @@
 use core::cell::RefCell;
-use core::fmt::Write;
 use critical_section::{CriticalSection, Mutex};
 use defmt::info;
-use embassy_rp::gpio::Output;
+use embassy_rp::gpio::{AnyPin, Output};
 use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, channel::Channel};
-use embassy_time::{Duration, Timer};
+use embassy_time::{Duration, Instant, Timer};
 use heapless::Vec;
 
 use self::{
     icons::{get_icon_struct, Icon},
-    text::{get_character_struct, Character},
+    mini_text::get_mini_character_struct,
+    text::{get_character_struct, take_characters, Character},
 };
 
 /// All the pins required for the display.
 pub struct DisplayPins<'a> {
     /// A0 pin.
-    a0: Output<'a, embassy_rp::peripherals::PIN_16>,
+    a0: Output<'a, AnyPin>,
 
     /// A1 pin.
-    a1: Output<'a, embassy_rp::peripherals::PIN_18>,
+    a1: Output<'a, AnyPin>,
 
     /// A2 pin.
-    a2: Output<'a, embassy_rp::peripherals::PIN_22>,
+    a2: Output<'a, AnyPin>,
 
     /// SDI pin.
-    sdi: Output<'a, embassy_rp::peripherals::PIN_11>,
+    sdi: Output<'a, AnyPin>,
 
     /// CLK pin.
-    clk: Output<'a, embassy_rp::peripherals::PIN_10>,
+    clk: Output<'a, AnyPin>,
 
     /// LE pin.
-    le: Output<'a, embassy_rp::peripherals::PIN_12>,
+    le: Output<'a, AnyPin>,
 }
 
 impl<'a> DisplayPins<'a> {
     /// Create a new display pins struct.
     pub fn new(
-        a0: Output<'a, embassy_rp::peripherals::PIN_16>,
-        a1: Output<'a, embassy_rp::peripherals::PIN_18>,
-        a2: Output<'a, embassy_rp::peripherals::PIN_22>,
-        sdi: Output<'a, embassy_rp::peripherals::PIN_11>,
-        clk: Output<'a, embassy_rp::peripherals::PIN_10>,
-        le: Output<'a, embassy_rp::peripherals::PIN_12>,
+        a0: Output<'a, AnyPin>,
+        a1: Output<'a, AnyPin>,
+        a2: Output<'a, AnyPin>,
+        sdi: Output<'a, AnyPin>,
+        clk: Output<'a, AnyPin>,
+        le: Output<'a, AnyPin>,
     ) -> Self {
         Self {
             a0,
@@ -54,29 +54,58 @@ impl<'a> DisplayPins<'a> {
     }
 }
 
+/// How long each row is lit for on each scan of the display, in microseconds.
+const ROW_MICROS: u64 = 1000;
+
+/// How long blinking icons are on, then off, for in milliseconds.
+const BLINK_MS: u64 = 500;
+
+/// How long pulsing icons take to fade up and back down, in milliseconds.
+const PULSE_MS: u64 = 2000;
+
 /// Update the display with accordance to the last known state of the matrix.
+///
+/// Each row is read from the [published rows](display_matrix::read_row) without any lock, so core 0 interrupts are never held off by the refresh.
+/// A row is only shifted in when it differs from the row already latched.
+///
+/// [Animated icons](display_matrix::IconAnimation) are handled here, so apps only have to set the animation once. Blinking icons are left out of the row every other half second.
+/// Pulsing icons are only lit for part of the time each row is lit, fading up and down like a dimmer.
 #[embassy_executor::task]
 pub async fn update_matrix(mut pins: DisplayPins<'static>) {
     let mut row: usize = 0;
+    let mut latched: Option<u32> = None;
 
     loop {
-        row = (row + 1) % 8;
+        // the backlight holds the display off in standby, so there is no need to keep scanning rows
+        if backlight::is_in_standby() {
+            Timer::after(Duration::from_millis(backlight::STANDBY_POLL_MS)).await;
+            continue;
+        }
 
-        critical_section::with(|cs| {
-            for col in display_matrix::DISPLAY_MATRIX.0.borrow_ref(cs)[row] {
-                pins.clk.set_low();
-                pins.sdi.set_low();
+        row = (row + 1) % 8;
 
-                if col == 1 {
-                    pins.sdi.set_high();
-                }
+        let mut bits = display_matrix::read_row(row);
+        let (blinking, pulsing) = display_matrix::read_animation(row);
+        let now_ms = Instant::now().as_millis();
 
-                pins.clk.set_high();
-            }
-        });
+        if (now_ms / BLINK_MS) % 2 == 1 {
+            bits &= !blinking;
+        }
 
-        pins.le.set_high();
-        pins.le.set_low();
+        // how long the pulsing pixels are lit for this row, rising then falling over the pulse
+        let pulse_ms = now_ms % PULSE_MS;
+        let half_ms = PULSE_MS / 2;
+        let pulse_micros = match pulse_ms < half_ms {
+            true => pulse_ms * ROW_MICROS / half_ms,
+            false => (PULSE_MS - pulse_ms) * ROW_MICROS / half_ms,
+        };
+        let dimmed = bits & pulsing != 0;
+
+        // rows are often the same, such as blank rows, so skip shifting in the same data again
+        if latched != Some(bits) {
+            shift_row(&mut pins, bits);
+            latched = Some(bits);
+        }
 
         if row & 0x01 != 0 {
             pins.a0.set_high();
@@ -96,27 +125,204 @@ pub async fn update_matrix(mut pins: DisplayPins<'static>) {
             pins.a2.set_low();
         }
 
-        Timer::after(Duration::from_millis(1)).await;
+        if dimmed {
+            // light the row with the pulsing pixels, then without them for the rest of the row time
+            Timer::after(Duration::from_micros(pulse_micros)).await;
+
+            let without_pulsing = bits & !pulsing;
+            shift_row(&mut pins, without_pulsing);
+            latched = Some(without_pulsing);
+
+            Timer::after(Duration::from_micros(ROW_MICROS - pulse_micros)).await;
+        } else {
+            Timer::after(Duration::from_micros(ROW_MICROS)).await;
+        }
     }
 }
 
+/// Shift the bits for a row into the display and latch them, with column 0 in the lowest bit.
+fn shift_row(pins: &mut DisplayPins<'static>, bits: u32) {
+    for col in 0..32 {
+        pins.clk.set_low();
+        pins.sdi.set_low();
+
+        if bits & (1 << col) != 0 {
+            pins.sdi.set_high();
+        }
+
+        pins.clk.set_high();
+    }
+
+    pins.le.set_high();
+    pins.le.set_low();
+}
+
 /// Backlight module. Will adjust backlight automatically.
 pub mod backlight {
+    use core::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+
+    use embassy_futures::select::{select, Either};
     use embassy_rp::{
         adc::{Adc, Async, Channel},
-        gpio::Output,
+        gpio::{AnyPin, Output},
     };
+    use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
     use embassy_time::{Duration, Instant, Timer};
 
-    use crate::config::{self};
+    use crate::config::{self, LightSensitivity};
 
     /// List of sleep durations, where higher numbers are brighter outputs.
     const LIGHT_LEVELS: [u64; 5] = [10, 100, 300, 700, 1000];
 
+    /// The light sensor readings where the light level changes, from brightest to darkest. Higher readings are darker.
+    ///
+    /// Used until the light sensor is calibrated in settings.
+    const LIGHT_THRESHOLDS: [i32; 4] = [3750, 3800, 3850, 3900];
+
+    /// How far past a threshold the reading has to go before the light level changes, so it does not flicker between levels.
+    const HYSTERESIS: i32 = 15;
+
+    /// How much each light sensor reading moves the average, where each reading moves it 1/N of the way.
+    const SMOOTHING: i32 = 4;
+
+    /// The light level to use when autolight is off, as an index into [LIGHT_LEVELS].
+    static BRIGHTNESS: AtomicUsize = AtomicUsize::new(3);
+
+    /// How long the display stays on for after being woken during night mode, in seconds.
+    const WAKE_DURATION_SECS: u64 = 10;
+
+    /// Whether night mode is active. The display is blanked unless woken.
+    static NIGHT_MODE: AtomicBool = AtomicBool::new(false);
+
+    /// Whether the display is currently blanked by night mode.
+    static DISPLAY_ASLEEP: AtomicBool = AtomicBool::new(false);
+
+    /// The last raw light sensor reading.
+    static LIGHT_READING: AtomicU32 = AtomicU32::new(0);
+
+    /// Whether the room is dark, as the light is at the dimmest level.
+    static DARK: AtomicBool = AtomicBool::new(false);
+
+    /// Whether night mode uses the low power standby, where the display stops scanning as well as being blanked.
+    static STANDBY: AtomicBool = AtomicBool::new(false);
+
+    /// How often the display tasks check for waking up while in standby, in milliseconds.
+    pub const STANDBY_POLL_MS: u64 = 100;
+
+    /// Named struct for the wake display signal.
+    struct WakeDisplay;
+
+    /// Signal to wake the display from night mode. Starts the wake timer in the backlight task.
+    static WAKE_DISPLAY: Signal<CriticalSectionRawMutex, WakeDisplay> = Signal::new();
+
+    /// Turn night mode on or off.
+    pub fn set_night_mode(state: bool) {
+        NIGHT_MODE.store(state, Ordering::Relaxed);
+
+        if !state {
+            DISPLAY_ASLEEP.store(false, Ordering::Relaxed);
+        }
+    }
+
+    /// Turn the low power standby for night mode on or off.
+    pub fn set_standby(state: bool) {
+        STANDBY.store(state, Ordering::Relaxed);
+    }
+
+    /// Get whether the display is currently in the low power standby.
+    pub fn is_in_standby() -> bool {
+        STANDBY.load(Ordering::Relaxed) && DISPLAY_ASLEEP.load(Ordering::Relaxed)
+    }
+
+    /// Set the light level to use when autolight is off, from 0 (dimmest) to 4 (brightest).
+    #[cfg(feature = "mqtt")]
+    pub fn set_brightness(level: usize) {
+        BRIGHTNESS.store(level.min(LIGHT_LEVELS.len() - 1), Ordering::Relaxed);
+    }
+
+    /// Get the last raw light sensor reading, taken every second. Higher readings are darker.
+    pub fn get_light_reading() -> u16 {
+        LIGHT_READING.load(Ordering::Relaxed) as u16
+    }
+
+    /// Get whether the room is dark, from the light sensor readings taken every second.
+    pub fn is_dark() -> bool {
+        DARK.load(Ordering::Relaxed)
+    }
+
+    /// Get whether the display is currently blanked by night mode.
+    pub fn is_display_asleep() -> bool {
+        DISPLAY_ASLEEP.load(Ordering::Relaxed)
+    }
+
+    /// Wake the display from night mode for `WAKE_DURATION_SECS`.
+    pub fn wake_display() {
+        DISPLAY_ASLEEP.store(false, Ordering::Relaxed);
+        WAKE_DISPLAY.signal(WakeDisplay);
+    }
+
+    /// Get the light level for a smoothed light sensor reading, as an index into [LIGHT_LEVELS].
+    ///
+    /// The level only changes once the reading is [HYSTERESIS] past the threshold, so a reading sat on a threshold keeps the current level.
+    fn get_light_level(
+        reading: i32,
+        current: usize,
+        thresholds: [i32; 4],
+        sensitivity: LightSensitivity,
+    ) -> usize {
+        let offset = sensitivity.threshold_offset();
+        let level_for = |reading: i32| {
+            thresholds
+                .iter()
+                .filter(|&&threshold| reading < threshold + offset)
+                .count()
+        };
+
+        let level = level_for(reading);
+        if level > current {
+            level_for(reading + HYSTERESIS).max(current)
+        } else if level < current {
+            level_for(reading - HYSTERESIS).min(current)
+        } else {
+            current
+        }
+    }
+
+    /// The config options the backlight uses, kept so they are only read again when the config changes.
+    #[derive(Clone, Copy)]
+    struct BacklightSettings {
+        /// The light sensor readings where the light level changes, calibrated or the defaults.
+        thresholds: [i32; 4],
+
+        /// How sensitive the light levels are to the light sensor.
+        sensitivity: LightSensitivity,
+
+        /// Whether the light level follows the light sensor.
+        autolight: bool,
+
+        /// How many minutes of darkness before the display turns off, or 0 for never.
+        lights_out_mins: u8,
+    }
+
+    impl BacklightSettings {
+        /// Read the backlight options from the config.
+        async fn load() -> Self {
+            Self {
+                thresholds: match config::get_light_calibration().await {
+                    Some(calibration) => calibration.thresholds(),
+                    None => LIGHT_THRESHOLDS,
+                },
+                sensitivity: config::get_light_sensitivity().await,
+                autolight: config::get_autolight().await,
+                lights_out_mins: config::get_lights_out().await,
+            }
+        }
+    }
+
     /// All the pins required for backlight implementation.
     pub struct BacklightPins<'a> {
         /// OE pin.
-        pub oe: Output<'static, embassy_rp::peripherals::PIN_13>,
+        pub oe: Output<'static, AnyPin>,
 
         /// ADC controller.
         pub adc: Adc<'a, Async>,
@@ -127,11 +333,7 @@ pub mod backlight {
 
     impl<'a> BacklightPins<'a> {
         /// Create a new backlight pins struct.
-        pub fn new(
-            oe: Output<'static, embassy_rp::peripherals::PIN_13>,
-            adc: Adc<'a, Async>,
-            ain: Channel<'a>,
-        ) -> Self {
+        pub fn new(oe: Output<'static, AnyPin>, adc: Adc<'a, Async>, ain: Channel<'a>) -> Self {
             Self { oe, adc, ain }
         }
     }
@@ -141,24 +343,103 @@ pub mod backlight {
     pub async fn update_backlight(mut pins: BacklightPins<'static>) {
         let mut last_backlight_read = Instant::now();
         let mut sleep_duration = LIGHT_LEVELS[3];
+        let mut woken_at: Option<Instant> = None;
+        let mut average: Option<i32> = None;
+        let mut level = 3;
+        let mut dark_since: Option<Instant> = None;
+        let mut lights_out_mins = 0;
+        let mut config_sub = config::subscribe();
+
+        // read with the first light reading, as the config may not be loaded yet when the display core starts
+        let mut settings: Option<BacklightSettings> = None;
 
         loop {
             let now_time = Instant::now();
+
+            if WAKE_DISPLAY.signaled() {
+                WAKE_DISPLAY.reset();
+                woken_at = Some(now_time);
+
+                // restart the lights out wait, so the display stays on while it is being used
+                dark_since = dark_since.map(|_| now_time);
+            }
+
             if now_time.duration_since(last_backlight_read) >= Duration::from_secs(1) {
                 // update last scan for backlight to now
                 last_backlight_read = now_time;
 
+                // always read the sensor, so it can be calibrated and checked for lights out with autolight off
+                let reading = pins.adc.read(&mut pins.ain).await.unwrap();
+                LIGHT_READING.store(reading as u32, Ordering::Relaxed);
+
+                let reading = reading as i32;
+                let smoothed = match average {
+                    Some(average) => average + (reading - average) / SMOOTHING,
+                    None => reading,
+                };
+                average = Some(smoothed);
+
+                // without a listener the settings are read every second instead
+                let changed = match config_sub.as_mut() {
+                    Some(sub) => sub.try_next_message().is_some(),
+                    None => true,
+                };
+                let current = match settings {
+                    Some(current) if !changed => current,
+                    _ => BacklightSettings::load().await,
+                };
+                settings = Some(current);
+
+                level = get_light_level(smoothed, level, current.thresholds, current.sensitivity);
+
                 // only update light level if autolight is enabled
-                if config::get_autolight().await {
-                    let level_read = pins.adc.read(&mut pins.ain).await.unwrap();
-                    sleep_duration = match level_read {
-                        0..=3749 => LIGHT_LEVELS[4],
-                        3750..=3799 => LIGHT_LEVELS[3],
-                        3800..=3849 => LIGHT_LEVELS[2],
-                        3850..=3899 => LIGHT_LEVELS[1],
-                        _ => LIGHT_LEVELS[0],
-                    };
+                if current.autolight {
+                    sleep_duration = LIGHT_LEVELS[level];
+                } else {
+                    sleep_duration = LIGHT_LEVELS[BRIGHTNESS.load(Ordering::Relaxed)];
+                }
+
+                // the room is dark once the light is at the dimmest level
+                if level > 0 {
+                    dark_since = None;
+                } else if dark_since.is_none() {
+                    dark_since = Some(now_time);
                 }
+                DARK.store(level == 0, Ordering::Relaxed);
+                lights_out_mins = current.lights_out_mins;
+            }
+
+            let awake = match woken_at {
+                Some(at) => now_time.duration_since(at) < Duration::from_secs(WAKE_DURATION_SECS),
+                None => false,
+            };
+
+            let lights_out = match dark_since {
+                Some(since) if lights_out_mins > 0 => {
+                    now_time.duration_since(since)
+                        >= Duration::from_secs(lights_out_mins as u64 * 60)
+                }
+                _ => false,
+            };
+
+            let asleep = (NIGHT_MODE.load(Ordering::Relaxed) || lights_out) && !awake;
+            DISPLAY_ASLEEP.store(asleep, Ordering::Relaxed);
+
+            if asleep {
+                // keep the display off
+                pins.oe.set_high();
+
+                if STANDBY.load(Ordering::Relaxed) {
+                    // wait for longer in standby, but still wake straight away on a button press
+                    let wake = Timer::after(Duration::from_millis(STANDBY_POLL_MS));
+                    if let Either::Second(_) = select(wake, WAKE_DISPLAY.wait()).await {
+                        woken_at = Some(Instant::now());
+                        dark_since = dark_since.map(|_| Instant::now());
+                    }
+                } else {
+                    Timer::after(Duration::from_millis(1)).await;
+                }
+                continue;
             }
 
             pins.oe.set_low();
@@ -173,26 +454,62 @@ pub mod backlight {
 ///
 /// Contains all required data for updating state of waht to show on the display.
 pub mod display_matrix {
+    use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
     use chrono::Weekday;
     use embassy_futures::select::select;
     use embassy_sync::signal::Signal;
-    use heapless::String;
 
-    use crate::config::{TemperaturePreference, TimePreference};
+    use crate::{
+        config::{Days, TemperaturePrecision, TemperaturePreference, TimePreference},
+        formatting, instrumentation,
+    };
 
     use super::*;
 
     /// Process the text buffer background task.
     ///
-    /// Waits for text buffer to be updated and then will show the text. Each showing of the text can be cancelled by signalling the cancel signal.
+    /// Waits for text buffer to be updated and then will show the text. Each showing of the text can be cancelled by signalling the cancel signal, including part way through its repeats.
     #[embassy_executor::task]
     pub async fn process_text_buffer() -> ! {
+        // the chunks of the latest text, so it can be shown again when it repeats
+        let mut chunks: Vec<TextBufferItem<'_>, MAX_REPEAT_CHUNKS> = Vec::new();
+        let mut chunks_complete = true;
+
         loop {
             let item = TEXT_BUFFER.recv().await;
 
+            if !item.continuation {
+                chunks.clear();
+                chunks_complete = true;
+            }
+            if chunks_complete {
+                chunks_complete = chunks.push(item.clone()).is_ok();
+            }
+
             CANCEL_SIGNAL.reset();
 
-            select(DISPLAY_MATRIX.show_text(item), CANCEL_SIGNAL.wait()).await;
+            let times = item.times;
+            let repeat = async {
+                DISPLAY_MATRIX.show_text(item).await;
+
+                if times > 1 && !chunks_complete {
+                    info!("Text too long to repeat");
+                    return;
+                }
+
+                for _ in 1..times {
+                    for chunk in &chunks {
+                        DISPLAY_MATRIX.show_text(chunk.clone()).await;
+                    }
+                }
+            };
+
+            select(repeat, CANCEL_SIGNAL.wait()).await;
+
+            // the text may have been cancelled part way through scrolling
+            SCROLLING.store(false, Ordering::Relaxed);
+            SCROLL_PAUSED.store(false, Ordering::Relaxed);
         }
     }
 
@@ -212,6 +529,7 @@ pub mod display_matrix {
     }
 
     /// Item to be added to the text buffer.
+    #[derive(Clone)]
     struct TextBufferItem<'a> {
         /// A list of upto 32 [characters](Character).
         text: Vec<&'a Character<'a>, 32>,
@@ -229,8 +547,23 @@ pub mod display_matrix {
 
         /// Scroll text off the display.
         scroll_off_display: bool,
+
+        /// Carry on from the item before without clearing the display, as the next chunk of text too long for one item.
+        continuation: bool,
+
+        /// Part of text too long for one item, split into chunks, which can be paused while it scrolls.
+        chunked: bool,
+
+        /// How many times to show the text. Only the last chunk of the text has the count, and shows the text again from its first chunk.
+        times: u8,
+
+        /// A pattern to show in place of the text, such as two lines of mini text. The text is empty when there is a pattern.
+        pattern: Option<[u32; 7]>,
     }
 
+    /// The most chunks text can be split into and still be repeated, enough for 256 characters.
+    const MAX_REPEAT_CHUNKS: usize = 8;
+
     /// Named struct for cancel signal.
     struct DisplayClearSignal;
 
@@ -240,6 +573,23 @@ pub mod display_matrix {
     /// Cancel signal. Will cancel the current text being shown minimum wait.
     static CANCEL_SIGNAL: Signal<ThreadModeRawMutex, DisplayClearSignal> = Signal::new();
 
+    /// How long scrolling text stays paused before it carries on by itself.
+    const SCROLL_PAUSE_SECS: u64 = 30;
+
+    /// Whether text too long for one item is scrolling across the display, so it can be paused.
+    ///
+    /// Shorter text that only just overflows, such as app names and settings labels, is not counted so it does not take the top button press.
+    static SCROLLING: AtomicBool = AtomicBool::new(false);
+
+    /// Whether the scrolling text is paused.
+    static SCROLL_PAUSED: AtomicBool = AtomicBool::new(false);
+
+    /// Named struct for the scroll resume signal.
+    struct ScrollResumeSignal;
+
+    /// Scroll resume signal. Will carry on scrolling paused text.
+    static SCROLL_RESUME_SIGNAL: Signal<ThreadModeRawMutex, ScrollResumeSignal> = Signal::new();
+
     /// Display matrix struct.
     pub struct DisplayMatrix(pub Mutex<RefCell<[[usize; 32]; 8]>>);
 
@@ -247,6 +597,100 @@ pub mod display_matrix {
     pub static DISPLAY_MATRIX: DisplayMatrix =
         DisplayMatrix(Mutex::new(RefCell::new([[0; 32]; 8])));
 
+    /// The rows of the matrix packed into bits, with column 0 in the lowest bit, for the display core to read.
+    ///
+    /// Each row is published after every change to the matrix, so the display core never has to take the critical section.
+    static ROWS: [AtomicU32; 8] = [
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+    ];
+
+    /// Pixels lit on top of each row of the matrix, packed the same as the published rows.
+    ///
+    /// Kept apart from the matrix so they can move without redrawing, or being wiped by, the text underneath.
+    static OVERLAY: [AtomicU32; 8] = [
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+    ];
+
+    /// How an icon is animated by the display core.
+    #[derive(Clone, Copy, PartialEq)]
+    pub enum IconAnimation {
+        /// Lit all the time it is shown.
+        Steady,
+
+        /// Turned on and off every half second, such as for something that needs attention now.
+        Blink,
+
+        /// Fades up and down, such as for something that is armed and waiting.
+        Pulse,
+    }
+
+    /// The pixels of blinking icons in each row, packed the same as the published rows.
+    static BLINKING: [AtomicU32; 8] = [
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+    ];
+
+    /// The pixels of pulsing icons in each row, packed the same as the published rows.
+    static PULSING: [AtomicU32; 8] = [
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+        AtomicU32::new(0),
+    ];
+
+    /// Get the pixels of a row that are (blinking, pulsing), for the display core to animate.
+    ///
+    /// Only the pixels that are lit in the published row are animated, so a hidden icon stays hidden.
+    pub fn read_animation(row: usize) -> (u32, u32) {
+        (
+            BLINKING[row].load(Ordering::Relaxed),
+            PULSING[row].load(Ordering::Relaxed),
+        )
+    }
+
+    /// Get the latest published bits for a row of the matrix.
+    pub fn read_row(row: usize) -> u32 {
+        ROWS[row].load(Ordering::Acquire)
+    }
+
+    /// Pack each row of the matrix into bits and publish them for the display core.
+    ///
+    /// Must be called in the same critical section as the change, so changes are published in order.
+    fn publish(matrix: &[[usize; 32]; 8]) {
+        for ((bits, overlay), row) in ROWS.iter().zip(&OVERLAY).zip(matrix) {
+            let packed = row
+                .iter()
+                .enumerate()
+                .filter(|(_, &col)| col == 1)
+                .fold(0, |packed, (col, _)| packed | (1 << col));
+            bits.store(packed | overlay.load(Ordering::Relaxed), Ordering::Release);
+        }
+    }
+
     impl DisplayMatrix {
         /// The first column after the icons.
         pub const DISPLAY_OFFSET: usize = 2;
@@ -257,7 +701,7 @@ pub mod display_matrix {
         /// The delay between shifting the display items left.
         pub const SCROLL_DELAY: u64 = 150;
 
-        /// Clear the entire display. Includes icons.
+        /// Clear the entire display. Includes icons, and stops any icon animations.
         ///
         /// # Arguments
         ///
@@ -269,6 +713,12 @@ pub mod display_matrix {
             }
 
             self.0.replace(cs, [[0; 32]; 8]);
+            publish(&self.0.borrow_ref(cs));
+
+            // the next app starts with steady icons
+            for mask in BLINKING.iter().chain(&PULSING) {
+                mask.store(0, Ordering::Relaxed);
+            }
         }
 
         /// Clear the display. Does not include icons.
@@ -289,6 +739,82 @@ pub mod display_matrix {
                     matrix[row][col] = 0;
                 }
             }
+
+            publish(&matrix);
+        }
+
+        /// Show a pattern on the display, replacing what was shown. Does not include icons.
+        ///
+        /// Each row of the pattern is packed into bits, with the display offset column in the lowest bit.
+        /// Bits past the last index are not shown.
+        ///
+        /// # Arguments
+        ///
+        /// * `cs` - The critical section to access the display matrix.
+        /// * `pattern` - The 7 rows below the day of week icons, from top to bottom.
+        pub fn show_pattern(&self, cs: CriticalSection, pattern: &[u32; 7]) {
+            let mut matrix = self.0.borrow_ref_mut(cs);
+
+            for (row, bits) in matrix.iter_mut().skip(1).zip(pattern) {
+                for (col, item) in row.iter_mut().enumerate().skip(Self::DISPLAY_OFFSET) {
+                    let bit = (bits >> (col - Self::DISPLAY_OFFSET)) & 1;
+                    *item = if col <= Self::LAST_INDEX {
+                        bit as usize
+                    } else {
+                        0
+                    };
+                }
+            }
+
+            publish(&matrix);
+        }
+
+        /// Show a pattern in some of the columns, leaving the rest of the display as it is. Does not include icons.
+        ///
+        /// Used to draw next to text, such as the pomodoro hourglass beside the time.
+        ///
+        /// # Arguments
+        ///
+        /// * `cs` - The critical section to access the display matrix.
+        /// * `first_col` - The column to draw the lowest bit of each row in.
+        /// * `width` - How many columns to draw. Columns past the last index are not shown.
+        /// * `pattern` - The 7 rows below the day of week icons, from top to bottom.
+        pub fn show_columns(
+            &self,
+            cs: CriticalSection,
+            first_col: usize,
+            width: usize,
+            pattern: &[u32; 7],
+        ) {
+            let mut matrix = self.0.borrow_ref_mut(cs);
+
+            let last_col = (first_col + width - 1).min(Self::LAST_INDEX);
+            for (row, bits) in matrix.iter_mut().skip(1).zip(pattern) {
+                for (col, item) in row
+                    .iter_mut()
+                    .enumerate()
+                    .take(last_col + 1)
+                    .skip(first_col.max(Self::DISPLAY_OFFSET))
+                {
+                    *item = ((bits >> (col - first_col)) & 1) as usize;
+                }
+            }
+
+            publish(&matrix);
+        }
+
+        /// Light pixels in a row on top of whatever is shown, replacing the last pixels set for that row.
+        ///
+        /// The matrix underneath is not changed, so the pixels can move along a row of text without disturbing it. Pass 0 to remove them.
+        ///
+        /// # Arguments
+        ///
+        /// * `cs` - The critical section to access the display matrix.
+        /// * `row` - The row to light the pixels in, from 0 for the day of week icons.
+        /// * `bits` - The pixels to light, with column 0 in the lowest bit.
+        pub fn show_overlay(&self, cs: CriticalSection, row: usize, bits: u32) {
+            OVERLAY[row].store(bits, Ordering::Relaxed);
+            publish(&self.0.borrow_ref(cs));
         }
 
         /// Queue text into the text buffer. Will append to the queue.
@@ -298,6 +824,8 @@ pub mod display_matrix {
         ///
         /// Scrolling will be automatic if the text is too big to fit on the display.
         ///
+        /// Text longer than 32 characters is queued in chunks that scroll on from each other, so all of it is shown.
+        ///
         /// # Arguments
         ///
         /// * `text` - The text to show on the display.
@@ -310,38 +838,66 @@ pub mod display_matrix {
             hold_end_ms: u64,
             show_now: bool,
             scroll_off_display: bool,
+        ) {
+            self.queue_text_repeat(text, hold_end_ms, show_now, scroll_off_display, 1)
+                .await;
+        }
+
+        /// Queue text into the text buffer to be shown a number of times in a row. Will append to the queue.
+        ///
+        /// The text is only queued once, and is shown again by the text buffer, so it can still be cancelled part way through.
+        /// Text longer than 256 characters is only shown once.
+        ///
+        /// # Arguments
+        ///
+        /// * `text` - The text to show on the display.
+        /// * `hold_end_ms` - Minimum period to show the text for each time.
+        /// * `show_now` - Set true if you want to cancel the current display wait and remove all items in the text buffer queue.
+        /// * `scroll_off_display` - Set true if you want the text to scroll off the display each time.
+        /// * `times` - How many times to show the text.
+        pub async fn queue_text_repeat(
+            &self,
+            text: &str,
+            hold_end_ms: u64,
+            show_now: bool,
+            scroll_off_display: bool,
+            times: u8,
         ) {
             if show_now {
                 Self::cancel_and_remove_queue()
             }
 
-            let mut final_text = text;
-            if text.len() > 32 {
-                final_text = &text[0..32];
-            }
-
-            let mut chars: Vec<&Character<'_>, 32> = Vec::new();
-
-            for c in final_text.chars() {
-                let character: Option<&Character> = get_character_struct(c);
+            let mut remaining = text.chars().peekable();
+            let mut continuation = false;
 
-                match character {
-                    Some(ch) => {
-                        chars.extend([ch]);
-                    }
-                    None => info!("Character {} not found", c),
+            loop {
+                let chars = take_characters(&mut remaining);
+
+                // only the last chunk holds and scrolls off, the others carry straight on
+                let last = remaining.peek().is_none();
+                let buf = TextBufferItem {
+                    text: chars,
+                    hold_end_ms: if last { hold_end_ms } else { 0 },
+                    start_position: if continuation {
+                        Self::LAST_INDEX + 1
+                    } else {
+                        Self::DISPLAY_OFFSET
+                    },
+                    end_position: Self::LAST_INDEX,
+                    scroll_off_display: last && scroll_off_display,
+                    continuation,
+                    chunked: continuation || !last,
+                    times: if last { times } else { 1 },
+                    pattern: None,
+                };
+
+                instrumentation::measure("Display queue", TEXT_BUFFER.send(buf)).await;
+
+                if last {
+                    break;
                 }
+                continuation = true;
             }
-
-            let buf = TextBufferItem {
-                text: chars,
-                hold_end_ms,
-                start_position: Self::DISPLAY_OFFSET,
-                end_position: Self::LAST_INDEX,
-                scroll_off_display,
-            };
-
-            TEXT_BUFFER.send(buf).await;
         }
 
         /// Queue text into the text buffer. Will append to the queue.
@@ -368,33 +924,19 @@ pub mod display_matrix {
                 Self::cancel_and_remove_queue()
             }
 
-            let mut final_text = text;
-            if text.len() > 32 {
-                final_text = &text[0..32];
-            }
-
-            let mut chars: Vec<&Character<'_>, 32> = Vec::new();
-
-            for c in final_text.chars() {
-                let character: Option<&Character> = get_character_struct(c);
-
-                match character {
-                    Some(ch) => {
-                        chars.extend([ch]);
-                    }
-                    None => info!("Character {} not found", c),
-                }
-            }
-
             let buf = TextBufferItem {
-                text: chars,
+                text: take_characters(&mut text.chars()),
                 hold_end_ms,
                 start_position,
                 end_position: Self::LAST_INDEX,
                 scroll_off_display: false,
+                continuation: false,
+                chunked: false,
+                times: 1,
+                pattern: None,
             };
 
-            TEXT_BUFFER.send(buf).await;
+            instrumentation::measure("Display queue", TEXT_BUFFER.send(buf)).await;
         }
 
         /// Queue text into the text buffer. Will append to the queue.
@@ -421,33 +963,19 @@ pub mod display_matrix {
                 Self::cancel_and_remove_queue()
             }
 
-            let mut final_text = text;
-            if text.len() > 32 {
-                final_text = &text[0..32];
-            }
-
-            let mut chars: Vec<&Character<'_>, 32> = Vec::new();
-
-            for c in final_text.chars() {
-                let character: Option<&Character> = get_character_struct(c);
-
-                match character {
-                    Some(ch) => {
-                        chars.extend([ch]);
-                    }
-                    None => info!("Character {} not found", c),
-                }
-            }
-
             let buf = TextBufferItem {
-                text: chars,
+                text: take_characters(&mut text.chars()),
                 hold_end_ms,
                 start_position: Self::DISPLAY_OFFSET,
                 end_position,
                 scroll_off_display: false,
+                continuation: false,
+                chunked: false,
+                times: 1,
+                pattern: None,
             };
 
-            TEXT_BUFFER.send(buf).await;
+            instrumentation::measure("Display queue", TEXT_BUFFER.send(buf)).await;
         }
 
         /// Queue the time into the text buffer. Will append to the queue.
@@ -480,26 +1008,7 @@ pub mod display_matrix {
             show_now: bool,
             scroll_off_display: bool,
         ) {
-            let mut time = String::<8>::new();
-
-            if left < 10 {
-                _ = write!(time, "0{left}");
-            } else {
-                _ = write!(time, "{left}");
-            }
-
-            match colon {
-                TimeColon::Full => _ = write!(time, ":"),
-                TimeColon::Empty => _ = write!(time, " "),
-                TimeColon::Top => _ = write!(time, "±"),
-                TimeColon::Bottom => _ = write!(time, "§"),
-            }
-
-            if right < 10 {
-                _ = write!(time, "0{right}");
-            } else {
-                _ = write!(time, "{right}");
-            }
+            let time = formatting::format_time(left, right, colon);
 
             self.queue_text(time.as_str(), hold_end_ms, show_now, scroll_off_display)
                 .await;
@@ -529,15 +1038,7 @@ pub mod display_matrix {
             hold_end_ms: u64,
             show_now: bool,
         ) {
-            let mut time = String::<8>::new();
-
-            _ = write!(time, ":");
-
-            if right < 10 {
-                _ = write!(time, "0{right}");
-            } else {
-                _ = write!(time, "{right}");
-            }
+            let time = formatting::format_right_side(':', right);
 
             self.queue_text_from(12, time.as_str(), hold_end_ms, show_now)
                 .await;
@@ -567,15 +1068,7 @@ pub mod display_matrix {
             hold_end_ms: u64,
             show_now: bool,
         ) {
-            let mut time = String::<8>::new();
-
-            if left < 10 {
-                _ = write!(time, "0{left}");
-            } else {
-                _ = write!(time, "{left}");
-            }
-
-            _ = write!(time, ":");
+            let time = formatting::format_left_side(left, ':');
 
             self.queue_text_to(13, time.as_str(), hold_end_ms, show_now)
                 .await;
@@ -597,9 +1090,7 @@ pub mod display_matrix {
         /// DISPLAY_MATRIX.queue_year(2023, 1000, false).await; // will render as 2023 for at least 1 second.
         /// ```
         pub async fn queue_year(&self, year: i32, hold_end_ms: u64, show_now: bool) {
-            let mut text: String<8> = String::<8>::new();
-
-            _ = write!(text, "{year}");
+            let text = formatting::format_year(year);
 
             self.queue_text(text.as_str(), hold_end_ms, show_now, false)
                 .await;
@@ -625,21 +1116,7 @@ pub mod display_matrix {
         /// DISPLAY_MATRIX.queue_date(1, 12, 1000, false).await; // will render as 01:12 for at least 1 second.
         /// ```
         pub async fn queue_date(&self, left: u32, right: u32, hold_end_ms: u64, show_now: bool) {
-            let mut date = String::<8>::new();
-
-            if left < 10 {
-                _ = write!(date, "0{left}");
-            } else {
-                _ = write!(date, "{left}");
-            }
-
-            _ = write!(date, "/");
-
-            if right < 10 {
-                _ = write!(date, "0{right}");
-            } else {
-                _ = write!(date, "{right}");
-            }
+            let date = formatting::format_date(left, right);
 
             self.queue_text(date.as_str(), hold_end_ms, show_now, false)
                 .await;
@@ -668,17 +1145,9 @@ pub mod display_matrix {
             hold_end_ms: u64,
             show_now: bool,
         ) {
-            let mut time = String::<8>::new();
-
-            _ = write!(time, "/");
-
-            if right < 10 {
-                _ = write!(time, "0{right}");
-            } else {
-                _ = write!(time, "{right}");
-            }
+            let date = formatting::format_right_side('/', right);
 
-            self.queue_text_from(12, time.as_str(), hold_end_ms, show_now)
+            self.queue_text_from(12, date.as_str(), hold_end_ms, show_now)
                 .await;
         }
 
@@ -705,17 +1174,9 @@ pub mod display_matrix {
             hold_end_ms: u64,
             show_now: bool,
         ) {
-            let mut time = String::<8>::new();
+            let date = formatting::format_left_side(left, '/');
 
-            if left < 10 {
-                _ = write!(time, "0{left}");
-            } else {
-                _ = write!(time, "{left}");
-            }
-
-            _ = write!(time, "/");
-
-            self.queue_text_to(13, time.as_str(), hold_end_ms, show_now)
+            self.queue_text_to(13, date.as_str(), hold_end_ms, show_now)
                 .await;
         }
 
@@ -727,29 +1188,25 @@ pub mod display_matrix {
         ///
         /// * `temp` - The temperature to show.
         /// * `pref` - What the temperature reporting preference is.
+        /// * `precision` - How many decimal places to show.
         /// * `show_now` - Set true if you want to cancel the current display wait and remove all items in the text buffer queue.
         /// * `scroll_off_display` - Set true if you want the text to scroll off the display.
         ///
         /// # Example
         ///
         /// ```rust
-        /// DISPLAY_MATRIX.queue_temperature(25, TemperaturePreference::Celcius, false).await; // will render as 20°C.
-        /// DISPLAY_MATRIX.queue_temperature(50, TemperaturePreference::Fahrenheit, true).await; // will render as 50°F and scroll off the display.
+        /// DISPLAY_MATRIX.queue_temperature(25, TemperaturePreference::Celcius, TemperaturePrecision::Whole, false).await; // will render as 20°C.
+        /// DISPLAY_MATRIX.queue_temperature(50, TemperaturePreference::Fahrenheit, TemperaturePrecision::Whole, true).await; // will render as 50°F and scroll off the display.
+        /// DISPLAY_MATRIX.queue_temperature(23.4, TemperaturePreference::Celcius, TemperaturePrecision::Tenths, false).await; // will render as 23.4°C.
         pub async fn queue_temperature(
             &self,
             temp: f32,
             pref: TemperaturePreference,
+            precision: TemperaturePrecision,
             show_now: bool,
             scroll_off_display: bool,
         ) {
-            let mut text = String::<8>::new();
-
-            _ = write!(text, "{:.0}", temp);
-
-            match pref {
-                TemperaturePreference::Celcius => _ = write!(text, "°C"),
-                TemperaturePreference::Fahrenheit => _ = write!(text, "°F"),
-            }
+            let text = formatting::format_temp(temp, pref, precision);
 
             self.queue_text(text.as_str(), 2500, show_now, scroll_off_display)
                 .await;
@@ -765,78 +1222,177 @@ pub mod display_matrix {
         /// * `min` - The minute to show.
         /// * `temp` - The temperature to show.
         /// * `pref` - What the temperature reporting preference is.
+        /// * `precision` - How many decimal places to show.
         /// * `show_now` - Set true if you want to cancel the current display wait and remove all items in the text buffer queue.
         ///
         /// # Example
         ///
         /// ```rust
-        /// DISPLAY_MATRIX.queue_time_temperature(22, 10, 25, TemperaturePreference::Celcius, false).await; // will render as 22:10  20°C and scroll off the display.
-        /// DISPLAY_MATRIX.queue_time_temperature(6, 30, 50, TemperaturePreference::Fahrenheit, true).await; // will render as 06:30  50°F and scroll off the display.
+        /// DISPLAY_MATRIX.queue_time_temperature(22, 10, 25, TemperaturePreference::Celcius, TemperaturePrecision::Whole, false).await; // will render as 22:10  20°C and scroll off the display.
+        /// DISPLAY_MATRIX.queue_time_temperature(6, 30, 50, TemperaturePreference::Fahrenheit, TemperaturePrecision::Whole, true).await; // will render as 06:30  50°F and scroll off the display.
         pub async fn queue_time_temperature(
             &self,
             hour: u32,
             min: u32,
             temp: f32,
             pref: TemperaturePreference,
+            precision: TemperaturePrecision,
             show_now: bool,
         ) {
-            let mut text = String::<16>::new();
+            let text = formatting::format_time_temp(hour, min, temp, pref, precision);
 
-            if hour < 10 {
-                _ = write!(text, "0{hour}");
-            } else {
-                _ = write!(text, "{hour}");
-            }
+            self.queue_text(text.as_str(), 0, show_now, true).await;
+        }
 
-            _ = write!(text, ":");
+        /// Queue the lowest and highest temperature into the text buffer. Will append to the queue.
+        ///
+        /// Will scroll the entire text base until it is empty.
+        ///
+        /// # Arguments
+        ///
+        /// * `min` - The lowest temperature to show.
+        /// * `max` - The highest temperature to show.
+        /// * `pref` - What the temperature reporting preference is.
+        /// * `precision` - How many decimal places to show.
+        /// * `show_now` - Set true if you want to cancel the current display wait and remove all items in the text buffer queue.
+        ///
+        /// # Example
+        ///
+        /// ```rust
+        /// DISPLAY_MATRIX.queue_temperature_range(19, 26, TemperaturePreference::Celcius, TemperaturePrecision::Whole, false).await; // will render as HI 26°C LO 19°C and scroll off the display.
+        pub async fn queue_temperature_range(
+            &self,
+            min: f32,
+            max: f32,
+            pref: TemperaturePreference,
+            precision: TemperaturePrecision,
+            show_now: bool,
+        ) {
+            let text = formatting::format_temp_range(min, max, pref, precision);
 
-            if min < 10 {
-                _ = write!(text, "0{min}");
-            } else {
-                _ = write!(text, "{min}");
+            self.queue_text(text.as_str(), 0, show_now, true).await;
+        }
+
+        /// Queue two lines of mini text into the text buffer, one above the other. Will append to the queue.
+        ///
+        /// Each line is 3 pixels high, the top line in the first 3 rows below the day of week icons and the bottom line in the last 3 rows.
+        /// Mini text can not scroll, so anything past the last index is cut off.
+        ///
+        /// # Arguments
+        ///
+        /// * `top` - The text to show on the top line.
+        /// * `bottom` - The text to show on the bottom line.
+        /// * `hold_end_ms` - Minimum period to show the text for.
+        /// * `show_now` - Set true if you want to cancel the current display wait and remove all items in the text buffer queue.
+        ///
+        /// # Example
+        ///
+        /// ```rust
+        /// DISPLAY_MATRIX.queue_mini_lines("10:30", "21°C", 0, false).await; // will render 10:30 above 21°C.
+        /// ```
+        pub async fn queue_mini_lines(
+            &self,
+            top: &str,
+            bottom: &str,
+            hold_end_ms: u64,
+            show_now: bool,
+        ) {
+            if show_now {
+                Self::cancel_and_remove_queue()
             }
 
-            _ = write!(text, "  {:.0}", temp);
+            let mut pattern = [0; 7];
+            Self::draw_mini_line(&mut pattern[0..3], top);
+            Self::draw_mini_line(&mut pattern[4..7], bottom);
 
-            match pref {
-                TemperaturePreference::Celcius => _ = write!(text, "°C"),
-                TemperaturePreference::Fahrenheit => _ = write!(text, "°F"),
-            }
+            let buf = TextBufferItem {
+                text: Vec::new(),
+                hold_end_ms,
+                start_position: Self::DISPLAY_OFFSET,
+                end_position: Self::LAST_INDEX,
+                scroll_off_display: false,
+                continuation: false,
+                chunked: false,
+                times: 1,
+                pattern: Some(pattern),
+            };
 
-            self.queue_text(text.as_str(), 0, show_now, true).await;
+            instrumentation::measure("Display queue", TEXT_BUFFER.send(buf)).await;
+        }
+
+        /// Draw a line of mini text into 3 rows of a pattern, with the display offset column in the lowest bit.
+        fn draw_mini_line(rows: &mut [u32], text: &str) {
+            let last_col = (Self::LAST_INDEX - Self::DISPLAY_OFFSET) as u32;
+            let mut pos = 0;
+
+            for c in text.chars() {
+                let character = match get_mini_character_struct(c) {
+                    Some(character) => character,
+                    None => {
+                        info!("Mini character {} not found", c);
+                        continue;
+                    }
+                };
+
+                if pos + character.width as u32 > last_col + 1 {
+                    break;
+                }
+
+                for (row, bits) in rows.iter_mut().zip(character.values) {
+                    *row |= bits << pos;
+                }
+                pos += character.width as u32 + 1;
+            }
         }
 
         /// Show text on the display. It will always clear what was shown previously.
         ///
         /// Responsible for moving items on the display left (animation) if the position of the last item is at the end of the display.
         async fn show_text(&self, item: TextBufferItem<'_>) {
+            if let Some(pattern) = item.pattern {
+                critical_section::with(|cs| {
+                    self.show_pattern(cs, &pattern);
+                });
+
+                Timer::after(Duration::from_millis(item.hold_end_ms)).await;
+                return;
+            }
+
             let mut total_width = 0;
 
             for c in &item.text {
                 total_width += c.width;
-                total_width += 1;
+                total_width += c.spacing;
             }
 
-            // if width is greater than matrix size with whitespace accounted for
-            if total_width < Self::LAST_INDEX - 2 {
-                critical_section::with(|cs| {
-                    self.clear(cs, false);
-                });
+            if item.chunked {
+                SCROLLING.store(true, Ordering::Relaxed);
             }
 
             let mut pos = item.start_position;
-            let space_char = get_character_struct('_').unwrap();
-            for space in 2..pos {
-                self.show_char(space_char, space).await;
-            }
 
-            for space in item.end_position..Self::LAST_INDEX {
-                self.show_char(space_char, space).await;
+            // a continuation scrolls on from the text already shown, so leave it in place
+            if !item.continuation {
+                // if width is greater than matrix size with whitespace accounted for
+                if total_width < Self::LAST_INDEX - 2 {
+                    critical_section::with(|cs| {
+                        self.clear(cs, false);
+                    });
+                }
+
+                let space_char = get_character_struct('_').unwrap();
+                for space in 2..pos {
+                    self.show_char(space_char, space).await;
+                }
+
+                for space in item.end_position..Self::LAST_INDEX {
+                    self.show_char(space_char, space).await;
+                }
             }
 
             for c in item.text {
                 pos = self.show_char(c, pos).await;
-                pos += 2;
+                pos += 1 + c.spacing;
 
                 // if the position is greater than the last possible index and the total width is also greater (this won't be true for perfect fit items)
                 if pos > Self::LAST_INDEX && total_width >= Self::LAST_INDEX {
@@ -858,11 +1414,46 @@ pub mod display_matrix {
                 while pos > Self::DISPLAY_OFFSET {
                     self.shift_text_left(false);
                     Timer::after(Duration::from_millis(Self::SCROLL_DELAY)).await;
+                    Self::wait_while_paused().await;
                     pos -= 1;
                 }
             }
         }
 
+        /// Determine if text too long for one item, over 32 characters, is scrolling across the display.
+        pub fn is_scrolling(&self) -> bool {
+            SCROLLING.load(Ordering::Relaxed)
+        }
+
+        /// Pause the text scrolling across the display, or carry on if it is already paused.
+        ///
+        /// Paused text carries on by itself after 30 seconds. Does nothing if no text over 32 characters is scrolling.
+        pub fn toggle_scroll_pause(&self) {
+            if !self.is_scrolling() {
+                return;
+            }
+
+            if SCROLL_PAUSED.load(Ordering::Relaxed) {
+                SCROLL_RESUME_SIGNAL.signal(ScrollResumeSignal);
+            } else {
+                SCROLL_RESUME_SIGNAL.reset();
+                SCROLL_PAUSED.store(true, Ordering::Relaxed);
+            }
+        }
+
+        /// Wait while the scrolling text is paused, until it is resumed or the pause times out.
+        async fn wait_while_paused() {
+            if SCROLL_PAUSED.load(Ordering::Relaxed) {
+                select(
+                    SCROLL_RESUME_SIGNAL.wait(),
+                    Timer::after(Duration::from_secs(SCROLL_PAUSE_SECS)),
+                )
+                .await;
+
+                SCROLL_PAUSED.store(false, Ordering::Relaxed);
+            }
+        }
+
         /// Show an individual [character](Character) at the given position.
         ///
         /// Will move the display left (animation) if the column exceeds the `LAST_INDEX`.
@@ -889,6 +1480,7 @@ pub mod display_matrix {
                     self.shift_text_left(false);
 
                     Timer::after(Duration::from_millis(Self::SCROLL_DELAY)).await;
+                    Self::wait_while_paused().await;
 
                     // grab matrix again after update
                     matrix = critical_section::with(|cs| *self.0.borrow_ref(cs));
@@ -899,7 +1491,10 @@ pub mod display_matrix {
                     item[pos] = (byte >> col) % 2;
                 }
 
-                critical_section::with(|cs| self.0.replace(cs, matrix));
+                critical_section::with(|cs| {
+                    self.0.replace(cs, matrix);
+                    publish(&matrix);
+                });
             }
 
             pos
@@ -920,6 +1515,7 @@ pub mod display_matrix {
                         for w in 0..i.width {
                             matrix[i.col][i.row + w] = 1;
                         }
+                        publish(&matrix);
                     }
                     None => info!("Icon {} not found", icon_text),
                 }
@@ -941,12 +1537,72 @@ pub mod display_matrix {
                         for w in 0..i.width {
                             matrix[i.col][i.row + w] = 0;
                         }
+                        publish(&matrix);
                     }
                     None => info!("Icon {} not found", icon_text),
                 }
             })
         }
 
+        /// Animate an icon, or stop animating it with [Steady](IconAnimation::Steady). The display core animates it from then on.
+        ///
+        /// Does not show or hide the icon, so the animation can be set before or after it is shown.
+        ///
+        /// `icon_text` should be a string that can be returned from the [lookup table fn](get_icon_struct).
+        pub fn animate_icon(&self, icon_text: &str, animation: IconAnimation) {
+            let icon = match get_icon_struct(icon_text) {
+                Some(icon) => icon,
+                None => {
+                    info!("Icon {} not found", icon_text);
+                    return;
+                }
+            };
+
+            let mask = (0..icon.width).fold(0, |mask, w| mask | (1 << (icon.row + w)));
+            let (blink, pulse) = match animation {
+                IconAnimation::Steady => (false, false),
+                IconAnimation::Blink => (true, false),
+                IconAnimation::Pulse => (false, true),
+            };
+
+            // the RP2040 has no atomic read-modify-write, so change the masks in a critical section
+            critical_section::with(|_| {
+                for (masks, on) in [(&BLINKING, blink), (&PULSING, pulse)] {
+                    let bits = masks[icon.col].load(Ordering::Relaxed);
+                    let bits = if on { bits | mask } else { bits & !mask };
+                    masks[icon.col].store(bits, Ordering::Relaxed);
+                }
+            });
+        }
+
+        /// Hide all of the day icons.
+        pub fn hide_day_icons(&self) {
+            for icon in ["Mon", "Tue", "Wed", "Thur", "Fri", "Sat", "Sun"] {
+                self.hide_icon(icon);
+            }
+        }
+
+        /// Show the icon of each day in `days`, hiding the others.
+        pub fn show_day_icons(&self, days: Days) {
+            let icons = [
+                (Weekday::Mon, "Mon"),
+                (Weekday::Tue, "Tue"),
+                (Weekday::Wed, "Wed"),
+                (Weekday::Thu, "Thur"),
+                (Weekday::Fri, "Fri"),
+                (Weekday::Sat, "Sat"),
+                (Weekday::Sun, "Sun"),
+            ];
+
+            for (day, icon) in icons {
+                if days.contains(day) {
+                    self.show_icon(icon);
+                } else {
+                    self.hide_icon(icon);
+                }
+            }
+        }
+
         /// Show a day icon, determined from `day`.
         ///
         /// **This is intended for use during normal function where days are incremented at 12am. It will only hide the previous day icon, not all other days.**
@@ -1040,7 +1696,10 @@ pub mod display_matrix {
                 }
             }
 
-            critical_section::with(|cs| self.0.replace(cs, matrix));
+            critical_section::with(|cs| {
+                self.0.replace(cs, matrix);
+                publish(&matrix);
+            });
         }
 
         /// Cancel the current minimum display task and clear the text buffer.
@@ -1064,231 +1723,57 @@ pub mod display_matrix {
 }
 
 /// Module for handling text on the display.
-mod text {
-    /// Represent text display on the display.
-    #[derive(Clone)]
-    pub struct Character<'a> {
+mod text;
+
+/// Module for handling mini text on the display, 3 pixels high so two lines fit one above the other.
+mod mini_text {
+    /// Represent a mini character on the display.
+    pub struct MiniCharacter {
         /// The width of the character.
-        pub width: &'a usize,
+        pub width: usize,
 
-        /// The hex representation for each row and column.
-        pub values: &'a [usize],
+        /// The bits for each of the 3 rows, with the left column in the lowest bit.
+        pub values: [u32; 3],
     }
 
-    impl<'a> Character<'a> {
-        /// Create a new character.
-        const fn new(width: &'a usize, values: &'a [usize]) -> Self {
+    impl MiniCharacter {
+        /// Create a new mini character.
+        const fn new(width: usize, values: [u32; 3]) -> Self {
             Self { width, values }
         }
     }
 
-    /// All supported characters lookup table.
-    const CHARACTER_TABLE: [(char, Character); 46] = [
-        (
-            '0',
-            Character::new(&4, &[0x06, 0x09, 0x09, 0x09, 0x09, 0x09, 0x06]),
-        ),
-        (
-            '1',
-            Character::new(&4, &[0x04, 0x06, 0x04, 0x04, 0x04, 0x04, 0x0E]),
-        ),
-        (
-            '2',
-            Character::new(&4, &[0x06, 0x09, 0x08, 0x04, 0x02, 0x01, 0x0F]),
-        ),
-        (
-            '3',
-            Character::new(&4, &[0x06, 0x09, 0x08, 0x06, 0x08, 0x09, 0x06]),
-        ),
-        (
-            '4',
-            Character::new(&4, &[0x08, 0x0C, 0x0A, 0x09, 0x0F, 0x08, 0x08]),
-        ),
-        (
-            '5',
-            Character::new(&4, &[0x0F, 0x01, 0x07, 0x08, 0x08, 0x09, 0x06]),
-        ),
-        (
-            '6',
-            Character::new(&4, &[0x04, 0x02, 0x01, 0x07, 0x09, 0x09, 0x06]),
-        ),
-        (
-            '7',
-            Character::new(&4, &[0x0F, 0x09, 0x04, 0x04, 0x04, 0x04, 0x04]),
-        ),
-        (
-            '8',
-            Character::new(&4, &[0x06, 0x09, 0x09, 0x06, 0x09, 0x09, 0x06]),
-        ),
-        (
-            '9',
-            Character::new(&4, &[0x06, 0x09, 0x09, 0x0E, 0x08, 0x04, 0x02]),
-        ),
-        (
-            'A',
-            Character::new(&4, &[0x06, 0x09, 0x09, 0x0F, 0x09, 0x09, 0x09]),
-        ),
-        (
-            'B',
-            Character::new(&4, &[0x07, 0x09, 0x09, 0x07, 0x09, 0x09, 0x07]),
-        ),
-        (
-            'C',
-            Character::new(&4, &[0x06, 0x09, 0x01, 0x01, 0x01, 0x09, 0x06]),
-        ),
-        (
-            'D',
-            Character::new(&4, &[0x07, 0x09, 0x09, 0x09, 0x09, 0x09, 0x07]),
-        ),
-        (
-            'E',
-            Character::new(&4, &[0x0F, 0x01, 0x01, 0x0F, 0x01, 0x01, 0x0F]),
-        ),
-        (
-            'F',
-            Character::new(&4, &[0x0F, 0x01, 0x01, 0x0F, 0x01, 0x01, 0x01]),
-        ),
-        (
-            'G',
-            Character::new(&4, &[0x06, 0x09, 0x01, 0x0D, 0x09, 0x09, 0x06]),
-        ),
-        (
-            'H',
-            Character::new(&4, &[0x09, 0x09, 0x09, 0x0F, 0x09, 0x09, 0x09]),
-        ),
-        (
-            'I',
-            Character::new(&3, &[0x07, 0x02, 0x02, 0x02, 0x02, 0x02, 0x07]),
-        ),
-        (
-            'J',
-            Character::new(&4, &[0x0F, 0x08, 0x08, 0x08, 0x09, 0x09, 0x06]),
-        ),
-        (
-            'K',
-            Character::new(&4, &[0x09, 0x05, 0x03, 0x01, 0x03, 0x05, 0x09]),
-        ),
-        (
-            'L',
-            Character::new(&4, &[0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x0F]),
-        ),
-        (
-            'M',
-            Character::new(&5, &[0x11, 0x1B, 0x15, 0x11, 0x11, 0x11, 0x11]),
-        ),
-        (
-            'N',
-            Character::new(&4, &[0x09, 0x09, 0x0B, 0x0D, 0x09, 0x09, 0x09]),
-        ),
-        (
-            'O',
-            Character::new(&4, &[0x06, 0x09, 0x09, 0x09, 0x09, 0x09, 0x06]),
-        ),
-        (
-            'P',
-            Character::new(&4, &[0x07, 0x09, 0x09, 0x07, 0x01, 0x01, 0x01]),
-        ),
-        (
-            'Q',
-            Character::new(&5, &[0x0E, 0x11, 0x11, 0x11, 0x15, 0x19, 0x0E]),
-        ),
-        (
-            'R',
-            Character::new(&4, &[0x07, 0x09, 0x09, 0x07, 0x03, 0x05, 0x09]),
-        ),
-        (
-            'S',
-            Character::new(&4, &[0x06, 0x09, 0x02, 0x04, 0x08, 0x09, 0x06]),
-        ),
-        (
-            'T',
-            Character::new(&5, &[0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04]),
-        ),
-        (
-            'U',
-            Character::new(&4, &[0x09, 0x09, 0x09, 0x09, 0x09, 0x09, 0x06]),
-        ),
-        (
-            'V',
-            Character::new(&5, &[0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04]),
-        ),
-        (
-            'W',
-            Character::new(&5, &[0x11, 0x11, 0x11, 0x15, 0x15, 0x1B, 0x11]),
-        ),
-        (
-            'X',
-            Character::new(&5, &[0x11, 0x0A, 0x04, 0x04, 0x04, 0x0A, 0x11]),
-        ),
-        (
-            'Y',
-            Character::new(&5, &[0x11, 0x11, 0x0A, 0x04, 0x04, 0x04, 0x04]),
-        ),
-        (
-            'Z',
-            Character::new(&4, &[0x0F, 0x08, 0x04, 0x02, 0x01, 0x0F, 0x00]),
-        ),
-        (
-            ':',
-            Character::new(&2, &[0x00, 0x03, 0x03, 0x00, 0x03, 0x03, 0x00]),
-        ),
+    /// All supported mini characters lookup table. Enough for the time and temperature.
+    const MINI_CHARACTER_TABLE: [(char, MiniCharacter); 20] = [
+        ('0', MiniCharacter::new(3, [0x07, 0x05, 0x07])),
+        ('1', MiniCharacter::new(3, [0x03, 0x02, 0x07])),
+        ('2', MiniCharacter::new(3, [0x03, 0x02, 0x06])),
+        ('3', MiniCharacter::new(3, [0x07, 0x06, 0x07])),
+        ('4', MiniCharacter::new(3, [0x05, 0x07, 0x04])),
+        ('5', MiniCharacter::new(3, [0x06, 0x02, 0x03])),
+        ('6', MiniCharacter::new(3, [0x01, 0x07, 0x07])),
+        ('7', MiniCharacter::new(3, [0x07, 0x04, 0x04])),
+        ('8', MiniCharacter::new(3, [0x05, 0x07, 0x05])),
+        ('9', MiniCharacter::new(3, [0x07, 0x07, 0x04])),
+        (':', MiniCharacter::new(1, [0x01, 0x00, 0x01])),
         // top half of a : only
-        (
-            '±',
-            Character::new(&2, &[0x00, 0x03, 0x03, 0x00, 0x00, 0x00, 0x00]),
-        ),
+        ('±', MiniCharacter::new(1, [0x01, 0x00, 0x00])),
         // bottom half of a : only
-        (
-            '§',
-            Character::new(&2, &[0x00, 0x00, 0x00, 0x00, 0x03, 0x03, 0x00]),
-        ),
-        (
-            ' ',
-            Character::new(&2, &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
-        ),
-        (
-            '°',
-            Character::new(&2, &[0x03, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00]),
-        ),
-        (
-            '.',
-            Character::new(&1, &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]),
-        ),
-        (
-            '-',
-            Character::new(&2, &[0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00]),
-        ),
-        (
-            '/',
-            Character::new(&2, &[0x02, 0x02, 0x02, 0x01, 0x01, 0x01, 0x01, 0x01]),
-        ),
-        (
-            '+',
-            Character::new(&5, &[0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00]),
-        ),
-        // empty space
-        (
-            '_',
-            Character::new(&1, &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
-        ),
+        ('§', MiniCharacter::new(1, [0x00, 0x00, 0x01])),
+        (' ', MiniCharacter::new(1, [0x00, 0x00, 0x00])),
+        ('°', MiniCharacter::new(1, [0x01, 0x00, 0x00])),
+        ('.', MiniCharacter::new(1, [0x00, 0x00, 0x01])),
+        ('-', MiniCharacter::new(2, [0x00, 0x03, 0x00])),
+        ('%', MiniCharacter::new(3, [0x05, 0x02, 0x05])),
+        ('C', MiniCharacter::new(2, [0x03, 0x01, 0x03])),
+        ('F', MiniCharacter::new(2, [0x03, 0x03, 0x01])),
     ];
 
-    /// Find the [character](Character) for the `character` param.
-    ///
-    /// Will return [None](Option::None) if the icon is not found in the [lookup table](CHARACTER_TABLE).
+    /// Find the [mini character](MiniCharacter) for the `character` param.
     ///
-    /// # Example
-    /// ```rust
-    /// let char_text = 'A';
-    /// let ch: Option<&Character> = get_character_struct(char_text);
-    /// match ch {
-    ///     Some(c) => info!("Character {} found!", char_text),
-    ///     None => info!("Character {} not found", char_text),
-    /// }
-    /// // prints: Character A found!
-    /// ```
-    pub fn get_character_struct(character: char) -> Option<&'static Character<'static>> {
-        for &(c, ref info) in &CHARACTER_TABLE {
+    /// Will return [None](Option::None) if the character is not found in the [lookup table](MINI_CHARACTER_TABLE).
+    pub fn get_mini_character_struct(character: char) -> Option<&'static MiniCharacter> {
+        for &(c, ref info) in &MINI_CHARACTER_TABLE {
             if c == character.to_ascii_uppercase() {
                 return Some(info);
             }
@@ -0,0 +1,464 @@
+use defmt::info;
+use heapless::Vec;
+
+/// Represent text display on the display.
+#[derive(Clone)]
+pub struct Character<'a> {
+    /// The width of the character.
+    pub width: &'a usize,
+
+    /// The hex representation for each row and column.
+    pub values: &'a [usize],
+
+    /// The number of empty columns to leave after the character.
+    pub spacing: usize,
+}
+
+impl<'a> Character<'a> {
+    /// Create a new character.
+    const fn new(width: &'a usize, values: &'a [usize]) -> Self {
+        Self {
+            width,
+            values,
+            spacing: 1,
+        }
+    }
+
+    /// Create a new character that is not followed by an empty column.
+    ///
+    /// Used for punctuation like the decimal point, so "23.4°C" fits on the display without scrolling.
+    const fn new_tight(width: &'a usize, values: &'a [usize]) -> Self {
+        Self {
+            width,
+            values,
+            spacing: 0,
+        }
+    }
+}
+
+/// All supported characters lookup table.
+const CHARACTER_TABLE: [(char, Character); 59] = [
+    (
+        '0',
+        Character::new(&4, &[0x06, 0x09, 0x09, 0x09, 0x09, 0x09, 0x06]),
+    ),
+    (
+        '1',
+        Character::new(&4, &[0x04, 0x06, 0x04, 0x04, 0x04, 0x04, 0x0E]),
+    ),
+    (
+        '2',
+        Character::new(&4, &[0x06, 0x09, 0x08, 0x04, 0x02, 0x01, 0x0F]),
+    ),
+    (
+        '3',
+        Character::new(&4, &[0x06, 0x09, 0x08, 0x06, 0x08, 0x09, 0x06]),
+    ),
+    (
+        '4',
+        Character::new(&4, &[0x08, 0x0C, 0x0A, 0x09, 0x0F, 0x08, 0x08]),
+    ),
+    (
+        '5',
+        Character::new(&4, &[0x0F, 0x01, 0x07, 0x08, 0x08, 0x09, 0x06]),
+    ),
+    (
+        '6',
+        Character::new(&4, &[0x04, 0x02, 0x01, 0x07, 0x09, 0x09, 0x06]),
+    ),
+    (
+        '7',
+        Character::new(&4, &[0x0F, 0x09, 0x04, 0x04, 0x04, 0x04, 0x04]),
+    ),
+    (
+        '8',
+        Character::new(&4, &[0x06, 0x09, 0x09, 0x06, 0x09, 0x09, 0x06]),
+    ),
+    (
+        '9',
+        Character::new(&4, &[0x06, 0x09, 0x09, 0x0E, 0x08, 0x04, 0x02]),
+    ),
+    (
+        'A',
+        Character::new(&4, &[0x06, 0x09, 0x09, 0x0F, 0x09, 0x09, 0x09]),
+    ),
+    (
+        'B',
+        Character::new(&4, &[0x07, 0x09, 0x09, 0x07, 0x09, 0x09, 0x07]),
+    ),
+    (
+        'C',
+        Character::new(&4, &[0x06, 0x09, 0x01, 0x01, 0x01, 0x09, 0x06]),
+    ),
+    (
+        'D',
+        Character::new(&4, &[0x07, 0x09, 0x09, 0x09, 0x09, 0x09, 0x07]),
+    ),
+    (
+        'E',
+        Character::new(&4, &[0x0F, 0x01, 0x01, 0x0F, 0x01, 0x01, 0x0F]),
+    ),
+    (
+        'F',
+        Character::new(&4, &[0x0F, 0x01, 0x01, 0x0F, 0x01, 0x01, 0x01]),
+    ),
+    (
+        'G',
+        Character::new(&4, &[0x06, 0x09, 0x01, 0x0D, 0x09, 0x09, 0x06]),
+    ),
+    (
+        'H',
+        Character::new(&4, &[0x09, 0x09, 0x09, 0x0F, 0x09, 0x09, 0x09]),
+    ),
+    (
+        'I',
+        Character::new(&3, &[0x07, 0x02, 0x02, 0x02, 0x02, 0x02, 0x07]),
+    ),
+    (
+        'J',
+        Character::new(&4, &[0x0F, 0x08, 0x08, 0x08, 0x09, 0x09, 0x06]),
+    ),
+    (
+        'K',
+        Character::new(&4, &[0x09, 0x05, 0x03, 0x01, 0x03, 0x05, 0x09]),
+    ),
+    (
+        'L',
+        Character::new(&4, &[0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x0F]),
+    ),
+    (
+        'M',
+        Character::new(&5, &[0x11, 0x1B, 0x15, 0x11, 0x11, 0x11, 0x11]),
+    ),
+    (
+        'N',
+        Character::new(&4, &[0x09, 0x09, 0x0B, 0x0D, 0x09, 0x09, 0x09]),
+    ),
+    (
+        'O',
+        Character::new(&4, &[0x06, 0x09, 0x09, 0x09, 0x09, 0x09, 0x06]),
+    ),
+    (
+        'P',
+        Character::new(&4, &[0x07, 0x09, 0x09, 0x07, 0x01, 0x01, 0x01]),
+    ),
+    (
+        'Q',
+        Character::new(&5, &[0x0E, 0x11, 0x11, 0x11, 0x15, 0x19, 0x0E]),
+    ),
+    (
+        'R',
+        Character::new(&4, &[0x07, 0x09, 0x09, 0x07, 0x03, 0x05, 0x09]),
+    ),
+    (
+        'S',
+        Character::new(&4, &[0x06, 0x09, 0x02, 0x04, 0x08, 0x09, 0x06]),
+    ),
+    (
+        'T',
+        Character::new(&5, &[0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04]),
+    ),
+    (
+        'U',
+        Character::new(&4, &[0x09, 0x09, 0x09, 0x09, 0x09, 0x09, 0x06]),
+    ),
+    (
+        'V',
+        Character::new(&5, &[0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04]),
+    ),
+    (
+        'W',
+        Character::new(&5, &[0x11, 0x11, 0x11, 0x15, 0x15, 0x1B, 0x11]),
+    ),
+    (
+        'X',
+        Character::new(&5, &[0x11, 0x0A, 0x04, 0x04, 0x04, 0x0A, 0x11]),
+    ),
+    (
+        'Y',
+        Character::new(&5, &[0x11, 0x11, 0x0A, 0x04, 0x04, 0x04, 0x04]),
+    ),
+    (
+        'Z',
+        Character::new(&4, &[0x0F, 0x08, 0x04, 0x02, 0x01, 0x0F, 0x00]),
+    ),
+    // accented letters are shorter to leave the top rows for the accent, Å uses its ring as the top of the A
+    (
+        'À',
+        Character::new(&4, &[0x02, 0x00, 0x06, 0x09, 0x0F, 0x09, 0x09]),
+    ),
+    (
+        'Â',
+        Character::new(&4, &[0x06, 0x00, 0x06, 0x09, 0x0F, 0x09, 0x09]),
+    ),
+    (
+        'Ä',
+        Character::new(&4, &[0x09, 0x00, 0x06, 0x09, 0x0F, 0x09, 0x09]),
+    ),
+    (
+        'Å',
+        Character::new(&4, &[0x06, 0x06, 0x09, 0x09, 0x0F, 0x09, 0x09]),
+    ),
+    (
+        'Ç',
+        Character::new(&4, &[0x06, 0x09, 0x01, 0x01, 0x09, 0x06, 0x04]),
+    ),
+    (
+        'È',
+        Character::new(&4, &[0x02, 0x00, 0x0F, 0x01, 0x07, 0x01, 0x0F]),
+    ),
+    (
+        'É',
+        Character::new(&4, &[0x04, 0x00, 0x0F, 0x01, 0x07, 0x01, 0x0F]),
+    ),
+    (
+        'Ê',
+        Character::new(&4, &[0x06, 0x00, 0x0F, 0x01, 0x07, 0x01, 0x0F]),
+    ),
+    (
+        'Ñ',
+        Character::new(&4, &[0x0A, 0x00, 0x09, 0x0B, 0x0D, 0x09, 0x09]),
+    ),
+    (
+        'Ö',
+        Character::new(&4, &[0x09, 0x00, 0x06, 0x09, 0x09, 0x09, 0x06]),
+    ),
+    (
+        'Ü',
+        Character::new(&4, &[0x09, 0x00, 0x09, 0x09, 0x09, 0x09, 0x06]),
+    ),
+    (
+        ':',
+        Character::new(&2, &[0x00, 0x03, 0x03, 0x00, 0x03, 0x03, 0x00]),
+    ),
+    // top half of a : only
+    (
+        '±',
+        Character::new(&2, &[0x00, 0x03, 0x03, 0x00, 0x00, 0x00, 0x00]),
+    ),
+    // bottom half of a : only
+    (
+        '§',
+        Character::new(&2, &[0x00, 0x00, 0x00, 0x00, 0x03, 0x03, 0x00]),
+    ),
+    (
+        ' ',
+        Character::new(&2, &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+    ),
+    (
+        '°',
+        Character::new(&2, &[0x03, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00]),
+    ),
+    (
+        '.',
+        Character::new_tight(&1, &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]),
+    ),
+    (
+        '-',
+        Character::new(&2, &[0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00]),
+    ),
+    (
+        '/',
+        Character::new(&2, &[0x02, 0x02, 0x02, 0x01, 0x01, 0x01, 0x01, 0x01]),
+    ),
+    (
+        '+',
+        Character::new(&5, &[0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00]),
+    ),
+    (
+        '?',
+        Character::new(&4, &[0x06, 0x09, 0x08, 0x04, 0x02, 0x00, 0x02]),
+    ),
+    (
+        '\'',
+        Character::new(&1, &[0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00]),
+    ),
+    // empty space
+    (
+        '_',
+        Character::new(&1, &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+    ),
+];
+
+/// Find the [character](Character) for the `character` param.
+///
+/// Lowercase letters use the uppercase character, including accented letters such as 'é'.
+/// Characters not in the table are shown as their [ASCII fallback](ascii_fallback), e.g. 'ô' as 'O'.
+///
+/// Will return [None](Option::None) if the character and its fallback are not found in the [lookup table](CHARACTER_TABLE).
+///
+/// # Example
+/// ```rust
+/// let char_text = 'A';
+/// let ch: Option<&Character> = get_character_struct(char_text);
+/// match ch {
+///     Some(c) => info!("Character {} found!", char_text),
+///     None => info!("Character {} not found", char_text),
+/// }
+/// // prints: Character A found!
+/// ```
+pub fn get_character_struct(character: char) -> Option<&'static Character<'static>> {
+    let upper = character.to_uppercase().next().unwrap_or(character);
+    match find_character(upper) {
+        Some(info) => Some(info),
+        None => find_character(ascii_fallback(character)?),
+    }
+}
+
+/// Find the [character](Character) in the [lookup table](CHARACTER_TABLE) exactly as it is passed.
+fn find_character(character: char) -> Option<&'static Character<'static>> {
+    for &(c, ref info) in &CHARACTER_TABLE {
+        if c == character {
+            return Some(info);
+        }
+    }
+    None
+}
+
+/// Get the closest character in the [lookup table](CHARACTER_TABLE) for one that is not in it, such as a letter without its accent.
+///
+/// Other ways of writing the degree symbol, such as 'º', are shown as '°'.
+///
+/// Will return [None](Option::None) if there is no close character.
+fn ascii_fallback(character: char) -> Option<char> {
+    let fallback = match character {
+        'º' | '˚' | '⁰' => '°',
+        'À'..='Å' | 'à'..='å' => 'A',
+        'Ç' | 'ç' => 'C',
+        'È'..='Ë' | 'è'..='ë' => 'E',
+        'Ì'..='Ï' | 'ì'..='ï' => 'I',
+        'Ñ' | 'ñ' => 'N',
+        'Ò'..='Ö' | 'Ø' | 'ò'..='ö' | 'ø' => 'O',
+        'Ù'..='Ü' | 'ù'..='ü' => 'U',
+        'Ý' | 'ý' | 'ÿ' => 'Y',
+        'ß' => 'S',
+        '‘' | '’' | '`' | '´' | '"' | '“' | '”' => '\'',
+        '–' | '—' | '−' => '-',
+        '…' => '.',
+        '\t' | '\u{a0}' => ' ',
+        _ => return None,
+    };
+
+    Some(fallback)
+}
+
+/// Take up to 32 [characters](Character) from the text, skipping any that can not be shown.
+///
+/// Works a character at a time rather than on bytes, so text with multi-byte characters such as '°' is never split.
+pub fn take_characters(
+    text: &mut impl Iterator<Item = char>,
+) -> Vec<&'static Character<'static>, 32> {
+    let mut chars = Vec::new();
+
+    while !chars.is_full() {
+        match text.next() {
+            Some(c) => match get_character_struct(c) {
+                Some(ch) => chars.extend([ch]),
+                None => info!("Character {} not found", c),
+            },
+            None => break,
+        }
+    }
+
+    chars
+}
+
+/// Tests run on the host by the `host-tests` crate.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Determine if the character shown is the one in the table for the passed character.
+    fn is_character(shown: &Character<'_>, character: char) -> bool {
+        core::ptr::eq(shown, find_character(character).unwrap())
+    }
+
+    #[test]
+    fn takes_at_most_32_characters() {
+        let mut text = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789".chars();
+
+        let first = take_characters(&mut text);
+        assert_eq!(first.len(), 32);
+        assert!(is_character(first[31], '5'));
+
+        let second = take_characters(&mut text);
+        assert_eq!(second.len(), 4);
+        assert!(is_character(second[0], '6'));
+
+        assert!(take_characters(&mut text).is_empty());
+    }
+
+    #[test]
+    fn degree_symbol_at_the_cut_is_not_split() {
+        // '°' is two bytes, so it falls across byte 32 and 33 of the text
+        for before in 30..=33 {
+            let mut text: heapless::String<64> = heapless::String::new();
+            for _ in 0..before {
+                text.push('1').unwrap();
+            }
+            text.push_str("°C").unwrap();
+
+            let mut chars = text.chars();
+            let first = take_characters(&mut chars);
+            let second = take_characters(&mut chars);
+
+            let shown: Vec<_, 64> = first.iter().chain(second.iter()).collect();
+            assert_eq!(shown.len(), before + 2);
+            assert!(is_character(shown[before], '°'));
+            assert!(is_character(shown[before + 1], 'C'));
+        }
+    }
+
+    #[test]
+    fn skips_characters_that_can_not_be_shown() {
+        let chars = take_characters(&mut "A☃B".chars());
+        assert_eq!(chars.len(), 2);
+        assert!(is_character(chars[0], 'A'));
+        assert!(is_character(chars[1], 'B'));
+    }
+
+    #[test]
+    fn lowercase_uses_uppercase() {
+        assert!(is_character(get_character_struct('a').unwrap(), 'A'));
+        assert!(is_character(get_character_struct('z').unwrap(), 'Z'));
+    }
+
+    #[test]
+    fn unknown_characters_map_to_fallbacks() {
+        assert_eq!(ascii_fallback('º'), Some('°'));
+        assert_eq!(ascii_fallback('˚'), Some('°'));
+        assert_eq!(ascii_fallback('é'), Some('E'));
+        assert_eq!(ascii_fallback('Ô'), Some('O'));
+        assert_eq!(ascii_fallback('ø'), Some('O'));
+        assert_eq!(ascii_fallback('ñ'), Some('N'));
+        assert_eq!(ascii_fallback('ß'), Some('S'));
+        assert_eq!(ascii_fallback('’'), Some('\''));
+        assert_eq!(ascii_fallback('“'), Some('\''));
+        assert_eq!(ascii_fallback('—'), Some('-'));
+        assert_eq!(ascii_fallback('…'), Some('.'));
+        assert_eq!(ascii_fallback('\u{a0}'), Some(' '));
+        assert_eq!(ascii_fallback('☃'), None);
+        assert_eq!(ascii_fallback('A'), None);
+    }
+
+    #[test]
+    fn fallbacks_are_in_the_table() {
+        for character in "ºéÉôÔøçñüÿß’“—…\u{a0}\t".chars() {
+            let fallback = ascii_fallback(character).unwrap();
+            assert!(find_character(fallback).is_some());
+            assert!(get_character_struct(character).is_some());
+        }
+    }
+
+    #[test]
+    fn accents_not_in_the_table_use_fallbacks() {
+        assert!(is_character(get_character_struct('ô').unwrap(), 'O'));
+        assert!(is_character(get_character_struct('ø').unwrap(), 'O'));
+        assert!(is_character(get_character_struct('ÿ').unwrap(), 'Y'));
+        assert!(is_character(get_character_struct('º').unwrap(), '°'));
+        assert!(is_character(get_character_struct('’').unwrap(), '\''));
+
+        // accented letters in the table are shown as they are
+        assert!(is_character(get_character_struct('é').unwrap(), 'É'));
+    }
+}
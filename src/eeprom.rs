@@ -0,0 +1,129 @@
+use embassy_rp::i2c;
+use embassy_time::{Duration, Timer};
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+use crate::i2c_bus::SharedI2c;
+
+/// The I2C address of the AT24C32 on the RTC board.
+const ADDRESS: u8 = 0x57;
+
+/// The size of the EEPROM in bytes.
+pub const EEPROM_SIZE: usize = 4096;
+
+/// The size of a page. A single write can not cross a page boundary.
+const PAGE_SIZE: usize = 32;
+
+/// How long the EEPROM needs to finish writing a page before it will answer again.
+const WRITE_CYCLE_MS: u64 = 10;
+
+/// Bytes to use to represent erased data.
+pub const ERASED_BYTES: u8 = 0xFF;
+
+/// The offset and end offset for the daily temperature range.
+pub const TEMPERATURE_RANGE: (usize, usize) = (0, 12);
+
+/// The offset and end offset for the last time sync and measured drift.
+pub const TIME_SYNC: (usize, usize) = (TEMPERATURE_RANGE.1, TEMPERATURE_RANGE.1 + 12);
+
+/// The offset and end offset for the total uptime and unexpected reset count.
+pub const UPTIME_STATS: (usize, usize) = (TIME_SYNC.1, TIME_SYNC.1 + 8);
+
+/// The offset and end offset for the pomodoro and stopwatch timer snapshots.
+pub const TIMER_SNAPSHOTS: (usize, usize) = (UPTIME_STATS.1, UPTIME_STATS.1 + 24);
+
+/// The offset and end offset for the hourly temperature log, an index followed by a week of entries.
+pub const TEMPERATURE_LOG: (usize, usize) = (TIMER_SNAPSHOTS.1, TIMER_SNAPSHOTS.1 + 2 + 6 * 7 * 24);
+
+/// Every region of the EEPROM, so a factory reset can erase them all. Add new regions here too.
+pub const REGIONS: [(usize, usize); 5] = [
+    TEMPERATURE_RANGE,
+    TIME_SYNC,
+    UPTIME_STATS,
+    TIMER_SNAPSHOTS,
+    TEMPERATURE_LOG,
+];
+
+/// Errors that can happen when using the EEPROM.
+#[derive(defmt::Format)]
+pub enum Error {
+    /// The EEPROM did not answer on the bus, or the transfer failed.
+    Bus,
+
+    /// The data does not fit within the EEPROM.
+    OutOfRange,
+}
+
+impl From<i2c::Error> for Error {
+    fn from(_: i2c::Error) -> Self {
+        Error::Bus
+    }
+}
+
+/// Read bytes from the EEPROM, starting at `address`, to fill `buf`.
+pub async fn read(address: usize, buf: &mut [u8]) -> Result<(), Error> {
+    check_range(address, buf.len())?;
+
+    let mut bus = SharedI2c::new();
+    bus.write_read(ADDRESS, &(address as u16).to_be_bytes(), buf)?;
+
+    Ok(())
+}
+
+/// Write bytes to the EEPROM, starting at `address`.
+///
+/// The data is written a page at a time, and pages that already hold the same data are skipped to save wear.
+pub async fn write(mut address: usize, mut data: &[u8]) -> Result<(), Error> {
+    check_range(address, data.len())?;
+
+    let mut bus = SharedI2c::new();
+
+    while !data.is_empty() {
+        let len = (PAGE_SIZE - address % PAGE_SIZE).min(data.len());
+        let (chunk, rest) = data.split_at(len);
+
+        let mut current = [0u8; PAGE_SIZE];
+        bus.write_read(
+            ADDRESS,
+            &(address as u16).to_be_bytes(),
+            &mut current[..len],
+        )?;
+
+        if current[..len] != *chunk {
+            // the address goes before the data in the same write
+            let mut buf = [0u8; 2 + PAGE_SIZE];
+            buf[..2].copy_from_slice(&(address as u16).to_be_bytes());
+            buf[2..2 + len].copy_from_slice(chunk);
+            bus.write(ADDRESS, &buf[..2 + len])?;
+
+            Timer::after(Duration::from_millis(WRITE_CYCLE_MS)).await;
+        }
+
+        address += len;
+        data = rest;
+    }
+
+    Ok(())
+}
+
+/// Erase bytes in the EEPROM, from `start` to `end`.
+pub async fn erase(start: usize, end: usize) -> Result<(), Error> {
+    let erased = [ERASED_BYTES; PAGE_SIZE];
+
+    let mut address = start;
+    while address < end {
+        let len = (end - address).min(PAGE_SIZE);
+        write(address, &erased[..len]).await?;
+        address += len;
+    }
+
+    Ok(())
+}
+
+/// Check that `len` bytes from `address` are within the EEPROM.
+fn check_range(address: usize, len: usize) -> Result<(), Error> {
+    if address + len > EEPROM_SIZE {
+        return Err(Error::OutOfRange);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,260 @@
+use core::fmt::Write as _;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use defmt::info;
+use embassy_executor::_export::StaticCell;
+use embassy_rp::{
+    bind_interrupts,
+    peripherals::{PIN_0, PIN_1, UART0},
+    uart::{BufferedInterruptHandler, BufferedUart, Config},
+};
+use embassy_time::{with_timeout, Duration, Timer};
+use embedded_io::asynch::{Read, Write};
+use heapless::String;
+
+use crate::{
+    config,
+    time_sync::{self, Error as SyncError},
+};
+
+bind_interrupts!(struct Irqs {
+    UART0_IRQ => BufferedInterruptHandler<UART0>;
+});
+
+/// The baud rate the ESP-AT firmware uses by default.
+const BAUD_RATE: u32 = 115_200;
+
+/// How long to wait for the module to reply to a command.
+const COMMAND_TIMEOUT_SECS: u64 = 5;
+
+/// How long to wait for the module to join the network.
+const JOIN_TIMEOUT_SECS: u64 = 20;
+
+/// How long to give the module to get the time from the NTP server before asking for it.
+const SNTP_WAIT_SECS: u64 = 2;
+
+/// How many times to ask the module for the time before giving up.
+const SNTP_ATTEMPTS: u32 = 5;
+
+/// The longest line that can be sent to or read from the module.
+const LINE_SIZE: usize = 128;
+
+/// The reply prefix when asking the module for the time.
+const SNTP_TIME_PREFIX: &str = "+CIPSNTPTIME:";
+
+/// The short month names the module uses in the time.
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Static buffer for bytes waiting to be sent to the module.
+static TX_BUFFER: StaticCell<[u8; 64]> = StaticCell::new();
+
+/// Static buffer for bytes received from the module.
+static RX_BUFFER: StaticCell<[u8; 256]> = StaticCell::new();
+
+/// The peripherals used by an ESP-AT module on the UART header.
+pub struct EspAtPeripherals {
+    /// The UART connected to the module.
+    pub uart: UART0,
+
+    /// The UART transmit pin, connected to the module RX.
+    pub tx: PIN_0,
+
+    /// The UART receive pin, connected to the module TX.
+    pub rx: PIN_1,
+}
+
+/// Minimal driver for an ESP8266/ESP32 running the ESP-AT firmware.
+struct EspAt {
+    /// The UART connected to the module.
+    uart: BufferedUart<'static, UART0>,
+
+    /// The last line read from the module.
+    line: String<LINE_SIZE>,
+}
+
+impl EspAt {
+    /// Send a command and wait for it to finish.
+    async fn command(&mut self, command: &str, timeout_secs: u64) -> Result<(), SyncError> {
+        self.send(command).await?;
+
+        loop {
+            self.read_line(timeout_secs).await?;
+            match self.line.as_str() {
+                "OK" => return Ok(()),
+                "ERROR" | "FAIL" => {
+                    info!("ESP-AT command failed: {}", command);
+                    return Err(SyncError::Network);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Send a query and return the reply line starting with the prefix passed, without the prefix.
+    async fn query(&mut self, command: &str, prefix: &str) -> Result<String<LINE_SIZE>, SyncError> {
+        self.send(command).await?;
+
+        let mut reply = None;
+        loop {
+            self.read_line(COMMAND_TIMEOUT_SECS).await?;
+            match self.line.as_str() {
+                "OK" => return reply.ok_or(SyncError::Network),
+                "ERROR" | "FAIL" => return Err(SyncError::Network),
+                line => {
+                    if let Some(value) = line.strip_prefix(prefix) {
+                        reply = Some(String::from(value));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Send a command, ending it with a new line.
+    async fn send(&mut self, command: &str) -> Result<(), SyncError> {
+        self.uart
+            .write_all(command.as_bytes())
+            .await
+            .map_err(|_| SyncError::Network)?;
+        self.uart
+            .write_all(b"\r\n")
+            .await
+            .map_err(|_| SyncError::Network)
+    }
+
+    /// Read the next line that is not empty from the module, without the new line.
+    async fn read_line(&mut self, timeout_secs: u64) -> Result<(), SyncError> {
+        self.line.clear();
+
+        loop {
+            let mut byte = [0u8];
+            match with_timeout(Duration::from_secs(timeout_secs), self.uart.read(&mut byte)).await {
+                Ok(Ok(1)) => {}
+                _ => return Err(SyncError::Network),
+            }
+
+            match byte[0] {
+                b'\r' => {}
+                b'\n' if self.line.is_empty() => {}
+                b'\n' => return Ok(()),
+                // lines too long for the buffer are only ever ignored, so can be cut off
+                byte => _ = self.line.push(byte as char),
+            }
+        }
+    }
+}
+
+/// Start the ESP-AT module, and keep the RTC synced to the time from an NTP server.
+///
+/// The module joins the network in the config, the same as the Pico W wifi.
+#[embassy_executor::task]
+pub async fn esp_at_task(peripherals: EspAtPeripherals) -> ! {
+    let mut config = Config::default();
+    config.baudrate = BAUD_RATE;
+
+    let uart = BufferedUart::new(
+        peripherals.uart,
+        Irqs,
+        peripherals.tx,
+        peripherals.rx,
+        TX_BUFFER.init([0; 64]),
+        RX_BUFFER.init([0; 256]),
+        config,
+    );
+    let mut esp = EspAt {
+        uart,
+        line: String::new(),
+    };
+
+    config::wait_for_init().await;
+
+    loop {
+        let wait_secs = match sync(&mut esp).await {
+            Ok(()) => time_sync::SYNC_SECS,
+            Err(e) => {
+                info!("Failed to sync the time with the ESP-AT module: {}", e);
+                time_sync::RETRY_SECS
+            }
+        };
+
+        Timer::after(Duration::from_secs(wait_secs)).await;
+    }
+}
+
+/// Join the network, get the time from the NTP server and set it into the RTC.
+async fn sync(esp: &mut EspAt) -> Result<(), SyncError> {
+    let credentials = config::get_wifi_credentials().await;
+    if credentials.ssid.is_empty() {
+        info!("No wifi network set for the ESP-AT module");
+        return Err(SyncError::Network);
+    }
+
+    // turn off echo, so only replies are read back
+    esp.command("ATE0", COMMAND_TIMEOUT_SECS).await?;
+    esp.command("AT+CWMODE=1", COMMAND_TIMEOUT_SECS).await?;
+
+    let mut command: String<LINE_SIZE> = String::new();
+    command
+        .push_str("AT+CWJAP=\"")
+        .map_err(|_| SyncError::Network)?;
+    push_escaped(&mut command, &credentials.ssid)?;
+    command.push_str("\",\"").map_err(|_| SyncError::Network)?;
+    push_escaped(&mut command, &credentials.password)?;
+    command.push('"').map_err(|_| SyncError::Network)?;
+    esp.command(&command, JOIN_TIMEOUT_SECS).await?;
+
+    // the timezone is left at UTC, the same as the Pico W wifi
+    command.clear();
+    _ = write!(command, "AT+CIPSNTPCFG=1,0,\"{}\"", time_sync::NTP_SERVER);
+    esp.command(&command, COMMAND_TIMEOUT_SECS).await?;
+
+    for _ in 0..SNTP_ATTEMPTS {
+        Timer::after(Duration::from_secs(SNTP_WAIT_SECS)).await;
+
+        let reply = esp.query("AT+CIPSNTPTIME?", SNTP_TIME_PREFIX).await?;
+        match parse_sntp_time(&reply) {
+            Some(utc) => return time_sync::sync_utc(utc).await,
+            None => info!("ESP-AT module has not got the time yet"),
+        }
+    }
+
+    Err(SyncError::InvalidTime)
+}
+
+/// Add a value for a quoted AT command parameter, escaping the characters the firmware needs escaping.
+fn push_escaped(command: &mut String<LINE_SIZE>, value: &str) -> Result<(), SyncError> {
+    for c in value.chars() {
+        if matches!(c, '"' | ',' | '\\') {
+            command.push('\\').map_err(|_| SyncError::Network)?;
+        }
+        command.push(c).map_err(|_| SyncError::Network)?;
+    }
+
+    Ok(())
+}
+
+/// Read the time from the module, in the form "Thu Aug 04 14:48:05 2016".
+///
+/// Returns [None](Option::None) if the time is not valid, or the module has not got the time yet (it will be in 1970).
+fn parse_sntp_time(text: &str) -> Option<NaiveDateTime> {
+    let mut parts = text.split_whitespace();
+    let _weekday = parts.next()?;
+    let month_name = parts.next()?;
+    let day = parts.next()?.parse().ok()?;
+    let time = parts.next()?;
+    let year = parts.next()?.parse().ok()?;
+
+    if year <= 1970 {
+        return None;
+    }
+
+    let month = MONTHS.iter().position(|month| *month == month_name)? as u32 + 1;
+
+    let mut time = time.split(':');
+    let hour = time.next()?.parse().ok()?;
+    let minute = time.next()?.parse().ok()?;
+    let second = time.next()?.parse().ok()?;
+
+    NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(hour, minute, second)
+}
@@ -0,0 +1,107 @@
+#[cfg(feature = "usb-console")]
+use core::{cell::RefCell, fmt};
+
+use defmt::info;
+#[cfg(feature = "usb-console")]
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+#[cfg(feature = "usb-console")]
+use embassy_time::Instant;
+#[cfg(feature = "usb-console")]
+use heapless::{HistoryBuffer, Vec};
+
+use crate::buttons::{Button, ButtonPress};
+
+/// The number of events kept in the ring buffer. Older events are overwritten.
+#[cfg(feature = "usb-console")]
+const CAPACITY: usize = 32;
+
+/// Something that happened on the clock, kept to help with bug reports.
+#[derive(Clone, Copy, defmt::Format)]
+pub enum Event {
+    /// The clock started up.
+    Boot,
+
+    /// A button was pressed.
+    ButtonPress(Button, ButtonPress),
+
+    /// An app was started.
+    AppStarted(&'static str),
+
+    /// The RTC failed to answer, even after retrying.
+    RtcError,
+
+    /// The config was written to flash.
+    FlashWrite,
+
+    /// An operation took too long, with how long it took in milliseconds.
+    #[cfg(feature = "instrumentation")]
+    Slow(&'static str, u32),
+}
+
+/// An event with when it happened.
+#[cfg(feature = "usb-console")]
+#[derive(Clone, Copy)]
+pub struct Entry {
+    /// When the event happened, since boot.
+    pub at: Instant,
+
+    /// The event.
+    pub event: Event,
+}
+
+/// Static ring buffer of the most recent events.
+///
+/// Uses a critical section mutex, so events can be recorded from either core.
+#[cfg(feature = "usb-console")]
+static EVENTS: Mutex<CriticalSectionRawMutex, RefCell<HistoryBuffer<Entry, CAPACITY>>> =
+    Mutex::new(RefCell::new(HistoryBuffer::new()));
+
+/// Record an event in the log.
+///
+/// The event is always logged with defmt, and is kept in the ring buffer when it can be read over the usb console.
+pub fn record(event: Event) {
+    info!("Event: {}", event);
+
+    #[cfg(feature = "usb-console")]
+    EVENTS.lock(|events| {
+        events.borrow_mut().write(Entry {
+            at: Instant::now(),
+            event,
+        })
+    });
+}
+
+/// Get a copy of the events in the ring buffer, oldest first.
+#[cfg(feature = "usb-console")]
+pub fn entries() -> Vec<Entry, CAPACITY> {
+    EVENTS.lock(|events| events.borrow().oldest_ordered().copied().collect())
+}
+
+#[cfg(feature = "usb-console")]
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Boot => write!(f, "Boot"),
+            Event::ButtonPress(button, press) => {
+                let button = match button {
+                    Button::Top => "Top",
+                    Button::Middle => "Middle",
+                    Button::Bottom => "Bottom",
+                };
+                let press = match press {
+                    ButtonPress::Short => "short",
+                    ButtonPress::Long => "long",
+                    ButtonPress::Double => "double",
+                    ButtonPress::Triple => "triple",
+                    ButtonPress::Combo(_) => "combo",
+                };
+                write!(f, "{button} button {press} press")
+            }
+            Event::AppStarted(name) => write!(f, "Started {name}"),
+            Event::RtcError => write!(f, "RTC error"),
+            Event::FlashWrite => write!(f, "Config written to flash"),
+            #[cfg(feature = "instrumentation")]
+            Event::Slow(name, ms) => write!(f, "{name} took {ms}ms"),
+        }
+    }
+}
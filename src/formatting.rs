@@ -0,0 +1,353 @@
+use core::fmt::Write;
+
+use heapless::String;
+
+use crate::{
+    config::{TemperaturePrecision, TemperaturePreference},
+    display::display_matrix::TimeColon,
+};
+
+/// Format the time, with both sides padded to two digits, e.g. "05:30".
+pub fn format_time(left: u32, right: u32, colon: TimeColon) -> String<8> {
+    let mut text = String::new();
+
+    push_two_digits(&mut text, left);
+
+    let colon = match colon {
+        TimeColon::Full => ":",
+        TimeColon::Empty => " ",
+        TimeColon::Top => "±",
+        TimeColon::Bottom => "§",
+    };
+    _ = text.push_str(colon);
+
+    push_two_digits(&mut text, right);
+
+    text
+}
+
+/// Format the date, with both sides padded to two digits, e.g. "01/12".
+pub fn format_date(left: u32, right: u32) -> String<8> {
+    let mut text = String::new();
+
+    push_two_digits(&mut text, left);
+    _ = text.push('/');
+    push_two_digits(&mut text, right);
+
+    text
+}
+
+/// Format only the left side of a time or date, padded to two digits and followed by the separator, e.g. "05:".
+pub fn format_left_side(left: u32, separator: char) -> String<8> {
+    let mut text = String::new();
+
+    push_two_digits(&mut text, left);
+    _ = text.push(separator);
+
+    text
+}
+
+/// Format only the right side of a time or date, padded to two digits and after the separator, e.g. ":05".
+pub fn format_right_side(separator: char, right: u32) -> String<8> {
+    let mut text = String::new();
+
+    _ = text.push(separator);
+    push_two_digits(&mut text, right);
+
+    text
+}
+
+/// Format the year, e.g. "2023".
+pub fn format_year(year: i32) -> String<8> {
+    let mut text = String::new();
+    _ = write!(text, "{year}");
+    text
+}
+
+/// Format the temperature to the decimal places of the precision, with the symbol for the preference, e.g. "23.4°C".
+pub fn format_temp(
+    temp: f32,
+    pref: TemperaturePreference,
+    precision: TemperaturePrecision,
+) -> String<16> {
+    // a temperature just below zero that rounds to zero would otherwise show as "-0"
+    let mut half_step = 0.5;
+    for _ in 0..precision.decimal_places() {
+        half_step /= 10.0;
+    }
+    let temp = if temp < 0.0 && temp > -half_step {
+        0.0
+    } else {
+        temp
+    };
+
+    let mut text = String::new();
+    _ = write!(
+        text,
+        "{:.*}{}",
+        precision.decimal_places(),
+        temp,
+        temp_symbol(pref)
+    );
+    text
+}
+
+/// Format the time followed by the temperature, e.g. "06:30  50°F".
+pub fn format_time_temp(
+    hour: u32,
+    min: u32,
+    temp: f32,
+    pref: TemperaturePreference,
+    precision: TemperaturePrecision,
+) -> String<24> {
+    let mut text = String::new();
+
+    _ = text.push_str(&format_time(hour, min, TimeColon::Full));
+    _ = text.push_str("  ");
+    _ = text.push_str(&format_temp(temp, pref, precision));
+
+    text
+}
+
+/// Format the highest and lowest temperature, e.g. "HI 26°C LO 19°C".
+pub fn format_temp_range(
+    min: f32,
+    max: f32,
+    pref: TemperaturePreference,
+    precision: TemperaturePrecision,
+) -> String<32> {
+    let mut text = String::new();
+
+    _ = text.push_str("HI ");
+    _ = text.push_str(&format_temp(max, pref, precision));
+    _ = text.push_str(" LO ");
+    _ = text.push_str(&format_temp(min, pref, precision));
+
+    text
+}
+
+/// Format the temperature, the highest and lowest temperature and the humidity together, e.g. "23°C  HI 26°C LO 19°C  45 RH".
+///
+/// The range and humidity are left out if there is none.
+pub fn format_environment(
+    temp: f32,
+    range: Option<(f32, f32)>,
+    humidity: Option<f32>,
+    pref: TemperaturePreference,
+    precision: TemperaturePrecision,
+) -> String<64> {
+    let mut text = String::new();
+
+    _ = text.push_str(&format_temp(temp, pref, precision));
+
+    if let Some((min, max)) = range {
+        _ = text.push_str("  ");
+        _ = text.push_str(&format_temp_range(min, max, pref, precision));
+    }
+
+    if let Some(humidity) = humidity {
+        _ = write!(text, "  {humidity:.0} RH");
+    }
+
+    text
+}
+
+/// Format the time as words, to the nearest five minutes, e.g. "QUARTER TO 8".
+///
+/// The hour is always 12hr, as "TEN PAST 19" does not read well.
+pub fn format_words(hour: u32, minute: u32) -> String<32> {
+    let mut text = String::new();
+
+    let rounded = (minute + 2) / 5 * 5;
+    let (words, past) = match rounded {
+        5 => ("FIVE", true),
+        10 => ("TEN", true),
+        15 => ("QUARTER", true),
+        20 => ("TWENTY", true),
+        25 => ("TWENTY FIVE", true),
+        30 => ("HALF", true),
+        35 => ("TWENTY FIVE", false),
+        40 => ("TWENTY", false),
+        45 => ("QUARTER", false),
+        50 => ("TEN", false),
+        55 => ("FIVE", false),
+        _ => ("", true),
+    };
+
+    // after half past, the time is said to the next hour
+    let hour = if rounded > 30 { hour + 1 } else { hour };
+    let hour = match hour % 12 {
+        0 => 12,
+        hour => hour,
+    };
+
+    if words.is_empty() {
+        _ = write!(text, "{hour} O'CLOCK");
+    } else if past {
+        _ = write!(text, "{words} PAST {hour}");
+    } else {
+        _ = write!(text, "{words} TO {hour}");
+    }
+
+    text
+}
+
+/// Get the symbol for the temperature preference.
+fn temp_symbol(pref: TemperaturePreference) -> &'static str {
+    match pref {
+        TemperaturePreference::Celcius => "°C",
+        TemperaturePreference::Fahrenheit => "°F",
+    }
+}
+
+/// Add a number to the text, with a 0 before it if it is below 10.
+fn push_two_digits<const N: usize>(text: &mut String<N>, value: u32) {
+    _ = write!(text, "{value:02}");
+}
+
+/// Tests run on the host by the `host-tests` crate.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pads_time() {
+        assert_eq!(format_time(5, 3, TimeColon::Full), "05:03");
+        assert_eq!(format_time(0, 0, TimeColon::Full), "00:00");
+        assert_eq!(format_time(12, 45, TimeColon::Full), "12:45");
+        assert_eq!(format_time(9, 30, TimeColon::Empty), "09 30");
+        assert_eq!(format_time(9, 30, TimeColon::Top), "09±30");
+        assert_eq!(format_time(9, 30, TimeColon::Bottom), "09§30");
+    }
+
+    #[test]
+    fn pads_date() {
+        assert_eq!(format_date(1, 2), "01/02");
+        assert_eq!(format_date(31, 12), "31/12");
+    }
+
+    #[test]
+    fn pads_sides() {
+        assert_eq!(format_left_side(5, ':'), "05:");
+        assert_eq!(format_right_side(':', 7), ":07");
+        assert_eq!(format_year(2023), "2023");
+    }
+
+    #[test]
+    fn formats_whole_temperatures() {
+        let whole = TemperaturePrecision::Whole;
+        assert_eq!(
+            format_temp(23.4, TemperaturePreference::Celcius, whole),
+            "23°C"
+        );
+        assert_eq!(
+            format_temp(23.6, TemperaturePreference::Celcius, whole),
+            "24°C"
+        );
+        assert_eq!(
+            format_temp(74.1, TemperaturePreference::Fahrenheit, whole),
+            "74°F"
+        );
+        assert_eq!(
+            format_temp(0.0, TemperaturePreference::Celcius, whole),
+            "0°C"
+        );
+    }
+
+    #[test]
+    fn formats_tenths_temperatures() {
+        let tenths = TemperaturePrecision::Tenths;
+        assert_eq!(
+            format_temp(23.44, TemperaturePreference::Celcius, tenths),
+            "23.4°C"
+        );
+        assert_eq!(
+            format_temp(23.0, TemperaturePreference::Celcius, tenths),
+            "23.0°C"
+        );
+        assert_eq!(
+            format_temp(74.16, TemperaturePreference::Fahrenheit, tenths),
+            "74.2°F"
+        );
+    }
+
+    #[test]
+    fn formats_negative_temperatures() {
+        let celcius = TemperaturePreference::Celcius;
+        assert_eq!(
+            format_temp(-5.4, celcius, TemperaturePrecision::Whole),
+            "-5°C"
+        );
+        assert_eq!(
+            format_temp(-5.4, celcius, TemperaturePrecision::Tenths),
+            "-5.4°C"
+        );
+        assert_eq!(
+            format_temp(-0.6, celcius, TemperaturePrecision::Whole),
+            "-1°C"
+        );
+        assert_eq!(
+            format_temp(-0.4, celcius, TemperaturePrecision::Whole),
+            "0°C"
+        );
+        assert_eq!(
+            format_temp(-0.04, celcius, TemperaturePrecision::Tenths),
+            "0.0°C"
+        );
+        assert_eq!(
+            format_temp(-0.4, celcius, TemperaturePrecision::Tenths),
+            "-0.4°C"
+        );
+    }
+
+    #[test]
+    fn formats_time_and_temperature() {
+        assert_eq!(
+            format_time_temp(
+                6,
+                30,
+                50.0,
+                TemperaturePreference::Fahrenheit,
+                TemperaturePrecision::Whole
+            ),
+            "06:30  50°F"
+        );
+    }
+
+    #[test]
+    fn formats_environment() {
+        let celcius = TemperaturePreference::Celcius;
+        let whole = TemperaturePrecision::Whole;
+        assert_eq!(
+            format_environment(23.0, Some((19.0, 26.0)), Some(45.2), celcius, whole),
+            "23°C  HI 26°C LO 19°C  45 RH"
+        );
+        assert_eq!(format_environment(23.0, None, None, celcius, whole), "23°C");
+    }
+
+    #[test]
+    fn formats_words_past_the_hour() {
+        assert_eq!(format_words(8, 0), "8 O'CLOCK");
+        assert_eq!(format_words(8, 2), "8 O'CLOCK");
+        assert_eq!(format_words(8, 3), "FIVE PAST 8");
+        assert_eq!(format_words(8, 15), "QUARTER PAST 8");
+        assert_eq!(format_words(8, 30), "HALF PAST 8");
+    }
+
+    #[test]
+    fn formats_words_to_the_next_hour() {
+        assert_eq!(format_words(8, 33), "TWENTY FIVE TO 9");
+        assert_eq!(format_words(8, 45), "QUARTER TO 9");
+        assert_eq!(format_words(8, 57), "FIVE TO 9");
+        assert_eq!(format_words(8, 58), "9 O'CLOCK");
+    }
+
+    #[test]
+    fn formats_words_in_12_hour_time() {
+        assert_eq!(format_words(0, 0), "12 O'CLOCK");
+        assert_eq!(format_words(12, 10), "TEN PAST 12");
+        assert_eq!(format_words(19, 10), "TEN PAST 7");
+        assert_eq!(format_words(23, 58), "12 O'CLOCK");
+        assert_eq!(format_words(11, 45), "QUARTER TO 12");
+    }
+}
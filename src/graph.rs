@@ -0,0 +1,261 @@
+use core::{
+    fmt::Write,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, select3, select4, Either, Either3, Either4};
+use embassy_sync::{
+    blocking_mutex::raw::ThreadModeRawMutex, pubsub::PubSubChannel, signal::Signal,
+};
+use embassy_time::{Duration, Timer};
+use heapless::String;
+
+use crate::{
+    app::{self, App, StopAppTasks},
+    buttons::ButtonPress,
+    config::TemperaturePreference,
+    display::display_matrix::{DisplayMatrix, DISPLAY_MATRIX},
+    temperature::{self, HISTORY_LEN},
+    temperature_log::LOG_LEN,
+};
+
+/// Channel for firing events of when tasks should be stopped.
+static STOP_APP_CHANNEL: PubSubChannel<ThreadModeRawMutex, StopAppTasks, 1, 1, 1> =
+    PubSubChannel::new();
+
+/// Named struct for the show range signal.
+struct ShowRange;
+
+/// Signal to scroll the lowest and highest temperature in the graph, before drawing the graph again.
+static SHOW_RANGE: Signal<ThreadModeRawMutex, ShowRange> = Signal::new();
+
+/// Named struct for the page changed signal.
+struct PageChanged;
+
+/// Signal to show which page of the temperature log the graph is on, before drawing the graph for it.
+static PAGE_CHANGED: Signal<ThreadModeRawMutex, PageChanged> = Signal::new();
+
+/// The page of the temperature log shown, with 0 for the newest temperatures and each page after going back [HISTORY_LEN] hours.
+static PAGE: AtomicUsize = AtomicUsize::new(0);
+
+/// The last page that can have temperatures in the log.
+const LAST_PAGE: usize = (LOG_LEN - 1) / HISTORY_LEN;
+
+/// How long to show which page the graph is on before drawing it.
+const SHOW_PAGE_MS: u64 = 1500;
+
+/// The number of rows a bar can fill, all the rows below the day of week icons.
+const ROWS: usize = 7;
+
+/// The number of columns the graph can be drawn in, all the columns text can be shown in.
+const COLS: usize = DisplayMatrix::LAST_INDEX - DisplayMatrix::DISPLAY_OFFSET + 1;
+
+/// How often the graph is drawn again, to pick up new hourly temperatures.
+const REDRAW_SECS: u64 = 60;
+
+/// How long to leave the range scrolling before drawing the graph again.
+const SHOW_RANGE_SECS: u64 = 12;
+
+/// Graph app.
+/// Draws the hourly temperatures as a bar chart, with the newest temperature on the right.
+pub struct GraphApp {}
+
+impl GraphApp {
+    /// Create a new graph app.
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl App for GraphApp {
+    fn get_name(&self) -> &str {
+        "Graph"
+    }
+
+    async fn start(&mut self, spawner: Spawner) {
+        critical_section::with(|cs| {
+            DISPLAY_MATRIX.clear_all(cs, true);
+        });
+
+        SHOW_RANGE.reset();
+        PAGE_CHANGED.reset();
+        PAGE.store(0, Ordering::Relaxed);
+        app::spawn_app_task(spawner, "graph", graph).await;
+    }
+
+    async fn stop(&mut self) {
+        STOP_APP_CHANNEL
+            .immediate_publisher()
+            .publish_immediate(StopAppTasks);
+    }
+
+    async fn button_one_short_press(&mut self, _: Spawner) {}
+
+    async fn button_two_press(&mut self, press: ButtonPress, _: Spawner) {
+        match press {
+            ButtonPress::Short => SHOW_RANGE.signal(ShowRange),
+            ButtonPress::Long => change_page(false),
+            _ => {}
+        }
+    }
+
+    async fn button_three_press(&mut self, press: ButtonPress, _: Spawner) {
+        match press {
+            ButtonPress::Short => SHOW_RANGE.signal(ShowRange),
+            ButtonPress::Long => change_page(true),
+            _ => {}
+        }
+    }
+}
+
+/// Move to an older or newer page of the temperature log, stopping at either end.
+fn change_page(older: bool) {
+    let page = PAGE.load(Ordering::Relaxed);
+    let page = if older {
+        (page + 1).min(LAST_PAGE)
+    } else {
+        page.saturating_sub(1)
+    };
+
+    PAGE.store(page, Ordering::Relaxed);
+    PAGE_CHANGED.signal(PageChanged);
+}
+
+/// Get the lowest and highest of the temperatures, or [None](Option::None) if there are none.
+fn range(history: &[f32]) -> Option<(f32, f32)> {
+    let first = *history.first()?;
+    Some(history.iter().fold((first, first), |(min, max), &temp| {
+        (min.min(temp), max.max(temp))
+    }))
+}
+
+/// Work out the bars for the temperatures, right aligned so the newest temperature is in the last column.
+///
+/// Each bar is scaled between the lowest and highest temperature, so the lowest is 1 row high and the highest fills every row.
+/// If every temperature is the same, the bars are half height.
+fn bars(history: &[f32]) -> [u32; ROWS] {
+    let mut pattern = [0; ROWS];
+
+    let (min, max) = match range(history) {
+        Some(range) => range,
+        None => return pattern,
+    };
+
+    let first_col = COLS.saturating_sub(history.len());
+    for (col, &temp) in (first_col..COLS).zip(history) {
+        let height = if max > min {
+            1 + ((temp - min) / (max - min) * (ROWS - 1) as f32 + 0.5) as usize
+        } else {
+            ROWS / 2 + 1
+        };
+
+        // the bottom row is the last in the pattern, so bars fill upwards from there
+        for row in pattern.iter_mut().skip(ROWS - height.min(ROWS)) {
+            *row |= 1 << col;
+        }
+    }
+
+    pattern
+}
+
+/// Draw the graph of the hourly temperatures on the page shown, or show that there are none.
+async fn draw() {
+    let history = temperature::get_history_page(PAGE.load(Ordering::Relaxed)).await;
+
+    if history.is_empty() {
+        DISPLAY_MATRIX.queue_text("No data", 0, true, false).await;
+        return;
+    }
+
+    let pattern = bars(&history);
+    critical_section::with(|cs| {
+        DISPLAY_MATRIX.clear(cs, true);
+        DISPLAY_MATRIX.show_pattern(cs, &pattern);
+    });
+}
+
+/// Scroll the lowest and highest temperature in the graph, based on the current user preference.
+async fn show_range() {
+    let history = temperature::get_history_page(PAGE.load(Ordering::Relaxed)).await;
+    let (min, max) = match range(&history) {
+        Some(range) => range,
+        None => return,
+    };
+
+    let pref = temperature::get_temperature_preference().await;
+    let precision = temperature::get_temperature_precision().await;
+    let (min, max) = match pref {
+        TemperaturePreference::Celcius => (min, max),
+        TemperaturePreference::Fahrenheit => ((min * 1.8) + 32.0, (max * 1.8) + 32.0),
+    };
+
+    DISPLAY_MATRIX
+        .queue_temperature_range(min, max, pref, precision, true)
+        .await;
+}
+
+/// Show how many hours back the newest temperature on the page is, e.g. "-22h".
+async fn show_page() {
+    let page = PAGE.load(Ordering::Relaxed);
+
+    let mut text: String<8> = String::new();
+    if page == 0 {
+        _ = write!(text, "Now");
+    } else {
+        _ = write!(text, "-{}h", page * HISTORY_LEN);
+    }
+
+    DISPLAY_MATRIX.queue_text(&text, 0, true, false).await;
+}
+
+/// Draw the graph every [REDRAW_SECS], or scroll the range or change page when asked to.
+#[embassy_executor::task]
+async fn graph() {
+    let _running = app::RunningAppTask::new();
+    let mut stop_task_sub = STOP_APP_CHANNEL.subscriber().unwrap();
+
+    loop {
+        draw().await;
+
+        let res = select4(
+            stop_task_sub.next_message(),
+            SHOW_RANGE.wait(),
+            PAGE_CHANGED.wait(),
+            Timer::after(Duration::from_secs(REDRAW_SECS)),
+        )
+        .await;
+
+        match res {
+            Either4::First(_) => break,
+            Either4::Second(_) => {
+                show_range().await;
+
+                let res = select3(
+                    stop_task_sub.next_message(),
+                    SHOW_RANGE.wait(),
+                    Timer::after(Duration::from_secs(SHOW_RANGE_SECS)),
+                )
+                .await;
+
+                if let Either3::First(_) = res {
+                    break;
+                }
+            }
+            Either4::Third(_) => {
+                show_page().await;
+
+                let res = select(
+                    stop_task_sub.next_message(),
+                    Timer::after(Duration::from_millis(SHOW_PAGE_MS)),
+                )
+                .await;
+
+                if let Either::First(_) = res {
+                    break;
+                }
+            }
+            Either4::Fourth(_) => {}
+        }
+    }
+}
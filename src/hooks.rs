@@ -0,0 +1,88 @@
+use core::cell::RefCell;
+
+use chrono::Timelike;
+use defmt::info;
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use heapless::Vec;
+
+use crate::{rtc, temperature};
+
+/// The most hooks that can be registered.
+const MAX_HOOKS: usize = 8;
+
+/// The time boundaries hooks can run on.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Boundary {
+    /// At the start of every hour, including midnight.
+    Hour,
+
+    /// At midnight, when the day changes.
+    Midnight,
+}
+
+/// Work a subsystem needs doing when the hour or day changes.
+///
+/// Hooks are tasks rather than callbacks, in the same way as the scheduler jobs, so they can be async without storing futures.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Hook {
+    /// Start a new day of the temperature range.
+    ResetTemperatureRange,
+}
+
+/// Static registry of the hooks and the boundary each runs on, in the order they were registered.
+static HOOKS: Mutex<CriticalSectionRawMutex, RefCell<Vec<(Boundary, Hook), MAX_HOOKS>>> =
+    Mutex::new(RefCell::new(Vec::new()));
+
+/// Register a hook to run on each boundary passed. Registering the same hook twice does nothing.
+///
+/// The hook is dropped if the registry is full.
+pub fn register(boundary: Boundary, hook: Hook) {
+    HOOKS.lock(|hooks| {
+        let mut hooks = hooks.borrow_mut();
+        if hooks.contains(&(boundary, hook)) {
+            return;
+        }
+
+        if hooks.push((boundary, hook)).is_err() {
+            info!("Hook registry full, dropping hook");
+        }
+    });
+}
+
+/// Run the hooks as the hour and day change, whichever app is active.
+///
+/// The last hour and date are kept here rather than in the clock app, so a boundary passed while another app is
+/// open still runs. When the date changes by more than a day, such as after the time is set, the hooks run once for the new day.
+#[embassy_executor::task]
+pub async fn boundary_task() -> ! {
+    let mut last = rtc::get_datetime_or_estimate().await;
+
+    loop {
+        rtc::wait_for_tick().await;
+        let now = rtc::get_datetime_or_estimate().await;
+
+        if now.date() != last.date() || now.hour() != last.hour() {
+            run(Boundary::Hour).await;
+        }
+
+        if now.date() != last.date() {
+            run(Boundary::Midnight).await;
+        }
+
+        last = now;
+    }
+}
+
+/// Run every hook registered for the boundary, in the order they were registered.
+async fn run(boundary: Boundary) {
+    let hooks = HOOKS.lock(|hooks| hooks.borrow().clone());
+
+    for (_, hook) in hooks.iter().filter(|(b, _)| *b == boundary) {
+        match hook {
+            Hook::ResetTemperatureRange => {
+                temperature::reset_temperature_range().await;
+                temperature::record_temperature().await;
+            }
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use core::cell::RefCell;
+
+use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, mutex::Mutex};
+
+/// A reading from the external humidity sensor.
+#[derive(Copy, Clone)]
+pub struct Reading {
+    /// The temperature in celcius.
+    pub temperature: f32,
+
+    /// The relative humidity as a percentage.
+    pub humidity: f32,
+}
+
+/// The last successful reading from the external humidity sensor.
+///
+/// Will always be None if the sensor is not fitted or the firmware is built without a sensor feature.
+static LAST_READING: Mutex<ThreadModeRawMutex, RefCell<Option<Reading>>> =
+    Mutex::new(RefCell::new(None));
+
+/// Get the last reading from the external humidity sensor, if there is one.
+pub async fn get_reading() -> Option<Reading> {
+    *LAST_READING.lock().await.borrow()
+}
+
+/// Get the last relative humidity from the external humidity sensor, if there is one.
+pub async fn get_humidity() -> Option<f32> {
+    get_reading().await.map(|reading| reading.humidity)
+}
+
+/// Set the last reading from the external humidity sensor.
+#[cfg(any(feature = "dht22", feature = "bme280"))]
+pub async fn set_reading(reading: Option<Reading>) {
+    LAST_READING.lock().await.replace(reading);
+}
+
+/// Driver for the DHT22 (AM2302) sensor, connected to a spare GPIO with a pull up.
+#[cfg(feature = "dht22")]
+pub mod dht22 {
+    use defmt::info;
+    use embassy_rp::gpio::{AnyPin, Flex, Pull};
+    use embassy_time::{Duration, Instant, Timer};
+
+    use super::Reading;
+
+    /// How often to read the sensor. The DHT22 can not be read more than once every 2 seconds.
+    const READ_INTERVAL_SECS: u64 = 30;
+
+    /// How many reads in a row can fail before the last reading is thrown away.
+    const MAX_FAILED_READS: u8 = 3;
+
+    /// The longest time to wait for the sensor to change the data line level.
+    const LEVEL_TIMEOUT_US: u64 = 200;
+
+    /// A high pulse longer than this is a 1 bit, otherwise it is a 0 bit.
+    const ONE_BIT_THRESHOLD_US: u64 = 48;
+
+    /// Errors that can happen when reading the sensor.
+    #[derive(defmt::Format)]
+    enum Error {
+        /// The sensor did not change the data line in time, likely because it is not fitted.
+        Timeout,
+
+        /// The data read did not match the checksum.
+        Checksum,
+    }
+
+    /// Read the sensor on a loop and update the last reading.
+    ///
+    /// Readings are thrown away after a few failed reads, so the clock falls back to the RTC temperature when the sensor is removed.
+    #[embassy_executor::task]
+    pub async fn dht22_task(mut pin: Flex<'static, AnyPin>) {
+        pin.set_pull(Pull::Up);
+        pin.set_as_input();
+
+        // the sensor needs time to settle after power on
+        Timer::after(Duration::from_secs(2)).await;
+
+        let mut failed_reads = 0;
+
+        loop {
+            match read(&mut pin).await {
+                Ok(reading) => {
+                    failed_reads = 0;
+                    super::set_reading(Some(reading)).await;
+                }
+                Err(e) => {
+                    info!("DHT22 read failed: {}", e);
+
+                    failed_reads += 1;
+                    if failed_reads >= MAX_FAILED_READS {
+                        failed_reads = MAX_FAILED_READS;
+                        super::set_reading(None).await;
+                    }
+                }
+            }
+
+            Timer::after(Duration::from_secs(READ_INTERVAL_SECS)).await;
+        }
+    }
+
+    /// Read the temperature and humidity from the sensor.
+    async fn read(pin: &mut Flex<'static, AnyPin>) -> Result<Reading, Error> {
+        // start signal, hold the line low for at least 1ms
+        pin.set_as_output();
+        pin.set_low();
+        Timer::after(Duration::from_millis(2)).await;
+
+        // the response and data are timed in microseconds, so must not be interrupted by other tasks
+        let mut data = [0u8; 5];
+        critical_section::with(|_| {
+            pin.set_as_input();
+
+            // the sensor responds with 80us low then 80us high before the data
+            wait_for_level(pin, false)?;
+            wait_for_level(pin, true)?;
+            wait_for_level(pin, false)?;
+
+            // each bit is 50us low then a high pulse, the length of which is the bit value
+            for i in 0..40 {
+                wait_for_level(pin, true)?;
+                let high_us = wait_for_level(pin, false)?;
+
+                data[i / 8] <<= 1;
+                if high_us > ONE_BIT_THRESHOLD_US {
+                    data[i / 8] |= 1;
+                }
+            }
+
+            Ok(())
+        })?;
+
+        let checksum = data[0]
+            .wrapping_add(data[1])
+            .wrapping_add(data[2])
+            .wrapping_add(data[3]);
+        if checksum != data[4] {
+            return Err(Error::Checksum);
+        }
+
+        let humidity = u16::from_be_bytes([data[0], data[1]]) as f32 / 10.0;
+
+        // the top bit of the temperature is the sign
+        let mut temperature = u16::from_be_bytes([data[2] & 0x7F, data[3]]) as f32 / 10.0;
+        if data[2] & 0x80 != 0 {
+            temperature = -temperature;
+        }
+
+        Ok(Reading {
+            temperature,
+            humidity,
+        })
+    }
+
+    /// Busy wait for the data line to reach the level passed.
+    ///
+    /// Returns how many microseconds were waited for.
+    fn wait_for_level(pin: &Flex<'static, AnyPin>, high: bool) -> Result<u64, Error> {
+        let start = Instant::now();
+        while pin.is_high() != high {
+            if start.elapsed().as_micros() > LEVEL_TIMEOUT_US {
+                return Err(Error::Timeout);
+            }
+        }
+
+        Ok(start.elapsed().as_micros())
+    }
+}
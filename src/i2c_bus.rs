@@ -0,0 +1,65 @@
+use core::cell::RefCell;
+
+use embassy_rp::{i2c, peripherals::I2C1};
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embedded_hal::blocking::i2c::{Read, Write, WriteRead};
+
+/// The I2C bus that the RTC and any other sensors on the clock are connected to.
+pub type I2cBus = i2c::I2c<'static, I2C1, i2c::Blocking>;
+
+/// Static reference to the shared I2C bus.
+///
+/// **Init must be called first to set the value, or any use of a [SharedI2c] will panic.**
+static I2C_BUS: Mutex<CriticalSectionRawMutex, RefCell<Option<I2cBus>>> =
+    Mutex::new(RefCell::new(None));
+
+/// Initialise the static I2C bus value.
+pub fn init(bus: I2cBus) {
+    I2C_BUS.lock(|cell| cell.replace(Some(bus)));
+}
+
+/// A handle to the shared I2C bus, to give to each device driver instead of the bus itself.
+///
+/// The bus is locked for the length of each transaction, so devices can not interrupt each other.
+pub struct SharedI2c;
+
+impl SharedI2c {
+    /// Create a new handle to the shared I2C bus.
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Run a transaction with exclusive access to the bus.
+    fn transaction<R>(&mut self, f: impl FnOnce(&mut I2cBus) -> R) -> R {
+        I2C_BUS.lock(|cell| f(cell.borrow_mut().as_mut().unwrap()))
+    }
+}
+
+impl Write for SharedI2c {
+    type Error = i2c::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.transaction(|bus| bus.write(address, bytes))
+    }
+}
+
+impl Read for SharedI2c {
+    type Error = i2c::Error;
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.transaction(|bus| bus.read(address, buffer))
+    }
+}
+
+impl WriteRead for SharedI2c {
+    type Error = i2c::Error;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.transaction(|bus| bus.write_read(address, bytes, buffer))
+    }
+}
@@ -0,0 +1,37 @@
+use core::future::Future;
+
+#[cfg(feature = "instrumentation")]
+use embassy_time::Instant;
+
+#[cfg(feature = "instrumentation")]
+use crate::event_log::{self, Event};
+
+/// Operations that take longer than this are logged, in milliseconds.
+///
+/// Long enough to skip the normal await points, but short enough to catch a flash write or a full display queue.
+#[cfg(feature = "instrumentation")]
+const SLOW_MS: u64 = 50;
+
+/// Run an operation, recording it in the event log if it takes longer than [SLOW_MS].
+///
+/// Without the instrumentation feature, this only runs the operation.
+pub async fn measure<F: Future>(name: &'static str, operation: F) -> F::Output {
+    #[cfg(feature = "instrumentation")]
+    {
+        let start = Instant::now();
+        let output = operation.await;
+
+        let elapsed_ms = start.elapsed().as_millis();
+        if elapsed_ms > SLOW_MS {
+            event_log::record(Event::Slow(name, elapsed_ms as u32));
+        }
+
+        output
+    }
+
+    #[cfg(not(feature = "instrumentation"))]
+    {
+        _ = name;
+        operation.await
+    }
+}
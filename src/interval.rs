@@ -0,0 +1,451 @@
+use core::{cell::RefCell, fmt::Write};
+
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, mutex::Mutex, pubsub::PubSubChannel};
+use embassy_time::{Duration, Instant, Timer};
+use heapless::String;
+
+use crate::{
+    app::{self, App, StopAppTasks},
+    buttons::ButtonPress,
+    config::{self, IntervalProtocol},
+    display::display_matrix::{TimeColon, DISPLAY_MATRIX},
+    speaker::{self, SoundType},
+};
+
+/// Channel for firing events of when tasks should be stopped.
+static STOP_APP_CHANNEL: PubSubChannel<ThreadModeRawMutex, StopAppTasks, 1, 1, 1> =
+    PubSubChannel::new();
+
+/// How many seconds each press changes the work or rest interval by.
+const STEP_SECS: u16 = 5;
+
+/// Depict the current running state of the interval timer.
+#[derive(Clone, Copy)]
+enum RunningState {
+    /// When the interval app is first created or after reset. This should allow modification to the protocol.
+    NotStarted,
+
+    /// When the workout is running. This should *not* allow modification to the protocol.
+    Running,
+
+    /// When the workout has been paused. This should *not* allow modification to the protocol, reset instead.
+    Paused,
+
+    /// When the last round has finished.
+    Finished,
+}
+
+/// The part of a round the workout is in.
+#[derive(Clone, Copy)]
+enum Phase {
+    /// Working.
+    Work,
+
+    /// Resting after the work.
+    Rest,
+}
+
+/// The part of the protocol being changed while the workout is not started.
+#[derive(Clone, Copy)]
+enum Field {
+    /// The work interval.
+    Work,
+
+    /// The rest interval.
+    Rest,
+
+    /// The number of rounds.
+    Rounds,
+}
+
+impl Field {
+    /// The field after this one, going back to the first after the last.
+    fn next(&self) -> Self {
+        match self {
+            Field::Work => Field::Rest,
+            Field::Rest => Field::Rounds,
+            Field::Rounds => Field::Work,
+        }
+    }
+
+    /// The text to show when the field is selected.
+    fn label(&self) -> &'static str {
+        match self {
+            Field::Work => "Work",
+            Field::Rest => "Rest",
+            Field::Rounds => "Rnds",
+        }
+    }
+}
+
+/// Manage active state of the interval app.
+struct IntervalState {
+    /// The current running state.
+    running: RunningState,
+
+    /// The protocol being set up or run.
+    protocol: IntervalProtocol,
+
+    /// The part of the protocol being changed.
+    field: Field,
+
+    /// The part of the round the workout is in.
+    phase: Phase,
+
+    /// The round the workout is in, starting from 1.
+    round: u8,
+
+    /// The seconds left in the current phase.
+    remaining_secs: u16,
+}
+
+/// Static reference to the interval state so it can be accessed by static tasks.
+static INTERVAL_STATE: Mutex<ThreadModeRawMutex, RefCell<IntervalState>> =
+    Mutex::new(RefCell::new(IntervalState {
+        running: RunningState::NotStarted,
+        protocol: IntervalProtocol::DEFAULT,
+        field: Field::Work,
+        phase: Phase::Work,
+        round: 1,
+        remaining_secs: 0,
+    }));
+
+/// Interval app.
+/// Runs rounds of work and rest, for interval training.
+pub struct IntervalApp {}
+
+impl IntervalApp {
+    /// Create a new interval app.
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl App for IntervalApp {
+    fn get_name(&self) -> &str {
+        "Interval"
+    }
+
+    async fn start(&mut self, spawner: Spawner) {
+        critical_section::with(|cs| {
+            DISPLAY_MATRIX.clear_all(cs, true);
+        });
+
+        match get_running_state().await {
+            RunningState::NotStarted | RunningState::Finished => {
+                let protocol = config::get_interval_protocol().await;
+
+                let guard = INTERVAL_STATE.lock().await;
+                let mut state = guard.borrow_mut();
+                state.running = RunningState::NotStarted;
+                state.protocol = protocol;
+                state.field = Field::Work;
+                drop(state);
+                drop(guard);
+
+                show_field().await;
+            }
+            // stopping the app pauses the workout, so carry on from where it was
+            RunningState::Running | RunningState::Paused => {
+                show_remaining().await;
+                app::spawn_app_task(spawner, "workout", workout).await;
+            }
+        }
+    }
+
+    async fn stop(&mut self) {
+        if let RunningState::Running = get_running_state().await {
+            set_running(RunningState::Paused).await;
+        }
+
+        STOP_APP_CHANNEL
+            .immediate_publisher()
+            .publish_immediate(StopAppTasks);
+    }
+
+    async fn button_one_short_press(&mut self, spawner: Spawner) {
+        match get_running_state().await {
+            RunningState::NotStarted => {
+                // keep the protocol for next time, without writing to flash when it has not changed
+                let protocol = INTERVAL_STATE.lock().await.borrow().protocol;
+                if config::get_interval_protocol().await != protocol {
+                    config::set_interval_protocol(protocol).await;
+                }
+
+                start_phase(Phase::Work, 1).await;
+                set_running(RunningState::Running).await;
+                app::spawn_app_task(spawner, "workout", workout).await;
+            }
+            RunningState::Running => set_running(RunningState::Paused).await,
+            RunningState::Paused => set_running(RunningState::Running).await,
+            RunningState::Finished => {
+                // stop the finished alarm if it is still sounding
+                speaker::cancel();
+                reset().await;
+            }
+        }
+    }
+
+    async fn button_two_press(&mut self, press: ButtonPress, _: Spawner) {
+        if let RunningState::NotStarted = get_running_state().await {
+            match press {
+                ButtonPress::Short => change_field(true).await,
+                ButtonPress::Long => {
+                    let guard = INTERVAL_STATE.lock().await;
+                    let mut state = guard.borrow_mut();
+                    state.field = state.field.next();
+                    drop(state);
+                    drop(guard);
+
+                    show_field().await;
+                }
+                ButtonPress::Double => {}
+                ButtonPress::Triple => {}
+                ButtonPress::Combo(_) => {}
+            }
+        }
+    }
+
+    async fn button_three_press(&mut self, press: ButtonPress, _: Spawner) {
+        match (get_running_state().await, press) {
+            (RunningState::NotStarted, ButtonPress::Short) => change_field(false).await,
+            (RunningState::NotStarted, ButtonPress::Long) => {
+                INTERVAL_STATE.lock().await.borrow_mut().protocol = IntervalProtocol::DEFAULT;
+                show_field().await;
+            }
+            // end the workout early
+            (RunningState::Paused, ButtonPress::Long) => reset().await,
+            _ => {}
+        }
+    }
+}
+
+/// Get the running state value from the static interval state.
+async fn get_running_state() -> RunningState {
+    INTERVAL_STATE.lock().await.borrow().running
+}
+
+/// Set the running state on the static interval state.
+/// Will show/hide the CountDown icon on the display depending on the state passed.
+async fn set_running(running: RunningState) {
+    INTERVAL_STATE.lock().await.borrow_mut().running = running;
+
+    if let RunningState::Running = running {
+        DISPLAY_MATRIX.show_icon("CountDown");
+    } else {
+        DISPLAY_MATRIX.hide_icon("CountDown");
+    }
+
+    if let RunningState::Finished = running {
+        speaker::alarm(SoundType::RepeatLongBeep(3));
+        DISPLAY_MATRIX.queue_text("Done", 0, true, false).await;
+    }
+}
+
+/// Go back to setting up the protocol.
+async fn reset() {
+    set_running(RunningState::NotStarted).await;
+    INTERVAL_STATE.lock().await.borrow_mut().field = Field::Work;
+    show_field().await;
+}
+
+/// Start a phase of a round, with a beep for the phase and the phase shown on the display.
+///
+/// Work starts with a long beep and shows the round, rest starts with two short beeps.
+async fn start_phase(phase: Phase, round: u8) {
+    let guard = INTERVAL_STATE.lock().await;
+    let mut state = guard.borrow_mut();
+
+    state.phase = phase;
+    state.round = round;
+    state.remaining_secs = match phase {
+        Phase::Work => state.protocol.work_secs,
+        Phase::Rest => state.protocol.rest_secs,
+    };
+
+    drop(state);
+    drop(guard);
+
+    let mut text = String::<8>::new();
+    match phase {
+        Phase::Work => {
+            speaker::alarm(SoundType::LongBeep);
+            _ = write!(text, "R{round}");
+        }
+        Phase::Rest => {
+            speaker::alarm(SoundType::RepeatShortBeep(2));
+            _ = write!(text, "Rest");
+        }
+    }
+
+    DISPLAY_MATRIX
+        .queue_text(text.as_str(), 0, true, false)
+        .await;
+}
+
+/// Count down a second of the current phase, moving on to the next phase or finishing when it ends.
+///
+/// The last round has no rest after it. Returns true when the workout has finished.
+async fn tick() -> bool {
+    let guard = INTERVAL_STATE.lock().await;
+    let mut state = guard.borrow_mut();
+
+    state.remaining_secs = state.remaining_secs.saturating_sub(1);
+    if state.remaining_secs > 0 {
+        drop(state);
+        drop(guard);
+
+        show_remaining().await;
+        return false;
+    }
+
+    let phase = state.phase;
+    let round = state.round;
+    let protocol = state.protocol;
+
+    drop(state);
+    drop(guard);
+
+    match phase {
+        Phase::Work if round >= protocol.rounds => {
+            set_running(RunningState::Finished).await;
+            return true;
+        }
+        Phase::Work if protocol.rest_secs > 0 => start_phase(Phase::Rest, round).await,
+        Phase::Work | Phase::Rest => start_phase(Phase::Work, round + 1).await,
+    }
+
+    false
+}
+
+/// Increase or decrease the field being changed, wrapping around at either end.
+async fn change_field(increase: bool) {
+    let guard = INTERVAL_STATE.lock().await;
+    let mut state = guard.borrow_mut();
+    let field = state.field;
+    let protocol = &mut state.protocol;
+
+    match (field, increase) {
+        (Field::Work, true) if protocol.work_secs >= IntervalProtocol::MAX_SECS => {
+            protocol.work_secs = STEP_SECS
+        }
+        (Field::Work, true) => protocol.work_secs += STEP_SECS,
+        (Field::Work, false) if protocol.work_secs <= STEP_SECS => {
+            protocol.work_secs = IntervalProtocol::MAX_SECS
+        }
+        (Field::Work, false) => protocol.work_secs -= STEP_SECS,
+        (Field::Rest, true) if protocol.rest_secs >= IntervalProtocol::MAX_SECS => {
+            protocol.rest_secs = 0
+        }
+        (Field::Rest, true) => protocol.rest_secs += STEP_SECS,
+        (Field::Rest, false) if protocol.rest_secs == 0 => {
+            protocol.rest_secs = IntervalProtocol::MAX_SECS
+        }
+        (Field::Rest, false) => protocol.rest_secs = protocol.rest_secs.saturating_sub(STEP_SECS),
+        (Field::Rounds, true) if protocol.rounds >= IntervalProtocol::MAX_ROUNDS => {
+            protocol.rounds = 1
+        }
+        (Field::Rounds, true) => protocol.rounds += 1,
+        (Field::Rounds, false) if protocol.rounds <= 1 => {
+            protocol.rounds = IntervalProtocol::MAX_ROUNDS
+        }
+        (Field::Rounds, false) => protocol.rounds -= 1,
+    }
+
+    drop(state);
+    drop(guard);
+
+    show_value(true).await;
+}
+
+/// Show the label of the field being changed, followed by its value.
+async fn show_field() {
+    let field = INTERVAL_STATE.lock().await.borrow().field;
+
+    DISPLAY_MATRIX
+        .queue_text(field.label(), 1000, true, false)
+        .await;
+    show_value(false).await;
+}
+
+/// Show the value of the field being changed.
+async fn show_value(show_now: bool) {
+    let guard = INTERVAL_STATE.lock().await;
+    let state = guard.borrow();
+    let field = state.field;
+    let protocol = state.protocol;
+    drop(state);
+    drop(guard);
+
+    match field {
+        Field::Work => show_secs(protocol.work_secs, show_now).await,
+        Field::Rest => show_secs(protocol.rest_secs, show_now).await,
+        Field::Rounds => {
+            let mut text = String::<8>::new();
+            _ = write!(text, "x{}", protocol.rounds);
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 0, show_now, false)
+                .await;
+        }
+    }
+}
+
+/// Show the seconds left in the current phase.
+async fn show_remaining() {
+    let remaining_secs = INTERVAL_STATE.lock().await.borrow().remaining_secs;
+    show_secs(remaining_secs, true).await;
+}
+
+/// Show a number of seconds as minutes and seconds.
+async fn show_secs(secs: u16, show_now: bool) {
+    let secs = secs as u32;
+    DISPLAY_MATRIX
+        .queue_time(secs / 60, secs % 60, TimeColon::Full, 0, show_now, false)
+        .await;
+}
+
+/// The workout loop, counting down each phase every second.
+///
+/// Will continue to run as long as the running state is running or paused.
+#[embassy_executor::task]
+async fn workout() {
+    let _running = app::RunningAppTask::new();
+    let mut stop_task_sub = STOP_APP_CHANNEL.subscriber().unwrap();
+
+    let mut next_tick = Instant::now() + Duration::from_secs(1);
+
+    loop {
+        match get_running_state().await {
+            RunningState::Running => {
+                let res = select(stop_task_sub.next_message(), Timer::at(next_tick)).await;
+                if let Either::First(_) = res {
+                    break;
+                }
+
+                // the state can change while waiting for the second to pass
+                if let RunningState::Running = get_running_state().await {
+                    next_tick += Duration::from_secs(1);
+                    if tick().await {
+                        break;
+                    }
+                }
+            }
+            RunningState::Paused => {
+                let res = select(
+                    stop_task_sub.next_message(),
+                    Timer::after(Duration::from_millis(100)),
+                )
+                .await;
+                if let Either::First(_) = res {
+                    break;
+                }
+
+                // start a full second from when it is resumed
+                next_tick = Instant::now() + Duration::from_secs(1);
+            }
+            RunningState::NotStarted | RunningState::Finished => break,
+        }
+    }
+}
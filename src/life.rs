@@ -0,0 +1,161 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, pubsub::PubSubChannel};
+use embassy_time::{Duration, Timer};
+
+use crate::{
+    app::{self, App, StopAppTasks},
+    buttons::ButtonPress,
+    display::display_matrix::{DisplayMatrix, DISPLAY_MATRIX},
+    rng,
+};
+
+/// Channel for firing events of when tasks should be stopped.
+static STOP_APP_CHANNEL: PubSubChannel<ThreadModeRawMutex, StopAppTasks, 1, 1, 1> =
+    PubSubChannel::new();
+
+/// Whether the grid should be seeded again on the next generation.
+static RESEED: AtomicBool = AtomicBool::new(false);
+
+/// The number of rows in the grid, all the rows below the day of week icons.
+const ROWS: usize = 7;
+
+/// The number of columns in the grid, all the columns text can be shown in.
+const COLS: usize = DisplayMatrix::LAST_INDEX - DisplayMatrix::DISPLAY_OFFSET + 1;
+
+/// The bits of a row that are in the grid.
+const ROW_MASK: u32 = (1 << COLS) - 1;
+
+/// How long each generation is shown for, in milliseconds.
+const GENERATION_MS: u64 = 300;
+
+/// The most generations to run before seeding again, in case the grid settles into a long cycle.
+const MAX_GENERATIONS: u32 = 500;
+
+/// The grid of cells, one row per element with the leftmost column in the lowest bit.
+type Grid = [u32; ROWS];
+
+/// Life app.
+/// Runs Conway's Game of Life across the display, seeding again whenever it settles.
+pub struct LifeApp {}
+
+impl LifeApp {
+    /// Create a new life app.
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl App for LifeApp {
+    fn get_name(&self) -> &str {
+        "Life"
+    }
+
+    async fn start(&mut self, spawner: Spawner) {
+        critical_section::with(|cs| {
+            DISPLAY_MATRIX.clear_all(cs, true);
+        });
+
+        app::spawn_app_task(spawner, "life", life).await;
+    }
+
+    async fn stop(&mut self) {
+        STOP_APP_CHANNEL
+            .immediate_publisher()
+            .publish_immediate(StopAppTasks);
+    }
+
+    async fn button_one_short_press(&mut self, _: Spawner) {
+        RESEED.store(true, Ordering::Relaxed);
+    }
+
+    async fn button_two_press(&mut self, _: ButtonPress, _: Spawner) {
+        RESEED.store(true, Ordering::Relaxed);
+    }
+
+    async fn button_three_press(&mut self, _: ButtonPress, _: Spawner) {
+        RESEED.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Fill the grid with random cells.
+fn seed() -> Grid {
+    core::array::from_fn(|_| rng::next_u32() & ROW_MASK)
+}
+
+/// Count the live cells around a cell. The grid wraps around at the edges.
+fn neighbours(grid: &Grid, row: usize, col: usize) -> u32 {
+    let mut count = 0;
+
+    for row_offset in [ROWS - 1, 0, 1] {
+        for col_offset in [COLS - 1, 0, 1] {
+            if row_offset == 0 && col_offset == 0 {
+                continue;
+            }
+
+            let neighbour_row = grid[(row + row_offset) % ROWS];
+            count += (neighbour_row >> ((col + col_offset) % COLS)) & 1;
+        }
+    }
+
+    count
+}
+
+/// Work out the next generation of the grid.
+///
+/// A live cell stays alive with 2 or 3 live neighbours, and a dead cell comes alive with exactly 3.
+fn step(grid: &Grid) -> Grid {
+    core::array::from_fn(|row| {
+        (0..COLS).fold(0, |next, col| {
+            let alive = (grid[row] >> col) & 1 == 1;
+            match neighbours(grid, row, col) {
+                3 => next | (1 << col),
+                2 if alive => next | (1 << col),
+                _ => next,
+            }
+        })
+    })
+}
+
+/// Show a generation every [GENERATION_MS], seeding again when the grid dies out, stops changing or repeats every other generation.
+#[embassy_executor::task]
+async fn life() {
+    let _running = app::RunningAppTask::new();
+    let mut stop_task_sub = STOP_APP_CHANNEL.subscriber().unwrap();
+
+    let mut previous: Grid = [0; ROWS];
+    let mut grid = seed();
+    let mut generation = 0;
+
+    loop {
+        critical_section::with(|cs| {
+            DISPLAY_MATRIX.show_pattern(cs, &grid);
+        });
+
+        let res = select(
+            stop_task_sub.next_message(),
+            Timer::after(Duration::from_millis(GENERATION_MS)),
+        )
+        .await;
+
+        if let Either::First(_) = res {
+            break;
+        }
+
+        let next = step(&grid);
+        generation += 1;
+
+        let settled = next == grid || next == previous || next.iter().all(|&row| row == 0);
+        if settled || generation >= MAX_GENERATIONS || RESEED.load(Ordering::Relaxed) {
+            RESEED.store(false, Ordering::Relaxed);
+            previous = [0; ROWS];
+            grid = seed();
+            generation = 0;
+        } else {
+            previous = grid;
+            grid = next;
+        }
+    }
+}
@@ -11,6 +11,12 @@
 /// Use app module.
 mod app;
 
+/// Use board module.
+mod board;
+
+/// Use bme280 module.
+mod bme280;
+
 /// Use button module.
 mod buttons;
 
@@ -20,45 +26,167 @@ mod config;
 /// Use clock module.
 mod clock;
 
+/// Use date module.
+mod date;
+
+/// Use diagnostics module.
+mod diagnostics;
+
+/// Use esp at module.
+#[cfg(feature = "esp-at")]
+mod esp_at;
+
+/// Use dice module.
+mod dice;
+
 /// Use display module.
 mod display;
 
+/// Use eeprom module.
+mod eeprom;
+
+/// Use event log module.
+mod event_log;
+
+/// Use formatting module.
+mod formatting;
+
+/// Use graph module.
+mod graph;
+
+/// Use hooks module.
+mod hooks;
+
+/// Use humidity module.
+mod humidity;
+
+/// Use instrumentation module.
+mod instrumentation;
+
+/// Use interval module.
+mod interval;
+
+/// Use i2c bus module.
+mod i2c_bus;
+
+/// Use life module.
+mod life;
+
+/// Use mqtt module.
+#[cfg(feature = "mqtt")]
+mod mqtt;
+
+/// Use notification module.
+mod notification;
+
 /// Use pomodoro module.
 mod pomodoro;
 
+/// Use prompt module.
+mod prompt;
+
+/// Use radio clock module.
+#[cfg(any(feature = "dcf77", feature = "wwvb"))]
+mod radio_clock;
+
+/// Use reaction module.
+mod reaction;
+
+/// Use rng module.
+mod rng;
+
 /// Use rtc module.
 mod rtc;
 
 /// Use temperature module.
 mod temperature;
 
+/// Use temperature log module.
+mod temperature_log;
+
+/// Use scheduler module.
+mod scheduler;
+
+/// Use sequence module.
+mod sequence;
+
 /// Use settings module.
 mod settings;
 
+/// Use software clock module.
+mod software_clock;
+
 /// Use speaker module.
 mod speaker;
 
+/// Use still running module.
+mod still_running;
+
 /// Use stopwatch module.
 mod stopwatch;
 
+/// Use time display module.
+mod time_display;
+
+/// Use time sync module.
+#[cfg(any(
+    feature = "wifi",
+    feature = "esp-at",
+    feature = "dcf77",
+    feature = "wwvb"
+))]
+mod time_sync;
+
+/// Use timer snapshot module.
+mod timer_snapshot;
+
+/// Use timer warning module.
+mod timer_warning;
+
+/// Use usb module.
+#[cfg(any(feature = "usb-hid", feature = "usb-console"))]
+mod usb;
+
+/// Use usb console module.
+#[cfg(feature = "usb-console")]
+mod usb_console;
+
+/// Use usb hid module.
+#[cfg(feature = "usb-hid")]
+mod usb_hid;
+
+/// Use wifi module.
+#[cfg(feature = "wifi")]
+mod wifi;
+
 use app::AppController;
+use board::BoardPins;
+use buttons::ButtonDriver;
 use clock::ClockApp;
 use config::flash_config::FLASH_SIZE;
+use defmt::info;
+use dice::DiceApp;
 use display::{backlight::BacklightPins, display_matrix::DISPLAY_MATRIX, DisplayPins};
 use ds323x::Ds323x;
 use embassy_executor::{Executor, Spawner, _export::StaticCell};
 use embassy_rp::{
-    adc::{Adc, Channel, Config as ADCConfig, InterruptHandler},
+    adc::{Adc, Config as ADCConfig, InterruptHandler},
     bind_interrupts,
     flash::{Async, Flash},
-    gpio::{Input, Level, Output, Pull},
+    gpio::{AnyPin, Input, Output},
     i2c::{self, Config as I2CConfig},
     multicore::Stack,
-    peripherals::*,
+    watchdog::Watchdog,
 };
 use embassy_time::{Duration, Timer};
+use graph::GraphApp;
+use i2c_bus::SharedI2c;
+use interval::IntervalApp;
+use life::LifeApp;
 use pomodoro::PomodoroApp;
+use reaction::ReactionApp;
 use rtc::Ds3231;
+use sequence::SequenceApp;
 use settings::SettingsApp;
 use stopwatch::StopwatchApp;
 use {defmt as _, defmt_rtt as _, panic_probe as _};
@@ -70,6 +198,8 @@ static EXECUTOR0: StaticCell<Executor> = StaticCell::new();
 static EXECUTOR1: StaticCell<Executor> = StaticCell::new();
 
 /// Preallocate stack memory for the second pico core.
+///
+/// Starts zeroed, which [diagnostics::get_core1_stack_free] uses to measure the stack headroom.
 static mut CORE1_STACK: Stack<4096> = Stack::new();
 
 bind_interrupts!(struct Irqs {
@@ -83,36 +213,46 @@ fn main() -> ! {
 
     // get flash config
     let flash = Flash::<_, Async, FLASH_SIZE>::new(p.FLASH, p.DMA_CH0);
+    let watchdog = Watchdog::new(p.WATCHDOG);
 
-    // init rtc
+    // init i2c bus, shared by the rtc and any sensors
     let i2c = i2c::I2c::new_blocking(p.I2C1, p.PIN_7, p.PIN_6, I2CConfig::default());
-    let ds323x: Ds323x<
-        ds323x::interface::I2cInterface<i2c::I2c<'_, I2C1, i2c::Blocking>>,
-        ds323x::ic::DS3231,
-    > = Ds323x::new_ds3231(i2c);
+    i2c_bus::init(i2c);
+
+    // init rtc
+    let ds323x: Ds323x<ds323x::interface::I2cInterface<SharedI2c>, ds323x::ic::DS3231> =
+        Ds323x::new_ds3231(SharedI2c::new());
     let ds3231 = Ds3231(ds323x);
 
-    // init buttons
-    let button_one: Input<'_, PIN_2> = Input::new(p.PIN_2, Pull::Up);
-    let button_two: Input<'_, PIN_17> = Input::new(p.PIN_17, Pull::Up);
-    let button_three: Input<'_, PIN_15> = Input::new(p.PIN_15, Pull::Up);
-
-    // init speaker
-    let speaker: Output<'_, PIN_14> = Output::new(p.PIN_14, Level::Low);
-
-    // init display
-    let a0: Output<'_, PIN_16> = Output::new(p.PIN_16, Level::Low);
-    let a1: Output<'_, PIN_18> = Output::new(p.PIN_18, Level::Low);
-    let a2: Output<'_, PIN_22> = Output::new(p.PIN_22, Level::Low);
-    let oe: Output<'_, PIN_13> = Output::new(p.PIN_13, Level::Low);
-    let sdi: Output<'_, PIN_11> = Output::new(p.PIN_11, Level::Low);
-    let clk: Output<'_, PIN_10> = Output::new(p.PIN_10, Level::Low);
-    let le: Output<'_, PIN_12> = Output::new(p.PIN_12, Level::Low);
+    // init the buttons, speaker, display and sensor pins
+    let pins: BoardPins = board::pico_clock_green!(p);
+
     let adc = Adc::new(p.ADC, Irqs, ADCConfig::default());
-    let ain = Channel::new_pin(p.PIN_26, Pull::None);
-    let display_pins: DisplayPins<'_> = DisplayPins::new(a0, a1, a2, sdi, clk, le);
-    let backlight_pins: BacklightPins<'_> = BacklightPins::new(oe, adc, ain);
-    // let display: Display<'_> = Display::new(display_pins);
+    let display_pins = pins.display;
+    let backlight_pins: BacklightPins<'_> = BacklightPins::new(pins.oe, adc, pins.light_sensor);
+
+    // init usb, for notifications and the console from a computer
+    #[cfg(any(feature = "usb-hid", feature = "usb-console"))]
+    let usb = p.USB;
+
+    // init wifi on the pico w
+    #[cfg(feature = "wifi")]
+    let wifi_peripherals = wifi::WifiPeripherals {
+        pwr: p.PIN_23,
+        dio: p.PIN_24,
+        cs: p.PIN_25,
+        clk: p.PIN_29,
+        pio: p.PIO0,
+        dma: p.DMA_CH1,
+    };
+
+    // init an esp-at module on the uart header, for boards without wifi
+    #[cfg(feature = "esp-at")]
+    let esp_at_peripherals = esp_at::EspAtPeripherals {
+        uart: p.UART0,
+        tx: p.PIN_0,
+        rx: p.PIN_1,
+    };
 
     embassy_rp::multicore::spawn_core1(p.CORE1, unsafe { &mut CORE1_STACK }, move || {
         let executor1 = EXECUTOR1.init(Executor::new());
@@ -129,13 +269,41 @@ fn main() -> ! {
             .spawn(main_core(
                 spawner,
                 flash,
+                watchdog,
                 ds3231,
-                button_one,
-                button_two,
-                button_three,
-                speaker,
+                pins.sqw,
+                pins.button_one,
+                pins.button_two,
+                pins.button_three,
+                pins.speaker,
             ))
             .unwrap();
+
+        #[cfg(feature = "dht22")]
+        spawner
+            .spawn(humidity::dht22::dht22_task(pins.dht22))
+            .unwrap();
+
+        #[cfg(feature = "bme280")]
+        spawner.spawn(bme280::driver::bme280_task()).unwrap();
+
+        #[cfg(any(feature = "dcf77", feature = "wwvb"))]
+        spawner
+            .spawn(radio_clock::radio_clock_task(pins.radio_clock))
+            .unwrap();
+
+        #[cfg(feature = "wifi")]
+        spawner
+            .spawn(wifi::wifi_task(spawner, wifi_peripherals))
+            .unwrap();
+
+        #[cfg(feature = "esp-at")]
+        spawner
+            .spawn(esp_at::esp_at_task(esp_at_peripherals))
+            .unwrap();
+
+        #[cfg(any(feature = "usb-hid", feature = "usb-console"))]
+        spawner.spawn(usb::usb_task(usb)).unwrap();
     });
 }
 
@@ -144,32 +312,53 @@ fn main() -> ! {
 async fn main_core(
     spawner: Spawner,
     flash: Flash<'static, embassy_rp::peripherals::FLASH, Async, FLASH_SIZE>,
-    ds3231: Ds3231<'static>,
-    button_one: Input<'static, PIN_2>,
-    button_two: Input<'static, PIN_17>,
-    button_three: Input<'static, PIN_15>,
-    speaker: Output<'static, PIN_14>,
+    watchdog: Watchdog,
+    ds3231: Ds3231,
+    sqw: Input<'static, AnyPin>,
+    button_one: ButtonDriver<'static, AnyPin>,
+    button_two: ButtonDriver<'static, AnyPin>,
+    button_three: ButtonDriver<'static, AnyPin>,
+    speaker: Output<'static, AnyPin>,
 ) {
     Timer::after(Duration::from_millis(10)).await;
+    event_log::record(event_log::Event::Boot);
 
-    config::init(flash).await;
+    config::init(flash, watchdog).await;
     rtc::init(ds3231).await;
+    if let Err(e) = rtc::set_aging_offset(config::get_aging_offset().await).await {
+        info!("Failed to set the RTC aging offset: {}", e);
+    }
+    temperature::load_temperature_range().await;
+    temperature::register_hooks();
+
+    spawner.spawn(config::flush_task()).unwrap();
+    spawner.spawn(rtc::sqw_task(sqw)).unwrap();
+    spawner.spawn(hooks::boundary_task()).unwrap();
+    spawner.spawn(diagnostics::uptime_task()).unwrap();
+    spawner.spawn(temperature::history_task()).unwrap();
 
     spawner
         .spawn(display::display_matrix::process_text_buffer())
         .unwrap();
 
-    spawner.spawn(buttons::button_one_task(button_one)).unwrap();
-    spawner.spawn(buttons::button_two_task(button_two)).unwrap();
-    spawner
-        .spawn(buttons::button_three_task(button_three))
-        .unwrap();
+    spawner.spawn(buttons::button_task(button_one)).unwrap();
+    spawner.spawn(buttons::button_task(button_two)).unwrap();
+    spawner.spawn(buttons::button_task(button_three)).unwrap();
 
     spawner.spawn(speaker::speaker_task(speaker)).unwrap();
 
+    spawner.spawn(notification::notification_task()).unwrap();
+    spawner.spawn(still_running::still_running_task()).unwrap();
+
     let clock_app = ClockApp::new();
     let pomodoro_app = PomodoroApp::new();
     let stopwatch_app = StopwatchApp::new();
+    let reaction_app = ReactionApp::new();
+    let dice_app = DiceApp::new();
+    let interval_app = IntervalApp::new();
+    let life_app = LifeApp::new();
+    let graph_app = GraphApp::new();
+    let sequence_app = SequenceApp::new();
     let settings_app = SettingsApp::new();
 
     let mut app_controller = AppController::new(
@@ -177,6 +366,12 @@ async fn main_core(
         clock_app,
         pomodoro_app,
         stopwatch_app,
+        reaction_app,
+        dice_app,
+        interval_app,
+        life_app,
+        graph_app,
+        sequence_app,
         settings_app,
     );
     app_controller.run_forever().await;
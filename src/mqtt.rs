@@ -0,0 +1,185 @@
+use core::fmt::Write;
+
+use defmt::{info, Debug2Format};
+use embassy_futures::select::{select, Either};
+use embassy_net::{tcp::TcpSocket, Ipv4Address};
+use embassy_time::{Duration, Instant, Timer};
+use heapless::String;
+use rust_mqtt::{
+    client::{
+        client::MqttClient,
+        client_config::{ClientConfig, MqttVersion},
+    },
+    packet::v5::{publish_packet::QualityOfService, reason_codes::ReasonCode},
+    utils::rng_generator::CountingRng,
+};
+
+use crate::{
+    config,
+    display::backlight,
+    humidity,
+    notification::{self, Notification},
+    speaker::{self, SoundType},
+    temperature,
+    wifi::NetStack,
+};
+
+/// The id the clock connects to the broker with.
+const CLIENT_ID: &str = "pico-clock";
+
+/// The topic the temperature in celcius is published to.
+const TEMPERATURE_TOPIC: &str = "pico-clock/temperature";
+
+/// The topic the relative humidity is published to, when there is a humidity sensor.
+const HUMIDITY_TOPIC: &str = "pico-clock/humidity";
+
+/// The topic for messages to scroll across the display.
+const MESSAGE_TOPIC: &str = "pico-clock/message";
+
+/// The topic for setting the brightness. Either "auto", or a level from 0 to 4.
+const BRIGHTNESS_TOPIC: &str = "pico-clock/brightness";
+
+/// The topic for sounding the buzzer. Either empty for a single beep, or the number of beeps.
+const BUZZER_TOPIC: &str = "pico-clock/buzzer";
+
+/// The default port for the broker, when `MQTT_PORT` is not set.
+const DEFAULT_PORT: u16 = 1883;
+
+/// How often to publish the sensor readings.
+const PUBLISH_SECS: u64 = 60;
+
+/// How long to wait before connecting to the broker again after the connection fails.
+const RETRY_SECS: u64 = 30;
+
+/// The size of the socket and mqtt packet buffers.
+const BUFFER_SIZE: usize = 256;
+
+/// Publish the sensor readings to the broker set at build time, and act on messages sent to the clock.
+///
+/// The broker address is set with the `MQTT_BROKER` environment variable, and optionally `MQTT_PORT`.
+#[embassy_executor::task]
+pub async fn mqtt_task(stack: &'static NetStack) {
+    let broker = match option_env!("MQTT_BROKER").map(|address| address.parse::<Ipv4Address>()) {
+        Some(Ok(address)) => address,
+        _ => {
+            info!("MQTT_BROKER not set to an IPv4 address, mqtt is disabled");
+            return;
+        }
+    };
+
+    let port = match option_env!("MQTT_PORT").map(|port| port.parse::<u16>()) {
+        Some(Ok(port)) => port,
+        _ => DEFAULT_PORT,
+    };
+
+    loop {
+        while !stack.is_config_up() {
+            Timer::after(Duration::from_secs(1)).await;
+        }
+
+        match run(stack, broker, port).await {
+            Ok(()) => {}
+            Err(e) => info!("MQTT connection failed: {}", Debug2Format(&e)),
+        }
+
+        Timer::after(Duration::from_secs(RETRY_SECS)).await;
+    }
+}
+
+/// Connect to the broker, then publish and receive until the connection fails.
+async fn run(stack: &'static NetStack, broker: Ipv4Address, port: u16) -> Result<(), ReasonCode> {
+    let mut rx_buffer = [0u8; BUFFER_SIZE];
+    let mut tx_buffer = [0u8; BUFFER_SIZE];
+    let mut socket = TcpSocket::new(stack, &mut rx_buffer, &mut tx_buffer);
+
+    if let Err(e) = socket.connect((broker, port)).await {
+        info!("Failed to connect to the mqtt broker: {}", e);
+        return Err(ReasonCode::NetworkError);
+    }
+
+    let mut config = ClientConfig::new(MqttVersion::MQTTv5, CountingRng(20000));
+    config.add_max_subscribe_qos(QualityOfService::QoS0);
+    config.add_client_id(CLIENT_ID);
+    config.max_packet_size = BUFFER_SIZE as u32;
+
+    let mut write_buffer = [0u8; BUFFER_SIZE];
+    let mut recv_buffer = [0u8; BUFFER_SIZE];
+    let mut client = MqttClient::<_, 5, _>::new(
+        socket,
+        &mut write_buffer,
+        BUFFER_SIZE,
+        &mut recv_buffer,
+        BUFFER_SIZE,
+        config,
+    );
+
+    client.connect_to_broker().await?;
+    client.subscribe_to_topic(MESSAGE_TOPIC).await?;
+    client.subscribe_to_topic(BRIGHTNESS_TOPIC).await?;
+    client.subscribe_to_topic(BUZZER_TOPIC).await?;
+    info!("Connected to the mqtt broker");
+
+    loop {
+        let mut payload: String<16> = String::new();
+
+        _ = write!(payload, "{:.1}", temperature::get_celcius().await);
+        client
+            .send_message(
+                TEMPERATURE_TOPIC,
+                payload.as_bytes(),
+                QualityOfService::QoS0,
+                true,
+            )
+            .await?;
+
+        if let Some(humidity) = humidity::get_humidity().await {
+            payload.clear();
+            _ = write!(payload, "{humidity:.0}");
+            client
+                .send_message(
+                    HUMIDITY_TOPIC,
+                    payload.as_bytes(),
+                    QualityOfService::QoS0,
+                    true,
+                )
+                .await?;
+        }
+
+        let next_publish = Instant::now() + Duration::from_secs(PUBLISH_SECS);
+        while Instant::now() < next_publish {
+            match select(client.receive_message(), Timer::at(next_publish)).await {
+                Either::First(message) => {
+                    let (topic, payload) = message?;
+                    handle_message(topic, payload).await;
+                }
+                Either::Second(_) => {}
+            }
+        }
+    }
+}
+
+/// Act on a message sent to the clock.
+async fn handle_message(topic: &str, payload: &[u8]) {
+    let payload = match core::str::from_utf8(payload) {
+        Ok(payload) => payload.trim(),
+        Err(_) => return,
+    };
+
+    match topic {
+        MESSAGE_TOPIC => notification::notify(Notification::new(payload, None)),
+        BRIGHTNESS_TOPIC => match payload {
+            "auto" => config::set_autolight(true).await,
+            level => {
+                if let Ok(level) = level.parse::<usize>() {
+                    config::set_autolight(false).await;
+                    backlight::set_brightness(level);
+                }
+            }
+        },
+        BUZZER_TOPIC => match payload.parse::<u8>() {
+            Ok(times) => speaker::sound(SoundType::RepeatShortBeep(times)),
+            Err(_) => speaker::sound(SoundType::ShortBeep),
+        },
+        _ => {}
+    }
+}
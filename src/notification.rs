@@ -0,0 +1,153 @@
+use defmt::info;
+use embassy_futures::select::{select, Either};
+use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, channel::Channel};
+use embassy_time::{Duration, Timer};
+use heapless::String;
+
+use crate::{
+    config,
+    display::display_matrix::{DisplayMatrix, DISPLAY_MATRIX},
+    speaker::{self, SoundType},
+};
+
+/// The shortest time each notification is kept on the display for, including the time taken to scroll it.
+const SHOW_SECS: u64 = 6;
+
+/// Roughly how many columns each character takes to scroll across, with the space after it.
+const CHAR_COLUMNS: u64 = 5;
+
+/// The most bytes of text a notification can hold. Longer text is scrolled across in chunks by the display.
+const MAX_TEXT_LEN: usize = 128;
+
+/// How soon a notification should be shown compared to the others waiting.
+pub enum Priority {
+    /// Shown in the order it was sent, after any urgent notifications.
+    Normal,
+
+    /// Shown before any normal notifications waiting, such as a fault the user should know about.
+    Urgent,
+}
+
+/// A short message to show on the display on top of whichever app is active.
+pub struct Notification {
+    /// The text to scroll across the display.
+    text: String<MAX_TEXT_LEN>,
+
+    /// The name of the icon to show with the text, if any.
+    icon: Option<String<16>>,
+
+    /// The sound to make when the notification is shown, if any.
+    sound: Option<SoundType>,
+
+    /// How soon the notification should be shown.
+    priority: Priority,
+
+    /// How many times to scroll the text across the display.
+    times: u8,
+}
+
+impl Notification {
+    /// Create a new notification with normal priority and no sound, cutting off any text that is too long.
+    ///
+    /// `icon` should be a name from the display icon table, e.g. "AlarmOn".
+    pub fn new(text: &str, icon: Option<&str>) -> Self {
+        Self {
+            text: truncate(text),
+            icon: icon.map(truncate),
+            sound: None,
+            priority: Priority::Normal,
+            times: 1,
+        }
+    }
+
+    /// Make a sound when the notification is shown.
+    pub fn with_sound(mut self, sound: SoundType) -> Self {
+        self.sound = Some(sound);
+        self
+    }
+
+    /// Scroll the text across the display a number of times, rather than once.
+    pub fn repeat(mut self, times: u8) -> Self {
+        self.times = times.max(1);
+        self
+    }
+
+    /// Show the notification before any normal notifications waiting.
+    pub fn urgent(mut self) -> Self {
+        self.priority = Priority::Urgent;
+        self
+    }
+}
+
+/// Notification queue channel. Can store up to 4 notifications waiting to be shown.
+static NOTIFICATIONS: Channel<ThreadModeRawMutex, Notification, 4> = Channel::new();
+
+/// Urgent notification queue channel. Can store up to 2 notifications waiting to be shown.
+static URGENT_NOTIFICATIONS: Channel<ThreadModeRawMutex, Notification, 2> = Channel::new();
+
+/// Add a notification to the end of the queue for its priority, to show after any notifications already waiting.
+///
+/// If the queue is full, the notification is dropped.
+pub fn notify(notification: Notification) {
+    let dropped = match notification.priority {
+        Priority::Normal => NOTIFICATIONS.try_send(notification).is_err(),
+        Priority::Urgent => URGENT_NOTIFICATIONS.try_send(notification).is_err(),
+    };
+
+    if dropped {
+        info!("Notification queue full, dropping notification");
+    }
+}
+
+/// Show each notification in the queue, on top of whichever app is active.
+///
+/// Urgent notifications are always shown before normal ones that are waiting.
+#[embassy_executor::task]
+pub async fn notification_task() -> ! {
+    loop {
+        // select polls the urgent queue first, so it wins when both have a notification waiting
+        let notification = match select(URGENT_NOTIFICATIONS.recv(), NOTIFICATIONS.recv()).await {
+            Either::First(notification) => notification,
+            Either::Second(notification) => notification,
+        };
+
+        if let Some(icon) = &notification.icon {
+            DISPLAY_MATRIX.show_icon(icon);
+        }
+
+        // do not disturb holds back the sound, but still shows the notification
+        if let Some(sound) = notification.sound {
+            if !config::get_do_not_disturb().await {
+                speaker::sound(sound);
+            }
+        }
+
+        DISPLAY_MATRIX
+            .queue_text_repeat(&notification.text, 2000, true, true, notification.times)
+            .await;
+
+        // long or repeated text takes longer to scroll than the usual show time, so wait for it to finish
+        let scroll_ms =
+            notification.text.chars().count() as u64 * CHAR_COLUMNS * DisplayMatrix::SCROLL_DELAY;
+        Timer::after(Duration::from_millis(
+            (SHOW_SECS * 1000).max((scroll_ms + 2000) * notification.times as u64),
+        ))
+        .await;
+
+        if let Some(icon) = &notification.icon {
+            DISPLAY_MATRIX.hide_icon(icon);
+        }
+    }
+}
+
+/// Copy as much of the text as fits into a fixed size string.
+fn truncate<const N: usize>(text: &str) -> String<N> {
+    let mut truncated = String::new();
+    for c in text.chars() {
+        if truncated.push(c).is_err() {
+            break;
+        }
+    }
+
+    truncated
+}
@@ -6,19 +6,35 @@ use embassy_futures::select::{
     Either::{self},
 };
 use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, mutex::Mutex, pubsub::PubSubChannel};
-use embassy_time::{Duration, Timer};
+use embassy_time::{Duration, Instant, Timer};
 
 use crate::{
-    app::{App, StopAppTasks},
+    app::{self, App, StopAppTasks},
     buttons::ButtonPress,
-    display::display_matrix::{TimeColon, DISPLAY_MATRIX},
+    config,
+    display::display_matrix::{DisplayMatrix, IconAnimation, TimeColon, DISPLAY_MATRIX},
+    prompt,
     speaker::{self, SoundType},
+    timer_snapshot::{self, TimerApp},
+    timer_warning,
 };
 
 /// Channel for firing events of when tasks should be stopped.
 static STOP_APP_CHANNEL: PubSubChannel<ThreadModeRawMutex, StopAppTasks, 1, 1, 1> =
     PubSubChannel::new();
 
+/// The number of rows in each bulb of the hourglass. The row between them is the neck.
+const HOURGLASS_BULB_ROWS: u32 = 3;
+
+/// How many minutes the break offered after a pomodoro finishes lasts.
+const BREAK_MINUTES: u32 = 5;
+
+/// How long the pomodoro has to be paused for before nudging to resume it.
+const PAUSE_NUDGE_SECS: u64 = 5 * 60;
+
+/// How often to beep while nudging to resume the pomodoro.
+const PAUSE_NUDGE_BEEP_SECS: u64 = 3 * 60;
+
 /// Depict the current running state of the pomodoro timer.
 #[derive(Clone, Copy)]
 enum RunningState {
@@ -45,6 +61,12 @@ struct PomoState {
 
     /// The number of seconds. Used for display purposes and should not be set during configuration.
     seconds: u32,
+
+    /// The number of seconds the countdown was set to, so the hourglass can show how much is left.
+    total_secs: u32,
+
+    /// Whether the countdown is a break, so another break is not offered when it finishes.
+    on_break: bool,
 }
 
 impl PomoState {
@@ -54,6 +76,8 @@ impl PomoState {
             running: RunningState::NotStarted,
             minutes: 30,
             seconds: 0,
+            total_secs: 30 * 60,
+            on_break: false,
         }
     }
 
@@ -61,6 +85,8 @@ impl PomoState {
     pub fn reset(&mut self) {
         self.minutes = 30;
         self.seconds = 0;
+        self.total_secs = 30 * 60;
+        self.on_break = false;
         self.running = RunningState::NotStarted;
     }
 }
@@ -92,9 +118,16 @@ impl App for PomodoroApp {
 
         match get_running_state().await {
             RunningState::NotStarted => {}
-            RunningState::Running => {}
-            RunningState::Paused => spawner.spawn(countdown()).unwrap(),
-            RunningState::Finished => POMO_STATE.lock().await.borrow_mut().get_mut().reset(),
+            // only running on start when restored after a power cut, as stopping the app pauses it
+            RunningState::Running => {
+                DISPLAY_MATRIX.show_icon("CountDown");
+                app::spawn_app_task(spawner, "countdown", countdown).await
+            }
+            RunningState::Paused => app::spawn_app_task(spawner, "countdown", countdown).await,
+            RunningState::Finished => {
+                POMO_STATE.lock().await.borrow_mut().get_mut().reset();
+                save_snapshot().await;
+            }
         }
 
         show_time().await;
@@ -114,7 +147,7 @@ impl App for PomodoroApp {
         match get_running_state().await {
             RunningState::NotStarted => {
                 set_running(RunningState::Running).await;
-                spawner.spawn(countdown()).unwrap()
+                app::spawn_app_task(spawner, "countdown", countdown).await
             }
             RunningState::Running => {
                 // due to running delay, 1s is lost on button press, so add them back
@@ -128,17 +161,15 @@ impl App for PomodoroApp {
                 }
                 set_time(minutes, seconds).await;
                 show_time().await;
-                set_running(RunningState::Paused).await
+                set_running(RunningState::Paused).await;
+                show_hourglass(false).await;
             }
             RunningState::Paused => set_running(RunningState::Running).await,
-            RunningState::Finished => {
-                POMO_STATE.lock().await.borrow_mut().get_mut().reset();
-                show_time().await;
-            }
+            RunningState::Finished => reset().await,
         }
     }
 
-    async fn button_two_press(&mut self, press: ButtonPress, _: Spawner) {
+    async fn button_two_press(&mut self, press: ButtonPress, spawner: Spawner) {
         if let RunningState::Running = get_running_state().await {
             return;
         }
@@ -157,20 +188,22 @@ impl App for PomodoroApp {
                     minutes += 1;
                 }
             }
-            ButtonPress::Double => {
-                if minutes > 55 {
-                    minutes = 1;
-                } else {
-                    minutes += 5;
-                }
-            }
+            ButtonPress::Double => return quick_start(0, spawner).await,
+            // a triple press of the middle button always saves the config, so is never passed on
+            ButtonPress::Triple => {}
+            ButtonPress::Combo(_) => {}
         }
 
-        set_time(minutes, seconds).await;
+        set_start_time(minutes, seconds).await;
         show_time().await;
+        show_hourglass(false).await;
+
+        if let RunningState::Paused = get_running_state().await {
+            save_snapshot().await;
+        }
     }
 
-    async fn button_three_press(&mut self, press: ButtonPress, _: Spawner) {
+    async fn button_three_press(&mut self, press: ButtonPress, spawner: Spawner) {
         if let RunningState::Running = get_running_state().await {
             return;
         }
@@ -189,25 +222,113 @@ impl App for PomodoroApp {
                     minutes -= 1;
                 }
             }
-            ButtonPress::Double => {
-                if minutes < 5 {
-                    minutes = 60;
-                } else {
-                    minutes -= 5;
-                }
-            }
+            ButtonPress::Double => return quick_start(1, spawner).await,
+            ButtonPress::Triple => return quick_start(2, spawner).await,
+            ButtonPress::Combo(_) => {}
         }
 
-        set_time(minutes, seconds).await;
+        set_start_time(minutes, seconds).await;
         show_time().await;
+        show_hourglass(false).await;
+
+        if let RunningState::Paused = get_running_state().await {
+            save_snapshot().await;
+        }
     }
 }
 
+/// Start the countdown straight away from one of the quick start presets in the config.
+async fn quick_start(preset: usize, spawner: Spawner) {
+    let minutes = config::get_timer_presets().await[preset] as u32;
+    let running = get_running_state().await;
+
+    // stop the finished alarm if it is still sounding
+    if let RunningState::Finished = running {
+        speaker::cancel();
+    }
+
+    set_start_time(minutes, 0).await;
+    POMO_STATE.lock().await.borrow_mut().get_mut().on_break = false;
+    set_running(RunningState::Running).await;
+    show_time().await;
+    show_hourglass(false).await;
+
+    // a paused countdown is still running, and carries on from the new time
+    if let RunningState::NotStarted | RunningState::Finished = running {
+        app::spawn_app_task(spawner, "countdown", countdown).await;
+    }
+}
+
+/// Go back to setting up the pomodoro once it has finished.
+async fn reset() {
+    // stop the finished alarm if it is still sounding
+    speaker::cancel();
+    POMO_STATE.lock().await.borrow_mut().get_mut().reset();
+    save_snapshot().await;
+    show_time().await;
+    show_hourglass(false).await;
+}
+
+/// Offer a break after a pomodoro has finished, asking again if skipping it is not confirmed.
+///
+/// Returns true if the break should be started.
+async fn offer_break() -> bool {
+    loop {
+        if prompt::ask("BREAK?").await {
+            return true;
+        }
+
+        if prompt::confirm().await {
+            return false;
+        }
+    }
+}
+
+/// Start the break after a pomodoro has finished, counting down from [BREAK_MINUTES].
+async fn start_break() {
+    speaker::cancel();
+    set_start_time(BREAK_MINUTES, 0).await;
+    POMO_STATE.lock().await.borrow_mut().get_mut().on_break = true;
+    set_running(RunningState::Running).await;
+}
+
+/// Restore the pomodoro from before a power cut, taking off the time it would have been running for.
+///
+/// If it would have finished, the finished alarm sounds. Returns true if it is still running, so the app should be started.
+pub async fn restore() -> bool {
+    let snapshot = match timer_snapshot::load(TimerApp::Pomodoro).await {
+        Some(snapshot) => snapshot,
+        None => return false,
+    };
+
+    let remaining =
+        (snapshot.minutes * 60 + snapshot.seconds).saturating_sub(snapshot.elapsed_secs().await);
+    set_start_time(remaining / 60, remaining % 60).await;
+
+    if !snapshot.running {
+        POMO_STATE.lock().await.borrow_mut().get_mut().running = RunningState::Paused;
+        return false;
+    }
+
+    if remaining == 0 {
+        set_running(RunningState::Finished).await;
+        return false;
+    }
+
+    POMO_STATE.lock().await.borrow_mut().get_mut().running = RunningState::Running;
+    true
+}
+
 /// Get the running state value from the static pomodoro state.
 async fn get_running_state() -> RunningState {
     POMO_STATE.lock().await.borrow().running
 }
 
+/// Determine if the pomodoro is counting, not paused or stopped.
+pub async fn is_running() -> bool {
+    matches!(get_running_state().await, RunningState::Running)
+}
+
 /// Get the (minutes, seconds) state value from the static pomodoro state.
 async fn get_time() -> (u32, u32) {
     let minutes = POMO_STATE.lock().await.borrow().minutes;
@@ -224,6 +345,12 @@ async fn set_time(minutes: u32, seconds: u32) {
     state.seconds = seconds;
 }
 
+/// Set the time to count down from, which the hourglass is then measured against.
+async fn set_start_time(minutes: u32, seconds: u32) {
+    set_time(minutes, seconds).await;
+    POMO_STATE.lock().await.borrow_mut().get_mut().total_secs = minutes * 60 + seconds;
+}
+
 /// Set the running state on the static pomodoro state.
 /// Will show/hide the CountDown icon on the display depending on the state passed.
 async fn set_running(running: RunningState) {
@@ -232,6 +359,9 @@ async fn set_running(running: RunningState) {
 
     state.running = running;
 
+    // stop any pause nudge blinking
+    DISPLAY_MATRIX.animate_icon("CountDown", IconAnimation::Steady);
+
     if let RunningState::Running = running {
         DISPLAY_MATRIX.show_icon("CountDown");
     } else {
@@ -239,7 +369,27 @@ async fn set_running(running: RunningState) {
     }
 
     if let RunningState::Finished = running {
-        speaker::sound(SoundType::RepeatLongBeep(3));
+        speaker::alarm(SoundType::RepeatLongBeep(3));
+    }
+
+    drop(guard);
+    save_snapshot().await;
+}
+
+/// Save the pomodoro state, so it can be restored after a power cut.
+async fn save_snapshot() {
+    let (minutes, seconds) = get_time().await;
+
+    match get_running_state().await {
+        RunningState::Running => {
+            timer_snapshot::save(TimerApp::Pomodoro, true, minutes, seconds).await
+        }
+        RunningState::Paused => {
+            timer_snapshot::save(TimerApp::Pomodoro, false, minutes, seconds).await
+        }
+        RunningState::NotStarted | RunningState::Finished => {
+            timer_snapshot::clear(TimerApp::Pomodoro).await
+        }
     }
 }
 
@@ -251,27 +401,112 @@ async fn show_time() {
         .await;
 }
 
+/// Draw the hourglass in the last column of the display, next to the time.
+///
+/// The sand in the top bulb goes down with the time left, and piles up in the bottom bulb.
+/// The neck is lit while `falling`, so the sand looks like it trickles through once a second.
+/// Nothing is drawn unless the countdown is running or paused.
+async fn show_hourglass(falling: bool) {
+    let (running, remaining, total) = {
+        let guard = POMO_STATE.lock().await;
+        let state = guard.borrow();
+        (
+            state.running,
+            state.minutes * 60 + state.seconds,
+            state.total_secs,
+        )
+    };
+
+    let mut pattern = [0; 7];
+
+    if let RunningState::Running | RunningState::Paused = running {
+        // round up, so the last grain only falls once the time is up
+        let top = (remaining * HOURGLASS_BULB_ROWS)
+            .div_ceil(total.max(1))
+            .min(HOURGLASS_BULB_ROWS);
+        let bottom = HOURGLASS_BULB_ROWS - top;
+
+        // sand sits at the bottom of each bulb
+        for row in pattern
+            .iter_mut()
+            .take(HOURGLASS_BULB_ROWS as usize)
+            .skip((HOURGLASS_BULB_ROWS - top) as usize)
+        {
+            *row = 1;
+        }
+        for row in pattern.iter_mut().skip(7 - bottom as usize) {
+            *row = 1;
+        }
+
+        if falling {
+            pattern[HOURGLASS_BULB_ROWS as usize] = 1;
+        }
+    }
+
+    critical_section::with(|cs| {
+        DISPLAY_MATRIX.show_columns(cs, DisplayMatrix::LAST_INDEX, 1, &pattern);
+    });
+}
+
 /// The pomodoro countdown loop.
 ///
 /// Will continue to run as long as the running state is running or paused.
+///
+/// If the pause nudge is turned on, the CountDown icon blinks and a beep sounds every [PAUSE_NUDGE_BEEP_SECS] once it has been paused for [PAUSE_NUDGE_SECS].
+///
+/// If the pre-finish warning is turned on, a single beep sounds when that many minutes are left.
+///
+/// When a pomodoro finishes, a break is offered with "BREAK?". The top button starts the break and the bottom button skips it, once confirmed.
 #[embassy_executor::task]
 async fn countdown() {
+    let _running = app::RunningAppTask::new();
     let mut stop_task_sub = STOP_APP_CHANNEL.subscriber().unwrap();
 
+    let should_nudge = config::get_pause_nudge().await;
+    let pre_finish_minutes = config::get_pre_finish_minutes().await as u32;
+    let mut paused_since: Option<Instant> = None;
+    let mut last_beep: Option<Instant> = None;
+
     show_time().await;
+    show_hourglass(false).await;
 
     loop {
         let running_state = get_running_state().await;
         match running_state {
             RunningState::NotStarted => break,
             RunningState::Running => {
+                paused_since = None;
+                last_beep = None;
+
                 let (mut minutes, mut seconds) = get_time().await;
                 show_time().await;
+                show_hourglass(seconds % 2 == 0).await;
+                timer_warning::final_countdown(minutes * 60 + seconds).await;
+
+                if pre_finish_minutes > 0 && minutes == pre_finish_minutes && seconds == 0 {
+                    pre_finish_warning(pre_finish_minutes).await;
+                }
 
                 if seconds == 0 {
                     if minutes == 0 {
                         set_running(RunningState::Finished).await;
-                        break;
+                        show_hourglass(false).await;
+
+                        if POMO_STATE.lock().await.borrow().on_break {
+                            break;
+                        }
+
+                        match select(stop_task_sub.next_message(), offer_break()).await {
+                            Either::First(_) => break,
+                            Either::Second(true) => {
+                                start_break().await;
+                                continue;
+                            }
+                            Either::Second(false) => {
+                                reset().await;
+                                break;
+                            }
+                        }
                     }
 
                     minutes -= 1;
@@ -294,10 +529,57 @@ async fn countdown() {
                 }
             }
             RunningState::Paused => {
-                Timer::after(Duration::from_millis(100)).await;
-                continue;
+                let since = *paused_since.get_or_insert_with(Instant::now);
+                if should_nudge {
+                    nudge(since, &mut last_beep);
+                }
+
+                let res = select(
+                    stop_task_sub.next_message(),
+                    Timer::after(Duration::from_millis(100)),
+                )
+                .await;
+
+                if let Either::First(_) = res {
+                    DISPLAY_MATRIX.hide_icon("CountDown");
+                    DISPLAY_MATRIX.animate_icon("CountDown", IconAnimation::Steady);
+                    break;
+                }
             }
             RunningState::Finished => break,
         }
     }
 }
+
+/// Beep once to warn the pomodoro is about to finish, so there is time to wrap up.
+///
+/// Only beeps if the countdown was set to longer than the warning, otherwise it would beep as soon as it starts.
+async fn pre_finish_warning(minutes: u32) {
+    let total_secs = POMO_STATE.lock().await.borrow().total_secs;
+    if total_secs > minutes * 60 {
+        speaker::sound(SoundType::ShortBeep);
+    }
+}
+
+/// Nudge to resume the pomodoro if it has been paused for long enough, by blinking the CountDown icon and beeping.
+///
+/// Called every 100ms while paused. `last_beep` is when the nudge last beeped, if it has yet.
+fn nudge(paused_since: Instant, last_beep: &mut Option<Instant>) {
+    let paused_ms = paused_since.elapsed().as_millis();
+    let nudge_ms = PAUSE_NUDGE_SECS * 1000;
+    if paused_ms < nudge_ms {
+        return;
+    }
+
+    DISPLAY_MATRIX.show_icon("CountDown");
+    DISPLAY_MATRIX.animate_icon("CountDown", IconAnimation::Blink);
+
+    let should_beep = match last_beep {
+        Some(last_beep) => last_beep.elapsed().as_secs() >= PAUSE_NUDGE_BEEP_SECS,
+        None => true,
+    };
+    if should_beep {
+        speaker::sound(SoundType::ShortBeep);
+        *last_beep = Some(Instant::now());
+    }
+}
@@ -0,0 +1,91 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, signal::Signal};
+
+use crate::display::display_matrix::DISPLAY_MATRIX;
+
+/// Static signal channel for the answer to the question being asked, true for yes.
+static ANSWER: Signal<ThreadModeRawMutex, bool> = Signal::new();
+
+/// Static signal channel for a question being asked, so the app controller can start answering it.
+static ASKED_SIGNAL: Signal<ThreadModeRawMutex, QuestionSignal> = Signal::new();
+
+/// Static signal channel for the question being answered or cancelled.
+static CLOSED_SIGNAL: Signal<ThreadModeRawMutex, QuestionSignal> = Signal::new();
+
+/// Named struct for the question signals.
+struct QuestionSignal;
+
+/// Whether a question is being asked and is waiting for an answer.
+static ASKING: AtomicBool = AtomicBool::new(false);
+
+/// Marks a question as being asked until it is dropped, so a cancelled question is not left waiting.
+struct Asking;
+
+impl Drop for Asking {
+    fn drop(&mut self) {
+        ASKING.store(false, Ordering::Relaxed);
+        CLOSED_SIGNAL.signal(QuestionSignal);
+    }
+}
+
+/// The question asked to confirm a change that can not be undone.
+const CONFIRM_QUESTION: &str = "SURE?";
+
+/// Ask a yes or no question, holding it on the display until it is answered with [answer].
+///
+/// Signals the app controller, which takes the next presses as the answer.
+/// Returns true if the answer was yes. The question can be cancelled by dropping the future, such as in a select.
+pub async fn ask(question: &str) -> bool {
+    ANSWER.reset();
+    CLOSED_SIGNAL.reset();
+    ASKING.store(true, Ordering::Relaxed);
+    let _asking = Asking;
+    ASKED_SIGNAL.signal(QuestionSignal);
+
+    DISPLAY_MATRIX.queue_text(question, 0, true, false).await;
+    ANSWER.wait().await
+}
+
+/// Ask to confirm a change before it is made, with "SURE?".
+///
+/// Returns true if it was confirmed.
+pub async fn confirm() -> bool {
+    ask(CONFIRM_QUESTION).await
+}
+
+/// Determine if a question is waiting for an answer.
+fn is_asking() -> bool {
+    ASKING.load(Ordering::Relaxed)
+}
+
+/// Wait until a question is asked. A question cancelled before it is seen is skipped.
+///
+/// Each question is only seen once, so a question that has been answered but not yet closed is not answered again.
+pub async fn wait_for_question() {
+    loop {
+        ASKED_SIGNAL.wait().await;
+
+        if is_asking() {
+            return;
+        }
+    }
+}
+
+/// Wait until the question being asked is answered or cancelled. Returns straight away if no question is being asked.
+pub async fn wait_for_close() {
+    if is_asking() {
+        CLOSED_SIGNAL.wait().await;
+    }
+}
+
+/// Answer the question being asked. The app controller answers yes with the top button and no with the bottom button.
+///
+/// Only the first answer counts, so call it once for each question seen with [wait_for_question].
+///
+/// Does nothing if no question is being asked.
+pub fn answer(yes: bool) {
+    if is_asking() {
+        ANSWER.signal(yes);
+    }
+}
@@ -0,0 +1,327 @@
+use chrono::{NaiveDateTime, Timelike};
+use defmt::info;
+use embassy_rp::gpio::{AnyPin, Input};
+use embassy_time::{with_timeout, Duration, Instant, Timer};
+
+use crate::{
+    rtc,
+    time_sync::{self, Error as SyncError},
+};
+
+#[cfg(feature = "dcf77")]
+use self::dcf77::Decoder;
+#[cfg(not(feature = "dcf77"))]
+use self::wwvb::Decoder;
+
+/// The hour of the night to sync the time from the radio signal, when reception is best.
+const SYNC_HOUR: u32 = 2;
+
+/// How long to listen for the time signal before giving up until the next night.
+const RECEIVE_MINS: u64 = 30;
+
+/// Keep the RTC synced to the radio time signal from a receiver module.
+///
+/// Syncs once at boot, then every night at [SYNC_HOUR].
+#[embassy_executor::task]
+pub async fn radio_clock_task(mut pin: Input<'static, AnyPin>) -> ! {
+    loop {
+        match with_timeout(Duration::from_secs(RECEIVE_MINS * 60), receive(&mut pin)).await {
+            Ok((utc, at)) => {
+                if let Err(e) = sync(utc, at).await {
+                    info!("Failed to sync the time from the radio signal: {}", e);
+                }
+            }
+            Err(_) => info!(
+                "Failed to sync the time from the radio signal: {}",
+                SyncError::NoSignal
+            ),
+        }
+
+        wait_for_sync_hour().await;
+    }
+}
+
+/// Listen to the time signal until two frames in a row decode to consecutive minutes.
+///
+/// Returns the UTC datetime and the instant it was correct at.
+async fn receive(pin: &mut Input<'static, AnyPin>) -> (NaiveDateTime, Instant) {
+    let mut decoder = Decoder::new();
+    let mut previous: Option<NaiveDateTime> = None;
+
+    loop {
+        // the receiver output is high while the carrier is reduced at the start of each second
+        pin.wait_for_rising_edge().await;
+        let start = Instant::now();
+        pin.wait_for_falling_edge().await;
+        let width_ms = start.elapsed().as_millis();
+
+        if let Some((utc, at)) = decoder.pulse(start, width_ms) {
+            if previous == Some(utc - chrono::Duration::minutes(1)) {
+                return (utc, at);
+            }
+            previous = Some(utc);
+        }
+    }
+}
+
+/// Set the RTC on the start of the next second, so it is not set part way through a second.
+async fn sync(utc: NaiveDateTime, at: Instant) -> Result<(), SyncError> {
+    let secs = at.elapsed().as_secs() + 1;
+    Timer::at(at + Duration::from_secs(secs)).await;
+
+    time_sync::sync_utc(utc + chrono::Duration::seconds(secs as i64)).await
+}
+
+/// Wait until the next [SYNC_HOUR].
+async fn wait_for_sync_hour() {
+    let now = rtc::get_datetime_or_estimate().await;
+    let mut next = now.date().and_hms_opt(SYNC_HOUR, 0, 0).unwrap();
+    if now.hour() >= SYNC_HOUR {
+        next += chrono::Duration::days(1);
+    }
+
+    let secs = (next - now).num_seconds().max(0) as u64;
+    Timer::after(Duration::from_secs(secs)).await;
+}
+
+/// Read a binary coded number from a frame.
+///
+/// # Arguments
+///
+/// * `bits` - The frame, with the bit for second 0 in the lowest bit.
+/// * `from` - The second the number starts from.
+/// * `weights` - The value of each bit in order, with 0 for unused bits.
+fn bcd(bits: u64, from: usize, weights: &[u32]) -> u32 {
+    weights
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| bits & (1 << (from + i)) != 0)
+        .map(|(_, weight)| weight)
+        .sum()
+}
+
+/// DCF77 decoding, for the signal from Mainflingen in Germany.
+#[cfg(feature = "dcf77")]
+mod dcf77 {
+    use chrono::{NaiveDate, NaiveDateTime};
+    use embassy_time::Instant;
+
+    use super::bcd;
+
+    /// The number of seconds with a pulse in each minute. There is no pulse in the last second, to mark the minute.
+    const FRAME_LEN: usize = 59;
+
+    /// A gap between pulses longer than this is the minute marker.
+    const MINUTE_GAP_MS: u64 = 1500;
+
+    /// Decodes the DCF77 signal one pulse at a time.
+    pub struct Decoder {
+        /// The bits received so far in this minute.
+        bits: u64,
+
+        /// The number of bits received so far in this minute.
+        len: usize,
+
+        /// Whether all the pulses in this minute have been read.
+        valid: bool,
+
+        /// When the last pulse started.
+        last_start: Option<Instant>,
+    }
+
+    impl Decoder {
+        /// Create a new decoder, that waits for the next minute marker.
+        pub fn new() -> Self {
+            Self {
+                bits: 0,
+                len: 0,
+                valid: false,
+                last_start: None,
+            }
+        }
+
+        /// Read a pulse, returning the UTC datetime if the pulse completes a valid frame.
+        ///
+        /// The datetime is correct at the start of the pulse.
+        pub fn pulse(&mut self, start: Instant, width_ms: u64) -> Option<(NaiveDateTime, Instant)> {
+            let gap_ms = self
+                .last_start
+                .replace(start)
+                .map(|last_start| (start - last_start).as_millis());
+
+            let mut decoded = None;
+            if matches!(gap_ms, Some(gap_ms) if gap_ms > MINUTE_GAP_MS) {
+                if self.valid && self.len == FRAME_LEN {
+                    decoded = decode(self.bits).map(|utc| (utc, start));
+                }
+
+                self.bits = 0;
+                self.len = 0;
+                self.valid = true;
+            }
+
+            // 100ms is a 0, 200ms is a 1
+            let bit = match width_ms {
+                40..=140 => false,
+                141..=260 => true,
+                _ => {
+                    self.valid = false;
+                    false
+                }
+            };
+
+            if self.len < FRAME_LEN {
+                if bit {
+                    self.bits |= 1 << self.len;
+                }
+                self.len += 1;
+            } else {
+                self.valid = false;
+            }
+
+            decoded
+        }
+    }
+
+    /// Decode a full frame into the UTC datetime of the minute that starts at the minute marker.
+    ///
+    /// Returns [None](Option::None) if any of the checks in the frame fail.
+    fn decode(bits: u64) -> Option<NaiveDateTime> {
+        let bit = |i: usize| bits & (1 << i) != 0;
+
+        // the start of the time is always 1, and exactly one of summer or winter time is set
+        if !bit(20) || bit(17) == bit(18) {
+            return None;
+        }
+
+        if !even_parity(bits, 21, 28) || !even_parity(bits, 29, 35) || !even_parity(bits, 36, 58) {
+            return None;
+        }
+
+        let minute = bcd(bits, 21, &[1, 2, 4, 8, 10, 20, 40]);
+        let hour = bcd(bits, 29, &[1, 2, 4, 8, 10, 20]);
+        let day = bcd(bits, 36, &[1, 2, 4, 8, 10, 20]);
+        let month = bcd(bits, 45, &[1, 2, 4, 8, 10]);
+        let year = 2000 + bcd(bits, 50, &[1, 2, 4, 8, 10, 20, 40, 80]) as i32;
+
+        // the time is in central european time, one hour ahead of UTC, or two in summer
+        let offset = if bit(17) { 2 } else { 1 };
+        let local = NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(hour, minute, 0)?;
+        Some(local - chrono::Duration::hours(offset))
+    }
+
+    /// Determine if the bits from `from` to `to` (inclusive) have an even number of ones.
+    fn even_parity(bits: u64, from: usize, to: usize) -> bool {
+        let mask = (1u64 << (to - from + 1)) - 1;
+        ((bits >> from) & mask).count_ones() % 2 == 0
+    }
+}
+
+/// WWVB decoding, for the signal from Fort Collins in the USA.
+#[cfg(not(feature = "dcf77"))]
+mod wwvb {
+    use chrono::{NaiveDate, NaiveDateTime};
+    use embassy_time::Instant;
+
+    use super::bcd;
+
+    /// The number of seconds in each frame.
+    const FRAME_LEN: usize = 60;
+
+    /// One second of the signal.
+    #[derive(Clone, Copy, PartialEq)]
+    enum Symbol {
+        /// A 0 bit.
+        Zero,
+
+        /// A 1 bit.
+        One,
+
+        /// A position marker.
+        Marker,
+    }
+
+    /// Decodes the WWVB signal one pulse at a time.
+    pub struct Decoder {
+        /// The bits received so far in this frame.
+        bits: u64,
+
+        /// The number of seconds received so far in this frame.
+        len: usize,
+
+        /// Whether all the pulses in this frame have been read, with the markers in the right places.
+        valid: bool,
+
+        /// Whether the last pulse was a marker.
+        last_marker: bool,
+    }
+
+    impl Decoder {
+        /// Create a new decoder, that waits for the start of the next frame.
+        pub fn new() -> Self {
+            Self {
+                bits: 0,
+                len: 0,
+                valid: false,
+                last_marker: false,
+            }
+        }
+
+        /// Read a pulse, returning the UTC datetime if the pulse completes a valid frame.
+        ///
+        /// The datetime is correct at the start of the pulse.
+        pub fn pulse(&mut self, start: Instant, width_ms: u64) -> Option<(NaiveDateTime, Instant)> {
+            // 200ms is a 0, 500ms is a 1, 800ms is a marker
+            let symbol = match width_ms {
+                100..=350 => Some(Symbol::Zero),
+                351..=650 => Some(Symbol::One),
+                651..=950 => Some(Symbol::Marker),
+                _ => None,
+            };
+
+            let mut decoded = None;
+            let is_marker = symbol == Some(Symbol::Marker);
+            if is_marker && self.last_marker {
+                // two markers in a row, so this pulse starts a new frame, a minute after the last frame
+                if self.valid && self.len == FRAME_LEN {
+                    decoded =
+                        decode(self.bits).map(|utc| (utc + chrono::Duration::minutes(1), start));
+                }
+
+                self.bits = 0;
+                self.len = 0;
+                self.valid = true;
+            }
+            self.last_marker = is_marker;
+
+            if symbol.is_none() || is_marker != is_marker_second(self.len) || self.len >= FRAME_LEN
+            {
+                self.valid = false;
+            }
+
+            if symbol == Some(Symbol::One) && self.len < FRAME_LEN {
+                self.bits |= 1 << self.len;
+            }
+            self.len = (self.len + 1).min(FRAME_LEN + 1);
+
+            decoded
+        }
+    }
+
+    /// Determine if the second of the frame should be a marker. They are on 0, 9, 19, and so on up to 59.
+    fn is_marker_second(second: usize) -> bool {
+        second == 0 || second % 10 == 9
+    }
+
+    /// Decode a full frame into the UTC datetime of the minute the frame started on.
+    ///
+    /// Returns [None](Option::None) if the date or time is not valid.
+    fn decode(bits: u64) -> Option<NaiveDateTime> {
+        let minute = bcd(bits, 1, &[40, 20, 10, 0, 8, 4, 2, 1]);
+        let hour = bcd(bits, 12, &[20, 10, 0, 8, 4, 2, 1]);
+        let day_of_year = bcd(bits, 22, &[200, 100, 0, 80, 40, 20, 10, 0, 8, 4, 2, 1]);
+        let year = 2000 + bcd(bits, 45, &[80, 40, 20, 10, 0, 8, 4, 2, 1]) as i32;
+
+        NaiveDate::from_yo_opt(year, day_of_year)?.and_hms_opt(hour, minute, 0)
+    }
+}
@@ -0,0 +1,269 @@
+use core::{cell::RefCell, fmt::Write};
+
+use embassy_executor::Spawner;
+use embassy_futures::select::{select3, Either3};
+use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, mutex::Mutex, pubsub::PubSubChannel};
+use embassy_time::{Duration, Instant, Timer};
+use heapless::String;
+
+use crate::{
+    app::{self, App, StopAppTasks},
+    buttons::{ButtonPress, BUTTON_DOWN},
+    display::display_matrix::DISPLAY_MATRIX,
+    rng,
+};
+
+/// Channel for firing events of when tasks should be stopped.
+static STOP_APP_CHANNEL: PubSubChannel<ThreadModeRawMutex, StopAppTasks, 1, 1, 1> =
+    PubSubChannel::new();
+
+/// The shortest wait before the display flashes, in milliseconds.
+const MIN_WAIT_MS: u64 = 2000;
+
+/// The longest wait before the display flashes, in milliseconds.
+const MAX_WAIT_MS: u64 = 6000;
+
+/// How long to wait for a press after the flash before giving up, in milliseconds.
+const TIMEOUT_MS: u64 = 5000;
+
+/// How long after a round to ignore presses, so the press that ended it does not start another.
+///
+/// The press type is only known a while after the button is released, which can be after the round has finished.
+const IGNORE_AFTER_MS: u64 = 1000;
+
+/// The pattern flashed on the display, with every pixel lit.
+const FLASH_PATTERN: [u32; 7] = [u32::MAX; 7];
+
+/// Depict the current state of a round.
+#[derive(Clone, Copy)]
+enum RoundState {
+    /// No round has been played since the app started.
+    Idle,
+
+    /// A round is running, either waiting for the flash or for the press.
+    Playing,
+
+    /// A round has finished, at the instant held.
+    Finished(Instant),
+}
+
+/// How a round ended.
+enum Outcome {
+    /// A button was pressed before the flash.
+    Early,
+
+    /// No button was pressed before the timeout.
+    Slow,
+
+    /// A button was pressed after the flash, with the reaction time in milliseconds.
+    Reacted(u64),
+}
+
+/// Manage active state of the reaction app.
+struct ReactionState {
+    /// The current round state.
+    round: RoundState,
+
+    /// The fastest reaction since boot, in milliseconds.
+    best_ms: Option<u64>,
+}
+
+/// Static reference to the reaction state so it can be accessed by static tasks.
+static REACTION_STATE: Mutex<ThreadModeRawMutex, RefCell<ReactionState>> =
+    Mutex::new(RefCell::new(ReactionState {
+        round: RoundState::Idle,
+        best_ms: None,
+    }));
+
+/// Reaction app.
+/// Blanks the display, flashes it after a random wait, and measures how quickly a button is pressed.
+pub struct ReactionApp {}
+
+impl ReactionApp {
+    /// Create a new reaction app.
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl App for ReactionApp {
+    fn get_name(&self) -> &str {
+        "React"
+    }
+
+    async fn start(&mut self, _: Spawner) {
+        critical_section::with(|cs| {
+            DISPLAY_MATRIX.clear_all(cs, true);
+        });
+
+        REACTION_STATE.lock().await.borrow_mut().round = RoundState::Idle;
+
+        show_best().await;
+    }
+
+    async fn stop(&mut self) {
+        STOP_APP_CHANNEL
+            .immediate_publisher()
+            .publish_immediate(StopAppTasks);
+
+        REACTION_STATE.lock().await.borrow_mut().round = RoundState::Idle;
+    }
+
+    async fn button_one_short_press(&mut self, spawner: Spawner) {
+        start_round(spawner).await;
+    }
+
+    async fn button_two_press(&mut self, press: ButtonPress, spawner: Spawner) {
+        if let ButtonPress::Short = press {
+            start_round(spawner).await;
+        }
+    }
+
+    async fn button_three_press(&mut self, press: ButtonPress, spawner: Spawner) {
+        match press {
+            ButtonPress::Short => start_round(spawner).await,
+            ButtonPress::Long => {
+                let guard = REACTION_STATE.lock().await;
+                let mut state = guard.borrow_mut();
+
+                if let RoundState::Playing = state.round {
+                    return;
+                }
+
+                state.best_ms = None;
+                drop(state);
+                drop(guard);
+
+                show_best().await;
+            }
+            ButtonPress::Double => {}
+            ButtonPress::Triple => {}
+            ButtonPress::Combo(_) => {}
+        }
+    }
+}
+
+/// Start a new round, unless one is already running or has only just finished.
+async fn start_round(spawner: Spawner) {
+    let guard = REACTION_STATE.lock().await;
+    let mut state = guard.borrow_mut();
+
+    match state.round {
+        RoundState::Playing => return,
+        RoundState::Finished(at) if at.elapsed() < Duration::from_millis(IGNORE_AFTER_MS) => return,
+        RoundState::Idle | RoundState::Finished(_) => {}
+    }
+
+    state.round = RoundState::Playing;
+    drop(state);
+    drop(guard);
+
+    app::spawn_app_task(spawner, "round", round).await;
+}
+
+/// Show the fastest reaction, or the app name if no round has been played.
+async fn show_best() {
+    let best_ms = REACTION_STATE.lock().await.borrow().best_ms;
+
+    match best_ms {
+        Some(best_ms) => {
+            DISPLAY_MATRIX.queue_text("Best", 1000, true, false).await;
+            show_ms(best_ms).await;
+        }
+        None => DISPLAY_MATRIX.queue_text("React", 0, true, false).await,
+    }
+}
+
+/// Show a reaction time in milliseconds.
+async fn show_ms(ms: u64) {
+    let mut text = String::<8>::new();
+    _ = write!(text, "{ms}");
+
+    DISPLAY_MATRIX
+        .queue_text(text.as_str(), 0, false, false)
+        .await;
+}
+
+/// Finish the round, showing how it ended and whether it is the new best.
+async fn finish(outcome: Outcome) {
+    critical_section::with(|cs| {
+        DISPLAY_MATRIX.clear(cs, true);
+    });
+
+    let guard = REACTION_STATE.lock().await;
+    let mut state = guard.borrow_mut();
+
+    state.round = RoundState::Finished(Instant::now());
+
+    let new_best = match outcome {
+        Outcome::Reacted(ms) if state.best_ms.map_or(true, |best_ms| ms < best_ms) => {
+            state.best_ms = Some(ms);
+            true
+        }
+        _ => false,
+    };
+
+    drop(state);
+    drop(guard);
+
+    match outcome {
+        Outcome::Early => DISPLAY_MATRIX.queue_text("Early", 0, false, false).await,
+        Outcome::Slow => DISPLAY_MATRIX.queue_text("Slow", 0, false, false).await,
+        Outcome::Reacted(ms) => {
+            if new_best {
+                DISPLAY_MATRIX.queue_text("Best", 1000, false, false).await;
+            }
+            show_ms(ms).await;
+        }
+    }
+}
+
+/// Play a single round, from blanking the display to the press after the flash.
+#[embassy_executor::task]
+async fn round() {
+    let _running = app::RunningAppTask::new();
+    let mut stop_task_sub = STOP_APP_CHANNEL.subscriber().unwrap();
+
+    critical_section::with(|cs| {
+        DISPLAY_MATRIX.clear(cs, true);
+    });
+
+    let wait_ms = MIN_WAIT_MS + rng::below((MAX_WAIT_MS - MIN_WAIT_MS + 1) as u32) as u64;
+
+    // forget the press that started the round
+    BUTTON_DOWN.reset();
+
+    let res = select3(
+        stop_task_sub.next_message(),
+        BUTTON_DOWN.wait(),
+        Timer::after(Duration::from_millis(wait_ms)),
+    )
+    .await;
+
+    match res {
+        Either3::First(_) => return,
+        Either3::Second(_) => return finish(Outcome::Early).await,
+        Either3::Third(_) => {}
+    }
+
+    critical_section::with(|cs| {
+        DISPLAY_MATRIX.show_pattern(cs, &FLASH_PATTERN);
+    });
+    let flashed_at = Instant::now();
+
+    let res = select3(
+        stop_task_sub.next_message(),
+        BUTTON_DOWN.wait(),
+        Timer::after(Duration::from_millis(TIMEOUT_MS)),
+    )
+    .await;
+
+    match res {
+        Either3::First(_) => {}
+        Either3::Second(pressed_at) => {
+            let reaction_ms = pressed_at.saturating_duration_since(flashed_at).as_millis();
+            finish(Outcome::Reacted(reaction_ms)).await;
+        }
+        Either3::Third(_) => finish(Outcome::Slow).await,
+    }
+}
@@ -0,0 +1,27 @@
+use embassy_rp::pac;
+
+/// Get a random bit from the ring oscillator.
+///
+/// The ring oscillator jitters against the system clock, so the bit is unpredictable, but is not good enough for cryptography.
+fn random_bit() -> bool {
+    pac::ROSC.randombit().read().randombit()
+}
+
+/// Get a random number, made from 32 bits of the ring oscillator.
+pub fn next_u32() -> u32 {
+    (0..32).fold(0, |value, _| (value << 1) | random_bit() as u32)
+}
+
+/// Get a random number from 0 up to, but not including, `max`.
+///
+/// Numbers that would make some results more likely than others are thrown away and taken again.
+pub fn below(max: u32) -> u32 {
+    let limit = u32::MAX - u32::MAX % max;
+
+    loop {
+        let value = next_u32();
+        if value < limit {
+            return value % max;
+        }
+    }
+}
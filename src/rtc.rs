@@ -1,104 +1,317 @@
-use chrono::{Datelike, NaiveDateTime, Timelike};
-use core::cell::RefCell;
-use ds323x::{DateTimeAccess, Ds323x};
-use embassy_rp::{i2c, peripherals::I2C1};
-use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, mutex::Mutex};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike, Weekday};
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use defmt::info;
+use ds323x::{DateTimeAccess, Ds323x, SqWFreq};
+use embassy_futures::select::select;
+use embassy_rp::{
+    gpio::{AnyPin, Input},
+    i2c,
+};
+use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, mutex::Mutex, pubsub::PubSubChannel};
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::{
+    date, diagnostics,
+    event_log::{self, Event},
+    i2c_bus::SharedI2c,
+    software_clock,
+};
+
+/// The Ds323x driver for the Ds3231 on the shared I2C bus.
+pub type Driver = Ds323x<ds323x::interface::I2cInterface<SharedI2c>, ds323x::ic::DS3231>;
 
 /// Wrapper around the Ds323x crate for the Ds3231 used in the pico clock.
-pub struct Ds3231<'a>(
-    pub  Ds323x<
-        ds323x::interface::I2cInterface<i2c::I2c<'a, I2C1, i2c::Blocking>>,
-        ds323x::ic::DS3231,
-    >,
-);
+pub struct Ds3231(pub Driver);
+
+/// Errors that can happen when using the RTC.
+#[derive(defmt::Format)]
+pub enum Error {
+    /// The RTC did not answer on the I2C bus, even after retrying.
+    Bus,
+
+    /// The RTC returned data that is not valid, such as an impossible date.
+    InvalidData,
+}
+
+impl<E, P> From<ds323x::Error<E, P>> for Error {
+    fn from(e: ds323x::Error<E, P>) -> Self {
+        match e {
+            ds323x::Error::Comm(_) => Error::Bus,
+            _ => Error::InvalidData,
+        }
+    }
+}
+
+/// The earliest year the RTC can hold.
+pub const MIN_YEAR: i32 = 2000;
+
+/// The latest year the RTC can hold.
+///
+/// The DS3231 only stores two digits of the year and a century bit, and the driver does not use the century after 2100.
+pub const MAX_YEAR: i32 = 2100;
+
+/// How many times to try an RTC operation before giving up.
+const ATTEMPTS: u32 = 3;
+
+/// How long to wait before the first retry of an RTC operation. Doubles for each retry after.
+const RETRY_DELAY_MS: u64 = 10;
 
 /// Static reference to the Ds3231.
 ///
 /// **Init must be called first to set the value, or it will return None.**
 static RTC: Mutex<ThreadModeRawMutex, RefCell<Option<Ds3231>>> = Mutex::new(RefCell::new(None));
 
+/// Named struct for the tick signal.
+#[derive(Clone)]
+struct Tick;
+
+/// The most tasks that can wait for the tick at once, the clock app and the [hooks](crate::hooks) boundary task.
+const TICK_WAITERS: usize = 2;
+
+/// Channel for the start of each second, from the RTC square wave.
+///
+/// A channel rather than a signal so more than one task can wait for the same tick.
+static TICK: PubSubChannel<ThreadModeRawMutex, Tick, 1, TICK_WAITERS, 1> = PubSubChannel::new();
+
+/// How long to wait for a tick before carrying on without one, such as when the square wave is not connected.
+const TICK_TIMEOUT_MS: u64 = 1100;
+
+/// When the last tick happened.
+static LAST_TICK: Mutex<ThreadModeRawMutex, RefCell<Option<Instant>>> =
+    Mutex::new(RefCell::new(None));
+
+/// When the RTC was last read to sync the [software clock](software_clock).
+static LAST_READ: Mutex<ThreadModeRawMutex, RefCell<Option<Instant>>> =
+    Mutex::new(RefCell::new(None));
+
+/// How often to read the RTC when there are no ticks, such as when the square wave is not connected.
+const READ_MS: u64 = 250;
+
+/// Whether the software clock needs syncing to the RTC, as the second has changed or the time has been set.
+static READ_STALE: AtomicBool = AtomicBool::new(true);
+
 /// Initialise the static RTC value.
-pub async fn init(ds3231: Ds3231<'static>) {
+///
+/// The INT/SQW output is set to a 1Hz square wave for the [tick task](sqw_task).
+pub async fn init(ds3231: Ds3231) {
     RTC.lock().await.replace(Some(ds3231));
+
+    let res = with_retry(|rtc| {
+        rtc.use_int_sqw_output_as_square_wave()?;
+        rtc.set_square_wave_frequency(SqWFreq::_1Hz)
+    })
+    .await;
+
+    if let Err(e) = res {
+        info!("Failed to set up the RTC square wave: {}", e);
+    }
 }
 
-/// Get the current datetime from the RTC.
-pub async fn get_datetime() -> NaiveDateTime {
-    RTC.lock()
-        .await
-        .borrow_mut()
-        .as_mut()
-        .unwrap()
-        .0
-        .datetime()
-        .unwrap()
+/// Run an operation on the RTC, retrying with a growing delay if it fails.
+async fn with_retry<T>(
+    mut operation: impl FnMut(&mut Driver) -> Result<T, ds323x::Error<i2c::Error, ()>>,
+) -> Result<T, Error> {
+    let mut delay_ms = RETRY_DELAY_MS;
+    let mut attempt = 1;
+
+    loop {
+        let res = operation(&mut RTC.lock().await.borrow_mut().as_mut().unwrap().0);
+
+        match res {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt >= ATTEMPTS => {
+                event_log::record(Event::RtcError);
+                return Err(e.into());
+            }
+            Err(_) => info!("RTC operation failed, retrying"),
+        }
+
+        Timer::after(Duration::from_millis(delay_ms)).await;
+        delay_ms *= 2;
+        attempt += 1;
+    }
+}
+
+/// Signal a tick at the start of each second from the RTC 1Hz square wave.
+///
+/// The seconds in the RTC change on the falling edge of the square wave.
+#[embassy_executor::task]
+pub async fn sqw_task(mut sqw: Input<'static, AnyPin>) {
+    loop {
+        sqw.wait_for_falling_edge().await;
+        let now = Instant::now();
+
+        LAST_TICK.lock().await.replace(Some(now));
+        READ_STALE.store(true, Ordering::Relaxed);
+        TICK.immediate_publisher().publish_immediate(Tick);
+    }
+}
+
+/// Wait for the start of the next second.
+///
+/// If the square wave is not connected, this will wait for just over a second instead.
+/// No more than [TICK_WAITERS] tasks should wait for the tick at a time, any more will always wait for the timeout.
+pub async fn wait_for_tick() {
+    match TICK.subscriber() {
+        Ok(mut sub) => {
+            select(
+                sub.next_message(),
+                Timer::after(Duration::from_millis(TICK_TIMEOUT_MS)),
+            )
+            .await;
+        }
+        Err(_) => {
+            info!("Too many tasks waiting for the tick");
+            Timer::after(Duration::from_millis(TICK_TIMEOUT_MS)).await;
+        }
+    }
+}
+
+/// Get the current datetime from the software clock, syncing it to the RTC first if needed.
+///
+/// The RTC is only read once per tick, or every [READ_MS] without ticks, so calls in between do not use the I2C bus.
+pub async fn get_datetime() -> Result<NaiveDateTime, Error> {
+    let guard = LAST_READ.lock().await;
+    let last_tick = *LAST_TICK.lock().await.borrow();
+
+    let ticking = match last_tick {
+        Some(tick) => tick.elapsed() < Duration::from_millis(TICK_TIMEOUT_MS),
+        None => false,
+    };
+
+    let fresh = match *guard.borrow() {
+        Some(read_at) => {
+            !READ_STALE.load(Ordering::Relaxed)
+                && (ticking || read_at.elapsed() < Duration::from_millis(READ_MS))
+        }
+        None => false,
+    };
+
+    if !fresh {
+        // cleared before reading, so a tick during the read makes the next call read again
+        READ_STALE.store(false, Ordering::Relaxed);
+
+        let datetime = read_datetime().await?;
+        let read_at = Instant::now();
+
+        // the seconds changed on the last tick, so that is when the datetime read became correct
+        let synced_at = match last_tick {
+            Some(tick) if ticking => tick,
+            _ => read_at,
+        };
+
+        software_clock::discipline(datetime, synced_at).await;
+        guard.replace(Some(read_at));
+    }
+
+    Ok(software_clock::now().await)
+}
+
+/// Get the current datetime from the RTC, or from the software clock if the RTC is not responding.
+///
+/// The software clock carries on from the last read of the RTC.
+pub async fn get_datetime_or_estimate() -> NaiveDateTime {
+    match get_datetime().await {
+        Ok(datetime) => datetime,
+        Err(_) => software_clock::now().await,
+    }
+}
+
+/// Read the current datetime from the RTC, skipping the software clock.
+async fn read_datetime() -> Result<NaiveDateTime, Error> {
+    with_retry(|rtc| rtc.datetime()).await
 }
 
-/// Get the current hour from the RTC.
+/// Get the current hour from the RTC, or an estimate if the RTC is not responding.
 pub async fn get_hour() -> u32 {
-    let datetime = get_datetime().await;
+    let datetime = get_datetime_or_estimate().await;
     datetime.hour()
 }
 
-/// Get the current minute from the RTC.
+/// Get the current minute from the RTC, or an estimate if the RTC is not responding.
 pub async fn get_minute() -> u32 {
-    let datetime = get_datetime().await;
+    let datetime = get_datetime_or_estimate().await;
     datetime.minute()
 }
 
-/// Get the current day from the RTC.
+/// Get the current day from the RTC, or an estimate if the RTC is not responding.
 pub async fn get_day() -> u32 {
-    let datetime = get_datetime().await;
+    let datetime = get_datetime_or_estimate().await;
     datetime.day()
 }
 
-/// Get the current month from the RTC.
+/// Get the current month from the RTC, or an estimate if the RTC is not responding.
 pub async fn get_month() -> u32 {
-    let datetime = get_datetime().await;
+    let datetime = get_datetime_or_estimate().await;
     datetime.month()
 }
 
-/// Get the current year from the RTC.
+/// Get the current year from the RTC, or an estimate if the RTC is not responding.
 pub async fn get_year() -> i32 {
-    let datetime = get_datetime().await;
+    let datetime = get_datetime_or_estimate().await;
     datetime.year()
 }
 
-/// Determine if it is a leap year based on the current value in RTC.
-pub async fn is_leap_year() -> bool {
-    let year = get_year().await;
-    is_leap_year_opt(year)
-}
-
-/// Determine if the passed year is a leap year.
-pub fn is_leap_year_opt(year: i32) -> bool {
-    (year % 400 == 0 || year % 100 != 0) && year % 4 == 0
-}
-
 /// Set the passed hour into the RTC.
 ///
-/// This will set the seconds to 0.
-pub async fn set_hour(hour: u32) {
-    let current_datetime = get_datetime().await;
+/// This will set the seconds to 0, and is recorded as a time sync for drift measurement.
+pub async fn set_hour(hour: u32) -> Result<(), Error> {
+    let current_datetime = read_datetime().await?;
     let new_datetime = current_datetime
         .with_hour(hour)
         .unwrap()
         .with_second(0)
         .unwrap();
-    set_datetime(&new_datetime).await;
+    set_datetime(&new_datetime).await?;
+    diagnostics::record_time_set(current_datetime, new_datetime).await;
+    Ok(())
 }
 
 /// Set the passed minute into the RTC.
 ///
-/// This will set the seconds to 0.
-pub async fn set_minute(minute: u32) {
-    let current_datetime = get_datetime().await;
+/// This will set the seconds to 0, and is recorded as a time sync for drift measurement.
+pub async fn set_minute(minute: u32) -> Result<(), Error> {
+    let current_datetime = read_datetime().await?;
     let new_datetime = current_datetime
         .with_minute(minute)
         .unwrap()
         .with_second(0)
         .unwrap();
-    set_datetime(&new_datetime).await;
+    set_datetime(&new_datetime).await?;
+    diagnostics::record_time_set(current_datetime, new_datetime).await;
+    Ok(())
+}
+
+/// Set the seconds in the RTC to 0, rounding to the nearest minute.
+///
+/// Done on the minute from a reference time signal, this syncs the clock to the second. It is recorded as a time sync for drift measurement.
+pub async fn sync_to_minute() -> Result<(), Error> {
+    let current_datetime = read_datetime().await?;
+    let mut new_datetime = current_datetime.with_second(0).unwrap();
+    if current_datetime.second() >= 30 {
+        new_datetime += chrono::Duration::minutes(1);
+    }
+
+    set_datetime(&new_datetime).await?;
+    diagnostics::record_time_set(current_datetime, new_datetime).await;
+    Ok(())
+}
+
+/// Set the full datetime into the RTC from an outside time source, such as the network.
+#[cfg(any(
+    feature = "wifi",
+    feature = "esp-at",
+    feature = "dcf77",
+    feature = "wwvb"
+))]
+pub async fn sync_datetime(datetime: NaiveDateTime) -> Result<(), Error> {
+    let current_datetime = read_datetime().await?;
+    set_datetime(&datetime).await?;
+    diagnostics::record_time_set(current_datetime, datetime).await;
+    Ok(())
 }
 
 /// Set the day into the RTC.
@@ -106,37 +319,21 @@ pub async fn set_minute(minute: u32) {
 /// It will automatically handle larger than allowed days by setting the value to the maximum allowed for the current month in the RTC.
 ///
 /// For example, setting February 29th on a non leap year will become 28th February.
-pub async fn set_day(mut day: u32) {
-    let current_datetime = get_datetime().await;
-
-    // ensure day does not exceed max day in month
-    let max_day = get_max_day_in_month(get_month().await).await;
-    if day > max_day {
-        day = max_day;
-    }
-
-    let new_datetime = current_datetime.with_day(day).unwrap();
-    set_datetime(&new_datetime).await;
+pub async fn set_day(day: u32) -> Result<(), Error> {
+    let current_datetime = read_datetime().await?;
+    let new_datetime = date::with_day(current_datetime, day).ok_or(Error::InvalidData)?;
+    set_datetime(&new_datetime).await
 }
 
 /// Set the month into the RTC.
 ///
 /// It will automatically handle larger than allowed days by setting the value to the maximum allowed for the month passed.
 ///
-/// For example, changing from 31st December into February will become 28th February.
-/// Note how leap years in this example are not handled, this will need to be done seperately.
-pub async fn set_month(month: u32) {
-    let mut current_datetime = get_datetime().await;
-
-    // check that the current day is not greater than what the month allows
-    let day = current_datetime.day();
-    let max_day = get_max_day_in_month(month).await;
-    if day > max_day {
-        current_datetime = current_datetime.with_day(max_day).unwrap();
-    }
-
-    let new_datetime = current_datetime.with_month(month).unwrap();
-    set_datetime(&new_datetime).await;
+/// For example, changing from 31st December into February will become 28th February, or 29th February in a leap year.
+pub async fn set_month(month: u32) -> Result<(), Error> {
+    let current_datetime = read_datetime().await?;
+    let new_datetime = date::with_month(current_datetime, month).ok_or(Error::InvalidData)?;
+    set_datetime(&new_datetime).await
 }
 
 /// Set the year into the RTC.
@@ -144,77 +341,123 @@ pub async fn set_month(month: u32) {
 /// It will automatically handle converting from a leap year to non leap year as required.
 ///
 /// For example, going from 29th February 2024 to 29th February 2025 will become 28th February 2025.
-pub async fn set_year(year: i32) {
-    let mut current_datetime = get_datetime().await;
+pub async fn set_year(year: i32) -> Result<(), Error> {
+    let current_datetime = read_datetime().await?;
+    let new_datetime = date::with_year(current_datetime, year).ok_or(Error::InvalidData)?;
+    set_datetime(&new_datetime).await
+}
+
+/// Replace the datetime in the RTC with the passed datetime.
+///
+/// The time is valid again once set, so the oscillator stop flag is cleared.
+async fn set_datetime(datetime: &NaiveDateTime) -> Result<(), Error> {
+    let res = with_retry(|rtc| {
+        rtc.set_datetime(datetime)?;
+        rtc.clear_has_been_stopped_flag()
+    })
+    .await;
 
-    // check for undoing leap year if year becomes not leap year
-    if !is_leap_year_opt(year) && current_datetime.month() == 2 && current_datetime.day() == 29 {
-        current_datetime = current_datetime.with_day(28).unwrap();
+    if res.is_ok() {
+        software_clock::set(*datetime, Instant::now()).await;
     }
+    READ_STALE.store(true, Ordering::Relaxed);
 
-    let new_datetime = current_datetime.with_year(year).unwrap();
-    set_datetime(&new_datetime).await;
+    res
 }
 
-/// Replace the datetime in the RTC with the passed datetime.
-async fn set_datetime(datetime: &NaiveDateTime) {
-    RTC.lock()
-        .await
-        .borrow_mut()
-        .as_mut()
-        .unwrap()
-        .0
-        .set_datetime(datetime)
-        .unwrap();
+/// Determine if the RTC oscillator has stopped since the time was last set, such as from a flat battery while unplugged.
+///
+/// If it has, the time in the RTC can not be trusted.
+pub async fn has_oscillator_stopped() -> Result<bool, Error> {
+    with_retry(|rtc| rtc.has_been_stopped()).await
 }
 
-/// Get the maximum possible day in the passed month.
+/// Check the date in the RTC can be right, and move it on to the firmware build date if it can not.
+///
+/// The date can not be right if it is not a real date, such as after the registers were corrupted, or if it is before the firmware was built, such as after a flat battery reset it to 2000.
+/// The time of day is kept, and the oscillator stop flag is left alone, so the time is still seen as lost.
 ///
-/// It will automatically handle leap years by adding a 1 to the February motnh.
+/// Returns true if the time needs setting, as it was lost or the date was moved on.
+pub async fn check_date() -> bool {
+    let stopped = matches!(has_oscillator_stopped().await, Ok(true));
+
+    let build_date = match date::build_date() {
+        Some(build_date) => build_date,
+        None => return stopped,
+    };
+
+    let datetime = match read_datetime().await {
+        Ok(datetime) if datetime.date() >= build_date => return stopped,
+        Ok(datetime) => build_date.and_time(datetime.time()),
+        Err(Error::InvalidData) => build_date.and_hms_opt(0, 0, 0).unwrap(),
+        Err(Error::Bus) => return stopped,
+    };
+
+    info!("RTC date is not valid, moving it on to the build date");
+
+    let res = with_retry(|rtc| rtc.set_datetime(&datetime)).await;
+    if res.is_ok() {
+        software_clock::set(datetime, Instant::now()).await;
+    }
+    READ_STALE.store(true, Ordering::Relaxed);
+
+    true
+}
+
+/// Get the aging offset from the RTC.
+pub async fn get_aging_offset() -> Result<i8, Error> {
+    with_retry(|rtc| rtc.aging_offset()).await
+}
+
+/// Set the aging offset into the RTC.
+///
+/// Each step changes the crystal frequency by about 0.1ppm, with positive values slowing the clock down.
+pub async fn set_aging_offset(offset: i8) -> Result<(), Error> {
+    with_retry(|rtc| rtc.set_aging_offset(offset)).await
+}
+
+/// Get the maximum possible day in the passed month, in the current year in the RTC.
+///
+/// It will automatically handle leap years by adding a 1 to the February month.
 pub async fn get_max_day_in_month(month: u32) -> u32 {
-    let mut day = MONTH_TABLE
-        .iter()
-        .find(|y: &&(u32, u32)| y.0 == month)
-        .unwrap()
-        .1;
+    let year = get_year().await;
+    date::max_day_in_month(year, month).unwrap()
+}
 
-    // handle leap year in feb
-    if month == 2 && is_leap_year().await {
-        day += 1;
+/// Get the day of the week a date would fall on once set into the RTC, without setting it.
+///
+/// The day is clamped to the days in the month in the same way as setting it, so the 31st of February gives the day of the 28th, or the 29th in a leap year.
+/// Returns [None](Option::None) if the date can not be held by the RTC.
+pub fn get_weekday(year: i32, month: u32, day: u32) -> Option<Weekday> {
+    if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+        return None;
     }
 
-    day
-}
-
-/// Days in month lookup table.
-const MONTH_TABLE: [(u32, u32); 12] = [
-    (1, 31),
-    (2, 28),
-    (3, 31),
-    (4, 30),
-    (5, 31),
-    (6, 30),
-    (7, 31),
-    (8, 31),
-    (9, 30),
-    (10, 31),
-    (11, 30),
-    (12, 31),
-];
+    let day = date::clamp_day(year, month, day)?;
+    NaiveDate::from_ymd_opt(year, month, day).map(|date| date.weekday())
+}
 
 /// All temperature related functionality.
 pub mod temperature {
+    use core::sync::atomic::AtomicU32;
+
     use super::*;
 
+    /// The bits of the last temperature read from the RTC.
+    static LAST_TEMPERATURE: AtomicU32 = AtomicU32::new(0);
+
     /// Get the current temperature from RTC.
-    pub async fn get_temperature() -> f32 {
-        RTC.lock()
-            .await
-            .borrow_mut()
-            .as_mut()
-            .unwrap()
-            .0
-            .temperature()
-            .unwrap()
+    pub async fn get_temperature() -> Result<f32, Error> {
+        let temp = with_retry(|rtc| rtc.temperature()).await?;
+        LAST_TEMPERATURE.store(temp.to_bits(), Ordering::Relaxed);
+        Ok(temp)
+    }
+
+    /// Get the current temperature from RTC, or the last temperature read if the RTC is not responding.
+    pub async fn get_temperature_or_last() -> f32 {
+        match get_temperature().await {
+            Ok(temp) => temp,
+            Err(_) => f32::from_bits(LAST_TEMPERATURE.load(Ordering::Relaxed)),
+        }
     }
 }
@@ -0,0 +1,105 @@
+use chrono::{NaiveDateTime, Timelike};
+use defmt::{debug, info};
+use heapless::Vec;
+
+/// A job that is due on a fixed interval, lined up with the start of the day.
+struct Job<T> {
+    /// The name of the job, for logging.
+    name: &'static str,
+
+    /// How often the job is due, in seconds.
+    interval_secs: u32,
+
+    /// How many seconds after each interval boundary the job is due.
+    offset_secs: u32,
+
+    /// What to run when the job is due.
+    task: T,
+
+    /// When the job is next due. [None](Option::None) until the first check.
+    next: Option<NaiveDateTime>,
+}
+
+/// Registry of periodic jobs, checked against the time on each tick.
+///
+/// Jobs are tasks of type `T`, usually an enum, which the owner runs when they are returned from [due](Scheduler::due).
+/// This keeps the jobs async without needing to store futures.
+pub struct Scheduler<T: Copy, const N: usize> {
+    /// The registered jobs.
+    jobs: Vec<Job<T>, N>,
+}
+
+impl<T: Copy, const N: usize> Scheduler<T, N> {
+    /// Create a new scheduler with no jobs.
+    pub const fn new() -> Self {
+        Self { jobs: Vec::new() }
+    }
+
+    /// Add a job that is due every `interval_secs`, `offset_secs` after each boundary.
+    ///
+    /// Boundaries are counted from midnight, so an interval of 300 with an offset of 25 is due at 12:00:25, 12:05:25 and so on.
+    /// The job is dropped if the scheduler is full.
+    pub fn add(&mut self, name: &'static str, interval_secs: u32, offset_secs: u32, task: T) {
+        let job = Job {
+            name,
+            interval_secs: interval_secs.max(1),
+            offset_secs,
+            task,
+            next: None,
+        };
+
+        if self.jobs.push(job).is_err() {
+            info!("Scheduler full, dropping job {}", name);
+        }
+    }
+
+    /// Remove every job with the name passed, such as when its interval has changed.
+    pub fn remove(&mut self, name: &str) {
+        self.jobs.retain(|job| job.name != name);
+    }
+
+    /// Get the tasks of all the jobs that are due at `now`, in the order they were added.
+    ///
+    /// Each job is then set to be due at its next boundary. A job is never due more than once a check, even if several intervals were missed.
+    pub fn due(&mut self, now: NaiveDateTime) -> Vec<T, N> {
+        let mut due = Vec::new();
+
+        for job in self.jobs.iter_mut() {
+            let next = match job.next {
+                // if the time has gone backwards, such as after setting the clock, start again from now
+                Some(next) if next - now > chrono::Duration::seconds(job.interval_secs as i64) => {
+                    next_due(now, job.interval_secs, job.offset_secs)
+                }
+                Some(next) => next,
+                None => next_due(now, job.interval_secs, job.offset_secs),
+            };
+
+            if now >= next {
+                debug!("Scheduled job due: {}", job.name);
+
+                // there is room for every job, as there are no more tasks than jobs
+                _ = due.push(job.task);
+                job.next = Some(next_due(now, job.interval_secs, job.offset_secs));
+            } else {
+                job.next = Some(next);
+            }
+        }
+
+        due
+    }
+}
+
+/// Get when a job is next due after `datetime`.
+fn next_due(datetime: NaiveDateTime, interval_secs: u32, offset_secs: u32) -> NaiveDateTime {
+    let interval = interval_secs as i64;
+    let secs_into_day = datetime.num_seconds_from_midnight() as i64 - offset_secs as i64;
+    let boundary = secs_into_day - secs_into_day.rem_euclid(interval);
+
+    let start_of_day = datetime.date().and_hms_opt(0, 0, 0).unwrap();
+    let due = start_of_day + chrono::Duration::seconds(boundary + offset_secs as i64);
+    if due > datetime {
+        due
+    } else {
+        due + chrono::Duration::seconds(interval)
+    }
+}
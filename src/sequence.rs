@@ -0,0 +1,383 @@
+use core::{cell::RefCell, fmt::Write};
+
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, mutex::Mutex, pubsub::PubSubChannel};
+use embassy_time::{Duration, Instant, Timer};
+use heapless::String;
+
+use crate::{
+    app::{self, App, StopAppTasks},
+    buttons::ButtonPress,
+    config::{self, TimerSequence},
+    display::display_matrix::{IconAnimation, TimeColon, DISPLAY_MATRIX},
+    speaker::{self, SoundType},
+    timer_warning,
+};
+
+/// Channel for firing events of when tasks should be stopped.
+static STOP_APP_CHANNEL: PubSubChannel<ThreadModeRawMutex, StopAppTasks, 1, 1, 1> =
+    PubSubChannel::new();
+
+/// How many seconds a short press changes a step by.
+const STEP_SECS: u16 = 10;
+
+/// How many seconds a double press changes a step by.
+const STEP_MINUTE_SECS: u16 = 60;
+
+/// Depict the current running state of the sequence timer.
+#[derive(Clone, Copy)]
+enum RunningState {
+    /// When the sequence app is first created or after reset. This should allow modification to the sequence.
+    NotStarted,
+
+    /// When the sequence is running. This should *not* allow modification to the sequence.
+    Running,
+
+    /// When the sequence has been paused. This should *not* allow modification to the sequence, reset instead.
+    Paused,
+
+    /// When the last step has finished.
+    Finished,
+}
+
+/// Manage active state of the sequence app.
+struct SequenceState {
+    /// The current running state.
+    running: RunningState,
+
+    /// The sequence being set up or run.
+    sequence: TimerSequence,
+
+    /// The step being changed while not started, or the step running, from 0.
+    step: usize,
+
+    /// The seconds left in the running step.
+    remaining_secs: u16,
+}
+
+/// Static reference to the sequence state so it can be accessed by static tasks.
+static SEQUENCE_STATE: Mutex<ThreadModeRawMutex, RefCell<SequenceState>> =
+    Mutex::new(RefCell::new(SequenceState {
+        running: RunningState::NotStarted,
+        sequence: TimerSequence::DEFAULT,
+        step: 0,
+        remaining_secs: 0,
+    }));
+
+/// Sequence app.
+/// Counts down a sequence of up to 5 steps one after another, such as the steps of a recipe.
+pub struct SequenceApp {}
+
+impl SequenceApp {
+    /// Create a new sequence app.
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl App for SequenceApp {
+    fn get_name(&self) -> &str {
+        "Recipe"
+    }
+
+    async fn start(&mut self, spawner: Spawner) {
+        critical_section::with(|cs| {
+            DISPLAY_MATRIX.clear_all(cs, true);
+        });
+
+        match get_running_state().await {
+            RunningState::NotStarted | RunningState::Finished => {
+                let sequence = config::get_timer_sequence().await;
+
+                let guard = SEQUENCE_STATE.lock().await;
+                let mut state = guard.borrow_mut();
+                state.running = RunningState::NotStarted;
+                state.sequence = sequence;
+                state.step = 0;
+                drop(state);
+                drop(guard);
+
+                show_step().await;
+            }
+            // stopping the app pauses the sequence, so carry on from where it was
+            RunningState::Running | RunningState::Paused => {
+                show_remaining().await;
+                app::spawn_app_task(spawner, "run", run).await;
+            }
+        }
+    }
+
+    async fn stop(&mut self) {
+        if let RunningState::Running = get_running_state().await {
+            set_running(RunningState::Paused).await;
+        }
+
+        STOP_APP_CHANNEL
+            .immediate_publisher()
+            .publish_immediate(StopAppTasks);
+    }
+
+    async fn button_one_short_press(&mut self, spawner: Spawner) {
+        match get_running_state().await {
+            RunningState::NotStarted => {
+                // keep the sequence for next time, without writing to flash when it has not changed
+                let sequence = SEQUENCE_STATE.lock().await.borrow().sequence;
+                if config::get_timer_sequence().await != sequence {
+                    config::set_timer_sequence(sequence).await;
+                }
+
+                start_step(0).await;
+                set_running(RunningState::Running).await;
+                app::spawn_app_task(spawner, "run", run).await;
+            }
+            RunningState::Running => set_running(RunningState::Paused).await,
+            RunningState::Paused => set_running(RunningState::Running).await,
+            RunningState::Finished => {
+                // stop the finished alarm if it is still sounding
+                speaker::cancel();
+                reset().await;
+            }
+        }
+    }
+
+    async fn button_two_press(&mut self, press: ButtonPress, _: Spawner) {
+        if let RunningState::NotStarted = get_running_state().await {
+            match press {
+                ButtonPress::Short => change_step(STEP_SECS, true).await,
+                ButtonPress::Double => change_step(STEP_MINUTE_SECS, true).await,
+                ButtonPress::Long => {
+                    let guard = SEQUENCE_STATE.lock().await;
+                    let mut state = guard.borrow_mut();
+                    state.step = (state.step + 1) % TimerSequence::MAX_STEPS;
+                    drop(state);
+                    drop(guard);
+
+                    show_step().await;
+                }
+                ButtonPress::Triple => {}
+                ButtonPress::Combo(_) => {}
+            }
+        }
+    }
+
+    async fn button_three_press(&mut self, press: ButtonPress, _: Spawner) {
+        match (get_running_state().await, press) {
+            (RunningState::NotStarted, ButtonPress::Short) => change_step(STEP_SECS, false).await,
+            (RunningState::NotStarted, ButtonPress::Double) => {
+                change_step(STEP_MINUTE_SECS, false).await
+            }
+            (RunningState::NotStarted, ButtonPress::Long) => {
+                let guard = SEQUENCE_STATE.lock().await;
+                let mut state = guard.borrow_mut();
+                state.sequence = TimerSequence::DEFAULT;
+                state.step = 0;
+                drop(state);
+                drop(guard);
+
+                show_step().await;
+            }
+            // end the sequence early
+            (RunningState::Paused, ButtonPress::Long) => reset().await,
+            _ => {}
+        }
+    }
+}
+
+/// Get the running state value from the static sequence state.
+async fn get_running_state() -> RunningState {
+    SEQUENCE_STATE.lock().await.borrow().running
+}
+
+/// Set the running state on the static sequence state.
+/// Will show/hide the CountDown icon on the display depending on the state passed.
+async fn set_running(running: RunningState) {
+    SEQUENCE_STATE.lock().await.borrow_mut().running = running;
+
+    // stop any final countdown blinking
+    DISPLAY_MATRIX.animate_icon("CountDown", IconAnimation::Steady);
+
+    if let RunningState::Running = running {
+        DISPLAY_MATRIX.show_icon("CountDown");
+    } else {
+        DISPLAY_MATRIX.hide_icon("CountDown");
+    }
+
+    if let RunningState::Finished = running {
+        speaker::alarm(SoundType::RepeatLongBeep(3));
+        DISPLAY_MATRIX.queue_text("Done", 0, true, false).await;
+    }
+}
+
+/// Go back to setting up the sequence.
+async fn reset() {
+    set_running(RunningState::NotStarted).await;
+    SEQUENCE_STATE.lock().await.borrow_mut().step = 0;
+    show_step().await;
+}
+
+/// Start a step, with a beep for each step number so each step can be told apart by ear.
+async fn start_step(step: usize) {
+    let guard = SEQUENCE_STATE.lock().await;
+    let mut state = guard.borrow_mut();
+
+    state.step = step;
+    state.remaining_secs = state.sequence.steps_secs[step];
+
+    drop(state);
+    drop(guard);
+
+    speaker::alarm(SoundType::RepeatShortBeep(step as u8 + 1));
+
+    let mut text = String::<8>::new();
+    _ = write!(text, "S{}", step + 1);
+    DISPLAY_MATRIX
+        .queue_text(text.as_str(), 0, true, false)
+        .await;
+}
+
+/// Count down a second of the running step, moving on to the next step or finishing when it ends.
+///
+/// The final seconds of the last step are warned about, as the sequence is nearly done.
+///
+/// Returns true when the sequence has finished.
+async fn tick() -> bool {
+    let guard = SEQUENCE_STATE.lock().await;
+    let mut state = guard.borrow_mut();
+
+    state.remaining_secs = state.remaining_secs.saturating_sub(1);
+    if state.remaining_secs > 0 {
+        let remaining_secs = state.remaining_secs as u32;
+        let is_last_step = state.step + 1 >= state.sequence.step_count();
+        drop(state);
+        drop(guard);
+
+        show_remaining().await;
+        if is_last_step {
+            timer_warning::final_countdown(remaining_secs).await;
+        }
+        return false;
+    }
+
+    let next_step = state.step + 1;
+    let step_count = state.sequence.step_count();
+
+    drop(state);
+    drop(guard);
+
+    if next_step >= step_count {
+        set_running(RunningState::Finished).await;
+        return true;
+    }
+
+    start_step(next_step).await;
+    false
+}
+
+/// Increase or decrease the step being changed, wrapping around at either end.
+///
+/// The first step can not be turned off, as the sequence would have no steps.
+async fn change_step(by_secs: u16, increase: bool) {
+    let guard = SEQUENCE_STATE.lock().await;
+    let mut state = guard.borrow_mut();
+    let step = state.step;
+    let min_secs = if step == 0 { STEP_SECS } else { 0 };
+    let secs = &mut state.sequence.steps_secs[step];
+
+    *secs = match increase {
+        true if *secs >= TimerSequence::MAX_SECS => min_secs,
+        true => (*secs + by_secs).min(TimerSequence::MAX_SECS),
+        false if *secs <= min_secs => TimerSequence::MAX_SECS,
+        false => secs.saturating_sub(by_secs).max(min_secs),
+    };
+
+    drop(state);
+    drop(guard);
+
+    show_value(true).await;
+}
+
+/// Show the number of the step being changed, followed by its time.
+async fn show_step() {
+    let step = SEQUENCE_STATE.lock().await.borrow().step;
+
+    let mut text = String::<8>::new();
+    _ = write!(text, "S{}", step + 1);
+    DISPLAY_MATRIX
+        .queue_text(text.as_str(), 1000, true, false)
+        .await;
+    show_value(false).await;
+}
+
+/// Show the time of the step being changed, or "Off" if the sequence ends before it.
+async fn show_value(show_now: bool) {
+    let guard = SEQUENCE_STATE.lock().await;
+    let state = guard.borrow();
+    let secs = state.sequence.steps_secs[state.step];
+    drop(state);
+    drop(guard);
+
+    if secs == 0 {
+        DISPLAY_MATRIX.queue_text("Off", 0, show_now, false).await;
+    } else {
+        show_secs(secs, show_now).await;
+    }
+}
+
+/// Show the seconds left in the running step.
+async fn show_remaining() {
+    let remaining_secs = SEQUENCE_STATE.lock().await.borrow().remaining_secs;
+    show_secs(remaining_secs, true).await;
+}
+
+/// Show a number of seconds as minutes and seconds.
+async fn show_secs(secs: u16, show_now: bool) {
+    let secs = secs as u32;
+    DISPLAY_MATRIX
+        .queue_time(secs / 60, secs % 60, TimeColon::Full, 0, show_now, false)
+        .await;
+}
+
+/// The sequence loop, counting down each step every second.
+///
+/// Will continue to run as long as the running state is running or paused.
+#[embassy_executor::task]
+async fn run() {
+    let _running = app::RunningAppTask::new();
+    let mut stop_task_sub = STOP_APP_CHANNEL.subscriber().unwrap();
+
+    let mut next_tick = Instant::now() + Duration::from_secs(1);
+
+    loop {
+        match get_running_state().await {
+            RunningState::Running => {
+                let res = select(stop_task_sub.next_message(), Timer::at(next_tick)).await;
+                if let Either::First(_) = res {
+                    break;
+                }
+
+                // the state can change while waiting for the second to pass
+                if let RunningState::Running = get_running_state().await {
+                    next_tick += Duration::from_secs(1);
+                    if tick().await {
+                        break;
+                    }
+                }
+            }
+            RunningState::Paused => {
+                let res = select(
+                    stop_task_sub.next_message(),
+                    Timer::after(Duration::from_millis(100)),
+                )
+                .await;
+                if let Either::First(_) = res {
+                    break;
+                }
+
+                // start a full second from when it is resumed
+                next_tick = Instant::now() + Duration::from_secs(1);
+            }
+            RunningState::NotStarted | RunningState::Finished => break,
+        }
+    }
+}
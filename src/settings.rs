@@ -1,23 +1,108 @@
+use core::fmt::Write;
+
+use chrono::{Datelike, Timelike, Weekday};
 use embassy_executor::Spawner;
 use embassy_futures::select::{select3, Either3::*};
 use embassy_sync::{
     blocking_mutex::raw::ThreadModeRawMutex, pubsub::PubSubChannel, signal::Signal,
 };
 use embassy_time::{Duration, Timer};
+use heapless::String;
 
 use crate::{
-    app::{App, ShowAppSwitcher, StopAppTasks, SHOW_APP_SWITCHER},
-    buttons::ButtonPress,
-    display::display_matrix::{TimeColon, DISPLAY_MATRIX},
+    app::{self, App, ShowAppSwitcher, StopAppTasks, SHOW_APP_SWITCHER},
+    buttons::{self, ButtonPress},
+    config::{self, Days, TimePreference},
+    display::{
+        backlight,
+        display_matrix::{TimeColon, DISPLAY_MATRIX},
+    },
+    rtc,
 };
 
 use self::configurations::{
-    AutoScrollTempConfiguration, Configuration, DayConfiguration, HourConfiguration,
-    HourlyRingConfiguration, MinuteConfiguration, MonthConfiguration, TimeColonConfiguration,
-    YearConfiguration,
+    AgingOffsetConfiguration, ClockActionsConfiguration, ClockFaceConfiguration, Configuration,
+    DayConfiguration, DisplayRotationConfiguration, DoNotDisturbConfiguration,
+    FinalCountdownConfiguration, FirmwareUpdateConfiguration, HourConfiguration,
+    HourlyRingConfiguration, KeypadLockConfiguration, LightCalibrationConfiguration,
+    LightReadingConfiguration, LightSensitivityConfiguration, LightsOutConfiguration,
+    MinuteConfiguration, MonthConfiguration, NightModeConfiguration, NightMuteConfiguration,
+    PauseNudgeConfiguration, QuarterChimeConfiguration, ResetConfiguration,
+    ScreensaverConfiguration, StandbyConfiguration, StillRunningConfiguration, SyncConfiguration,
+    SystemInfoConfiguration, TemperaturePrecisionConfiguration, TemperaturePreferenceConfiguration,
+    TemperatureSourceConfiguration, TimeColonConfiguration, TimePreferenceConfiguration,
+    TimerPresetsConfiguration, YearConfiguration,
 };
 
+/// The categories shown in the settings menu.
+#[derive(Clone, Copy, PartialEq)]
+enum SettingsCategory {
+    /// Time and date settings.
+    TimeDate,
+
+    /// Display settings.
+    Display,
+
+    /// Sound settings.
+    Sound,
+
+    /// System settings.
+    System,
+
+    /// Leave the settings app.
+    Exit,
+}
+
+impl SettingsCategory {
+    /// The name to show in the settings menu.
+    fn name(&self) -> &'static str {
+        match self {
+            SettingsCategory::TimeDate => "Time",
+            SettingsCategory::Display => "Disp",
+            SettingsCategory::Sound => "Sound",
+            SettingsCategory::System => "Sys",
+            SettingsCategory::Exit => "Done",
+        }
+    }
+
+    /// Get the category after this one, wrapping back to the first.
+    fn next(&self) -> Self {
+        match self {
+            SettingsCategory::TimeDate => SettingsCategory::Display,
+            SettingsCategory::Display => SettingsCategory::Sound,
+            SettingsCategory::Sound => SettingsCategory::System,
+            SettingsCategory::System => SettingsCategory::Exit,
+            SettingsCategory::Exit => SettingsCategory::TimeDate,
+        }
+    }
+
+    /// Get the category before this one, wrapping back to the last.
+    fn previous(&self) -> Self {
+        match self {
+            SettingsCategory::TimeDate => SettingsCategory::Exit,
+            SettingsCategory::Display => SettingsCategory::TimeDate,
+            SettingsCategory::Sound => SettingsCategory::Display,
+            SettingsCategory::System => SettingsCategory::Sound,
+            SettingsCategory::Exit => SettingsCategory::System,
+        }
+    }
+
+    /// The first configuration in the category.
+    ///
+    /// Returns [None](Option::None) if the category has no configurations.
+    fn first_config(&self) -> Option<SettingsConfig> {
+        match self {
+            SettingsCategory::TimeDate => Some(SettingsConfig::Hour),
+            SettingsCategory::Display => Some(SettingsConfig::TimeColon),
+            SettingsCategory::Sound => Some(SettingsConfig::HourlyRing),
+            SettingsCategory::System => Some(SettingsConfig::ClockActions),
+            SettingsCategory::Exit => None,
+        }
+    }
+}
+
 /// Each of the possible configurations to run through in the settings app.
+#[derive(Clone, Copy, PartialEq)]
 enum SettingsConfig {
     /// Modify the hour in the RTC.
     Hour,
@@ -25,6 +110,9 @@ enum SettingsConfig {
     /// Modify the minute in the RTC.
     Minute,
 
+    /// Sync the seconds in the RTC to a reference time.
+    Sync,
+
     /// Modify the year in the RTC.
     Year,
 
@@ -37,11 +125,177 @@ enum SettingsConfig {
     /// Modify the hourly ring setting.
     HourlyRing,
 
+    /// Modify the quarter chime setting.
+    QuarterChime,
+
+    /// Modify whether a paused pomodoro nudges to be resumed.
+    PauseNudge,
+
+    /// Modify the do not disturb setting.
+    DoNotDisturb,
+
+    /// Modify the night mute setting.
+    NightMute,
+
+    /// Modify the still running reminder hour.
+    StillRunning,
+
+    /// Modify the final countdown warning setting.
+    FinalCountdown,
+
+    /// Modify the pomodoro pre-finish warning minutes.
+    PreFinish,
+
     /// Modify the time colon setting.
     TimeColon,
 
-    /// Modify the auto scrolling of temperature setting.
-    AutoScrollTemp,
+    /// Modify the time preference.
+    TimePreference,
+
+    /// Modify the temperature preference.
+    TemperaturePreference,
+
+    /// Modify the temperature precision.
+    TemperaturePrecision,
+
+    /// Modify the temperature source.
+    TemperatureSource,
+
+    /// Modify the auto display rotation setting.
+    DisplayRotation,
+
+    /// Modify the night mode setting.
+    NightMode,
+
+    /// Modify the night mode standby setting.
+    Standby,
+
+    /// Modify how readily autolight dims the display.
+    LightSensitivity,
+
+    /// Calibrate the light sensor for autolight.
+    LightCalibration,
+
+    /// Modify how long the room is dark for before the display turns off.
+    LightsOut,
+
+    /// Modify how long the clock is left alone for before the screensaver starts.
+    Screensaver,
+
+    /// Modify how the clock app shows the time.
+    ClockFace,
+
+    /// Modify the clock app button actions.
+    ClockActions,
+
+    /// Modify the pomodoro quick start presets.
+    TimerPresets,
+
+    /// Modify the keypad lock.
+    KeypadLock,
+
+    /// Show the system info.
+    SystemInfo,
+
+    /// Show the live light sensor reading.
+    LightReading,
+
+    /// Reboot into the USB bootloader to update the firmware.
+    FirmwareUpdate,
+
+    /// Factory reset the clock.
+    Reset,
+
+    /// Modify the RTC aging offset. Hidden from the system category, opened with a double press of the bottom button on the menu.
+    AgingOffset,
+}
+
+impl SettingsConfig {
+    /// Get the configuration after this one in the same category.
+    ///
+    /// Returns [None](Option::None) if this is the last configuration in the category.
+    fn next(&self) -> Option<Self> {
+        match self {
+            SettingsConfig::Hour => Some(SettingsConfig::Minute),
+            SettingsConfig::Minute => Some(SettingsConfig::Sync),
+            SettingsConfig::Sync => Some(SettingsConfig::Year),
+            SettingsConfig::Year => Some(SettingsConfig::Month),
+            SettingsConfig::Month => Some(SettingsConfig::Day),
+            SettingsConfig::Day => None,
+            SettingsConfig::TimeColon => Some(SettingsConfig::TimePreference),
+            SettingsConfig::TimePreference => Some(SettingsConfig::TemperaturePreference),
+            SettingsConfig::TemperaturePreference => Some(SettingsConfig::TemperaturePrecision),
+            SettingsConfig::TemperaturePrecision => Some(SettingsConfig::TemperatureSource),
+            SettingsConfig::TemperatureSource => Some(SettingsConfig::DisplayRotation),
+            SettingsConfig::DisplayRotation => Some(SettingsConfig::NightMode),
+            SettingsConfig::NightMode => Some(SettingsConfig::Standby),
+            SettingsConfig::Standby => Some(SettingsConfig::LightSensitivity),
+            SettingsConfig::LightSensitivity => Some(SettingsConfig::LightCalibration),
+            SettingsConfig::LightCalibration => Some(SettingsConfig::LightsOut),
+            SettingsConfig::LightsOut => Some(SettingsConfig::Screensaver),
+            SettingsConfig::Screensaver => Some(SettingsConfig::ClockFace),
+            SettingsConfig::ClockFace => None,
+            SettingsConfig::HourlyRing => Some(SettingsConfig::QuarterChime),
+            SettingsConfig::QuarterChime => Some(SettingsConfig::PauseNudge),
+            SettingsConfig::PauseNudge => Some(SettingsConfig::DoNotDisturb),
+            SettingsConfig::DoNotDisturb => Some(SettingsConfig::NightMute),
+            SettingsConfig::NightMute => Some(SettingsConfig::StillRunning),
+            SettingsConfig::StillRunning => Some(SettingsConfig::FinalCountdown),
+            SettingsConfig::FinalCountdown => Some(SettingsConfig::PreFinish),
+            SettingsConfig::PreFinish => None,
+            SettingsConfig::ClockActions => Some(SettingsConfig::TimerPresets),
+            SettingsConfig::TimerPresets => Some(SettingsConfig::KeypadLock),
+            SettingsConfig::KeypadLock => Some(SettingsConfig::SystemInfo),
+            SettingsConfig::SystemInfo => Some(SettingsConfig::LightReading),
+            SettingsConfig::LightReading => Some(SettingsConfig::FirmwareUpdate),
+            SettingsConfig::FirmwareUpdate => Some(SettingsConfig::Reset),
+            SettingsConfig::Reset => None,
+            SettingsConfig::AgingOffset => None,
+        }
+    }
+
+    /// Get the configuration before this one in the same category.
+    ///
+    /// Returns [None](Option::None) if this is the first configuration in the category.
+    fn previous(&self) -> Option<Self> {
+        match self {
+            SettingsConfig::Hour => None,
+            SettingsConfig::Minute => Some(SettingsConfig::Hour),
+            SettingsConfig::Sync => Some(SettingsConfig::Minute),
+            SettingsConfig::Year => Some(SettingsConfig::Sync),
+            SettingsConfig::Month => Some(SettingsConfig::Year),
+            SettingsConfig::Day => Some(SettingsConfig::Month),
+            SettingsConfig::TimeColon => None,
+            SettingsConfig::TimePreference => Some(SettingsConfig::TimeColon),
+            SettingsConfig::TemperaturePreference => Some(SettingsConfig::TimePreference),
+            SettingsConfig::TemperaturePrecision => Some(SettingsConfig::TemperaturePreference),
+            SettingsConfig::TemperatureSource => Some(SettingsConfig::TemperaturePrecision),
+            SettingsConfig::DisplayRotation => Some(SettingsConfig::TemperatureSource),
+            SettingsConfig::NightMode => Some(SettingsConfig::DisplayRotation),
+            SettingsConfig::Standby => Some(SettingsConfig::NightMode),
+            SettingsConfig::LightSensitivity => Some(SettingsConfig::Standby),
+            SettingsConfig::LightCalibration => Some(SettingsConfig::LightSensitivity),
+            SettingsConfig::LightsOut => Some(SettingsConfig::LightCalibration),
+            SettingsConfig::Screensaver => Some(SettingsConfig::LightsOut),
+            SettingsConfig::ClockFace => Some(SettingsConfig::Screensaver),
+            SettingsConfig::HourlyRing => None,
+            SettingsConfig::QuarterChime => Some(SettingsConfig::HourlyRing),
+            SettingsConfig::PauseNudge => Some(SettingsConfig::QuarterChime),
+            SettingsConfig::DoNotDisturb => Some(SettingsConfig::PauseNudge),
+            SettingsConfig::NightMute => Some(SettingsConfig::DoNotDisturb),
+            SettingsConfig::StillRunning => Some(SettingsConfig::NightMute),
+            SettingsConfig::FinalCountdown => Some(SettingsConfig::StillRunning),
+            SettingsConfig::PreFinish => Some(SettingsConfig::FinalCountdown),
+            SettingsConfig::ClockActions => None,
+            SettingsConfig::TimerPresets => Some(SettingsConfig::ClockActions),
+            SettingsConfig::KeypadLock => Some(SettingsConfig::TimerPresets),
+            SettingsConfig::SystemInfo => Some(SettingsConfig::KeypadLock),
+            SettingsConfig::LightReading => Some(SettingsConfig::SystemInfo),
+            SettingsConfig::FirmwareUpdate => Some(SettingsConfig::LightReading),
+            SettingsConfig::Reset => Some(SettingsConfig::FirmwareUpdate),
+            SettingsConfig::AgingOffset => None,
+        }
+    }
 }
 
 /// Each of the possible configurations, but with data so the blink task can be displayed accurately.
@@ -55,6 +309,12 @@ enum BlinkTask {
     /// Blink the minute section of the display. (hour, minute)
     Minute(u32, u32),
 
+    /// Show the running minutes and seconds, without blinking.
+    Seconds,
+
+    /// Show the live light sensor reading, without blinking.
+    LightReading,
+
     /// Blink the full year in the display.
     Year(i32),
 
@@ -63,6 +323,9 @@ enum BlinkTask {
 
     /// Blink the day section of the display. (month, day)
     Day(u32, u32),
+
+    /// Light the icon of each selected day, blinking the day being changed. (selected days, day being changed)
+    Days(Days, Weekday),
 }
 
 /// Named struct for next settings start signal.
@@ -87,6 +350,9 @@ pub struct SettingsApp {
     /// The minute configuration mini app.
     minute_config: configurations::MinuteConfiguration,
 
+    /// The seconds sync mini app.
+    sync_config: configurations::SyncConfiguration,
+
     /// The year configuration mini app.
     year_config: configurations::YearConfiguration,
 
@@ -99,14 +365,95 @@ pub struct SettingsApp {
     /// The hourly ring configuration mini app.
     hourly_ring_config: configurations::HourlyRingConfiguration,
 
+    /// The quarter chime configuration mini app.
+    quarter_chime_config: configurations::QuarterChimeConfiguration,
+
+    /// The pause nudge configuration mini app.
+    pause_nudge_config: configurations::PauseNudgeConfiguration,
+
+    /// The do not disturb configuration mini app.
+    do_not_disturb_config: configurations::DoNotDisturbConfiguration,
+
+    /// The night mute configuration mini app.
+    night_mute_config: configurations::NightMuteConfiguration,
+
+    /// The still running reminder configuration mini app.
+    still_running_config: configurations::StillRunningConfiguration,
+
+    /// The final countdown warning setting configuration mini app.
+    final_countdown_config: configurations::FinalCountdownConfiguration,
+
+    /// The pomodoro pre-finish warning minutes configuration mini app.
+    pre_finish_config: configurations::PreFinishConfiguration,
+
     /// The time colon configuration mini app.
     time_colon_config: configurations::TimeColonConfiguration,
 
-    /// The auto scroll temp configuration mini app.
-    auto_scroll_temp_config: configurations::AutoScrollTempConfiguration,
+    /// The time preference configuration mini app.
+    time_preference_config: configurations::TimePreferenceConfiguration,
+
+    /// The temperature preference configuration mini app.
+    temperature_preference_config: configurations::TemperaturePreferenceConfiguration,
+
+    /// The temperature precision configuration mini app.
+    temperature_precision_config: configurations::TemperaturePrecisionConfiguration,
+
+    /// The temperature source configuration mini app.
+    temperature_source_config: configurations::TemperatureSourceConfiguration,
+
+    /// The auto display rotation configuration mini app.
+    display_rotation_config: configurations::DisplayRotationConfiguration,
+
+    /// The night mode configuration mini app.
+    night_mode_config: configurations::NightModeConfiguration,
+
+    /// The standby configuration mini app.
+    standby_config: configurations::StandbyConfiguration,
+
+    /// The light sensitivity configuration mini app.
+    light_sensitivity_config: configurations::LightSensitivityConfiguration,
+
+    /// The light calibration configuration mini app.
+    light_calibration_config: configurations::LightCalibrationConfiguration,
+
+    /// The lights out configuration mini app.
+    lights_out_config: configurations::LightsOutConfiguration,
+
+    /// The screensaver wait configuration mini app.
+    screensaver_config: configurations::ScreensaverConfiguration,
+
+    /// The clock face configuration mini app.
+    clock_face_config: configurations::ClockFaceConfiguration,
+
+    /// The clock actions configuration mini app.
+    clock_actions_config: configurations::ClockActionsConfiguration,
 
-    /// The current active mini app being configured.
-    active_config: SettingsConfig,
+    /// The timer presets configuration mini app.
+    timer_presets_config: configurations::TimerPresetsConfiguration,
+
+    /// The keypad lock configuration mini app.
+    keypad_lock_config: configurations::KeypadLockConfiguration,
+
+    /// The system info mini app.
+    system_info_config: configurations::SystemInfoConfiguration,
+
+    /// The light reading configuration mini app.
+    light_reading_config: configurations::LightReadingConfiguration,
+
+    /// The firmware update configuration mini app.
+    firmware_update_config: configurations::FirmwareUpdateConfiguration,
+
+    /// The factory reset configuration mini app.
+    reset_config: configurations::ResetConfiguration,
+
+    /// The RTC aging offset configuration mini app.
+    aging_offset_config: configurations::AgingOffsetConfiguration,
+
+    /// The category selected in the settings menu.
+    category: SettingsCategory,
+
+    /// The current active mini app being configured, or [None](Option::None) when in the settings menu.
+    active_config: Option<SettingsConfig>,
 }
 
 impl SettingsApp {
@@ -115,13 +462,41 @@ impl SettingsApp {
         Self {
             hour_config: HourConfiguration::new(),
             minute_config: MinuteConfiguration::new(),
+            sync_config: SyncConfiguration::new(),
             year_config: YearConfiguration::new(),
             month_config: MonthConfiguration::new(),
             day_config: DayConfiguration::new(),
             hourly_ring_config: HourlyRingConfiguration::new(),
+            quarter_chime_config: QuarterChimeConfiguration::new(),
+            pause_nudge_config: PauseNudgeConfiguration::new(),
+            do_not_disturb_config: DoNotDisturbConfiguration::new(),
+            night_mute_config: NightMuteConfiguration::new(),
+            still_running_config: StillRunningConfiguration::new(),
+            final_countdown_config: FinalCountdownConfiguration::new(),
+            pre_finish_config: PreFinishConfiguration::new(),
             time_colon_config: TimeColonConfiguration::new(),
-            auto_scroll_temp_config: AutoScrollTempConfiguration::new(),
-            active_config: SettingsConfig::Hour,
+            time_preference_config: TimePreferenceConfiguration::new(),
+            temperature_preference_config: TemperaturePreferenceConfiguration::new(),
+            temperature_precision_config: TemperaturePrecisionConfiguration::new(),
+            temperature_source_config: TemperatureSourceConfiguration::new(),
+            display_rotation_config: DisplayRotationConfiguration::new(),
+            night_mode_config: NightModeConfiguration::new(),
+            standby_config: StandbyConfiguration::new(),
+            light_sensitivity_config: LightSensitivityConfiguration::new(),
+            light_calibration_config: LightCalibrationConfiguration::new(),
+            lights_out_config: LightsOutConfiguration::new(),
+            screensaver_config: ScreensaverConfiguration::new(),
+            clock_face_config: ClockFaceConfiguration::new(),
+            clock_actions_config: ClockActionsConfiguration::new(),
+            timer_presets_config: TimerPresetsConfiguration::new(),
+            keypad_lock_config: KeypadLockConfiguration::new(),
+            system_info_config: SystemInfoConfiguration::new(),
+            light_reading_config: LightReadingConfiguration::new(),
+            firmware_update_config: FirmwareUpdateConfiguration::new(),
+            reset_config: ResetConfiguration::new(),
+            aging_offset_config: AgingOffsetConfiguration::new(),
+            category: SettingsCategory::TimeDate,
+            active_config: None,
         }
     }
 }
@@ -136,13 +511,17 @@ impl App for SettingsApp {
             DISPLAY_MATRIX.clear_all(cs, true);
         });
 
-        self.active_config = SettingsConfig::Hour;
-        self.hour_config.start().await;
+        self.category = SettingsCategory::TimeDate;
+        self.show_menu().await;
 
-        spawner.spawn(blink()).unwrap();
+        buttons::set_auto_repeat(true);
+
+        app::spawn_app_task(spawner, "blink", blink).await;
     }
 
     async fn stop(&mut self) {
+        buttons::set_auto_repeat(false);
+
         STOP_APP_CHANNEL
             .immediate_publisher()
             .publish_immediate(StopAppTasks);
@@ -150,44 +529,20 @@ impl App for SettingsApp {
 
     async fn button_one_short_press(&mut self, _: Spawner) {
         match self.active_config {
-            SettingsConfig::Hour => {
-                self.hour_config.save().await;
-                self.active_config = SettingsConfig::Minute;
-                self.minute_config.start().await;
-            }
-            SettingsConfig::Minute => {
-                self.minute_config.save().await;
-                self.active_config = SettingsConfig::Year;
-                self.year_config.start().await;
-            }
-            SettingsConfig::Year => {
-                self.year_config.save().await;
-                self.active_config = SettingsConfig::Month;
-                self.month_config.start().await;
-            }
-            SettingsConfig::Month => {
-                self.month_config.save().await;
-                self.active_config = SettingsConfig::Day;
-                self.day_config.start().await;
-            }
-            SettingsConfig::Day => {
-                self.day_config.save().await;
-                self.active_config = SettingsConfig::HourlyRing;
-                self.hourly_ring_config.start().await;
-            }
-            SettingsConfig::HourlyRing => {
-                self.hourly_ring_config.save().await;
-                self.active_config = SettingsConfig::TimeColon;
-                self.time_colon_config.start().await;
-            }
-            SettingsConfig::TimeColon => {
-                self.time_colon_config.save().await;
-                self.active_config = SettingsConfig::AutoScrollTemp;
-                self.auto_scroll_temp_config.start().await;
-            }
-            SettingsConfig::AutoScrollTemp => {
-                self.auto_scroll_temp_config.save().await;
-                self.end().await;
+            None => match self.category.first_config() {
+                Some(config) => self.start_config(config).await,
+                None => {
+                    self.end().await;
+                    return;
+                }
+            },
+            Some(config) => {
+                self.save_config(config).await;
+
+                match config.next() {
+                    Some(next) => self.start_config(next).await,
+                    None => self.show_menu().await,
+                }
             }
         }
 
@@ -195,37 +550,307 @@ impl App for SettingsApp {
     }
 
     async fn button_two_press(&mut self, press: ButtonPress, _: Spawner) {
-        match self.active_config {
+        let config = match self.active_config {
+            Some(config) => config,
+            None => {
+                self.category = self.category.next();
+                self.show_menu().await;
+                return;
+            }
+        };
+
+        match config {
             SettingsConfig::Hour => self.hour_config.button_two_press(press).await,
             SettingsConfig::Minute => self.minute_config.button_two_press(press).await,
+            SettingsConfig::Sync => self.sync_config.button_two_press(press).await,
             SettingsConfig::Year => self.year_config.button_two_press(press).await,
             SettingsConfig::Month => self.month_config.button_two_press(press).await,
             SettingsConfig::Day => self.day_config.button_two_press(press).await,
             SettingsConfig::HourlyRing => self.hourly_ring_config.button_two_press(press).await,
+            SettingsConfig::QuarterChime => self.quarter_chime_config.button_two_press(press).await,
+            SettingsConfig::PauseNudge => self.pause_nudge_config.button_two_press(press).await,
+            SettingsConfig::DoNotDisturb => {
+                self.do_not_disturb_config.button_two_press(press).await
+            }
+            SettingsConfig::NightMute => self.night_mute_config.button_two_press(press).await,
+            SettingsConfig::StillRunning => self.still_running_config.button_two_press(press).await,
+            SettingsConfig::FinalCountdown => {
+                self.final_countdown_config.button_two_press(press).await
+            }
+            SettingsConfig::PreFinish => self.pre_finish_config.button_two_press(press).await,
             SettingsConfig::TimeColon => self.time_colon_config.button_two_press(press).await,
-            SettingsConfig::AutoScrollTemp => {
-                self.auto_scroll_temp_config.button_two_press(press).await
+            SettingsConfig::TimePreference => {
+                self.time_preference_config.button_two_press(press).await
+            }
+            SettingsConfig::TemperaturePreference => {
+                self.temperature_preference_config
+                    .button_two_press(press)
+                    .await
+            }
+            SettingsConfig::TemperaturePrecision => {
+                self.temperature_precision_config
+                    .button_two_press(press)
+                    .await
             }
+            SettingsConfig::TemperatureSource => {
+                self.temperature_source_config.button_two_press(press).await
+            }
+            SettingsConfig::DisplayRotation => {
+                self.display_rotation_config.button_two_press(press).await
+            }
+            SettingsConfig::NightMode => self.night_mode_config.button_two_press(press).await,
+            SettingsConfig::Standby => self.standby_config.button_two_press(press).await,
+            SettingsConfig::LightSensitivity => {
+                self.light_sensitivity_config.button_two_press(press).await
+            }
+            SettingsConfig::LightCalibration => {
+                self.light_calibration_config.button_two_press(press).await
+            }
+            SettingsConfig::LightsOut => self.lights_out_config.button_two_press(press).await,
+            SettingsConfig::Screensaver => self.screensaver_config.button_two_press(press).await,
+            SettingsConfig::ClockFace => self.clock_face_config.button_two_press(press).await,
+            SettingsConfig::ClockActions => self.clock_actions_config.button_two_press(press).await,
+            SettingsConfig::TimerPresets => self.timer_presets_config.button_two_press(press).await,
+            SettingsConfig::KeypadLock => self.keypad_lock_config.button_two_press(press).await,
+            SettingsConfig::SystemInfo => self.system_info_config.button_two_press(press).await,
+            SettingsConfig::LightReading => self.light_reading_config.button_two_press(press).await,
+            SettingsConfig::FirmwareUpdate => {
+                self.firmware_update_config.button_two_press(press).await
+            }
+            SettingsConfig::Reset => self.reset_config.button_two_press(press).await,
+            SettingsConfig::AgingOffset => self.aging_offset_config.button_two_press(press).await,
         }
     }
 
     async fn button_three_press(&mut self, press: ButtonPress, _: Spawner) {
-        match self.active_config {
+        let config = match self.active_config {
+            Some(config) => config,
+            // the hidden aging offset is opened with a double press on the system category
+            None if self.category == SettingsCategory::System
+                && matches!(press, ButtonPress::Double) =>
+            {
+                self.start_config(SettingsConfig::AgingOffset).await;
+                return;
+            }
+            None => {
+                self.category = self.category.previous();
+                self.show_menu().await;
+                return;
+            }
+        };
+
+        match config {
             SettingsConfig::Hour => self.hour_config.button_three_press(press).await,
             SettingsConfig::Minute => self.minute_config.button_three_press(press).await,
+            SettingsConfig::Sync => self.sync_config.button_three_press(press).await,
             SettingsConfig::Year => self.year_config.button_three_press(press).await,
             SettingsConfig::Month => self.month_config.button_three_press(press).await,
             SettingsConfig::Day => self.day_config.button_three_press(press).await,
             SettingsConfig::HourlyRing => self.hourly_ring_config.button_two_press(press).await,
+            SettingsConfig::QuarterChime => {
+                self.quarter_chime_config.button_three_press(press).await
+            }
+            SettingsConfig::PauseNudge => self.pause_nudge_config.button_three_press(press).await,
+            SettingsConfig::DoNotDisturb => {
+                self.do_not_disturb_config.button_three_press(press).await
+            }
+            SettingsConfig::NightMute => self.night_mute_config.button_three_press(press).await,
+            SettingsConfig::StillRunning => {
+                self.still_running_config.button_three_press(press).await
+            }
+            SettingsConfig::FinalCountdown => {
+                self.final_countdown_config.button_three_press(press).await
+            }
+            SettingsConfig::PreFinish => self.pre_finish_config.button_three_press(press).await,
             SettingsConfig::TimeColon => self.time_colon_config.button_three_press(press).await,
-            SettingsConfig::AutoScrollTemp => {
-                self.auto_scroll_temp_config.button_three_press(press).await
+            SettingsConfig::TimePreference => {
+                self.time_preference_config.button_three_press(press).await
+            }
+            SettingsConfig::TemperaturePreference => {
+                self.temperature_preference_config
+                    .button_three_press(press)
+                    .await
+            }
+            SettingsConfig::TemperaturePrecision => {
+                self.temperature_precision_config
+                    .button_three_press(press)
+                    .await
+            }
+            SettingsConfig::TemperatureSource => {
+                self.temperature_source_config
+                    .button_three_press(press)
+                    .await
             }
+            SettingsConfig::DisplayRotation => {
+                self.display_rotation_config.button_three_press(press).await
+            }
+            SettingsConfig::NightMode => self.night_mode_config.button_three_press(press).await,
+            SettingsConfig::Standby => self.standby_config.button_three_press(press).await,
+            SettingsConfig::LightSensitivity => {
+                self.light_sensitivity_config
+                    .button_three_press(press)
+                    .await
+            }
+            SettingsConfig::LightCalibration => {
+                self.light_calibration_config
+                    .button_three_press(press)
+                    .await
+            }
+            SettingsConfig::LightsOut => self.lights_out_config.button_three_press(press).await,
+            SettingsConfig::Screensaver => self.screensaver_config.button_three_press(press).await,
+            SettingsConfig::ClockFace => self.clock_face_config.button_three_press(press).await,
+            SettingsConfig::ClockActions => {
+                self.clock_actions_config.button_three_press(press).await
+            }
+            SettingsConfig::TimerPresets => {
+                self.timer_presets_config.button_three_press(press).await
+            }
+            SettingsConfig::KeypadLock => self.keypad_lock_config.button_three_press(press).await,
+            SettingsConfig::SystemInfo => self.system_info_config.button_three_press(press).await,
+            SettingsConfig::LightReading => {
+                self.light_reading_config.button_three_press(press).await
+            }
+            SettingsConfig::FirmwareUpdate => {
+                self.firmware_update_config.button_three_press(press).await
+            }
+            SettingsConfig::Reset => self.reset_config.button_three_press(press).await,
+            SettingsConfig::AgingOffset => self.aging_offset_config.button_three_press(press).await,
         }
     }
 }
 
 impl SettingsApp {
+    /// Start the settings app straight into setting the hour, skipping the menu.
+    pub async fn start_time_setting(&mut self, spawner: Spawner) {
+        self.start(spawner).await;
+        self.start_config(SettingsConfig::Hour).await;
+        NEXT_SETTINGS_START.signal(NextSettingsStart);
+    }
+
+    /// Go back to the previous configuration without saving the current one.
+    /// From the first configuration in a category, this goes back to the settings menu.
+    ///
+    /// Returns false if already in the settings menu, so there is nothing to go back to.
+    pub async fn go_back(&mut self) -> bool {
+        let config = match self.active_config {
+            Some(config) => config,
+            None => return false,
+        };
+
+        match config.previous() {
+            Some(previous) => self.start_config(previous).await,
+            None => self.show_menu().await,
+        }
+
+        NEXT_SETTINGS_START.signal(NextSettingsStart);
+        true
+    }
+
+    /// Leave the settings app without saving the current configuration.
+    pub async fn exit_without_saving(&mut self) {
+        self.stop().await;
+        DISPLAY_MATRIX.queue_text("Cancel", 1000, true, false).await;
+        Timer::after(Duration::from_secs(1)).await;
+        SHOW_APP_SWITCHER.signal(ShowAppSwitcher);
+    }
+
+    /// Show the selected category in the settings menu.
+    async fn show_menu(&mut self) {
+        self.active_config = None;
+        SETTINGS_DISPLAY_QUEUE.signal(BlinkTask::None);
+        DISPLAY_MATRIX
+            .queue_text(self.category.name(), 1000, true, false)
+            .await;
+    }
+
+    /// Start configuring the passed configuration.
+    async fn start_config(&mut self, config: SettingsConfig) {
+        self.active_config = Some(config);
+
+        match config {
+            SettingsConfig::Hour => self.hour_config.start().await,
+            SettingsConfig::Minute => self.minute_config.start().await,
+            SettingsConfig::Sync => self.sync_config.start().await,
+            SettingsConfig::Year => self.year_config.start().await,
+            SettingsConfig::Month => self.month_config.start().await,
+            SettingsConfig::Day => self.day_config.start().await,
+            SettingsConfig::HourlyRing => self.hourly_ring_config.start().await,
+            SettingsConfig::QuarterChime => self.quarter_chime_config.start().await,
+            SettingsConfig::PauseNudge => self.pause_nudge_config.start().await,
+            SettingsConfig::DoNotDisturb => self.do_not_disturb_config.start().await,
+            SettingsConfig::NightMute => self.night_mute_config.start().await,
+            SettingsConfig::StillRunning => self.still_running_config.start().await,
+            SettingsConfig::FinalCountdown => self.final_countdown_config.start().await,
+            SettingsConfig::PreFinish => self.pre_finish_config.start().await,
+            SettingsConfig::TimeColon => self.time_colon_config.start().await,
+            SettingsConfig::TimePreference => self.time_preference_config.start().await,
+            SettingsConfig::TemperaturePreference => {
+                self.temperature_preference_config.start().await
+            }
+            SettingsConfig::TemperaturePrecision => self.temperature_precision_config.start().await,
+            SettingsConfig::TemperatureSource => self.temperature_source_config.start().await,
+            SettingsConfig::DisplayRotation => self.display_rotation_config.start().await,
+            SettingsConfig::NightMode => self.night_mode_config.start().await,
+            SettingsConfig::Standby => self.standby_config.start().await,
+            SettingsConfig::LightSensitivity => self.light_sensitivity_config.start().await,
+            SettingsConfig::LightCalibration => self.light_calibration_config.start().await,
+            SettingsConfig::LightsOut => self.lights_out_config.start().await,
+            SettingsConfig::Screensaver => self.screensaver_config.start().await,
+            SettingsConfig::ClockFace => self.clock_face_config.start().await,
+            SettingsConfig::ClockActions => self.clock_actions_config.start().await,
+            SettingsConfig::TimerPresets => self.timer_presets_config.start().await,
+            SettingsConfig::KeypadLock => self.keypad_lock_config.start().await,
+            SettingsConfig::SystemInfo => self.system_info_config.start().await,
+            SettingsConfig::LightReading => self.light_reading_config.start().await,
+            SettingsConfig::FirmwareUpdate => self.firmware_update_config.start().await,
+            SettingsConfig::Reset => self.reset_config.start().await,
+            SettingsConfig::AgingOffset => self.aging_offset_config.start().await,
+        }
+    }
+
+    /// Save the passed configuration.
+    async fn save_config(&mut self, config: SettingsConfig) {
+        match config {
+            SettingsConfig::Hour => self.hour_config.save().await,
+            SettingsConfig::Minute => self.minute_config.save().await,
+            SettingsConfig::Sync => self.sync_config.save().await,
+            SettingsConfig::Year => self.year_config.save().await,
+            SettingsConfig::Month => self.month_config.save().await,
+            SettingsConfig::Day => self.day_config.save().await,
+            SettingsConfig::HourlyRing => self.hourly_ring_config.save().await,
+            SettingsConfig::QuarterChime => self.quarter_chime_config.save().await,
+            SettingsConfig::PauseNudge => self.pause_nudge_config.save().await,
+            SettingsConfig::DoNotDisturb => self.do_not_disturb_config.save().await,
+            SettingsConfig::NightMute => self.night_mute_config.save().await,
+            SettingsConfig::StillRunning => self.still_running_config.save().await,
+            SettingsConfig::FinalCountdown => self.final_countdown_config.save().await,
+            SettingsConfig::PreFinish => self.pre_finish_config.save().await,
+            SettingsConfig::TimeColon => self.time_colon_config.save().await,
+            SettingsConfig::TimePreference => self.time_preference_config.save().await,
+            SettingsConfig::TemperaturePreference => {
+                self.temperature_preference_config.save().await
+            }
+            SettingsConfig::TemperaturePrecision => self.temperature_precision_config.save().await,
+            SettingsConfig::TemperatureSource => self.temperature_source_config.save().await,
+            SettingsConfig::DisplayRotation => self.display_rotation_config.save().await,
+            SettingsConfig::NightMode => self.night_mode_config.save().await,
+            SettingsConfig::Standby => self.standby_config.save().await,
+            SettingsConfig::LightSensitivity => self.light_sensitivity_config.save().await,
+            SettingsConfig::LightCalibration => self.light_calibration_config.save().await,
+            SettingsConfig::LightsOut => self.lights_out_config.save().await,
+            SettingsConfig::Screensaver => self.screensaver_config.save().await,
+            SettingsConfig::ClockFace => self.clock_face_config.save().await,
+            SettingsConfig::ClockActions => self.clock_actions_config.save().await,
+            SettingsConfig::TimerPresets => self.timer_presets_config.save().await,
+            SettingsConfig::KeypadLock => self.keypad_lock_config.save().await,
+            SettingsConfig::SystemInfo => self.system_info_config.save().await,
+            SettingsConfig::LightReading => self.light_reading_config.save().await,
+            SettingsConfig::FirmwareUpdate => self.firmware_update_config.save().await,
+            SettingsConfig::Reset => self.reset_config.save().await,
+            SettingsConfig::AgingOffset => self.aging_offset_config.save().await,
+        }
+    }
+
     /// End of settings configuration.
     ///
     /// Stop tasks, show "Done" and then show app switcher after delay.
@@ -237,35 +862,129 @@ impl SettingsApp {
     }
 }
 
+/// Show the day of week and AM/PM icons for the value being configured, so the clock context is kept whilst editing.
+async fn show_preview_icons(blink_task: &BlinkTask) {
+    if let BlinkTask::None = blink_task {
+        return;
+    }
+
+    let datetime = rtc::get_datetime_or_estimate().await;
+
+    match *blink_task {
+        BlinkTask::None | BlinkTask::Seconds | BlinkTask::LightReading | BlinkTask::Days(..) => {}
+        BlinkTask::Hour(hour, _) | BlinkTask::Minute(hour, _) => {
+            let time_pref = config::get_time_preference().await;
+            DISPLAY_MATRIX.show_time_icon(time_pref, hour);
+            show_preview_day_icon(datetime.year(), datetime.month(), datetime.day());
+        }
+        BlinkTask::Year(year) => show_preview_day_icon(year, datetime.month(), datetime.day()),
+        BlinkTask::Month(month, day) | BlinkTask::Day(month, day) => {
+            show_preview_day_icon(datetime.year(), month, day)
+        }
+    }
+}
+
+/// Show the day of week icon for the date, as it will be once saved.
+///
+/// A day past the end of the month is moved back the same as when it is saved, so the 31st of February shows the day of the 28th.
+fn show_preview_day_icon(year: i32, month: u32, day: u32) {
+    DISPLAY_MATRIX.hide_day_icons();
+
+    if let Some(weekday) = rtc::get_weekday(year, month, day) {
+        DISPLAY_MATRIX.show_day_icon(weekday);
+    }
+}
+
+/// Get the hour as it is shown on the clock, 1 to 12 with 12hr time, otherwise 0 to 23.
+fn shown_hour(hour: u32, time_pref: TimePreference) -> u32 {
+    match (time_pref, hour % 12) {
+        (TimePreference::TwentyFour, _) => hour,
+        (TimePreference::Twelve, 0) => 12,
+        (TimePreference::Twelve, hour) => hour,
+    }
+}
+
 /// Blink the active configuration background task.
 #[embassy_executor::task]
 async fn blink() {
+    let _running = app::RunningAppTask::new();
     let mut stop_task_sub = STOP_APP_CHANNEL.subscriber().unwrap();
     let mut blink_task = BlinkTask::Hour(0, 0);
+    let mut time_pref = config::get_time_preference().await;
+
+    // whether the blinking part is shown, for blinks that are not done with the text buffer
+    let mut blink_on = true;
 
     loop {
         if SETTINGS_DISPLAY_QUEUE.signaled() {
+            // the day icons are only lit while selecting days
+            if let BlinkTask::Days(..) = blink_task {
+                DISPLAY_MATRIX.hide_day_icons();
+            }
+
             blink_task = SETTINGS_DISPLAY_QUEUE.wait().await;
+            blink_on = true;
+            time_pref = config::get_time_preference().await;
+            show_preview_icons(&blink_task).await;
         }
 
         match blink_task {
             BlinkTask::None => {}
             BlinkTask::Hour(hour, min) => {
                 DISPLAY_MATRIX
-                    .queue_time(hour, min, TimeColon::Full, 750, true, false)
+                    .queue_time(
+                        shown_hour(hour, time_pref),
+                        min,
+                        TimeColon::Full,
+                        750,
+                        true,
+                        false,
+                    )
                     .await;
                 DISPLAY_MATRIX
                     .queue_time_left_side_blink(min, 350, false)
                     .await;
+
+                // AM/PM is part of the hour being changed, so it blinks with the hour
+                if let TimePreference::Twelve = time_pref {
+                    DISPLAY_MATRIX.show_time_icon(time_pref, hour);
+                    Timer::after(Duration::from_millis(750)).await;
+                    DISPLAY_MATRIX.show_time_icon(TimePreference::TwentyFour, hour);
+                }
             }
             BlinkTask::Minute(hour, min) => {
                 DISPLAY_MATRIX
-                    .queue_time(hour, min, TimeColon::Full, 750, true, false)
+                    .queue_time(
+                        shown_hour(hour, time_pref),
+                        min,
+                        TimeColon::Full,
+                        750,
+                        true,
+                        false,
+                    )
+                    .await;
+                DISPLAY_MATRIX
+                    .queue_time_right_side_blink(shown_hour(hour, time_pref), 350, false)
                     .await;
+            }
+            BlinkTask::Seconds => {
+                let datetime = rtc::get_datetime_or_estimate().await;
                 DISPLAY_MATRIX
-                    .queue_time_right_side_blink(hour, 350, false)
+                    .queue_time(
+                        datetime.minute(),
+                        datetime.second(),
+                        TimeColon::Full,
+                        0,
+                        true,
+                        false,
+                    )
                     .await;
             }
+            BlinkTask::LightReading => {
+                let mut text: String<8> = String::new();
+                _ = write!(text, "{}", backlight::get_light_reading());
+                DISPLAY_MATRIX.queue_text(&text, 0, true, false).await;
+            }
             BlinkTask::Year(year) => {
                 DISPLAY_MATRIX.queue_year(year, 750, true).await;
                 DISPLAY_MATRIX.queue_text(" ", 350, false, false).await;
@@ -282,12 +1001,31 @@ async fn blink() {
                     .queue_date_right_side_blink(month, 350, false)
                     .await;
             }
+            BlinkTask::Days(mut days, day) => {
+                if !blink_on {
+                    days.toggle(day);
+                }
+                DISPLAY_MATRIX.show_day_icons(days);
+                blink_on = !blink_on;
+            }
         }
 
+        let wait_ms = match blink_task {
+            // wake at the start of the next second to keep the seconds running
+            BlinkTask::Seconds => {
+                let datetime = rtc::get_datetime_or_estimate().await;
+                1000u64.saturating_sub(datetime.nanosecond() as u64 / 1_000_000)
+            }
+            BlinkTask::Days(..) => 500,
+            // the hour has already been shown while blinking the AM/PM icon
+            BlinkTask::Hour(..) if time_pref == TimePreference::Twelve => 350,
+            _ => 1100,
+        };
+
         let wait_task = select3(
             stop_task_sub.next_message(),
             NEXT_SETTINGS_START.wait(),
-            Timer::after(Duration::from_millis(1100)),
+            Timer::after(Duration::from_millis(wait_ms)),
         )
         .await;
 
@@ -301,16 +1039,32 @@ async fn blink() {
 
 /// All settings configurations mini apps.
 mod configurations {
-    use core::fmt::Write;
+    use chrono::Weekday;
+    use core::{
+        fmt::Write,
+        ops::{Add, Sub},
+    };
+    use embassy_rp::rom_data;
+    use embassy_time::{Duration, Instant, Timer};
     use heapless::String;
 
     use crate::{
+        app,
         buttons::ButtonPress,
-        config::{self, TimeColonPreference},
-        display::display_matrix::DISPLAY_MATRIX,
-        rtc,
+        config::{
+            self, ClockAction, ClockFace, Days, DisplayRotation, LightCalibration,
+            LightSensitivity, RotationItem, TemperaturePrecision, TemperaturePreference,
+            TemperatureSource, TimeColonPreference, TimePreference, CLOCK_ACTIONS_SIZE,
+            PRE_FINISH_MAX_MINUTES, ROTATION_ITEMS, TIMER_PRESETS_SIZE, TIMER_PRESET_MAX_MINS,
+        },
+        diagnostics,
+        display::{backlight, display_matrix::DISPLAY_MATRIX},
+        prompt, rtc, temperature,
     };
 
+    #[cfg(feature = "wifi")]
+    use crate::wifi;
+
     use super::SETTINGS_DISPLAY_QUEUE;
 
     /// Common trait that all settings configs should implement.
@@ -328,43 +1082,134 @@ mod configurations {
         async fn button_three_press(&mut self, press: ButtonPress);
     }
 
+    /// Show that the RTC did not accept a new value, holding it long enough to be read.
+    async fn show_rtc_error() {
+        DISPLAY_MATRIX
+            .queue_text("RTC ERR", 1000, true, false)
+            .await;
+        Timer::after(Duration::from_secs(1)).await;
+    }
+
+    /// A number that the middle button increases and the bottom button decreases, shared by the numeric configurations.
+    ///
+    /// Going past `max` or `min` goes round to the other end if `wrap` is set, otherwise the number stops at the end.
+    /// The configuration shows the number itself, as each one is shown differently.
+    struct NumberSpinner<T> {
+        /// The number being configured.
+        value: T,
+
+        /// The number set when starting configuration.
+        starting_value: T,
+
+        /// The lowest the number can be.
+        min: T,
+
+        /// The highest the number can be.
+        max: T,
+
+        /// How much each press changes the number by.
+        step: T,
+
+        /// Whether to go round to the other end when going past either end.
+        wrap: bool,
+    }
+
+    impl<T> NumberSpinner<T>
+    where
+        T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T>,
+    {
+        /// Create a new number spinner, starting at `min`.
+        fn new(min: T, max: T, step: T, wrap: bool) -> Self {
+            Self {
+                value: min,
+                starting_value: min,
+                min,
+                max,
+                step,
+                wrap,
+            }
+        }
+
+        /// Start configuring from the number passed.
+        fn start(&mut self, value: T) {
+            self.value = value;
+            self.starting_value = value;
+        }
+
+        /// Get the number being configured.
+        fn value(&self) -> T {
+            self.value
+        }
+
+        /// Get the number if it has changed since starting, or [None](Option::None) if it has not changed.
+        fn changed_value(&self) -> Option<T> {
+            if self.value != self.starting_value {
+                Some(self.value)
+            } else {
+                None
+            }
+        }
+
+        /// Change the highest the number can be, such as for the days in a month.
+        fn set_max(&mut self, max: T) {
+            self.max = max;
+        }
+
+        /// Increase the number by a step.
+        fn increase(&mut self) {
+            // compared against max - step, so the number can not overflow at the end of its type
+            self.value = if self.value > self.max - self.step {
+                if self.wrap {
+                    self.min
+                } else {
+                    self.max
+                }
+            } else {
+                self.value + self.step
+            };
+        }
+
+        /// Decrease the number by a step.
+        fn decrease(&mut self) {
+            self.value = if self.value < self.min + self.step {
+                if self.wrap {
+                    self.max
+                } else {
+                    self.min
+                }
+            } else {
+                self.value - self.step
+            };
+        }
+    }
+
     /// RTC hour configuration.
     pub struct HourConfiguration {
         /// The hour being configured.
-        hour: u32,
-
-        /// The hour set when starting configuration.
-        starting_hour: u32,
+        hour: NumberSpinner<u32>,
     }
 
     impl Configuration for HourConfiguration {
         async fn start(&mut self) {
-            self.hour = rtc::get_hour().await;
-            self.starting_hour = self.hour;
+            self.hour.start(rtc::get_hour().await);
             self.show().await;
         }
 
         async fn save(&mut self) {
-            if self.hour != self.starting_hour {
-                rtc::set_hour(self.hour).await;
+            if let Some(hour) = self.hour.changed_value() {
+                if rtc::set_hour(hour).await.is_err() {
+                    show_rtc_error().await;
+                }
             }
         }
 
         async fn button_two_press(&mut self, _: ButtonPress) {
-            if self.hour == 23 {
-                self.hour = 0;
-            } else {
-                self.hour += 1;
-            }
+            self.hour.increase();
             self.show().await;
         }
 
         async fn button_three_press(&mut self, _: ButtonPress) {
-            if self.hour == 0 {
-                self.hour = 23;
-            } else {
-                self.hour -= 1;
-            }
+            self.hour.decrease();
             self.show().await;
         }
     }
@@ -373,55 +1218,44 @@ mod configurations {
         /// Create a new hour configuration.
         pub fn new() -> Self {
             Self {
-                hour: 0,
-                starting_hour: 0,
+                hour: NumberSpinner::new(0, 23, 1, true),
             }
         }
 
         /// Show hour configuration in blink task.
         async fn show(&self) {
             let minute = rtc::get_minute().await;
-            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::Hour(self.hour, minute));
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::Hour(self.hour.value(), minute));
         }
     }
 
     /// RTC minute configuration.
     pub struct MinuteConfiguration {
         /// The minute being configured.
-        minute: u32,
-
-        /// The minute set when starting configuration.
-        starting_minute: u32,
+        minute: NumberSpinner<u32>,
     }
 
     impl Configuration for MinuteConfiguration {
         async fn start(&mut self) {
-            self.minute = rtc::get_minute().await;
-            self.starting_minute = self.minute;
+            self.minute.start(rtc::get_minute().await);
             self.show().await;
         }
 
         async fn save(&mut self) {
-            if self.minute != self.starting_minute {
-                rtc::set_minute(self.minute).await;
+            if let Some(minute) = self.minute.changed_value() {
+                if rtc::set_minute(minute).await.is_err() {
+                    show_rtc_error().await;
+                }
             }
         }
 
         async fn button_two_press(&mut self, _: ButtonPress) {
-            if self.minute == 59 {
-                self.minute = 0;
-            } else {
-                self.minute += 1;
-            }
+            self.minute.increase();
             self.show().await;
         }
 
         async fn button_three_press(&mut self, _: ButtonPress) {
-            if self.minute == 0 {
-                self.minute = 59;
-            } else {
-                self.minute -= 1;
-            }
+            self.minute.decrease();
             self.show().await;
         }
     }
@@ -430,55 +1264,82 @@ mod configurations {
         /// Create a new minute configuration.
         pub fn new() -> Self {
             Self {
-                minute: 0,
-                starting_minute: 0,
+                minute: NumberSpinner::new(0, 59, 1, true),
             }
         }
 
         /// Show minute configuration in blink task.
         async fn show(&self) {
             let hour = rtc::get_hour().await;
-            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::Minute(hour, self.minute));
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::Minute(hour, self.minute.value()));
+        }
+    }
+
+    /// RTC seconds sync configuration.
+    ///
+    /// Shows the running minutes and seconds. A press of the middle or bottom button sets the seconds to 0, rounding to the nearest minute, so the clock can be synced on the minute from a reference time signal.
+    pub struct SyncConfiguration {}
+
+    impl Configuration for SyncConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::Seconds);
+        }
+
+        async fn save(&mut self) {}
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.sync().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.sync().await;
+        }
+    }
+
+    impl SyncConfiguration {
+        /// Create a new seconds sync configuration.
+        pub fn new() -> Self {
+            Self {}
+        }
+
+        /// Sync the seconds, and show the new time straight away.
+        async fn sync(&self) {
+            if rtc::sync_to_minute().await.is_err() {
+                show_rtc_error().await;
+            }
+
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::Seconds);
+            super::NEXT_SETTINGS_START.signal(super::NextSettingsStart);
         }
     }
 
     /// RTC year configuration.
     pub struct YearConfiguration {
         /// The year being configured.
-        year: i32,
-
-        /// The year set when starting configuration.
-        starting_year: i32,
+        year: NumberSpinner<i32>,
     }
 
     impl Configuration for YearConfiguration {
         async fn start(&mut self) {
-            self.year = rtc::get_year().await;
-            self.starting_year = self.year;
+            self.year.start(rtc::get_year().await);
             self.show().await;
         }
 
         async fn save(&mut self) {
-            if self.year != self.starting_year {
-                rtc::set_year(self.year).await;
+            if let Some(year) = self.year.changed_value() {
+                if rtc::set_year(year).await.is_err() {
+                    show_rtc_error().await;
+                }
             }
         }
 
         async fn button_two_press(&mut self, _: ButtonPress) {
-            if self.year == 2100 {
-                self.year = 2000;
-            } else {
-                self.year += 1;
-            }
+            self.year.increase();
             self.show().await;
         }
 
         async fn button_three_press(&mut self, _: ButtonPress) {
-            if self.year == 2000 {
-                self.year = 2100;
-            } else {
-                self.year -= 1;
-            }
+            self.year.decrease();
             self.show().await;
         }
     }
@@ -487,54 +1348,43 @@ mod configurations {
         /// Create a new year configuration.
         pub fn new() -> Self {
             Self {
-                year: 0,
-                starting_year: 0,
+                year: NumberSpinner::new(rtc::MIN_YEAR, rtc::MAX_YEAR, 1, true),
             }
         }
 
         /// Show year configuration in blink task.
         async fn show(&self) {
-            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::Year(self.year));
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::Year(self.year.value()));
         }
     }
 
     /// RTC month configuration.
     pub struct MonthConfiguration {
         /// The month being configured.
-        month: u32,
-
-        /// The month set when starting configuration.
-        starting_month: u32,
+        month: NumberSpinner<u32>,
     }
 
     impl Configuration for MonthConfiguration {
         async fn start(&mut self) {
-            self.month = rtc::get_month().await;
-            self.starting_month = self.month;
+            self.month.start(rtc::get_month().await);
             self.show().await;
         }
 
         async fn save(&mut self) {
-            if self.month != self.starting_month {
-                rtc::set_month(self.month).await;
+            if let Some(month) = self.month.changed_value() {
+                if rtc::set_month(month).await.is_err() {
+                    show_rtc_error().await;
+                }
             }
         }
 
         async fn button_two_press(&mut self, _: ButtonPress) {
-            if self.month == 12 {
-                self.month = 1;
-            } else {
-                self.month += 1;
-            }
+            self.month.increase();
             self.show().await;
         }
 
         async fn button_three_press(&mut self, _: ButtonPress) {
-            if self.month == 1 {
-                self.month = 12;
-            } else {
-                self.month -= 1;
-            }
+            self.month.decrease();
             self.show().await;
         }
     }
@@ -543,25 +1393,21 @@ mod configurations {
         /// Create a new month configuration.
         pub fn new() -> Self {
             Self {
-                month: 0,
-                starting_month: 0,
+                month: NumberSpinner::new(1, 12, 1, true),
             }
         }
 
-        /// Show minute configuration in blink task.
+        /// Show month configuration in blink task.
         async fn show(&self) {
             let day = rtc::get_day().await;
-            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::Month(self.month, day));
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::Month(self.month.value(), day));
         }
     }
 
     /// RTC day configuration.
     pub struct DayConfiguration {
         /// The day being configured.
-        day: u32,
-
-        /// The day set when starting configuration.
-        starting_day: u32,
+        day: NumberSpinner<u32>,
 
         /// The current month in RTC. This is purely just a reference and should not be mutated.
         month: u32,
@@ -569,33 +1415,28 @@ mod configurations {
 
     impl Configuration for DayConfiguration {
         async fn start(&mut self) {
-            self.day = rtc::get_day().await;
-            self.starting_day = self.day;
             self.month = rtc::get_month().await;
+            self.day
+                .set_max(rtc::get_max_day_in_month(self.month).await);
+            self.day.start(rtc::get_day().await);
             self.show().await;
         }
 
         async fn save(&mut self) {
-            if self.day != self.starting_day {
-                rtc::set_day(self.day).await;
+            if let Some(day) = self.day.changed_value() {
+                if rtc::set_day(day).await.is_err() {
+                    show_rtc_error().await;
+                }
             }
         }
 
         async fn button_two_press(&mut self, _: ButtonPress) {
-            if self.day == rtc::get_max_day_in_month(self.month).await {
-                self.day = 1;
-            } else {
-                self.day += 1;
-            }
+            self.day.increase();
             self.show().await;
         }
 
         async fn button_three_press(&mut self, _: ButtonPress) {
-            if self.day == 1 {
-                self.day = rtc::get_max_day_in_month(self.month).await;
-            } else {
-                self.day -= 1;
-            }
+            self.day.decrease();
             self.show().await;
         }
     }
@@ -604,69 +1445,472 @@ mod configurations {
         /// Create a new day configuration.
         pub fn new() -> Self {
             Self {
-                day: 0,
-                starting_day: 0,
+                day: NumberSpinner::new(1, 31, 1, true),
                 month: 0,
             }
         }
 
         /// Show day configuration in blink task.
         async fn show(&self) {
-            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::Day(self.month, self.day));
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::Day(self.month, self.day.value()));
         }
     }
 
-    /// RTC day configuration.
-    pub struct HourlyRingConfiguration {
-        /// The ring state.
+    /// An on or off setting, shared by the configurations that are only turned on or off.
+    ///
+    /// Either button turns the setting on or off.
+    struct BoolSetting {
+        /// The label shown before whether the setting is on, e.g. QC.
+        label: &'static str,
+
+        /// Whether the setting is on.
         state: bool,
 
         /// The state set when starting configuration.
         starting_state: bool,
     }
 
+    impl BoolSetting {
+        /// Create a new on or off setting, that is off.
+        const fn new(label: &'static str) -> Self {
+            Self {
+                label,
+                state: false,
+                starting_state: false,
+            }
+        }
+
+        /// Start configuring from the state passed.
+        async fn start(&mut self, state: bool) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.state = state;
+            self.starting_state = state;
+            self.show().await;
+        }
+
+        /// Get the state if it has changed since starting, or [None](Option::None) if it has not changed.
+        fn changed_state(&self) -> Option<bool> {
+            if self.state != self.starting_state {
+                Some(self.state)
+            } else {
+                None
+            }
+        }
+
+        /// Turn the setting on if it is off, otherwise turn it off.
+        async fn toggle(&mut self) {
+            self.state = !self.state;
+            self.show().await;
+        }
+
+        /// Show the setting in blink task, e.g. QC:On.
+        async fn show(&self) {
+            let text = on_off_text(self.label, self.state);
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 1000, true, false)
+                .await;
+        }
+    }
+
+    /// Get the label and whether it is on, e.g. QC:On or QC:Of, so every on or off value reads the same.
+    fn on_off_text(label: &str, state: bool) -> String<16> {
+        let mut text: String<16> = String::new();
+        if state {
+            _ = write!(text, "{}:On", label);
+        } else {
+            _ = write!(text, "{}:Of", label);
+        }
+
+        text
+    }
+
+    /// Select a set of days one day at a time, shared by the configurations that are set for each day.
+    ///
+    /// The selected days have their icons lit, and the icon of the day being changed blinks.
+    /// The middle button adds or removes the day, and a double press cycles through every day, weekdays, weekends and no days.
+    /// The bottom button moves on to the next day.
+    struct DaySelector {
+        /// The label shown before whether the day being changed is selected, e.g. HR.
+        label: &'static str,
+
+        /// The selected days.
+        days: Days,
+
+        /// The days selected when starting configuration.
+        starting_days: Days,
+
+        /// The day being changed.
+        day: Weekday,
+    }
+
+    impl DaySelector {
+        /// Create a new day selector, with no days selected.
+        const fn new(label: &'static str) -> Self {
+            Self {
+                label,
+                days: Days::NONE,
+                starting_days: Days::NONE,
+                day: Weekday::Mon,
+            }
+        }
+
+        /// Start selecting days, from Monday.
+        async fn start(&mut self, days: Days) {
+            self.days = days;
+            self.starting_days = days;
+            self.day = Weekday::Mon;
+            self.show().await;
+        }
+
+        /// Get the days that have changed since starting, or [None](Option::None) if they have not changed.
+        fn changed_days(&self) -> Option<Days> {
+            if self.days != self.starting_days {
+                Some(self.days)
+            } else {
+                None
+            }
+        }
+
+        /// Add or remove the day being changed, or go to the next common set of days on a double press.
+        async fn button_two_press(&mut self, press: ButtonPress) {
+            match press {
+                ButtonPress::Double => self.days = self.days.next_preset(),
+                _ => self.days.toggle(self.day),
+            }
+            self.show().await;
+        }
+
+        /// Move on to the next day.
+        async fn button_three_press(&mut self) {
+            self.day = self.day.succ();
+            self.show().await;
+        }
+
+        /// Show the selected days in the blink task, and whether the day being changed is selected, e.g. HR:On.
+        async fn show(&self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::Days(self.days, self.day));
+
+            let text = on_off_text(self.label, self.days.contains(self.day));
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 1000, true, false)
+                .await;
+        }
+    }
+
+    /// Hourly ring configuration, for the days the clock beeps on the hour.
+    pub struct HourlyRingConfiguration {
+        /// The days the ring is on.
+        days: DaySelector,
+    }
+
     impl Configuration for HourlyRingConfiguration {
+        async fn start(&mut self) {
+            let days = config::get_hourly_ring_days().await;
+            self.days.start(days).await;
+        }
+
+        async fn save(&mut self) {
+            if let Some(days) = self.days.changed_days() {
+                config::set_hourly_ring_days(days).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, press: ButtonPress) {
+            self.days.button_two_press(press).await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.days.button_three_press().await;
+        }
+    }
+
+    impl HourlyRingConfiguration {
+        /// Create a new hourly ring configuration.
+        pub fn new() -> Self {
+            Self {
+                days: DaySelector::new("HR"),
+            }
+        }
+    }
+
+    /// Quarter chime configuration.
+    pub struct QuarterChimeConfiguration {
+        /// The chime state.
+        state: BoolSetting,
+    }
+
+    impl Configuration for QuarterChimeConfiguration {
+        async fn start(&mut self) {
+            let state = config::get_quarter_chime().await;
+            self.state.start(state).await;
+        }
+
+        async fn save(&mut self) {
+            if let Some(state) = self.state.changed_state() {
+                config::set_quarter_chime(state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.state.toggle().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.state.toggle().await;
+        }
+    }
+
+    impl QuarterChimeConfiguration {
+        /// Create a new quarter chime configuration.
+        pub fn new() -> Self {
+            Self {
+                state: BoolSetting::new("QC"),
+            }
+        }
+    }
+
+    /// Pause nudge configuration, for whether a paused pomodoro nudges to be resumed.
+    pub struct PauseNudgeConfiguration {
+        /// The nudge state.
+        state: BoolSetting,
+    }
+
+    impl Configuration for PauseNudgeConfiguration {
+        async fn start(&mut self) {
+            let state = config::get_pause_nudge().await;
+            self.state.start(state).await;
+        }
+
+        async fn save(&mut self) {
+            if let Some(state) = self.state.changed_state() {
+                config::set_pause_nudge(state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.state.toggle().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.state.toggle().await;
+        }
+    }
+
+    impl PauseNudgeConfiguration {
+        /// Create a new pause nudge configuration.
+        pub fn new() -> Self {
+            Self {
+                state: BoolSetting::new("PN"),
+            }
+        }
+    }
+
+    /// Do not disturb configuration, for holding back hourly chimes, the auto display rotation and notification sounds.
+    pub struct DoNotDisturbConfiguration {
+        /// The do not disturb state.
+        state: BoolSetting,
+    }
+
+    impl Configuration for DoNotDisturbConfiguration {
+        async fn start(&mut self) {
+            let state = config::get_do_not_disturb().await;
+            self.state.start(state).await;
+        }
+
+        async fn save(&mut self) {
+            if let Some(state) = self.state.changed_state() {
+                config::set_do_not_disturb(state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.state.toggle().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.state.toggle().await;
+        }
+    }
+
+    impl DoNotDisturbConfiguration {
+        /// Create a new do not disturb configuration.
+        pub fn new() -> Self {
+            Self {
+                state: BoolSetting::new("DN"),
+            }
+        }
+    }
+
+    /// Night mute configuration, for muting the speaker on the nights night mode is on.
+    pub struct NightMuteConfiguration {
+        /// The night mute state.
+        state: BoolSetting,
+    }
+
+    impl Configuration for NightMuteConfiguration {
+        async fn start(&mut self) {
+            let state = config::get_night_mute().await;
+            self.state.start(state).await;
+        }
+
+        async fn save(&mut self) {
+            if let Some(state) = self.state.changed_state() {
+                config::set_night_mute(state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.state.toggle().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.state.toggle().await;
+        }
+    }
+
+    impl NightMuteConfiguration {
+        /// Create a new night mute configuration.
+        pub fn new() -> Self {
+            Self {
+                state: BoolSetting::new("QN"),
+            }
+        }
+    }
+
+    /// Still running reminder configuration, for the hour from which a timer left running in the dark is reminded about.
+    pub struct StillRunningConfiguration {
+        /// The hour being configured, where 0 is off.
+        hour: NumberSpinner<u8>,
+    }
+
+    impl Configuration for StillRunningConfiguration {
         async fn start(&mut self) {
             SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
-            self.state = config::get_hourly_ring().await;
-            self.starting_state = self.state;
+            self.hour.start(config::get_still_running_hour().await);
             self.show().await;
         }
 
         async fn save(&mut self) {
-            if self.state != self.starting_state {
-                config::set_hourly_ring(self.state).await;
+            if let Some(hour) = self.hour.changed_value() {
+                config::set_still_running_hour(hour).await;
             }
         }
 
         async fn button_two_press(&mut self, _: ButtonPress) {
-            self.state = !self.state;
+            self.hour.increase();
             self.show().await;
         }
 
         async fn button_three_press(&mut self, _: ButtonPress) {
-            self.state = !self.state;
+            self.hour.decrease();
             self.show().await;
         }
     }
 
-    impl HourlyRingConfiguration {
-        /// Create a new day configuration.
+    impl StillRunningConfiguration {
+        /// Create a new still running reminder configuration.
         pub fn new() -> Self {
             Self {
-                state: false,
-                starting_state: false,
+                hour: NumberSpinner::new(0, 23, 1, true),
             }
         }
 
-        /// Show day configuration in blink task.
+        /// Show still running reminder configuration.
         async fn show(&self) {
             let mut text: String<16> = String::new();
-            _ = write!(text, "HR:");
-            if self.state {
-                _ = write!(text, "On");
+            if self.hour.value() == 0 {
+                _ = write!(text, "SR:Of");
+            } else {
+                _ = write!(text, "SR:{}", self.hour.value());
+            }
+
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 1000, true, false)
+                .await;
+        }
+    }
+
+    /// Final countdown configuration, for whether timers blink and tick through their final seconds.
+    pub struct FinalCountdownConfiguration {
+        /// The final countdown warning state.
+        state: BoolSetting,
+    }
+
+    impl Configuration for FinalCountdownConfiguration {
+        async fn start(&mut self) {
+            let state = config::get_final_countdown().await;
+            self.state.start(state).await;
+        }
+
+        async fn save(&mut self) {
+            if let Some(state) = self.state.changed_state() {
+                config::set_final_countdown(state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.state.toggle().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.state.toggle().await;
+        }
+    }
+
+    impl FinalCountdownConfiguration {
+        /// Create a new final countdown configuration.
+        pub fn new() -> Self {
+            Self {
+                state: BoolSetting::new("TK"),
+            }
+        }
+    }
+
+    /// Pre-finish warning configuration, for how many minutes before the pomodoro finishes to beep.
+    pub struct PreFinishConfiguration {
+        /// The minutes being configured, where 0 is off.
+        minutes: NumberSpinner<u8>,
+    }
+
+    impl Configuration for PreFinishConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.minutes.start(config::get_pre_finish_minutes().await);
+            self.show().await;
+        }
+
+        async fn save(&mut self) {
+            if let Some(minutes) = self.minutes.changed_value() {
+                config::set_pre_finish_minutes(minutes).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.minutes.increase();
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.minutes.decrease();
+            self.show().await;
+        }
+    }
+
+    impl PreFinishConfiguration {
+        /// Create a new pre-finish warning configuration.
+        pub fn new() -> Self {
+            Self {
+                minutes: NumberSpinner::new(0, PRE_FINISH_MAX_MINUTES, 1, true),
+            }
+        }
+
+        /// Show pre-finish warning configuration.
+        async fn show(&self) {
+            let mut text: String<16> = String::new();
+            if self.minutes.value() == 0 {
+                _ = write!(text, "PF:Of");
             } else {
-                _ = write!(text, "Of");
+                _ = write!(text, "PF:{}", self.minutes.value());
             }
 
             DISPLAY_MATRIX
@@ -702,16 +1946,18 @@ mod configurations {
             match self.state {
                 TimeColonPreference::Solid => self.state = TimeColonPreference::Blink,
                 TimeColonPreference::Blink => self.state = TimeColonPreference::Alt,
-                TimeColonPreference::Alt => self.state = TimeColonPreference::Solid,
+                TimeColonPreference::Alt => self.state = TimeColonPreference::Seconds,
+                TimeColonPreference::Seconds => self.state = TimeColonPreference::Solid,
             }
             self.show().await;
         }
 
         async fn button_three_press(&mut self, _: ButtonPress) {
             match self.state {
-                TimeColonPreference::Solid => self.state = TimeColonPreference::Alt,
+                TimeColonPreference::Solid => self.state = TimeColonPreference::Seconds,
                 TimeColonPreference::Blink => self.state = TimeColonPreference::Solid,
                 TimeColonPreference::Alt => self.state = TimeColonPreference::Blink,
+                TimeColonPreference::Seconds => self.state = TimeColonPreference::Alt,
             }
             self.show().await;
         }
@@ -732,32 +1978,1152 @@ mod configurations {
                 TimeColonPreference::Solid => ":SLD",
                 TimeColonPreference::Blink => ":BLK",
                 TimeColonPreference::Alt => ":ALT",
+                TimeColonPreference::Seconds => ":SEC",
             };
 
             DISPLAY_MATRIX.queue_text(text, 1000, true, false).await;
         }
     }
 
-    /// RTC day configuration.
-    pub struct AutoScrollTempConfiguration {
-        /// The ring state.
-        state: bool,
+    /// Time preference configuration.
+    pub struct TimePreferenceConfiguration {
+        /// The time preference state.
+        state: TimePreference,
 
         /// The state set when starting configuration.
-        starting_state: bool,
+        starting_state: TimePreference,
+    }
+
+    impl Configuration for TimePreferenceConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.state = config::get_time_preference().await;
+            self.starting_state = self.state;
+            self.show().await;
+        }
+
+        async fn save(&mut self) {
+            if self.state != self.starting_state {
+                config::set_time_preference(self.state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.toggle();
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.toggle();
+            self.show().await;
+        }
+    }
+
+    impl TimePreferenceConfiguration {
+        /// Create a new time preference configuration.
+        pub fn new() -> Self {
+            Self {
+                state: TimePreference::Twelve,
+                starting_state: TimePreference::Twelve,
+            }
+        }
+
+        /// Switch between 12hr and 24hr.
+        fn toggle(&mut self) {
+            self.state = match self.state {
+                TimePreference::Twelve => TimePreference::TwentyFour,
+                TimePreference::TwentyFour => TimePreference::Twelve,
+            };
+        }
+
+        /// Show time preference configuration in blink task.
+        async fn show(&self) {
+            let text = match self.state {
+                TimePreference::Twelve => "12H",
+                TimePreference::TwentyFour => "24H",
+            };
+
+            DISPLAY_MATRIX.queue_text(text, 1000, true, false).await;
+        }
+    }
+
+    /// Temperature preference configuration.
+    ///
+    /// The temperature icon is shown as a preview of the unit being set.
+    pub struct TemperaturePreferenceConfiguration {
+        /// The temperature preference state.
+        state: TemperaturePreference,
+
+        /// The state set when starting configuration.
+        starting_state: TemperaturePreference,
+    }
+
+    impl Configuration for TemperaturePreferenceConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.state = config::get_temperature_preference().await;
+            self.starting_state = self.state;
+            self.show().await;
+        }
+
+        async fn save(&mut self) {
+            DISPLAY_MATRIX.hide_icon("°C");
+            DISPLAY_MATRIX.hide_icon("°F");
+
+            if self.state != self.starting_state {
+                config::set_temperature_preference(self.state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.toggle();
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.toggle();
+            self.show().await;
+        }
+    }
+
+    impl TemperaturePreferenceConfiguration {
+        /// Create a new temperature preference configuration.
+        pub fn new() -> Self {
+            Self {
+                state: TemperaturePreference::Celcius,
+                starting_state: TemperaturePreference::Celcius,
+            }
+        }
+
+        /// Switch between celcius and fahrenheit.
+        fn toggle(&mut self) {
+            self.state = match self.state {
+                TemperaturePreference::Celcius => TemperaturePreference::Fahrenheit,
+                TemperaturePreference::Fahrenheit => TemperaturePreference::Celcius,
+            };
+        }
+
+        /// Show temperature preference configuration in blink task, with the icon as a preview.
+        async fn show(&self) {
+            let text = match self.state {
+                TemperaturePreference::Celcius => "°C",
+                TemperaturePreference::Fahrenheit => "°F",
+            };
+
+            DISPLAY_MATRIX.show_temperature_icon(self.state);
+            DISPLAY_MATRIX.queue_text(text, 1000, true, false).await;
+        }
+    }
+
+    /// Temperature precision configuration.
+    ///
+    /// The current temperature is shown with the precision being set.
+    pub struct TemperaturePrecisionConfiguration {
+        /// The temperature precision state.
+        state: TemperaturePrecision,
+
+        /// The state set when starting configuration.
+        starting_state: TemperaturePrecision,
+    }
+
+    impl Configuration for TemperaturePrecisionConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.state = config::get_temperature_precision().await;
+            self.starting_state = self.state;
+            self.show().await;
+        }
+
+        async fn save(&mut self) {
+            if self.state != self.starting_state {
+                config::set_temperature_precision(self.state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.toggle();
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.toggle();
+            self.show().await;
+        }
+    }
+
+    impl TemperaturePrecisionConfiguration {
+        /// Create a new temperature precision configuration.
+        pub fn new() -> Self {
+            Self {
+                state: TemperaturePrecision::Whole,
+                starting_state: TemperaturePrecision::Whole,
+            }
+        }
+
+        /// Switch between whole degrees and one decimal place.
+        fn toggle(&mut self) {
+            self.state = match self.state {
+                TemperaturePrecision::Whole => TemperaturePrecision::Tenths,
+                TemperaturePrecision::Tenths => TemperaturePrecision::Whole,
+            };
+        }
+
+        /// Show temperature precision configuration in blink task.
+        async fn show(&self) {
+            let pref = temperature::get_temperature_preference().await;
+            let temp = temperature::get_temperature_off_preference().await;
+
+            DISPLAY_MATRIX
+                .queue_temperature(temp, pref, self.state, true, false)
+                .await;
+        }
+    }
+
+    /// Temperature source configuration.
+    pub struct TemperatureSourceConfiguration {
+        /// The temperature source state.
+        state: TemperatureSource,
+
+        /// The state set when starting configuration.
+        starting_state: TemperatureSource,
+    }
+
+    impl Configuration for TemperatureSourceConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.state = config::get_temperature_source().await;
+            self.starting_state = self.state;
+            self.show().await;
+        }
+
+        async fn save(&mut self) {
+            if self.state != self.starting_state {
+                config::set_temperature_source(self.state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.toggle();
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.toggle();
+            self.show().await;
+        }
+    }
+
+    impl TemperatureSourceConfiguration {
+        /// Create a new temperature source configuration.
+        pub fn new() -> Self {
+            Self {
+                state: TemperatureSource::Rtc,
+                starting_state: TemperatureSource::Rtc,
+            }
+        }
+
+        /// Switch between the RTC and external sensor.
+        fn toggle(&mut self) {
+            self.state = match self.state {
+                TemperatureSource::Rtc => TemperatureSource::External,
+                TemperatureSource::External => TemperatureSource::Rtc,
+            };
+        }
+
+        /// Show temperature source configuration in blink task.
+        async fn show(&self) {
+            let text = match self.state {
+                TemperatureSource::Rtc => "RTC",
+                TemperatureSource::External => "EXT",
+            };
+            DISPLAY_MATRIX.queue_text(text, 1000, true, false).await;
+        }
+    }
+
+    /// Auto display rotation configuration.
+    ///
+    /// The middle button changes the shown option, the bottom button moves to the next option.
+    /// The options are each of [ROTATION_ITEMS] followed by the interval.
+    pub struct DisplayRotationConfiguration {
+        /// The display rotation being configured.
+        state: DisplayRotation,
+
+        /// The display rotation set when starting configuration.
+        starting_state: DisplayRotation,
+
+        /// The index of the option being configured. Indexes past the end of [ROTATION_ITEMS] are the interval.
+        index: usize,
+    }
+
+    impl Configuration for DisplayRotationConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.state = config::get_display_rotation().await;
+            self.starting_state = self.state;
+            self.index = 0;
+            self.show().await;
+        }
+
+        async fn save(&mut self) {
+            if self.state != self.starting_state {
+                config::set_display_rotation(self.state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            match ROTATION_ITEMS.get(self.index) {
+                Some(&item) => self.state.set_enabled(item, !self.state.is_enabled(item)),
+                None => self.state.interval = self.state.interval.next(),
+            }
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.index = (self.index + 1) % (ROTATION_ITEMS.len() + 1);
+            self.show().await;
+        }
+    }
+
+    impl DisplayRotationConfiguration {
+        /// Create a new display rotation configuration.
+        pub fn new() -> Self {
+            Self {
+                state: DisplayRotation::new(),
+                starting_state: DisplayRotation::new(),
+                index: 0,
+            }
+        }
+
+        /// Show display rotation configuration in blink task.
+        ///
+        /// Items are shown with whether they are enabled, e.g. TP:On is temperature enabled.
+        /// The interval is shown in minutes, e.g. IV:10 is every 10 minutes.
+        async fn show(&self) {
+            let text = match ROTATION_ITEMS.get(self.index) {
+                Some(&item) => {
+                    let label = match item {
+                        RotationItem::Temperature => "TP",
+                        RotationItem::Humidity => "RH",
+                        RotationItem::Pressure => "PR",
+                        RotationItem::Date => "DT",
+                    };
+                    on_off_text(label, self.state.is_enabled(item))
+                }
+                None => {
+                    let mut text: String<16> = String::new();
+                    _ = write!(text, "IV:{}", self.state.interval.minutes());
+                    text
+                }
+            };
+
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 1000, true, false)
+                .await;
+        }
+    }
+
+    /// Night mode configuration, for the days the display turns off from night time.
+    pub struct NightModeConfiguration {
+        /// The days night mode starts on.
+        days: DaySelector,
+    }
+
+    impl Configuration for NightModeConfiguration {
+        async fn start(&mut self) {
+            let days = config::get_night_mode_days().await;
+            self.days.start(days).await;
+        }
+
+        async fn save(&mut self) {
+            if let Some(days) = self.days.changed_days() {
+                config::set_night_mode_days(days).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, press: ButtonPress) {
+            self.days.button_two_press(press).await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.days.button_three_press().await;
+        }
+    }
+
+    impl NightModeConfiguration {
+        /// Create a new night mode configuration.
+        pub fn new() -> Self {
+            Self {
+                days: DaySelector::new("NM"),
+            }
+        }
+    }
+
+    /// Night mode standby configuration.
+    ///
+    /// In standby the display stops scanning while it is blanked, to use less power on battery or UPS installs.
+    pub struct StandbyConfiguration {
+        /// The standby state.
+        state: BoolSetting,
+    }
+
+    impl Configuration for StandbyConfiguration {
+        async fn start(&mut self) {
+            let state = config::get_standby().await;
+            self.state.start(state).await;
+        }
+
+        async fn save(&mut self) {
+            if let Some(state) = self.state.changed_state() {
+                config::set_standby(state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.state.toggle().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.state.toggle().await;
+        }
+    }
+
+    impl StandbyConfiguration {
+        /// Create a new standby configuration.
+        pub fn new() -> Self {
+            Self {
+                state: BoolSetting::new("SB"),
+            }
+        }
+    }
+
+    /// Autolight sensitivity configuration.
+    pub struct LightSensitivityConfiguration {
+        /// The light sensitivity state.
+        state: LightSensitivity,
+
+        /// The state set when starting configuration.
+        starting_state: LightSensitivity,
+    }
+
+    impl Configuration for LightSensitivityConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.state = config::get_light_sensitivity().await;
+            self.starting_state = self.state;
+            self.show().await;
+        }
+
+        async fn save(&mut self) {
+            if self.state != self.starting_state {
+                config::set_light_sensitivity(self.state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.state = match self.state {
+                LightSensitivity::Low => LightSensitivity::Normal,
+                LightSensitivity::Normal => LightSensitivity::High,
+                LightSensitivity::High => LightSensitivity::Low,
+            };
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.state = match self.state {
+                LightSensitivity::Low => LightSensitivity::High,
+                LightSensitivity::Normal => LightSensitivity::Low,
+                LightSensitivity::High => LightSensitivity::Normal,
+            };
+            self.show().await;
+        }
+    }
+
+    impl LightSensitivityConfiguration {
+        /// Create a new light sensitivity configuration.
+        pub fn new() -> Self {
+            Self {
+                state: LightSensitivity::Normal,
+                starting_state: LightSensitivity::Normal,
+            }
+        }
+
+        /// Show light sensitivity configuration in blink task.
+        async fn show(&self) {
+            let text = match self.state {
+                LightSensitivity::Low => "LS:Lo",
+                LightSensitivity::Normal => "LS:Md",
+                LightSensitivity::High => "LS:Hi",
+            };
+
+            DISPLAY_MATRIX.queue_text(text, 1000, true, false).await;
+        }
+    }
+
+    /// The steps of the light sensor calibration.
+    #[derive(Clone, Copy, PartialEq)]
+    enum CalibrationStep {
+        /// Waiting for the dark reading.
+        Dark,
+
+        /// Waiting for the bright reading.
+        Bright,
+
+        /// Both readings taken.
+        Done,
+    }
+
+    /// Light sensor calibration configuration.
+    ///
+    /// The middle button takes a reading, first with the room dark and then with it bright. The bottom button goes back to the default thresholds.
+    pub struct LightCalibrationConfiguration {
+        /// The calibration state.
+        state: Option<LightCalibration>,
+
+        /// The state set when starting configuration.
+        starting_state: Option<LightCalibration>,
+
+        /// The current step.
+        step: CalibrationStep,
+
+        /// The dark reading, once taken.
+        dark: u16,
+    }
+
+    impl Configuration for LightCalibrationConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.state = config::get_light_calibration().await;
+            self.starting_state = self.state;
+            self.step = CalibrationStep::Dark;
+            self.show().await;
+        }
+
+        async fn save(&mut self) {
+            if self.state != self.starting_state {
+                config::set_light_calibration(self.state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            match self.step {
+                CalibrationStep::Dark => {
+                    self.dark = Self::sample().await;
+                    self.step = CalibrationStep::Bright;
+                }
+                CalibrationStep::Bright => {
+                    let calibration = LightCalibration {
+                        dark: self.dark,
+                        bright: Self::sample().await,
+                    };
+
+                    if calibration.is_valid() {
+                        self.state = Some(calibration);
+                        self.step = CalibrationStep::Done;
+                    } else {
+                        // the readings are too close to tell the levels apart, so start again
+                        DISPLAY_MATRIX.queue_text("Fail", 1000, true, false).await;
+                        self.step = CalibrationStep::Dark;
+                    }
+                }
+                CalibrationStep::Done => self.step = CalibrationStep::Dark,
+            }
+
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.state = None;
+            self.step = CalibrationStep::Done;
+            self.show().await;
+        }
+    }
+
+    impl LightCalibrationConfiguration {
+        /// The number of readings to average for each calibration point. The sensor is read once a second.
+        const SAMPLES: u32 = 3;
+
+        /// Create a new light calibration configuration.
+        pub fn new() -> Self {
+            Self {
+                state: None,
+                starting_state: None,
+                step: CalibrationStep::Dark,
+                dark: 0,
+            }
+        }
+
+        /// Average a few light sensor readings, showing that it is waiting while it does.
+        async fn sample() -> u16 {
+            DISPLAY_MATRIX.queue_text("Wait", 0, true, false).await;
+
+            let mut total = 0;
+            for _ in 0..Self::SAMPLES {
+                Timer::after(Duration::from_secs(1)).await;
+                total += backlight::get_light_reading() as u32;
+            }
+
+            (total / Self::SAMPLES) as u16
+        }
+
+        /// Show light calibration configuration in blink task.
+        async fn show(&self) {
+            let text = match (self.step, self.state) {
+                (CalibrationStep::Dark, _) => "Dark?",
+                (CalibrationStep::Bright, _) => "Light?",
+                (CalibrationStep::Done, Some(_)) => "Cal:Y",
+                (CalibrationStep::Done, None) => "Cal:N",
+            };
+
+            DISPLAY_MATRIX.queue_text(text, 1000, true, false).await;
+        }
+    }
+
+    /// Lights out configuration, for how many minutes the room has to be dark for before the display turns off.
+    pub struct LightsOutConfiguration {
+        /// The lights out state, in minutes.
+        state: u8,
+
+        /// The state set when starting configuration.
+        starting_state: u8,
+    }
+
+    impl Configuration for LightsOutConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.state = config::get_lights_out().await;
+            self.starting_state = self.state;
+            self.show().await;
+        }
+
+        async fn save(&mut self) {
+            if self.state != self.starting_state {
+                config::set_lights_out(self.state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            let index = Self::index(self.state);
+            self.state = Self::OPTIONS[(index + 1) % Self::OPTIONS.len()];
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            let index = Self::index(self.state);
+            self.state = Self::OPTIONS[(index + Self::OPTIONS.len() - 1) % Self::OPTIONS.len()];
+            self.show().await;
+        }
+    }
+
+    impl LightsOutConfiguration {
+        /// The minutes that can be chosen, where 0 is off.
+        const OPTIONS: [u8; 6] = [0, 5, 10, 15, 30, 60];
+
+        /// Create a new lights out configuration.
+        pub fn new() -> Self {
+            Self {
+                state: 0,
+                starting_state: 0,
+            }
+        }
+
+        /// Get the index of the minutes in [OPTIONS](Self::OPTIONS), or the first option if it is not one of them.
+        fn index(mins: u8) -> usize {
+            Self::OPTIONS
+                .iter()
+                .position(|&option| option == mins)
+                .unwrap_or(0)
+        }
+
+        /// Show lights out configuration in blink task.
+        async fn show(&self) {
+            let mut text: String<16> = String::new();
+            if self.state == 0 {
+                _ = write!(text, "LO:Of");
+            } else {
+                _ = write!(text, "LO:{}", self.state);
+            }
+
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 1000, true, false)
+                .await;
+        }
+    }
+
+    /// Screensaver configuration, for how many minutes the clock has to be left alone for before the screensaver starts.
+    pub struct ScreensaverConfiguration {
+        /// The screensaver wait, in minutes.
+        state: u8,
+
+        /// The state set when starting configuration.
+        starting_state: u8,
+    }
+
+    impl Configuration for ScreensaverConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.state = config::get_screensaver().await;
+            self.starting_state = self.state;
+            self.show().await;
+        }
+
+        async fn save(&mut self) {
+            if self.state != self.starting_state {
+                config::set_screensaver(self.state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            let index = Self::index(self.state);
+            self.state = Self::OPTIONS[(index + 1) % Self::OPTIONS.len()];
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            let index = Self::index(self.state);
+            self.state = Self::OPTIONS[(index + Self::OPTIONS.len() - 1) % Self::OPTIONS.len()];
+            self.show().await;
+        }
+    }
+
+    impl ScreensaverConfiguration {
+        /// The minutes that can be chosen, where 0 is off.
+        const OPTIONS: [u8; 7] = [0, 1, 5, 10, 15, 30, 60];
+
+        /// Create a new screensaver configuration.
+        pub fn new() -> Self {
+            Self {
+                state: 0,
+                starting_state: 0,
+            }
+        }
+
+        /// Get the index of the minutes in [OPTIONS](Self::OPTIONS), or the first option if it is not one of them.
+        fn index(mins: u8) -> usize {
+            Self::OPTIONS
+                .iter()
+                .position(|&option| option == mins)
+                .unwrap_or(0)
+        }
+
+        /// Show screensaver configuration in blink task.
+        async fn show(&self) {
+            let mut text: String<16> = String::new();
+            if self.state == 0 {
+                _ = write!(text, "SS:Of");
+            } else {
+                _ = write!(text, "SS:{}", self.state);
+            }
+
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 1000, true, false)
+                .await;
+        }
+    }
+
+    /// Clock face configuration, for showing the time as digits, words or stacked above the temperature.
+    pub struct ClockFaceConfiguration {
+        /// The clock face state.
+        state: ClockFace,
+
+        /// The state set when starting configuration.
+        starting_state: ClockFace,
+    }
+
+    impl Configuration for ClockFaceConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.state = config::get_clock_face().await;
+            self.starting_state = self.state;
+            self.show().await;
+        }
+
+        async fn save(&mut self) {
+            if self.state != self.starting_state {
+                config::set_clock_face(self.state).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.state = match self.state {
+                ClockFace::Digits => ClockFace::Words,
+                ClockFace::Words => ClockFace::Stacked,
+                ClockFace::Stacked => ClockFace::Digits,
+            };
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.state = match self.state {
+                ClockFace::Digits => ClockFace::Stacked,
+                ClockFace::Words => ClockFace::Digits,
+                ClockFace::Stacked => ClockFace::Words,
+            };
+            self.show().await;
+        }
+    }
+
+    impl ClockFaceConfiguration {
+        /// Create a new clock face configuration.
+        pub fn new() -> Self {
+            Self {
+                state: ClockFace::Digits,
+                starting_state: ClockFace::Digits,
+            }
+        }
+
+        /// Show clock face configuration in blink task.
+        async fn show(&self) {
+            let text = match self.state {
+                ClockFace::Digits => "FC:12",
+                ClockFace::Words => "FC:AB",
+                ClockFace::Stacked => "FC:ST",
+            };
+
+            DISPLAY_MATRIX.queue_text(text, 1000, true, false).await;
+        }
+    }
+
+    /// Live light sensor reading, to check the sensor and help with calibration. Nothing can be changed.
+    pub struct LightReadingConfiguration {}
+
+    impl Configuration for LightReadingConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::LightReading);
+        }
+
+        async fn save(&mut self) {}
+
+        async fn button_two_press(&mut self, _: ButtonPress) {}
+
+        async fn button_three_press(&mut self, _: ButtonPress) {}
+    }
+
+    impl LightReadingConfiguration {
+        /// Create a new light reading configuration.
+        pub fn new() -> Self {
+            Self {}
+        }
+    }
+
+    /// Clock app button actions configuration.
+    ///
+    /// The middle button changes the action for the shown button press, the bottom button moves to the next button press.
+    pub struct ClockActionsConfiguration {
+        /// The actions being configured.
+        actions: [ClockAction; CLOCK_ACTIONS_SIZE],
+
+        /// The actions set when starting configuration.
+        starting_actions: [ClockAction; CLOCK_ACTIONS_SIZE],
+
+        /// The index of the button press being configured.
+        index: usize,
     }
 
-    impl Configuration for AutoScrollTempConfiguration {
+    impl Configuration for ClockActionsConfiguration {
         async fn start(&mut self) {
             SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
-            self.state = config::get_auto_scroll_temp().await;
-            self.starting_state = self.state;
+            self.actions = config::get_clock_actions().await;
+            self.starting_actions = self.actions;
+            self.index = 0;
             self.show().await;
         }
 
         async fn save(&mut self) {
-            if self.state != self.starting_state {
-                config::set_auto_scroll_temp(self.state).await;
+            if self.actions != self.starting_actions {
+                config::set_clock_actions(self.actions).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.actions[self.index] = self.actions[self.index].next();
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.index = (self.index + 1) % CLOCK_ACTIONS_SIZE;
+            self.show().await;
+        }
+    }
+
+    impl ClockActionsConfiguration {
+        /// Create a new clock actions configuration.
+        pub fn new() -> Self {
+            Self {
+                actions: [ClockAction::Nothing; CLOCK_ACTIONS_SIZE],
+                starting_actions: [ClockAction::Nothing; CLOCK_ACTIONS_SIZE],
+                index: 0,
+            }
+        }
+
+        /// Show clock actions configuration in blink task.
+        ///
+        /// Shown as the button number, press type and action. E.g. 2S:TP is middle button short press to show temperature.
+        async fn show(&self) {
+            let button = if self.index < 3 { '2' } else { '3' };
+            let press = match self.index % 3 {
+                0 => 'S',
+                1 => 'L',
+                _ => 'D',
+            };
+            let action = match self.actions[self.index] {
+                ClockAction::Nothing => "--",
+                ClockAction::ShowTemperature => "TP",
+                ClockAction::ToggleTemperaturePreference => "TU",
+                ClockAction::ToggleTimePreference => "TF",
+                ClockAction::ShowDate => "DT",
+                ClockAction::ShowMessage => "MS",
+                ClockAction::ShowTemperatureRange => "HL",
+                ClockAction::CycleTimeColon => "CL",
+                ClockAction::ShowEnvironment => "EN",
+            };
+
+            let mut text: String<16> = String::new();
+            _ = write!(text, "{button}{press}:{action}");
+
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 1000, true, false)
+                .await;
+        }
+    }
+
+    /// Pomodoro quick start presets configuration.
+    ///
+    /// The middle button changes the minutes of the shown preset, the bottom button moves to the next preset.
+    pub struct TimerPresetsConfiguration {
+        /// The presets being configured, in minutes.
+        presets: [u8; TIMER_PRESETS_SIZE],
+
+        /// The presets set when starting configuration.
+        starting_presets: [u8; TIMER_PRESETS_SIZE],
+
+        /// The index of the preset being configured.
+        index: usize,
+    }
+
+    impl Configuration for TimerPresetsConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.presets = config::get_timer_presets().await;
+            self.starting_presets = self.presets;
+            self.index = 0;
+            self.show().await;
+        }
+
+        async fn save(&mut self) {
+            if self.presets != self.starting_presets {
+                config::set_timer_presets(self.presets).await;
+            }
+        }
+
+        async fn button_two_press(&mut self, press: ButtonPress) {
+            let mins = &mut self.presets[self.index];
+            let step = match press {
+                ButtonPress::Double => 5,
+                _ => 1,
+            };
+
+            *mins = if *mins + step > TIMER_PRESET_MAX_MINS {
+                1
+            } else {
+                *mins + step
+            };
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.index = (self.index + 1) % TIMER_PRESETS_SIZE;
+            self.show().await;
+        }
+    }
+
+    impl TimerPresetsConfiguration {
+        /// Create a new timer presets configuration.
+        pub fn new() -> Self {
+            Self {
+                presets: [1; TIMER_PRESETS_SIZE],
+                starting_presets: [1; TIMER_PRESETS_SIZE],
+                index: 0,
+            }
+        }
+
+        /// Show timer presets configuration in blink task.
+        ///
+        /// Shown as the preset number and minutes. E.g. P2:5 is the second preset of 5 minutes.
+        async fn show(&self) {
+            let mut text: String<16> = String::new();
+            _ = write!(text, "P{}:{}", self.index + 1, self.presets[self.index]);
+
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 1000, true, false)
+                .await;
+        }
+    }
+
+    /// Keypad lock configuration.
+    pub struct KeypadLockConfiguration {
+        /// The lock state.
+        state: BoolSetting,
+    }
+
+    impl Configuration for KeypadLockConfiguration {
+        async fn start(&mut self) {
+            let state = app::is_keypad_locked();
+            self.state.start(state).await;
+        }
+
+        async fn save(&mut self) {
+            if let Some(state) = self.state.changed_state() {
+                app::set_keypad_locked(state);
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.state.toggle().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.state.toggle().await;
+        }
+    }
+
+    impl KeypadLockConfiguration {
+        /// Create a new keypad lock configuration.
+        pub fn new() -> Self {
+            Self {
+                state: BoolSetting::new("KL"),
+            }
+        }
+    }
+
+    /// System info screen. Nothing is changed, the middle and bottom buttons show the info again.
+    pub struct SystemInfoConfiguration {}
+
+    impl Configuration for SystemInfoConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.show().await;
+        }
+
+        async fn save(&mut self) {}
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.show().await;
+        }
+    }
+
+    impl SystemInfoConfiguration {
+        /// Create a new system info screen.
+        pub fn new() -> Self {
+            Self {}
+        }
+
+        /// Scroll the system info.
+        ///
+        /// The RTC drift is shown in seconds per day, e.g. Drift +1.5s/d is gaining one and a half seconds a day.
+        /// With the wifi feature, whether the clock is connected is shown after.
+        /// The firmware version, git commit and build date are shown next, e.g. v0.1.0 1a2b3c4 2023-08-01.
+        /// Then the uptime and RTC temperature, e.g. Up 2d 04:31 RTC 21.5C.
+        /// Then the free core 1 stack in bytes and why the clock last reset, e.g. Stack 2816 Rst Power.
+        /// Then the total uptime across all boots and the number of unexpected resets, e.g. Total 152d Resets 3.
+        async fn show(&self) {
+            let mut text: String<32> = String::new();
+
+            match diagnostics::get_drift().await {
+                Some(drift) => _ = write!(text, "Drift {drift:+.1}s/d"),
+                None => _ = write!(text, "Drift --"),
+            }
+
+            #[cfg(feature = "wifi")]
+            {
+                let status = if wifi::is_connected() { "on" } else { "off" };
+                _ = write!(text, " WiFi {status}");
+            }
+
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 1000, true, false)
+                .await;
+
+            text.clear();
+            _ = write!(
+                text,
+                "v{} {} {}",
+                env!("CARGO_PKG_VERSION"),
+                env!("GIT_HASH"),
+                env!("BUILD_DATE")
+            );
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 1000, false, false)
+                .await;
+
+            text.clear();
+            let uptime = Instant::now().as_secs();
+            _ = write!(
+                text,
+                "Up {}d {:02}:{:02} RTC {:.1}C",
+                uptime / 86400,
+                uptime % 86400 / 3600,
+                uptime % 3600 / 60,
+                rtc::temperature::get_temperature_or_last().await
+            );
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 1000, false, false)
+                .await;
+
+            text.clear();
+            _ = write!(
+                text,
+                "Stack {} Rst {}",
+                diagnostics::get_core1_stack_free(),
+                diagnostics::get_reset_reason().name()
+            );
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 1000, false, false)
+                .await;
+
+            text.clear();
+            _ = write!(
+                text,
+                "Total {}d Resets {}",
+                diagnostics::get_total_uptime() / 86400,
+                diagnostics::get_unexpected_resets()
+            );
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 1000, false, false)
+                .await;
+        }
+    }
+
+    /// Firmware update configuration.
+    ///
+    /// Defaults to not updating, so the reboot into the USB bootloader has to be chosen by changing the option, then confirmed before moving on.
+    pub struct FirmwareUpdateConfiguration {
+        /// Whether to reboot into the USB bootloader.
+        state: bool,
+    }
+
+    impl Configuration for FirmwareUpdateConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.state = false;
+            self.show().await;
+        }
+
+        async fn save(&mut self) {
+            if self.state && prompt::confirm().await {
+                DISPLAY_MATRIX
+                    .queue_text("FW UPDATE", 1000, true, false)
+                    .await;
+                Timer::after(Duration::from_secs(2)).await;
+
+                // write any pending config, as the bootloader will not return to the flush task
+                config::flush().await;
+
+                // show as a usb drive to copy the uf2 file to, without reaching the BOOTSEL button
+                rom_data::reset_to_usb_boot(0, 0);
             }
         }
 
@@ -772,24 +3138,131 @@ mod configurations {
         }
     }
 
-    impl AutoScrollTempConfiguration {
-        /// Create a new day configuration.
+    impl FirmwareUpdateConfiguration {
+        /// Create a new firmware update configuration.
         pub fn new() -> Self {
-            Self {
-                state: false,
-                starting_state: false,
+            Self { state: false }
+        }
+
+        /// Show firmware update configuration in blink task.
+        async fn show(&self) {
+            let mut text: String<16> = String::new();
+            _ = write!(text, "FW:");
+            if self.state {
+                _ = write!(text, "Y");
+            } else {
+                _ = write!(text, "N");
+            }
+
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 1000, true, false)
+                .await;
+        }
+    }
+
+    /// Factory reset configuration.
+    ///
+    /// Defaults to not resetting, so the reset has to be chosen by changing the option, then confirmed before moving on.
+    pub struct ResetConfiguration {
+        /// Whether to factory reset.
+        state: bool,
+    }
+
+    impl Configuration for ResetConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.state = false;
+            self.show().await;
+        }
+
+        async fn save(&mut self) {
+            if self.state && prompt::confirm().await {
+                DISPLAY_MATRIX.queue_text("Reset", 1000, true, false).await;
+                Timer::after(Duration::from_secs(1)).await;
+                config::factory_reset().await;
             }
         }
 
-        /// Show day configuration in blink task.
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.state = !self.state;
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.state = !self.state;
+            self.show().await;
+        }
+    }
+
+    impl ResetConfiguration {
+        /// Create a new factory reset configuration.
+        pub fn new() -> Self {
+            Self { state: false }
+        }
+
+        /// Show factory reset configuration in blink task.
         async fn show(&self) {
             let mut text: String<16> = String::new();
-            _ = write!(text, "EX:");
+            _ = write!(text, "RST:");
             if self.state {
-                _ = write!(text, "On");
+                _ = write!(text, "Y");
             } else {
-                _ = write!(text, "Of");
+                _ = write!(text, "N");
+            }
+
+            DISPLAY_MATRIX
+                .queue_text(text.as_str(), 1000, true, false)
+                .await;
+        }
+    }
+
+    /// RTC aging offset configuration.
+    ///
+    /// The middle button increases the offset and the bottom button decreases it.
+    pub struct AgingOffsetConfiguration {
+        /// The aging offset being configured.
+        state: NumberSpinner<i8>,
+    }
+
+    impl Configuration for AgingOffsetConfiguration {
+        async fn start(&mut self) {
+            SETTINGS_DISPLAY_QUEUE.signal(super::BlinkTask::None);
+            self.state.start(config::get_aging_offset().await);
+            self.show().await;
+        }
+
+        async fn save(&mut self) {
+            if let Some(state) = self.state.changed_value() {
+                config::set_aging_offset(state).await;
+                if rtc::set_aging_offset(state).await.is_err() {
+                    show_rtc_error().await;
+                }
+            }
+        }
+
+        async fn button_two_press(&mut self, _: ButtonPress) {
+            self.state.increase();
+            self.show().await;
+        }
+
+        async fn button_three_press(&mut self, _: ButtonPress) {
+            self.state.decrease();
+            self.show().await;
+        }
+    }
+
+    impl AgingOffsetConfiguration {
+        /// Create a new aging offset configuration.
+        pub fn new() -> Self {
+            Self {
+                state: NumberSpinner::new(i8::MIN, i8::MAX, 1, false),
             }
+        }
+
+        /// Show aging offset configuration in blink task.
+        async fn show(&self) {
+            let mut text: String<16> = String::new();
+            _ = write!(text, "AG:{}", self.state.value());
 
             DISPLAY_MATRIX
                 .queue_text(text.as_str(), 1000, true, false)
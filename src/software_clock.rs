@@ -0,0 +1,81 @@
+use core::cell::RefCell;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, mutex::Mutex};
+use embassy_time::Instant;
+
+/// The state of the software clock.
+#[derive(Clone, Copy)]
+struct State {
+    /// The datetime at the last sync.
+    datetime: NaiveDateTime,
+
+    /// When the last sync happened.
+    synced_at: Instant,
+
+    /// The latest datetime given out, so the clock never goes backwards when it is disciplined.
+    last: NaiveDateTime,
+}
+
+/// Static reference to the software clock state.
+///
+/// Will be None until the clock is first synced.
+static STATE: Mutex<ThreadModeRawMutex, RefCell<Option<State>>> = Mutex::new(RefCell::new(None));
+
+/// Get the current datetime from the software clock, including the fraction of the second.
+///
+/// If the clock has never been synced, it counts from what the RTC resets to (2000-01-01) at boot.
+pub async fn now() -> NaiveDateTime {
+    let guard = STATE.lock().await;
+    let mut state = guard.borrow_mut();
+
+    match state.as_mut() {
+        Some(state) => {
+            let now = (state.datetime + elapsed(state.synced_at)).max(state.last);
+            state.last = now;
+            now
+        }
+        None => {
+            NaiveDate::from_ymd_opt(2000, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                + elapsed(Instant::from_ticks(0))
+        }
+    }
+}
+
+/// Discipline the software clock to a datetime that was correct at the instant passed.
+///
+/// The clock will not go backwards. If it has run ahead, it holds until the real time catches up.
+pub async fn discipline(datetime: NaiveDateTime, at: Instant) {
+    let guard = STATE.lock().await;
+    let mut state = guard.borrow_mut();
+
+    let last = match *state {
+        Some(state) => state.last,
+        None => datetime,
+    };
+
+    state.replace(State {
+        datetime,
+        synced_at: at,
+        last,
+    });
+}
+
+/// Set the software clock to a datetime that was correct at the instant passed.
+///
+/// Unlike [discipline], this can move the clock backwards, such as when the time is changed in settings.
+pub async fn set(datetime: NaiveDateTime, at: Instant) {
+    STATE.lock().await.replace(Some(State {
+        datetime,
+        synced_at: at,
+        last: datetime,
+    }));
+}
+
+/// Get the time since the instant passed, as a chrono duration.
+fn elapsed(since: Instant) -> chrono::Duration {
+    chrono::Duration::microseconds(since.elapsed().as_micros() as i64)
+}
@@ -1,7 +1,12 @@
-use embassy_rp::{gpio::Output, peripherals::*};
-use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, signal::Signal};
+use chrono::{Datelike, Timelike};
+use defmt::info;
+use embassy_futures::select::select;
+use embassy_rp::gpio::{AnyPin, Output};
+use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, channel::Channel, signal::Signal};
 use embassy_time::{Duration, Timer};
 
+use crate::{config, rtc};
+
 #[allow(dead_code)]
 
 /// The type of sound the speaker should make.
@@ -23,19 +28,102 @@ pub enum SoundType {
 
     /// Repeat a custom duration beep X times.
     RepeatBeep(u8, u64),
+
+    /// Play a Westminster chime melody.
+    Chime(Chime),
+}
+
+/// The Westminster chime sequences, named by the quarter of the hour they are played on.
+pub enum Chime {
+    /// Played at quarter past. One phrase.
+    Quarter,
+
+    /// Played at half past. Two phrases.
+    Half,
+
+    /// Played at quarter to. Three phrases.
+    ThreeQuarter,
+
+    /// Played on the hour. Four phrases.
+    Hour,
 }
 
-/// Signal for when the speaker should sound.
-static SOUND_SPEAKER: Signal<ThreadModeRawMutex, SoundType> = Signal::new();
+impl Chime {
+    /// The number of melody phrases to play for the chime.
+    fn phrases(&self) -> usize {
+        match self {
+            Chime::Quarter => 1,
+            Chime::Half => 2,
+            Chime::ThreeQuarter => 3,
+            Chime::Hour => 4,
+        }
+    }
+}
+
+/// A single note in a melody. (on duration in milliseconds, off duration in milliseconds)
+type Note = (u64, u64);
+
+/// A single phrase of the Westminster chime.
+///
+/// The buzzer only has one pitch, so the melody is carried by the rhythm alone.
+const CHIME_PHRASE: [Note; 4] = [(250, 150), (250, 150), (250, 150), (600, 600)];
+
+/// Named struct for cancel sound signal.
+struct CancelSound;
+
+/// A sound waiting in the queue.
+struct QueuedSound {
+    /// The sound to play.
+    sound_type: SoundType,
+
+    /// Whether the sound is an alarm, such as a timer finishing, which is played even when the speaker is muted for the night.
+    alarm: bool,
+}
+
+/// Sound queue channel. Can store up to 4 sounds waiting to be played.
+static SOUND_QUEUE: Channel<ThreadModeRawMutex, QueuedSound, 4> = Channel::new();
+
+/// Cancel signal. Will stop the sound currently being played.
+static CANCEL_SOUND: Signal<ThreadModeRawMutex, CancelSound> = Signal::new();
 
 /// Make the speaker play audio.
+///
+/// The sound is added to the end of the queue, so will play after any sound that is already playing.
+/// If the queue is full, the sound is dropped.
+///
+/// The sound is not played while the speaker is muted for the night. Use [alarm] for sounds that must be heard.
 #[allow(dead_code)]
 pub fn sound(t: SoundType) {
-    SOUND_SPEAKER.signal(t);
+    queue(t, false);
+}
+
+/// Make the speaker play audio, even while it is muted for the night, such as when a timer finishes.
+///
+/// The sound is added to the end of the queue in the same way as [sound].
+pub fn alarm(t: SoundType) {
+    queue(t, true);
+}
+
+/// Add a sound to the end of the queue, dropping it if the queue is full.
+fn queue(sound_type: SoundType, alarm: bool) {
+    if SOUND_QUEUE
+        .try_send(QueuedSound { sound_type, alarm })
+        .is_err()
+    {
+        info!("Sound queue full, dropping sound");
+    }
+}
+
+/// Stop the sound currently playing and remove all sounds waiting in the queue.
+pub fn cancel() {
+    // sound queue does not have clear, so create loop that runs until try_recv fails, then break
+    while SOUND_QUEUE.try_recv().is_ok() {}
+
+    CANCEL_SOUND.signal(CancelSound);
 }
 
 /// Play audio on the speaker.
-async fn play(speaker: &mut Output<'static, PIN_14>, times: u8, duration: Duration) {
+async fn play(speaker: &mut Output<'static, AnyPin>, times: u8, duration: Duration) {
     for _ in 0..times {
         speaker.set_high();
         Timer::after(duration).await;
@@ -44,29 +132,61 @@ async fn play(speaker: &mut Output<'static, PIN_14>, times: u8, duration: Durati
     }
 }
 
-/// Wait for a signal for the speaker to emit sound.
+/// Play each note of a melody on the speaker.
+async fn play_melody(speaker: &mut Output<'static, AnyPin>, melody: &[Note]) {
+    for &(on, off) in melody {
+        speaker.set_high();
+        Timer::after(Duration::from_millis(on)).await;
+        speaker.set_low();
+        Timer::after(Duration::from_millis(off)).await;
+    }
+}
+
+/// Play the passed sound type on the speaker.
+async fn play_sound(speaker: &mut Output<'static, AnyPin>, sound_type: SoundType) {
+    match sound_type {
+        SoundType::ShortBeep => play(speaker, 1, Duration::from_millis(100)).await,
+        SoundType::LongBeep => play(speaker, 1, Duration::from_millis(500)).await,
+        SoundType::Beep(duration) => play(speaker, 1, Duration::from_millis(duration)).await,
+        SoundType::RepeatShortBeep(times) => play(speaker, times, Duration::from_millis(100)).await,
+        SoundType::RepeatLongBeep(times) => play(speaker, times, Duration::from_millis(500)).await,
+        SoundType::RepeatBeep(times, duration) => {
+            play(speaker, times, Duration::from_millis(duration)).await
+        }
+        SoundType::Chime(chime) => {
+            for _ in 0..chime.phrases() {
+                play_melody(speaker, &CHIME_PHRASE).await;
+            }
+        }
+    }
+}
+
+/// Wait for sounds in the queue for the speaker to emit.
+///
+/// Each sound can be stopped early by calling [cancel].
 ///
 /// This task has no way of cancellation.
 #[embassy_executor::task]
-pub async fn speaker_task(mut speaker: Output<'static, PIN_14>) -> ! {
+pub async fn speaker_task(mut speaker: Output<'static, AnyPin>) -> ! {
     loop {
-        let sound_type = SOUND_SPEAKER.wait().await;
+        let sound = SOUND_QUEUE.recv().await;
 
-        match sound_type {
-            SoundType::ShortBeep => play(&mut speaker, 1, Duration::from_millis(100)).await,
-            SoundType::LongBeep => play(&mut speaker, 1, Duration::from_millis(500)).await,
-            SoundType::Beep(duration) => {
-                play(&mut speaker, 1, Duration::from_millis(duration)).await
-            }
-            SoundType::RepeatShortBeep(times) => {
-                play(&mut speaker, times, Duration::from_millis(100)).await
-            }
-            SoundType::RepeatLongBeep(times) => {
-                play(&mut speaker, times, Duration::from_millis(500)).await
-            }
-            SoundType::RepeatBeep(times, duration) => {
-                play(&mut speaker, times, Duration::from_millis(duration)).await
+        if !sound.alarm {
+            let datetime = rtc::get_datetime_or_estimate().await;
+            if config::is_quiet_time(datetime.weekday(), datetime.hour()).await {
+                continue;
             }
         }
+
+        CANCEL_SOUND.reset();
+
+        select(
+            play_sound(&mut speaker, sound.sound_type),
+            CANCEL_SOUND.wait(),
+        )
+        .await;
+
+        // make sure the speaker is not left on if cancelled mid beep
+        speaker.set_low();
     }
 }
@@ -0,0 +1,59 @@
+use chrono::Timelike;
+use embassy_time::{Duration, Timer};
+
+use crate::{
+    config::{self, NIGHT_END_HOUR},
+    display::backlight,
+    notification::{self, Notification},
+    pomodoro, rtc,
+    speaker::SoundType,
+    stopwatch,
+};
+
+/// How often to check for a timer left running in the dark, in seconds.
+const CHECK_SECS: u64 = 60;
+
+/// Remind that a pomodoro or stopwatch is still running once the room has gone dark, so it is not left going all night.
+///
+/// Only reminds from the hour set in settings until the end of the night, and only once until the light comes back or the timer stops.
+#[embassy_executor::task]
+pub async fn still_running_task() -> ! {
+    let mut reminded = false;
+
+    loop {
+        Timer::after(Duration::from_secs(CHECK_SECS)).await;
+
+        let should_remind = backlight::is_dark()
+            && is_reminder_hour().await
+            && (pomodoro::is_running().await || stopwatch::is_running().await);
+
+        if !should_remind {
+            reminded = false;
+        } else if !reminded {
+            reminded = true;
+            // scroll it a few times, as it is easy to miss in the dark
+            notification::notify(
+                Notification::new("STILL RUNNING", None)
+                    .with_sound(SoundType::RepeatShortBeep(2))
+                    .repeat(3),
+            );
+        }
+    }
+}
+
+/// Determine if it is between the reminder hour set in settings and the end of the night.
+///
+/// Always false when the reminder is turned off.
+async fn is_reminder_hour() -> bool {
+    let from = config::get_still_running_hour().await as u32;
+    if from == 0 {
+        return false;
+    }
+
+    let hour = rtc::get_datetime_or_estimate().await.hour();
+    if from >= NIGHT_END_HOUR {
+        hour >= from || hour < NIGHT_END_HOUR
+    } else {
+        hour >= from && hour < NIGHT_END_HOUR
+    }
+}
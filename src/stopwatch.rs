@@ -9,16 +9,23 @@ use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, mutex::Mutex, pubsub
 use embassy_time::{Duration, Timer};
 
 use crate::{
-    app::{App, StopAppTasks},
+    app::{self, App, StopAppTasks},
     buttons::ButtonPress,
     display::display_matrix::{TimeColon, DISPLAY_MATRIX},
     speaker::{self, SoundType},
+    timer_snapshot::{self, TimerApp},
 };
 
 /// Channel for firing events of when tasks should be stopped.
 static STOP_APP_CHANNEL: PubSubChannel<ThreadModeRawMutex, StopAppTasks, 1, 1, 1> =
     PubSubChannel::new();
 
+/// The longest time the stopwatch counts up to, 59:59, in seconds.
+const MAX_SECS: u32 = 59 * 60 + 59;
+
+/// How long the total and the current lap are each shown for before switching, once a lap has been marked.
+const LAP_SWITCH_SECS: u32 = 2;
+
 /// Depict the current running state of the stopwatch timer.
 #[derive(Clone, Copy)]
 enum RunningState {
@@ -45,6 +52,9 @@ struct StopwatchState {
 
     /// The number of seconds. Used for display purposes and should not be set during configuration.
     seconds: u32,
+
+    /// The total seconds when the last lap was marked, or [None](Option::None) if no laps have been marked.
+    lap_start_secs: Option<u32>,
 }
 
 impl StopwatchState {
@@ -54,6 +64,7 @@ impl StopwatchState {
             running: RunningState::NotStarted,
             minutes: 0,
             seconds: 0,
+            lap_start_secs: None,
         }
     }
 
@@ -61,6 +72,7 @@ impl StopwatchState {
     pub fn reset(&mut self) {
         self.minutes = 0;
         self.seconds = 0;
+        self.lap_start_secs = None;
         self.running = RunningState::NotStarted;
     }
 }
@@ -92,9 +104,16 @@ impl App for StopwatchApp {
 
         match get_running_state().await {
             RunningState::NotStarted => {}
-            RunningState::Running => {}
-            RunningState::Paused => spawner.spawn(stopwatch()).unwrap(),
-            RunningState::Finished => STOPWATCH_STATE.lock().await.borrow_mut().get_mut().reset(),
+            // only running on start when restored after a power cut, as stopping the app pauses it
+            RunningState::Running => {
+                DISPLAY_MATRIX.show_icon("CountUp");
+                app::spawn_app_task(spawner, "stopwatch", stopwatch).await
+            }
+            RunningState::Paused => app::spawn_app_task(spawner, "stopwatch", stopwatch).await,
+            RunningState::Finished => {
+                STOPWATCH_STATE.lock().await.borrow_mut().get_mut().reset();
+                save_snapshot().await;
+            }
         }
 
         show_time().await;
@@ -114,7 +133,7 @@ impl App for StopwatchApp {
         match get_running_state().await {
             RunningState::NotStarted => {
                 set_running(RunningState::Running).await;
-                spawner.spawn(stopwatch()).unwrap()
+                app::spawn_app_task(spawner, "stopwatch", stopwatch).await
             }
             RunningState::Running => {
                 // due to running delay, 1s is lost on button press, so take them back away
@@ -127,12 +146,15 @@ impl App for StopwatchApp {
                     seconds -= 1;
                 }
                 set_time(minutes, seconds).await;
+                set_running(RunningState::Paused).await;
                 show_time().await;
-                set_running(RunningState::Paused).await
             }
             RunningState::Paused => set_running(RunningState::Running).await,
             RunningState::Finished => {
+                // stop the finished alarm if it is still sounding
+                speaker::cancel();
                 STOPWATCH_STATE.lock().await.borrow_mut().get_mut().reset();
+                save_snapshot().await;
                 show_time().await;
             }
         }
@@ -140,6 +162,9 @@ impl App for StopwatchApp {
 
     async fn button_two_press(&mut self, press: ButtonPress, _: Spawner) {
         if let RunningState::Running = get_running_state().await {
+            if let ButtonPress::Short = press {
+                mark_lap().await;
+            }
             return;
         }
 
@@ -149,13 +174,20 @@ impl App for StopwatchApp {
             ButtonPress::Long => {
                 minutes = 0;
                 seconds = 0;
+                clear_laps().await;
             }
             ButtonPress::Short => {}
             ButtonPress::Double => {}
+            ButtonPress::Triple => {}
+            ButtonPress::Combo(_) => {}
         }
 
         set_time(minutes, seconds).await;
         show_time().await;
+
+        if let RunningState::Paused = get_running_state().await {
+            save_snapshot().await;
+        }
     }
 
     async fn button_three_press(&mut self, press: ButtonPress, _: Spawner) {
@@ -169,14 +201,49 @@ impl App for StopwatchApp {
             ButtonPress::Long => {
                 minutes = 0;
                 seconds = 0;
+                clear_laps().await;
             }
             ButtonPress::Short => {}
             ButtonPress::Double => {}
+            ButtonPress::Triple => {}
+            ButtonPress::Combo(_) => {}
         }
 
         set_time(minutes, seconds).await;
         show_time().await;
+
+        if let RunningState::Paused = get_running_state().await {
+            save_snapshot().await;
+        }
+    }
+}
+
+/// Restore the stopwatch from before a power cut, adding on the time it would have been running for.
+///
+/// If it would have reached the maximum, the finished alarm sounds. Returns true if it is still running, so the app should be started.
+pub async fn restore() -> bool {
+    let snapshot = match timer_snapshot::load(TimerApp::Stopwatch).await {
+        Some(snapshot) => snapshot,
+        None => return false,
+    };
+
+    let elapsed = (snapshot.minutes * 60 + snapshot.seconds)
+        .saturating_add(snapshot.elapsed_secs().await)
+        .min(MAX_SECS);
+    set_time(elapsed / 60, elapsed % 60).await;
+
+    if !snapshot.running {
+        STOPWATCH_STATE.lock().await.borrow_mut().get_mut().running = RunningState::Paused;
+        return false;
+    }
+
+    if elapsed == MAX_SECS {
+        set_running(RunningState::Finished).await;
+        return false;
     }
+
+    STOPWATCH_STATE.lock().await.borrow_mut().get_mut().running = RunningState::Running;
+    true
 }
 
 /// Get the running state value from the static stopwatch state.
@@ -184,6 +251,11 @@ async fn get_running_state() -> RunningState {
     STOPWATCH_STATE.lock().await.borrow().running
 }
 
+/// Determine if the stopwatch is counting, not paused or stopped.
+pub async fn is_running() -> bool {
+    matches!(get_running_state().await, RunningState::Running)
+}
+
 /// Get the (minutes, seconds) state value from the static stopwatch state.
 async fn get_time() -> (u32, u32) {
     let minutes = STOPWATCH_STATE.lock().await.borrow().minutes;
@@ -200,6 +272,30 @@ async fn set_time(minutes: u32, seconds: u32) {
     state.seconds = seconds;
 }
 
+/// Start a new lap from the current time.
+async fn mark_lap() {
+    let (minutes, seconds) = get_time().await;
+    STOPWATCH_STATE
+        .lock()
+        .await
+        .borrow_mut()
+        .get_mut()
+        .lap_start_secs = Some(minutes * 60 + seconds);
+
+    speaker::sound(SoundType::ShortBeep);
+}
+
+/// Forget any laps, so only the total is shown.
+async fn clear_laps() {
+    STOPWATCH_STATE
+        .lock()
+        .await
+        .borrow_mut()
+        .get_mut()
+        .lap_start_secs = None;
+    DISPLAY_MATRIX.hide_icon("MoveOn");
+}
+
 /// Set the running state on the static stopwatch state.
 /// Will show/hide the CountDown icon on the display depending on the state passed.
 async fn set_running(running: RunningState) {
@@ -215,15 +311,73 @@ async fn set_running(running: RunningState) {
     }
 
     if let RunningState::Finished = running {
-        speaker::sound(SoundType::RepeatLongBeep(3));
+        speaker::alarm(SoundType::RepeatLongBeep(3));
+    }
+
+    drop(guard);
+    save_snapshot().await;
+}
+
+/// Save the stopwatch state, so it can be restored after a power cut.
+async fn save_snapshot() {
+    let (minutes, seconds) = get_time().await;
+
+    match get_running_state().await {
+        RunningState::Running => {
+            timer_snapshot::save(TimerApp::Stopwatch, true, minutes, seconds).await
+        }
+        RunningState::Paused => {
+            timer_snapshot::save(TimerApp::Stopwatch, false, minutes, seconds).await
+        }
+        RunningState::NotStarted | RunningState::Finished => {
+            timer_snapshot::clear(TimerApp::Stopwatch).await
+        }
     }
 }
 
 /// Will show the time grabbed from the static stopwatch state.
+///
+/// Once a lap has been marked, the running stopwatch switches between the total and the current lap every [LAP_SWITCH_SECS].
+/// The MoveOn icon is shown while the current lap is shown.
 async fn show_time() {
     let (minutes, seconds) = get_time().await;
+    let total_secs = minutes * 60 + seconds;
+
+    let (running, lap_start_secs) = {
+        let guard = STOPWATCH_STATE.lock().await;
+        let state = guard.borrow();
+        (state.running, state.lap_start_secs)
+    };
+
+    let lap_secs = match (running, lap_start_secs) {
+        (RunningState::Running, Some(lap_start_secs))
+            if (total_secs / LAP_SWITCH_SECS) % 2 == 1 =>
+        {
+            Some(total_secs - lap_start_secs)
+        }
+        _ => None,
+    };
+
+    let shown_secs = match lap_secs {
+        Some(lap_secs) => {
+            DISPLAY_MATRIX.show_icon("MoveOn");
+            lap_secs
+        }
+        None => {
+            DISPLAY_MATRIX.hide_icon("MoveOn");
+            total_secs
+        }
+    };
+
     DISPLAY_MATRIX
-        .queue_time(minutes, seconds, TimeColon::Full, 0, true, false)
+        .queue_time(
+            shown_secs / 60,
+            shown_secs % 60,
+            TimeColon::Full,
+            0,
+            true,
+            false,
+        )
         .await;
 }
 
@@ -232,6 +386,7 @@ async fn show_time() {
 /// Will continue to run as long as the running state is running or paused.
 #[embassy_executor::task]
 async fn stopwatch() {
+    let _running = app::RunningAppTask::new();
     let mut stop_task_sub = STOP_APP_CHANNEL.subscriber().unwrap();
 
     show_time().await;
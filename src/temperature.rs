@@ -1,13 +1,44 @@
+use chrono::{Datelike, NaiveDate, Timelike};
+use core::cell::RefCell;
+use defmt::info;
+use embassy_sync::{blocking_mutex::raw::ThreadModeRawMutex, mutex::Mutex};
+use embassy_time::{Duration, Instant, Timer};
+use heapless::{HistoryBuffer, Vec};
+
 use crate::{
-    config::{self, TemperaturePreference},
-    rtc,
+    config::{self, TemperaturePrecision, TemperaturePreference, TemperatureSource},
+    eeprom,
+    hooks::{self, Boundary, Hook},
+    humidity, rtc,
+    temperature_log::{self, Sample},
 };
 
+/// The lowest and highest temperature in celcius recorded since the last reset, if any have been recorded.
+///
+/// A copy is kept in the EEPROM so the range survives a restart.
+static TEMPERATURE_RANGE: Mutex<ThreadModeRawMutex, RefCell<Option<(f32, f32)>>> =
+    Mutex::new(RefCell::new(None));
+
+/// How many hourly temperatures are kept, enough for one per column of the graph.
+pub const HISTORY_LEN: usize = 22;
+
+/// How often a temperature is added to the history, in seconds.
+const HISTORY_INTERVAL_SECS: u64 = 60 * 60;
+
+/// The temperatures in celcius taken every hour since boot, for the graph. Older temperatures are overwritten.
+static HISTORY: Mutex<ThreadModeRawMutex, RefCell<HistoryBuffer<f32, HISTORY_LEN>>> =
+    Mutex::new(RefCell::new(HistoryBuffer::new()));
+
 /// Get the temperature preference.
 pub async fn get_temperature_preference() -> TemperaturePreference {
     config::get_temperature_preference().await
 }
 
+/// Get the temperature precision.
+pub async fn get_temperature_precision() -> TemperaturePrecision {
+    config::get_temperature_precision().await
+}
+
 /// Get the temperature based on the current user preference.
 pub async fn get_temperature_off_preference() -> f32 {
     let pref = get_temperature_preference().await;
@@ -18,12 +49,186 @@ pub async fn get_temperature_off_preference() -> f32 {
 }
 
 /// Get the temperature in celcius.
+///
+/// Uses the external humidity sensor if it is the chosen source and has a reading, otherwise the RTC.
 pub async fn get_celcius() -> f32 {
-    rtc::temperature::get_temperature().await
+    if let TemperatureSource::External = config::get_temperature_source().await {
+        if let Some(reading) = humidity::get_reading().await {
+            return reading.temperature;
+        }
+    }
+
+    rtc::temperature::get_temperature_or_last().await
 }
 
 /// Get the temperature in fahrenheit.
 pub async fn get_fahrenheit() -> f32 {
-    let temp = rtc::temperature::get_temperature().await;
+    let temp = get_celcius().await;
     (temp * 1.8) + 32.0
 }
+
+/// Register the work done on time boundaries, starting a new day of the temperature range at midnight.
+pub fn register_hooks() {
+    hooks::register(Boundary::Midnight, Hook::ResetTemperatureRange);
+}
+
+/// Load the temperature range saved in the EEPROM, if it was recorded today.
+pub async fn load_temperature_range() {
+    let mut bytes = [0u8; eeprom::TEMPERATURE_RANGE.1 - eeprom::TEMPERATURE_RANGE.0];
+    if let Err(e) = eeprom::read(eeprom::TEMPERATURE_RANGE.0, &mut bytes).await {
+        info!("Failed to load temperature range: {}", e);
+        return;
+    }
+
+    let today = rtc::get_datetime_or_estimate().await.date();
+    if let Some(range) = temperature_range_from_bytes(&bytes, today) {
+        TEMPERATURE_RANGE.lock().await.replace(Some(range));
+    }
+}
+
+/// Record the current temperature into the daily lowest and highest range.
+///
+/// The range is only saved to the EEPROM when it changes.
+pub async fn record_temperature() {
+    let temp = get_celcius().await;
+
+    let guard = TEMPERATURE_RANGE.lock().await;
+    let old_range = *guard.borrow();
+    let (min, max) = match old_range {
+        Some((min, max)) => (min.min(temp), max.max(temp)),
+        None => (temp, temp),
+    };
+    guard.replace(Some((min, max)));
+    drop(guard);
+
+    if old_range != Some((min, max)) {
+        let today = rtc::get_datetime_or_estimate().await.date();
+        let bytes = temperature_range_to_bytes(today, min, max);
+        if let Err(e) = eeprom::write(eeprom::TEMPERATURE_RANGE.0, &bytes).await {
+            info!("Failed to save temperature range: {}", e);
+        }
+    }
+}
+
+/// Add the current temperature to the history and the temperature log every hour.
+///
+/// The history starts with the newest temperatures in the log, so the graph carries on after a restart.
+/// The first temperature is taken at boot, unless the log already has one from the last hour.
+#[embassy_executor::task]
+pub async fn history_task() -> ! {
+    let mut next = Instant::now();
+
+    let mut logged: Vec<f32, HISTORY_LEN> = Vec::new();
+    for age in (0..HISTORY_LEN).rev() {
+        if let Some(sample) = temperature_log::get(age).await {
+            // there is room for every temperature, as no more than the history length are read
+            _ = logged.push(sample.celcius);
+        }
+    }
+    HISTORY.lock().await.borrow_mut().extend_from_slice(&logged);
+
+    if let Some(newest) = temperature_log::get(0).await {
+        let since = rtc::get_datetime_or_estimate().await - newest.at;
+        let interval = chrono::Duration::seconds(HISTORY_INTERVAL_SECS as i64);
+        if since >= chrono::Duration::zero() && since < interval {
+            next += Duration::from_secs((interval - since).num_seconds() as u64);
+            Timer::at(next).await;
+        }
+    }
+
+    loop {
+        let temp = get_celcius().await;
+        HISTORY.lock().await.borrow_mut().write(temp);
+
+        let now = rtc::get_datetime_or_estimate().await;
+        temperature_log::record(Sample {
+            at: now.date().and_hms_opt(now.hour(), 0, 0).unwrap(),
+            celcius: temp,
+        })
+        .await;
+
+        next += Duration::from_secs(HISTORY_INTERVAL_SECS);
+        Timer::at(next).await;
+    }
+}
+
+/// Get the hourly temperatures in celcius, oldest first.
+pub async fn get_history() -> Vec<f32, HISTORY_LEN> {
+    HISTORY
+        .lock()
+        .await
+        .borrow()
+        .oldest_ordered()
+        .copied()
+        .collect()
+}
+
+/// Get a page of the hourly temperatures in celcius from the temperature log, oldest first.
+///
+/// Page 0 is the same as [get_history], with each page after going back another [HISTORY_LEN] hours.
+pub async fn get_history_page(page: usize) -> Vec<f32, HISTORY_LEN> {
+    if page == 0 {
+        return get_history().await;
+    }
+
+    let mut history = Vec::new();
+    for age in (page * HISTORY_LEN..(page + 1) * HISTORY_LEN).rev() {
+        if let Some(sample) = temperature_log::get(age).await {
+            // there is room for every temperature, as no more than the history length are read
+            _ = history.push(sample.celcius);
+        }
+    }
+
+    history
+}
+
+/// Clear the recorded temperature range, ready for a new day.
+pub async fn reset_temperature_range() {
+    TEMPERATURE_RANGE.lock().await.replace(None);
+
+    if let Err(e) = eeprom::erase(eeprom::TEMPERATURE_RANGE.0, eeprom::TEMPERATURE_RANGE.1).await {
+        info!("Failed to erase temperature range: {}", e);
+    }
+}
+
+/// Convert the temperature range to bytes, in the order (year, month, day, min, max).
+fn temperature_range_to_bytes(
+    date: NaiveDate,
+    min: f32,
+    max: f32,
+) -> [u8; eeprom::TEMPERATURE_RANGE.1 - eeprom::TEMPERATURE_RANGE.0] {
+    let mut bytes = [0u8; eeprom::TEMPERATURE_RANGE.1 - eeprom::TEMPERATURE_RANGE.0];
+    bytes[0..2].copy_from_slice(&(date.year() as u16).to_be_bytes());
+    bytes[2] = date.month() as u8;
+    bytes[3] = date.day() as u8;
+    bytes[4..8].copy_from_slice(&min.to_le_bytes());
+    bytes[8..12].copy_from_slice(&max.to_le_bytes());
+    bytes
+}
+
+/// Get the temperature range from the saved bytes.
+///
+/// Returns None if the range was not recorded on `today`, including when the bytes are erased.
+fn temperature_range_from_bytes(bytes: &[u8], today: NaiveDate) -> Option<(f32, f32)> {
+    let year = u16::from_be_bytes([bytes[0], bytes[1]]) as i32;
+    let date = NaiveDate::from_ymd_opt(year, bytes[2] as u32, bytes[3] as u32)?;
+    if date != today {
+        return None;
+    }
+
+    let min = f32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    let max = f32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
+    Some((min, max))
+}
+
+/// Get the recorded (lowest, highest) temperature based on the current user preference.
+///
+/// Returns None if no temperature has been recorded since the last reset.
+pub async fn get_temperature_range_off_preference() -> Option<(f32, f32)> {
+    let (min, max) = (*TEMPERATURE_RANGE.lock().await.borrow())?;
+    let pref = get_temperature_preference().await;
+    match pref {
+        config::TemperaturePreference::Celcius => Some((min, max)),
+        config::TemperaturePreference::Fahrenheit => Some(((min * 1.8) + 32.0, (max * 1.8) + 32.0)),
+    }
+}
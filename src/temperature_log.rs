@@ -0,0 +1,112 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use defmt::info;
+
+use crate::eeprom;
+
+/// How many hourly temperatures are kept in the log, a week's worth. Older temperatures are overwritten.
+pub const LOG_LEN: usize = 7 * 24;
+
+/// The number of bytes used for each temperature in the log.
+const ENTRY_SIZE: usize = 6;
+
+/// The number of bytes before the first entry, used for the index of the next entry to write.
+const HEADER_SIZE: usize = 2;
+
+/// A temperature taken for the log.
+#[derive(Clone, Copy)]
+pub struct Sample {
+    /// When the temperature was taken, to the hour.
+    pub at: NaiveDateTime,
+
+    /// The temperature in celcius.
+    pub celcius: f32,
+}
+
+/// Add a temperature to the log, overwriting the oldest once the log is full.
+pub async fn record(sample: Sample) {
+    let next = match read_next().await {
+        Ok(next) => next,
+        Err(e) => {
+            info!("Failed to read the temperature log: {}", e);
+            return;
+        }
+    };
+
+    let res = eeprom::write(entry_address(next), &sample_to_bytes(sample)).await;
+    if let Err(e) = res {
+        info!("Failed to write the temperature log: {}", e);
+        return;
+    }
+
+    let next = (next + 1) % LOG_LEN;
+    if let Err(e) = eeprom::write(eeprom::TEMPERATURE_LOG.0, &(next as u16).to_be_bytes()).await {
+        info!("Failed to write the temperature log: {}", e);
+    }
+}
+
+/// Get a temperature from the log, counting back from the newest at 0.
+///
+/// Returns [None](Option::None) if there is no temperature that far back, or the log could not be read.
+pub async fn get(age: usize) -> Option<Sample> {
+    if age >= LOG_LEN {
+        return None;
+    }
+
+    let next = read_next().await.ok()?;
+    let index = (next + LOG_LEN - 1 - age) % LOG_LEN;
+
+    let mut bytes = [0u8; ENTRY_SIZE];
+    eeprom::read(entry_address(index), &mut bytes).await.ok()?;
+    sample_from_bytes(&bytes)
+}
+
+/// Read the index of the next entry to write. An erased or damaged index starts the log again.
+async fn read_next() -> Result<usize, eeprom::Error> {
+    let mut bytes = [0u8; HEADER_SIZE];
+    eeprom::read(eeprom::TEMPERATURE_LOG.0, &mut bytes).await?;
+
+    let next = u16::from_be_bytes(bytes) as usize;
+    Ok(if next < LOG_LEN { next } else { 0 })
+}
+
+/// Get the EEPROM address of an entry.
+fn entry_address(index: usize) -> usize {
+    eeprom::TEMPERATURE_LOG.0 + HEADER_SIZE + index * ENTRY_SIZE
+}
+
+/// The datetime the hours in each entry are counted from.
+fn epoch() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2000, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+}
+
+/// Convert a sample to bytes, in the order (hours since 2000, hundredths of a degree).
+fn sample_to_bytes(sample: Sample) -> [u8; ENTRY_SIZE] {
+    let hours = (sample.at - epoch())
+        .num_hours()
+        .clamp(1, u32::MAX as i64 - 1) as u32;
+    let hundredths = (sample.celcius * 100.0) as i16;
+
+    let mut bytes = [0u8; ENTRY_SIZE];
+    bytes[0..4].copy_from_slice(&hours.to_be_bytes());
+    bytes[4..6].copy_from_slice(&hundredths.to_be_bytes());
+    bytes
+}
+
+/// Get a sample from the saved bytes.
+///
+/// Returns None if nothing has been written to the entry yet, so it is still erased or all zeros.
+fn sample_from_bytes(bytes: &[u8]) -> Option<Sample> {
+    let hours = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    if hours == u32::MAX || hours == 0 {
+        return None;
+    }
+
+    let hundredths = i16::from_be_bytes([bytes[4], bytes[5]]);
+    Some(Sample {
+        at: epoch() + chrono::Duration::hours(hours as i64),
+        celcius: hundredths as f32 / 100.0,
+    })
+}
@@ -0,0 +1,141 @@
+use crate::{
+    config::TimeColonPreference,
+    display::display_matrix::{DisplayMatrix, TimeColon},
+};
+
+/// The row the seconds pixel marches along, the bottom row of the display.
+pub const SECONDS_ROW: usize = 7;
+
+/// Get the colon to show for a second of the minute with the users colon preference.
+///
+/// The alternate colon shows which quarter of the minute it is. The top half blinks in the first quarter, the bottom half in the second, the halves swap in the third and the full colon blinks in the last.
+/// Each quarter starts with its colon shown, so the quarter can be read as soon as it changes.
+pub fn colon_phase(second: u32, preference: TimeColonPreference) -> TimeColon {
+    match preference {
+        TimeColonPreference::Solid | TimeColonPreference::Seconds => TimeColon::Full,
+        TimeColonPreference::Blink => {
+            if second % 2 == 0 {
+                TimeColon::Empty
+            } else {
+                TimeColon::Full
+            }
+        }
+        TimeColonPreference::Alt => {
+            // count from the start of the quarter, as 15 and 45 are odd
+            let shown = (second % 15) % 2 == 0;
+            match (second / 15, shown) {
+                (0, true) => TimeColon::Top,
+                (1, true) => TimeColon::Bottom,
+                (2, true) => TimeColon::Top,
+                (2, false) => TimeColon::Bottom,
+                (_, true) => TimeColon::Full,
+                (_, false) => TimeColon::Empty,
+            }
+        }
+    }
+}
+
+/// Get the pixels to light in the [seconds row](SECONDS_ROW) for a second of the minute with the users colon preference.
+///
+/// A single pixel moves from the first column after the icons to the last column as the minute goes by. Nothing is lit for the other colon preferences.
+pub fn seconds_pixel(second: u32, preference: TimeColonPreference) -> u32 {
+    if preference != TimeColonPreference::Seconds {
+        return 0;
+    }
+
+    let columns = (DisplayMatrix::LAST_INDEX - DisplayMatrix::DISPLAY_OFFSET + 1) as u32;
+    let col = DisplayMatrix::DISPLAY_OFFSET as u32 + second.min(59) * columns / 60;
+    1 << col
+}
+
+/// Tests run on the host by the `host-tests` crate.
+#[cfg(test)]
+mod tests {
+    use heapless::String;
+
+    use super::*;
+
+    /// Get a character for the colon, so a minute of colons can be written as a string.
+    fn symbol(colon: TimeColon) -> char {
+        match colon {
+            TimeColon::Full => 'F',
+            TimeColon::Empty => ' ',
+            TimeColon::Top => 'T',
+            TimeColon::Bottom => 'B',
+        }
+    }
+
+    /// Get the colon for each second of the minute, in order.
+    fn minute_of_colons(preference: TimeColonPreference) -> String<60> {
+        (0..60)
+            .map(|second| symbol(colon_phase(second, preference)))
+            .collect()
+    }
+
+    #[test]
+    fn solid_colon_is_always_full() {
+        assert_eq!(
+            minute_of_colons(TimeColonPreference::Solid).as_str(),
+            "F".repeat(60)
+        );
+    }
+
+    #[test]
+    fn seconds_colon_is_always_full() {
+        assert_eq!(
+            minute_of_colons(TimeColonPreference::Seconds).as_str(),
+            "F".repeat(60)
+        );
+    }
+
+    #[test]
+    fn blinking_colon_shows_on_odd_seconds() {
+        assert_eq!(
+            minute_of_colons(TimeColonPreference::Blink).as_str(),
+            " F".repeat(30)
+        );
+    }
+
+    #[test]
+    fn alternate_colon_shows_each_quarter() {
+        let quarters = [
+            "T T T T T T T T",
+            "B B B B B B B B",
+            "TBTBTBTBTBTBTBT",
+            "F F F F F F F F",
+        ];
+        assert_eq!(
+            minute_of_colons(TimeColonPreference::Alt).as_str(),
+            quarters.concat()
+        );
+    }
+
+    #[test]
+    fn seconds_pixel_marches_across_the_display() {
+        let first = 1 << DisplayMatrix::DISPLAY_OFFSET;
+        let last = 1 << DisplayMatrix::LAST_INDEX;
+        assert_eq!(seconds_pixel(0, TimeColonPreference::Seconds), first);
+        assert_eq!(seconds_pixel(59, TimeColonPreference::Seconds), last);
+        assert_eq!(seconds_pixel(60, TimeColonPreference::Seconds), last);
+
+        let mut previous = 0;
+        for second in 0..60 {
+            let pixel = seconds_pixel(second, TimeColonPreference::Seconds);
+            assert_eq!(pixel.count_ones(), 1);
+            assert!(pixel >= previous);
+            previous = pixel;
+        }
+    }
+
+    #[test]
+    fn seconds_pixel_is_off_for_other_colons() {
+        for preference in [
+            TimeColonPreference::Solid,
+            TimeColonPreference::Blink,
+            TimeColonPreference::Alt,
+        ] {
+            assert_eq!(seconds_pixel(0, preference), 0);
+            assert_eq!(seconds_pixel(59, preference), 0);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use chrono::NaiveDateTime;
+use defmt::info;
+
+use crate::rtc;
+
+/// How long to wait between time syncs, once the time has been synced.
+#[cfg(any(feature = "wifi", feature = "esp-at"))]
+pub const SYNC_SECS: u64 = 24 * 60 * 60;
+
+/// How long to wait before trying to sync the time again after failing.
+#[cfg(any(feature = "wifi", feature = "esp-at"))]
+pub const RETRY_SECS: u64 = 5 * 60;
+
+/// The NTP server to get the time from.
+#[cfg(any(feature = "wifi", feature = "esp-at"))]
+pub const NTP_SERVER: &str = "pool.ntp.org";
+
+/// The seconds between the NTP epoch (1900) and the unix epoch (1970).
+#[cfg(feature = "wifi")]
+const NTP_UNIX_OFFSET: i64 = 2_208_988_800;
+
+/// The seconds in an NTP era, after which NTP timestamps wrap around (in 2036).
+#[cfg(feature = "wifi")]
+const NTP_ERA_SECS: i64 = 1 << 32;
+
+/// Unix timestamps before this (2020-01-01) are taken to be from the next NTP era.
+#[cfg(feature = "wifi")]
+const NTP_ERA_PIVOT: i64 = 1_577_836_800;
+
+/// Errors that can happen when syncing the time.
+#[derive(defmt::Format)]
+pub enum Error {
+    /// The time could not be fetched from the network.
+    #[cfg(any(feature = "wifi", feature = "esp-at"))]
+    Network,
+
+    /// The time fetched from the network is not valid.
+    #[cfg(any(feature = "wifi", feature = "esp-at"))]
+    InvalidTime,
+
+    /// No valid time was received from the radio time signal.
+    #[cfg(any(feature = "dcf77", feature = "wwvb"))]
+    NoSignal,
+
+    /// The time could not be set into the RTC.
+    Rtc(rtc::Error),
+}
+
+impl From<rtc::Error> for Error {
+    fn from(e: rtc::Error) -> Self {
+        Error::Rtc(e)
+    }
+}
+
+/// Set the RTC to a UTC datetime from an outside time source, such as the network.
+///
+/// The datetime is moved into local time by the `UTC_OFFSET_MINUTES` environment variable set at build time, or left as UTC if it is not set.
+pub async fn sync_utc(utc: NaiveDateTime) -> Result<(), Error> {
+    let datetime = utc + utc_offset();
+    rtc::sync_datetime(datetime).await?;
+
+    info!("Time synced");
+    Ok(())
+}
+
+/// Convert the seconds and fraction of an NTP timestamp to a UTC datetime, rounded to the nearest second.
+#[cfg(feature = "wifi")]
+pub fn datetime_from_ntp(seconds: u32, fraction: u32) -> Option<NaiveDateTime> {
+    let mut unix = seconds as i64 - NTP_UNIX_OFFSET;
+    if unix < NTP_ERA_PIVOT {
+        unix += NTP_ERA_SECS;
+    }
+
+    // the fraction is in units of 1/2^32 seconds
+    if fraction >= 1 << 31 {
+        unix += 1;
+    }
+
+    NaiveDateTime::from_timestamp_opt(unix, 0)
+}
+
+/// Get the offset of local time from UTC, set at build time.
+fn utc_offset() -> chrono::Duration {
+    let minutes = match option_env!("UTC_OFFSET_MINUTES").map(|minutes| minutes.parse::<i64>()) {
+        Some(Ok(minutes)) => minutes,
+        _ => 0,
+    };
+
+    chrono::Duration::minutes(minutes)
+}
@@ -0,0 +1,106 @@
+use chrono::NaiveDateTime;
+use defmt::info;
+
+use crate::{eeprom, rtc};
+
+/// The size of each saved snapshot, in the order (running, minutes, seconds, unused, timestamp).
+const SNAPSHOT_SIZE: usize = 12;
+
+/// The apps with timers that are restored after a power cut.
+#[derive(Clone, Copy)]
+pub enum TimerApp {
+    /// The pomodoro countdown.
+    Pomodoro,
+
+    /// The stopwatch.
+    Stopwatch,
+}
+
+impl TimerApp {
+    /// The EEPROM address of the snapshot for the app.
+    const fn address(&self) -> usize {
+        match self {
+            TimerApp::Pomodoro => eeprom::TIMER_SNAPSHOTS.0,
+            TimerApp::Stopwatch => eeprom::TIMER_SNAPSHOTS.0 + SNAPSHOT_SIZE,
+        }
+    }
+}
+
+/// The state of a timer when it was last started, paused or changed.
+///
+/// While running, the time is worked out from the RTC time the snapshot was saved at.
+/// So it only needs saving when the timer changes, not every second.
+pub struct Snapshot {
+    /// Whether the timer was running. A paused timer is restored as it was.
+    pub running: bool,
+
+    /// The timer minutes when saved.
+    pub minutes: u32,
+
+    /// The timer seconds when saved.
+    pub seconds: u32,
+
+    /// The RTC time when saved.
+    pub saved_at: NaiveDateTime,
+}
+
+impl Snapshot {
+    /// Get how many seconds the timer would have been running for since the snapshot was saved.
+    ///
+    /// Always 0 for a paused timer.
+    pub async fn elapsed_secs(&self) -> u32 {
+        if !self.running {
+            return 0;
+        }
+
+        let elapsed = rtc::get_datetime_or_estimate().await - self.saved_at;
+        elapsed.num_seconds().clamp(0, u32::MAX as i64) as u32
+    }
+}
+
+/// Save the timer state of the app, so it can be restored after a power cut.
+pub async fn save(app: TimerApp, running: bool, minutes: u32, seconds: u32) {
+    let saved_at = rtc::get_datetime_or_estimate().await;
+
+    let mut bytes = [0u8; SNAPSHOT_SIZE];
+    bytes[0] = running as u8;
+    bytes[1] = minutes as u8;
+    bytes[2] = seconds as u8;
+    bytes[4..12].copy_from_slice(&saved_at.timestamp().to_le_bytes());
+
+    if let Err(e) = eeprom::write(app.address(), &bytes).await {
+        info!("Failed to save timer snapshot: {}", e);
+    }
+}
+
+/// Remove the saved timer state of the app, when the timer is reset or finished.
+pub async fn clear(app: TimerApp) {
+    if let Err(e) = eeprom::erase(app.address(), app.address() + SNAPSHOT_SIZE).await {
+        info!("Failed to clear timer snapshot: {}", e);
+    }
+}
+
+/// Load the saved timer state of the app.
+///
+/// Returns [None](Option::None) if there is no timer to restore.
+pub async fn load(app: TimerApp) -> Option<Snapshot> {
+    let mut bytes = [0u8; SNAPSHOT_SIZE];
+    if let Err(e) = eeprom::read(app.address(), &mut bytes).await {
+        info!("Failed to load timer snapshot: {}", e);
+        return None;
+    }
+
+    if bytes[0] == eeprom::ERASED_BYTES {
+        return None;
+    }
+
+    let mut timestamp = [0u8; 8];
+    timestamp.copy_from_slice(&bytes[4..12]);
+
+    Some(Snapshot {
+        running: bytes[0] == 1,
+        minutes: bytes[1] as u32,
+        seconds: bytes[2] as u32,
+        saved_at: NaiveDateTime::from_timestamp_opt(i64::from_le_bytes(timestamp), 0)?,
+    })
+}
@@ -0,0 +1,27 @@
+use crate::{
+    config,
+    display::display_matrix::{IconAnimation, DISPLAY_MATRIX},
+    speaker::{self, SoundType},
+};
+
+/// How many seconds before a timer finishes to start warning that time is nearly up.
+const FINAL_COUNTDOWN_SECS: u32 = 10;
+
+/// How long the tick for each of the final seconds is, in milliseconds.
+const FINAL_COUNTDOWN_TICK_MS: u64 = 15;
+
+/// Warn that a timer is nearly up by blinking the CountDown icon and ticking. Call once a second with the seconds left.
+///
+/// Shared by the pomodoro and the recipe timer.
+/// Does nothing before the final [FINAL_COUNTDOWN_SECS], or when turned off in settings. The timer should set the icon back to steady when it stops.
+pub async fn final_countdown(remaining_secs: u32) {
+    if remaining_secs == 0
+        || remaining_secs > FINAL_COUNTDOWN_SECS
+        || !config::get_final_countdown().await
+    {
+        return;
+    }
+
+    DISPLAY_MATRIX.animate_icon("CountDown", IconAnimation::Blink);
+    speaker::sound(SoundType::Beep(FINAL_COUNTDOWN_TICK_MS));
+}
@@ -0,0 +1,81 @@
+use embassy_futures::join::join3;
+use embassy_rp::{
+    bind_interrupts,
+    peripherals::USB,
+    usb::{Driver, InterruptHandler},
+};
+use embassy_usb::{Builder, Config};
+
+#[cfg(feature = "usb-console")]
+use crate::usb_console;
+#[cfg(feature = "usb-hid")]
+use crate::usb_hid;
+
+bind_interrupts!(struct Irqs {
+    USBCTRL_IRQ => InterruptHandler<USB>;
+});
+
+/// The USB vendor id, from pid.codes.
+const VENDOR_ID: u16 = 0x1209;
+
+/// The USB product id. This is the pid.codes test id, so must not be used outside of personal use.
+const PRODUCT_ID: u16 = 0x0001;
+
+/// The USB driver for the RP2040.
+pub type UsbDriver<'d> = Driver<'d, USB>;
+
+/// Run the USB device, with the classes for each of the usb features enabled.
+#[embassy_executor::task]
+pub async fn usb_task(usb: USB) {
+    let driver = Driver::new(usb, Irqs);
+
+    let mut config = Config::new(VENDOR_ID, PRODUCT_ID);
+    config.manufacturer = Some("domneedham");
+    config.product = Some("Pico Clock Green");
+    config.max_power = 100;
+    config.max_packet_size_0 = 64;
+
+    // a composite device, so the hid and console classes can be used together
+    config.device_class = 0xEF;
+    config.device_sub_class = 0x02;
+    config.device_protocol = 0x01;
+    config.composite_with_iads = true;
+
+    let mut device_descriptor = [0; 256];
+    let mut config_descriptor = [0; 256];
+    let mut bos_descriptor = [0; 256];
+    let mut control_buf = [0; 64];
+
+    #[cfg(feature = "usb-hid")]
+    let mut hid_state = embassy_usb::class::hid::State::new();
+    #[cfg(feature = "usb-console")]
+    let mut console_state = embassy_usb::class::cdc_acm::State::new();
+
+    let mut builder = Builder::new(
+        driver,
+        config,
+        &mut device_descriptor,
+        &mut config_descriptor,
+        &mut bos_descriptor,
+        &mut control_buf,
+    );
+
+    #[cfg(feature = "usb-hid")]
+    let hid = usb_hid::new(&mut builder, &mut hid_state);
+    #[cfg(feature = "usb-console")]
+    let console = usb_console::new(&mut builder, &mut console_state);
+
+    let mut usb = builder.build();
+
+    let hid_fut = async {
+        #[cfg(feature = "usb-hid")]
+        usb_hid::run(hid).await;
+    };
+
+    let console_fut = async {
+        #[cfg(feature = "usb-console")]
+        usb_console::run(console).await;
+    };
+
+    join3(usb.run(), hid_fut, console_fut).await;
+}
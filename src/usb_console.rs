@@ -0,0 +1,101 @@
+use core::fmt::Write;
+
+use chrono::{Datelike, Timelike};
+use embassy_usb::{
+    class::cdc_acm::{CdcAcmClass, State},
+    driver::EndpointError,
+    Builder,
+};
+use heapless::String;
+
+use crate::{
+    event_log,
+    temperature_log::{self, LOG_LEN},
+    usb::UsbDriver,
+};
+
+/// The largest packet sent or received over the console.
+const PACKET_SIZE: u16 = 64;
+
+/// Add the serial console class to the USB device.
+pub fn new<'d>(
+    builder: &mut Builder<'d, UsbDriver<'d>>,
+    state: &'d mut State<'d>,
+) -> CdcAcmClass<'d, UsbDriver<'d>> {
+    CdcAcmClass::new(builder, state, PACKET_SIZE)
+}
+
+/// Write the temperature log to the console when "t" is typed into it, or the event log when anything else is typed.
+pub async fn run(mut class: CdcAcmClass<'_, UsbDriver<'_>>) -> ! {
+    let mut buf = [0u8; PACKET_SIZE as usize];
+
+    loop {
+        class.wait_connection().await;
+
+        // a failed read or write means the console has disconnected
+        while let Ok(len) = class.read_packet(&mut buf).await {
+            let res = match buf[..len].first() {
+                Some(b't') | Some(b'T') => dump_temperatures(&mut class).await,
+                _ => dump_events(&mut class).await,
+            };
+
+            if res.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Write each event in the log on its own line, oldest first.
+async fn dump_events(class: &mut CdcAcmClass<'_, UsbDriver<'_>>) -> Result<(), EndpointError> {
+    let mut line: String<{ PACKET_SIZE as usize }> = String::new();
+
+    for entry in event_log::entries() {
+        line.clear();
+
+        // every line is shorter than a full packet, so each write is sent straight away
+        let millis = entry.at.as_millis();
+        _ = write!(
+            line,
+            "{:>6}.{:03} {}\r\n",
+            millis / 1000,
+            millis % 1000,
+            entry.event
+        );
+
+        class.write_packet(line.as_bytes()).await?;
+    }
+
+    class.write_packet(b"--\r\n").await
+}
+
+/// Write the temperature log as CSV, oldest first, with the time and the temperature in celcius on each line.
+async fn dump_temperatures(
+    class: &mut CdcAcmClass<'_, UsbDriver<'_>>,
+) -> Result<(), EndpointError> {
+    let mut line: String<{ PACKET_SIZE as usize }> = String::new();
+
+    class.write_packet(b"time,celcius\r\n").await?;
+
+    for age in (0..LOG_LEN).rev() {
+        let sample = match temperature_log::get(age).await {
+            Some(sample) => sample,
+            None => continue,
+        };
+
+        line.clear();
+        _ = write!(
+            line,
+            "{:04}-{:02}-{:02} {:02}:00,{:.2}\r\n",
+            sample.at.year(),
+            sample.at.month(),
+            sample.at.day(),
+            sample.at.hour(),
+            sample.celcius
+        );
+
+        class.write_packet(line.as_bytes()).await?;
+    }
+
+    class.write_packet(b"--\r\n").await
+}
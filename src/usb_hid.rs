@@ -0,0 +1,78 @@
+use defmt::info;
+use embassy_usb::{
+    class::hid::{Config as HidConfig, HidReaderWriter, State},
+    Builder,
+};
+
+use crate::{
+    notification::{self, Notification},
+    usb::UsbDriver,
+};
+
+/// The size of a report sent from the host.
+const REPORT_SIZE: usize = 64;
+
+/// Vendor defined HID report descriptor, with an 8 byte input report and a 64 byte output report.
+///
+/// The output report is the notification. The first byte is the length of the icon name, followed by the icon name, then the text, ending at the first 0 byte.
+const REPORT_DESCRIPTOR: &[u8] = &[
+    0x06, 0x00, 0xFF, // usage page (vendor defined 0xFF00)
+    0x09, 0x01, // usage (0x01)
+    0xA1, 0x01, // collection (application)
+    0x15, 0x00, // logical minimum (0)
+    0x26, 0xFF, 0x00, // logical maximum (255)
+    0x75, 0x08, // report size (8 bits)
+    0x09, 0x02, // usage (0x02)
+    0x95, 0x08, // report count (8)
+    0x81, 0x02, // input (data, variable, absolute)
+    0x09, 0x03, // usage (0x03)
+    0x95, 0x40, // report count (64)
+    0x91, 0x02, // output (data, variable, absolute)
+    0xC0, // end collection
+];
+
+/// The HID class, with the 64 byte output report read and the 8 byte input report written.
+type Hid<'d> = HidReaderWriter<'d, UsbDriver<'d>, REPORT_SIZE, 8>;
+
+/// Add the vendor HID class to the USB device.
+pub fn new<'d>(builder: &mut Builder<'d, UsbDriver<'d>>, state: &'d mut State<'d>) -> Hid<'d> {
+    let config = HidConfig {
+        report_descriptor: REPORT_DESCRIPTOR,
+        request_handler: None,
+        poll_ms: 60,
+        max_packet_size: 64,
+    };
+
+    Hid::new(builder, state, config)
+}
+
+/// Queue each notification sent from the host.
+pub async fn run(hid: Hid<'_>) -> ! {
+    let (mut reader, _writer) = hid.split();
+    let mut report = [0u8; REPORT_SIZE];
+
+    loop {
+        match reader.read(&mut report).await {
+            Ok(len) => match parse_report(&report[..len]) {
+                Some(notification) => notification::notify(notification),
+                None => info!("Invalid notification report"),
+            },
+            Err(e) => info!("USB read failed: {}", e),
+        }
+    }
+}
+
+/// Read a notification from an output report.
+///
+/// Returns [None](Option::None) if the report is too short or is not valid UTF-8.
+fn parse_report(report: &[u8]) -> Option<Notification> {
+    let icon_len = *report.first()? as usize;
+    let icon = core::str::from_utf8(report.get(1..1 + icon_len)?).ok()?;
+
+    let text = &report[1 + icon_len..];
+    let text_end = text.iter().position(|&b| b == 0).unwrap_or(text.len());
+    let text = core::str::from_utf8(&text[..text_end]).ok()?;
+
+    let icon = if icon.is_empty() { None } else { Some(icon) };
+    Some(Notification::new(text, icon))
+}
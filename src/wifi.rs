@@ -0,0 +1,270 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use cyw43::{Control, NetDriver, PowerManagementMode};
+use cyw43_pio::PioSpi;
+use defmt::info;
+use embassy_executor::{Spawner, _export::StaticCell};
+use embassy_futures::select::{select, Either};
+use embassy_net::{
+    dns::DnsQueryType,
+    udp::{PacketMetadata, UdpSocket},
+    Config, Stack, StackResources,
+};
+use embassy_rp::{
+    bind_interrupts,
+    gpio::{Level, Output},
+    peripherals::{DMA_CH1, PIN_23, PIN_24, PIN_25, PIN_29, PIO0},
+    pio::{InterruptHandler, Pio},
+};
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::{
+    config,
+    notification::{self, Notification},
+    time_sync::{self, Error as SyncError},
+};
+
+bind_interrupts!(struct Irqs {
+    PIO0_IRQ_0 => InterruptHandler<PIO0>;
+});
+
+/// The firmware for the wifi chip, copied from the cyw43-firmware folder in the embassy repository.
+///
+/// The build script copies it in, leaving it empty when it is missing so the wifi code can still be checked.
+const FIRMWARE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/43439A0.bin"));
+
+/// The country locale matrix for the wifi chip, copied from the cyw43-firmware folder in the embassy repository.
+const CLM: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/43439A0_clm.bin"));
+
+/// How long to wait before trying to join the network again after failing.
+const RETRY_SECS: u64 = 30;
+
+/// How long to wait before checking the config again when no network is set.
+const NO_NETWORK_SECS: u64 = 60;
+
+/// How often to check that the connection is still up.
+const LINK_CHECK_SECS: u64 = 5;
+
+/// The port NTP servers listen on.
+const NTP_PORT: u16 = 123;
+
+/// The size of an NTP packet, without any extensions.
+const NTP_PACKET_SIZE: usize = 48;
+
+/// How long to wait for the NTP server to reply.
+const NTP_TIMEOUT_SECS: u64 = 5;
+
+/// The SPI bus to the wifi chip, run by the PIO.
+type Spi = PioSpi<'static, PIN_25, PIO0, 0, DMA_CH1>;
+
+/// The network stack that runs over wifi.
+pub type NetStack = Stack<NetDriver<'static>>;
+
+/// Static state for the wifi chip driver.
+static STATE: StaticCell<cyw43::State> = StaticCell::new();
+
+/// Static network stack, shared by everything that uses the network.
+static STACK: StaticCell<NetStack> = StaticCell::new();
+
+/// Static memory for the sockets in the network stack.
+static RESOURCES: StaticCell<StackResources<4>> = StaticCell::new();
+
+/// Whether the clock is connected to a wifi network.
+static CONNECTED: AtomicBool = AtomicBool::new(false);
+
+/// The peripherals used by the wifi chip on the Pico W.
+pub struct WifiPeripherals {
+    /// The wifi chip power pin.
+    pub pwr: PIN_23,
+
+    /// The SPI data pin.
+    pub dio: PIN_24,
+
+    /// The SPI chip select pin.
+    pub cs: PIN_25,
+
+    /// The SPI clock pin.
+    pub clk: PIN_29,
+
+    /// The PIO block that runs the SPI bus.
+    pub pio: PIO0,
+
+    /// The DMA channel for the SPI bus.
+    pub dma: DMA_CH1,
+}
+
+/// Determine if the clock is connected to a wifi network.
+pub fn is_connected() -> bool {
+    CONNECTED.load(Ordering::Relaxed)
+}
+
+/// Start the wifi chip and keep the clock connected to the network in the config.
+#[embassy_executor::task]
+pub async fn wifi_task(spawner: Spawner, peripherals: WifiPeripherals) {
+    let pwr = Output::new(peripherals.pwr, Level::Low);
+    let cs = Output::new(peripherals.cs, Level::High);
+    let mut pio = Pio::new(peripherals.pio, Irqs);
+    let spi = PioSpi::new(
+        &mut pio.common,
+        pio.sm0,
+        pio.irq0,
+        cs,
+        peripherals.dio,
+        peripherals.clk,
+        peripherals.dma,
+    );
+
+    let state = STATE.init(cyw43::State::new());
+    let (net_device, mut control, runner) = cyw43::new(state, pwr, spi, FIRMWARE).await;
+    spawner.spawn(cyw43_task(runner)).unwrap();
+
+    control.init(CLM).await;
+    control
+        .set_power_management(PowerManagementMode::PowerSave)
+        .await;
+
+    // there is no hardware rng, so the uptime is used to seed the tcp sequence numbers
+    let seed = Instant::now().as_ticks();
+    let stack = &*STACK.init(Stack::new(
+        net_device,
+        Config::dhcpv4(Default::default()),
+        RESOURCES.init(StackResources::new()),
+        seed,
+    ));
+    spawner.spawn(net_task(stack)).unwrap();
+
+    spawner.spawn(sntp_task(stack)).unwrap();
+
+    #[cfg(feature = "mqtt")]
+    spawner.spawn(crate::mqtt::mqtt_task(stack)).unwrap();
+
+    config::wait_for_init().await;
+    connection_manager(&mut control, stack).await;
+}
+
+/// Run the wifi chip driver.
+#[embassy_executor::task]
+async fn cyw43_task(runner: cyw43::Runner<'static, Output<'static, PIN_23>, Spi>) -> ! {
+    runner.run().await
+}
+
+/// Run the network stack.
+#[embassy_executor::task]
+async fn net_task(stack: &'static NetStack) -> ! {
+    stack.run().await
+}
+
+/// Keep the RTC synced to the time from an NTP server while connected.
+#[embassy_executor::task]
+async fn sntp_task(stack: &'static NetStack) -> ! {
+    loop {
+        while !stack.is_config_up() {
+            Timer::after(Duration::from_secs(1)).await;
+        }
+
+        let wait_secs = match sntp_sync(stack).await {
+            Ok(()) => time_sync::SYNC_SECS,
+            Err(e) => {
+                info!("Failed to sync the time over wifi: {}", e);
+                time_sync::RETRY_SECS
+            }
+        };
+
+        Timer::after(Duration::from_secs(wait_secs)).await;
+    }
+}
+
+/// Get the time from the NTP server and set it into the RTC.
+async fn sntp_sync(stack: &'static NetStack) -> Result<(), SyncError> {
+    let address = match stack
+        .dns_query(time_sync::NTP_SERVER, DnsQueryType::A)
+        .await
+    {
+        Ok(addresses) => *addresses.first().ok_or(SyncError::Network)?,
+        Err(_) => return Err(SyncError::Network),
+    };
+
+    let mut rx_meta = [PacketMetadata::EMPTY; 1];
+    let mut rx_buffer = [0u8; NTP_PACKET_SIZE];
+    let mut tx_meta = [PacketMetadata::EMPTY; 1];
+    let mut tx_buffer = [0u8; NTP_PACKET_SIZE];
+    let mut socket = UdpSocket::new(
+        stack,
+        &mut rx_meta,
+        &mut rx_buffer,
+        &mut tx_meta,
+        &mut tx_buffer,
+    );
+    socket.bind(0).map_err(|_| SyncError::Network)?;
+
+    // leap indicator 0, version 4, mode 3 (client)
+    let mut packet = [0u8; NTP_PACKET_SIZE];
+    packet[0] = 0b0010_0011;
+    socket
+        .send_to(&packet, (address, NTP_PORT))
+        .await
+        .map_err(|_| SyncError::Network)?;
+
+    let timeout = Timer::after(Duration::from_secs(NTP_TIMEOUT_SECS));
+    let len = match select(socket.recv_from(&mut packet), timeout).await {
+        Either::First(Ok((len, _))) => len,
+        _ => return Err(SyncError::Network),
+    };
+    if len < NTP_PACKET_SIZE {
+        return Err(SyncError::InvalidTime);
+    }
+
+    // the transmit timestamp, when the server sent the reply
+    let seconds = u32::from_be_bytes([packet[40], packet[41], packet[42], packet[43]]);
+    let fraction = u32::from_be_bytes([packet[44], packet[45], packet[46], packet[47]]);
+    let utc = time_sync::datetime_from_ntp(seconds, fraction).ok_or(SyncError::InvalidTime)?;
+
+    time_sync::sync_utc(utc).await
+}
+
+/// Join the network in the config, and join again whenever the connection drops.
+async fn connection_manager(control: &mut Control<'static>, stack: &'static NetStack) -> ! {
+    loop {
+        let credentials = config::get_wifi_credentials().await;
+        if credentials.ssid.is_empty() {
+            Timer::after(Duration::from_secs(NO_NETWORK_SECS)).await;
+            continue;
+        }
+
+        let res = if credentials.password.is_empty() {
+            control.join_open(&credentials.ssid).await
+        } else {
+            control
+                .join_wpa2(&credentials.ssid, &credentials.password)
+                .await
+        };
+
+        match res {
+            Ok(()) => {
+                info!("Joined wifi network");
+                set_connected(true).await;
+
+                while stack.is_link_up() {
+                    Timer::after(Duration::from_secs(LINK_CHECK_SECS)).await;
+                }
+
+                info!("Wifi connection lost");
+                set_connected(false).await;
+            }
+            Err(e) => {
+                info!("Failed to join wifi network: {}", e);
+                Timer::after(Duration::from_secs(RETRY_SECS)).await;
+            }
+        }
+    }
+}
+
+/// Update the connection state, and show the change on the display.
+///
+/// There is no spare icon on the display for wifi, so the change is shown as a notification.
+async fn set_connected(connected: bool) {
+    CONNECTED.store(connected, Ordering::Relaxed);
+
+    let text = if connected { "WiFi on" } else { "WiFi off" };
+    notification::notify(Notification::new(text, None));
+}
@@ -0,0 +1,44 @@
+#!/usr/bin/env python3
+"""Send a notification to the clock over USB, when built with the usb-hid feature.
+
+Needs the hidapi bindings: pip install hid
+
+Usage: notify.py "Text to show" [icon]
+"""
+
+import sys
+
+import hid
+
+VENDOR_ID = 0x1209
+PRODUCT_ID = 0x0001
+REPORT_SIZE = 64
+
+
+def build_report(text, icon=""):
+    icon = icon.encode("utf-8")
+    report = bytes([len(icon)]) + icon + text.encode("utf-8")
+    if len(report) > REPORT_SIZE:
+        raise ValueError("notification is too long")
+
+    # report id 0, then the report padded with 0 bytes
+    return b"\x00" + report.ljust(REPORT_SIZE, b"\x00")
+
+
+def main():
+    if len(sys.argv) not in (2, 3):
+        print(__doc__)
+        sys.exit(1)
+
+    text = sys.argv[1]
+    icon = sys.argv[2] if len(sys.argv) == 3 else ""
+
+    device = hid.Device(VENDOR_ID, PRODUCT_ID)
+    try:
+        device.write(build_report(text, icon))
+    finally:
+        device.close()
+
+
+if __name__ == "__main__":
+    main()